@@ -0,0 +1,4 @@
+CREATE TABLE station_settings (
+    uuid TEXT NOT NULL PRIMARY KEY,
+    data TEXT NOT NULL
+);
@@ -0,0 +1,6 @@
+CREATE TABLE station_history (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    uuid TEXT NOT NULL,
+    started_at BIGINT NOT NULL,
+    duration INTEGER NOT NULL
+);
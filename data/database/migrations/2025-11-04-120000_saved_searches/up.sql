@@ -0,0 +1,4 @@
+CREATE TABLE saved_searches (
+    name TEXT NOT NULL PRIMARY KEY,
+    data TEXT NOT NULL
+);
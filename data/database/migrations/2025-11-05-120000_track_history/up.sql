@@ -0,0 +1,11 @@
+CREATE TABLE track_history (
+    uuid TEXT NOT NULL PRIMARY KEY,
+    title TEXT NOT NULL,
+    station_uuid TEXT NOT NULL,
+    station_is_local BOOLEAN NOT NULL DEFAULT FALSE,
+    station_data TEXT NOT NULL,
+    started_at BIGINT NOT NULL,
+    duration BIGINT NOT NULL,
+    state TEXT NOT NULL,
+    saved_to TEXT
+);
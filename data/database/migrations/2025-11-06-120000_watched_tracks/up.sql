@@ -0,0 +1,4 @@
+CREATE TABLE watched_tracks (
+    title TEXT NOT NULL PRIMARY KEY,
+    added_at BIGINT NOT NULL
+);
@@ -0,0 +1,4 @@
+CREATE TABLE automation_rules (
+    name TEXT NOT NULL PRIMARY KEY,
+    data TEXT NOT NULL
+);
@@ -0,0 +1,7 @@
+CREATE TABLE station_popularity (
+    uuid TEXT NOT NULL,
+    day BIGINT NOT NULL,
+    clickcount INTEGER NOT NULL,
+    votes INTEGER NOT NULL,
+    PRIMARY KEY (uuid, day)
+);
@@ -0,0 +1,72 @@
+// Shortwave - acoustid.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+/// Results with a lower score aren't considered as a match
+const MIN_SCORE: f64 = 0.5;
+
+/// Response of the AcoustID fingerprint lookup
+#[derive(Default, Debug, Clone, serde_derive::Deserialize)]
+#[serde(default)]
+pub struct AcoustIdResponse {
+    pub status: String,
+    pub results: Vec<AcoustIdResult>,
+}
+
+#[derive(Default, Debug, Clone, serde_derive::Deserialize)]
+#[serde(default)]
+pub struct AcoustIdResult {
+    pub score: f64,
+    pub recordings: Vec<AcoustIdRecording>,
+}
+
+#[derive(Default, Debug, Clone, serde_derive::Deserialize)]
+#[serde(default)]
+pub struct AcoustIdRecording {
+    pub title: String,
+    pub artists: Vec<AcoustIdArtist>,
+}
+
+#[derive(Default, Debug, Clone, serde_derive::Deserialize)]
+#[serde(default)]
+pub struct AcoustIdArtist {
+    pub name: String,
+}
+
+impl AcoustIdResponse {
+    /// The recording of the result with the highest score, if it's a confident match
+    pub fn best_recording(self) -> Option<AcoustIdRecording> {
+        self.results
+            .into_iter()
+            .filter(|result| result.score >= MIN_SCORE)
+            .max_by(|a, b| a.score.total_cmp(&b.score))?
+            .recordings
+            .into_iter()
+            .find(|recording| !recording.title.trim().is_empty())
+    }
+}
+
+impl AcoustIdRecording {
+    /// Title in the common "Artist - Title" notation
+    pub fn track_title(&self) -> String {
+        let artists: Vec<&str> = self.artists.iter().map(|a| a.name.as_str()).collect();
+
+        if artists.is_empty() {
+            self.title.clone()
+        } else {
+            format!("{} - {}", artists.join(", "), self.title)
+        }
+    }
+}
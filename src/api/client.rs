@@ -14,16 +14,16 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::net::IpAddr;
 use std::rc::Rc;
-use std::sync::LazyLock;
-use std::time::Duration;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
 
 use async_compat::Compat;
-use async_std_resolver::{config as rconfig, resolver, resolver_from_system_conf};
+use gtk::gio;
+use gtk::prelude::*;
 use rand::prelude::SliceRandom;
 use rand::rng;
-use reqwest::header::{self, HeaderMap};
+use reqwest::header;
 use reqwest::Request;
 use serde::de;
 use url::Url;
@@ -31,6 +31,7 @@ use url::Url;
 use crate::api::*;
 use crate::app::SwApplication;
 use crate::config;
+use crate::database::StationCredentials;
 use crate::settings::{settings_manager, Key};
 
 static USER_AGENT: LazyLock<String> = LazyLock::new(|| {
@@ -42,22 +43,66 @@ static USER_AGENT: LazyLock<String> = LazyLock::new(|| {
     )
 });
 
-static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
-    let mut headers = HeaderMap::new();
-    headers.insert(
-        "content-type",
-        header::HeaderValue::from_static("application/json"),
-    );
-
+/// Shared by all HTTP requests of the app, so that connections can be reused.
+/// Requests which need to fail faster set their own timeout.
+pub static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
     reqwest::ClientBuilder::new()
         .user_agent(USER_AGENT.as_str())
-        .default_headers(headers)
+        .connect_timeout(Duration::from_secs(5))
         .timeout(Duration::from_secs(15))
+        .gzip(true)
+        .brotli(true)
+        // Keep connections open between searches, so that we don't have
+        // to pay for the TCP and TLS handshakes every time
+        .pool_idle_timeout(Duration::from_secs(300))
+        .tcp_keepalive(Duration::from_secs(60))
+        .http2_keep_alive_interval(Duration::from_secs(30))
+        .http2_keep_alive_while_idle(true)
         .build()
         .unwrap()
 });
 
+static REQUEST_STATS: LazyLock<Mutex<RequestStats>> = LazyLock::new(Mutex::default);
+
+/// Timing of the API requests which got sent through the shared client
+#[derive(Debug, Default, Clone)]
+pub struct RequestStats {
+    pub count: u32,
+    pub failed: u32,
+    pub total: Duration,
+    pub slowest: Duration,
+    /// HTTP version of the last successful response, e.g. "HTTP/2.0"
+    pub last_version: Option<String>,
+}
+
+impl RequestStats {
+    pub fn average(&self) -> Option<Duration> {
+        (self.count > 0).then(|| self.total / self.count)
+    }
+
+    fn record(&mut self, duration: Duration, result: &Result<reqwest::Response, reqwest::Error>) {
+        self.count += 1;
+        self.total += duration;
+        self.slowest = self.slowest.max(duration);
+
+        match result {
+            Ok(response) => self.last_version = Some(format!("{:?}", response.version())),
+            Err(_) => self.failed += 1,
+        }
+    }
+}
+
+pub fn request_stats() -> RequestStats {
+    REQUEST_STATS.lock().unwrap().clone()
+}
+
 pub async fn station_request(request: StationRequest) -> Result<Vec<SwStation>, Error> {
+    // Stations which failed their last check get hidden, unless the user wants to see them
+    let request = StationRequest {
+        hidebroken: Some(settings_manager::boolean(Key::StationsHideBroken)),
+        ..request
+    };
+
     let url = build_url(STATION_SEARCH, Some(&request.url_encode()))?;
 
     let request = HTTP_CLIENT.get(url.as_ref()).build().map_err(Rc::new)?;
@@ -71,51 +116,146 @@ pub async fn station_request(request: StationRequest) -> Result<Vec<SwStation>,
     Ok(stations)
 }
 
+pub async fn station_metadata_by_uuid(uuid: &str) -> Result<Option<StationMetadata>, Error> {
+    let url = build_url(STATION_BY_UUID, Some(&format!("uuids={uuid}")))?;
+
+    let request = HTTP_CLIENT.get(url.as_ref()).build().map_err(Rc::new)?;
+    let stations_md = send_request_compat::<Vec<StationMetadata>>(request).await?;
+
+    Ok(stations_md.into_iter().next())
+}
+
+pub async fn station_by_url(url: &Url) -> Result<Option<SwStation>, Error> {
+    let query = serde_urlencoded::to_string([("url", url.as_str())]).unwrap();
+    let url = build_url(STATION_BY_URL, Some(&query))?;
+
+    let request = HTTP_CLIENT.get(url.as_ref()).build().map_err(Rc::new)?;
+    let stations_md = send_request_compat::<Vec<StationMetadata>>(request).await?;
+
+    let station = stations_md
+        .into_iter()
+        .next()
+        .map(|metadata| SwStation::new(&metadata.stationuuid.clone(), false, metadata, None));
+
+    Ok(station)
+}
+
+/// All languages which are used by working stations, the most used ones first
+pub async fn languages() -> Result<Vec<Language>, Error> {
+    let url = build_url(
+        LANGUAGES,
+        Some("order=stationcount&reverse=true&hidebroken=true"),
+    )?;
+
+    let request = HTTP_CLIENT.get(url.as_ref()).build().map_err(Rc::new)?;
+    let languages = send_request_compat::<Vec<Language>>(request).await?;
+
+    Ok(languages
+        .into_iter()
+        .filter(|language| !language.name.trim().is_empty())
+        .collect())
+}
+
+/// Identifies a recording by its Chromaprint fingerprint using AcoustID
+pub async fn acoustid_lookup(
+    api_key: &str,
+    fingerprint: &str,
+    duration: u64,
+) -> Result<Option<AcoustIdRecording>, Error> {
+    let query = serde_urlencoded::to_string([
+        ("client", api_key),
+        ("meta", "recordings"),
+        ("duration", &duration.to_string()),
+        ("fingerprint", fingerprint),
+    ])
+    .unwrap();
+    let url = Url::parse(&format!("{ACOUSTID_LOOKUP}?{query}")).unwrap();
+
+    let request = HTTP_CLIENT.get(url.as_ref()).build().map_err(Rc::new)?;
+    let response = send_request_compat::<AcoustIdResponse>(request).await?;
+
+    Ok(response.best_recording())
+}
+
+/// The name a stream announces in its `icy-name` header, if any
+pub async fn stream_icy_name(url: &Url) -> Result<Option<String>, Error> {
+    // Only the headers are needed, the body is the endless stream itself
+    let response = stream_response(url, None).await?;
+    Ok(StreamInfo::from_headers(response.headers()).name)
+}
+
+/// Connects to the stream and returns the information it announces in its headers.
+/// Fails if the stream can't be reached, or doesn't deliver any data.
+pub async fn probe_stream(
+    url: &Url,
+    credentials: Option<&StationCredentials>,
+) -> Result<StreamInfo, Error> {
+    let mut response = stream_response(url, credentials)
+        .await?
+        .error_for_status()
+        .map_err(Rc::new)?;
+    let info = StreamInfo::from_headers(response.headers());
+
+    // The first data shows whether the stream is actually running
+    let chunk = Compat::new(async move { response.chunk().await })
+        .await
+        .map_err(Rc::new)?;
+
+    match chunk {
+        Some(chunk) if !chunk.is_empty() => Ok(info),
+        _ => Err(Error::NoStreamData),
+    }
+}
+
+/// The favicon of a website, if there is one at the default location
+pub async fn favicon_url(homepage: &Url) -> Option<Url> {
+    let url = homepage.join("/favicon.ico").ok()?;
+    let request = HTTP_CLIENT.get(url.as_ref()).build().ok()?;
+
+    let response = Compat::new(async move { HTTP_CLIENT.execute(request).await })
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?;
+
+    let is_image = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("image/"));
+
+    is_image.then_some(url)
+}
+
 pub async fn lookup_rb_server() -> Option<String> {
     let lookup_domain = settings_manager::string(Key::ApiLookupDomain);
-    let resolver = if let Ok(resolver) = resolver_from_system_conf().await {
-        resolver
-    } else {
-        warn!("Unable to use dns resolver from system conf");
-
-        let config = rconfig::ResolverConfig::default();
-        let opts = rconfig::ResolverOpts::default();
-        resolver(config, opts).await
-    };
+    let resolver = gio::Resolver::default();
 
     // Do forward lookup to receive a list with the api servers
-    let response = resolver.lookup_ip(lookup_domain).await.ok()?;
-    let mut ips: Vec<IpAddr> = response.iter().collect();
+    let mut ips = resolver.lookup_by_name_future(&lookup_domain).await.ok()?;
 
     // Shuffle it to make sure we're not using always the same one
     ips.shuffle(&mut rng());
 
     for ip in ips {
         // Do a reverse lookup to get the hostname
-        let result = resolver
-            .reverse_lookup(ip)
-            .await
-            .ok()
-            .and_then(|r| r.into_iter().next());
-
-        if result.is_none() {
-            warn!("Reverse lookup for {} failed", ip);
+        let Ok(domain) = resolver.lookup_by_address_future(&ip).await else {
+            warn!("Reverse lookup for {} failed", ip.to_str());
             continue;
-        }
+        };
 
         // We need to strip the trailing "." from the domain name, otherwise TLS hostname verification fails
-        let domain = result.unwrap().to_string();
         let hostname = domain.trim_end_matches(".");
 
         // Check if the server is online / returns data
         // If not, try using the next one in the list
-        debug!("Trying to connect to {} ({})", hostname, ip.to_string());
+        debug!("Trying to connect to {} ({})", hostname, ip.to_str());
         match server_stats(hostname).await {
             Ok(stats) => {
                 debug!(
                     "Successfully connected to {} ({}), server version {}, {} stations",
                     hostname,
-                    ip.to_string(),
+                    ip.to_str(),
                     stats.software_version,
                     stats.stations
                 );
@@ -147,6 +287,23 @@ fn build_url(param: &str, options: Option<&str>) -> Result<Url, Error> {
     Ok(url)
 }
 
+async fn stream_response(
+    url: &Url,
+    credentials: Option<&StationCredentials>,
+) -> Result<reqwest::Response, Error> {
+    let mut request = HTTP_CLIENT.get(url.as_ref()).header("Icy-MetaData", "1");
+    if let Some(credentials) = credentials {
+        request = request.header(header::AUTHORIZATION, credentials.authorization_header());
+    }
+    let request = request.build().map_err(Rc::new)?;
+
+    let response = Compat::new(async move { HTTP_CLIENT.execute(request).await })
+        .await
+        .map_err(Rc::new)?;
+
+    Ok(response)
+}
+
 async fn server_stats(host: &str) -> Result<Stats, Error> {
     let request = HTTP_CLIENT
         .get(format!("https://{host}/{STATS}"))
@@ -157,7 +314,14 @@ async fn server_stats(host: &str) -> Result<Stats, Error> {
 }
 
 async fn send_request<T: de::DeserializeOwned>(request: Request) -> Result<T, Error> {
-    let response = HTTP_CLIENT.execute(request).await.map_err(Rc::new)?;
+    let start = Instant::now();
+    let result = HTTP_CLIENT.execute(request).await;
+    REQUEST_STATS
+        .lock()
+        .unwrap()
+        .record(start.elapsed(), &result);
+
+    let response = result.map_err(Rc::new)?;
     let json = response.text().await.map_err(Rc::new)?;
     let deserialized = serde_json::from_str(&json);
 
@@ -174,3 +338,96 @@ async fn send_request<T: de::DeserializeOwned>(request: Request) -> Result<T, Er
 async fn send_request_compat<T: de::DeserializeOwned>(request: Request) -> Result<T, Error> {
     Compat::new(async move { send_request(request).await }).await
 }
+
+#[cfg(test)]
+mod tests {
+    use futures_lite::future::block_on;
+
+    use super::*;
+    use crate::test_server::{IcecastStream, TestResponse, TestServer};
+
+    fn icecast_server() -> TestServer {
+        TestServer::start(|request| {
+            IcecastStream::response(request, &["Artist - Title"])
+                .header("icy-name", "Test Radio")
+                .header("icy-genre", "Jazz; Blues")
+                .header("icy-br", "128,128")
+        })
+    }
+
+    #[test]
+    fn probe_icecast_stream() {
+        let server = icecast_server();
+        let url = Url::parse(&server.url("/stream")).unwrap();
+
+        let info = block_on(probe_stream(&url, None)).unwrap();
+        assert_eq!(info.name.as_deref(), Some("Test Radio"));
+        assert_eq!(info.genre.as_deref(), Some("jazz,blues"));
+        assert_eq!(info.bitrate, Some(128));
+        assert_eq!(info.content_type.as_deref(), Some("audio/x-wav"));
+
+        // ICY metadata is requested, so that the stream titles are available
+        let request = &server.requests()[0];
+        assert_eq!(request.header("icy-metadata"), Some("1"));
+
+        let name = block_on(stream_icy_name(&url)).unwrap();
+        assert_eq!(name.as_deref(), Some("Test Radio"));
+    }
+
+    #[test]
+    fn probe_stream_without_data() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            "/empty" => TestResponse::new(Vec::new()).header("icy-name", "Test Radio"),
+            _ => TestResponse::new("Not Found").status(404),
+        });
+
+        let url = Url::parse(&server.url("/empty")).unwrap();
+        let result = block_on(probe_stream(&url, None));
+        assert!(matches!(result, Err(Error::NoStreamData)));
+
+        let url = Url::parse(&server.url("/missing")).unwrap();
+        let result = block_on(probe_stream(&url, None));
+        assert!(matches!(result, Err(Error::Network(_))));
+    }
+
+    #[test]
+    fn radio_browser_stations() {
+        let station = StationMetadata {
+            stationuuid: "960e57c5-0601-11e8-ae97-52543be04c81".into(),
+            name: "Test Radio".into(),
+            url: Url::parse("http://example.org/stream").ok(),
+            tags: "jazz,blues".into(),
+            countrycode: "DE".into(),
+            votes: 42,
+            lastcheckok: 1,
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&vec![station]).unwrap();
+
+        let server = TestServer::start(move |request| match request.path.as_str() {
+            "/json/stations/search?name=test" => {
+                TestResponse::new(json.clone()).header("Content-Type", "application/json")
+            }
+            _ => TestResponse::new("<html>Not Found</html>").status(404),
+        });
+
+        let url = server.url("/json/stations/search?name=test");
+        let request = HTTP_CLIENT.get(url).build().unwrap();
+        let stations: Vec<StationMetadata> = block_on(send_request_compat(request)).unwrap();
+
+        assert_eq!(stations.len(), 1);
+        assert_eq!(stations[0].name, "Test Radio");
+        assert_eq!(
+            stations[0].url.as_ref().unwrap().as_str(),
+            "http://example.org/stream"
+        );
+        assert_eq!(stations[0].votes, 42);
+        assert!(stations[0].is_online());
+
+        // Error pages of the server aren't JSON
+        let url = server.url("/json/stations/search?name=other");
+        let request = HTTP_CLIENT.get(url).build().unwrap();
+        let result = block_on(send_request_compat::<Vec<StationMetadata>>(request));
+        assert!(matches!(result, Err(Error::Deserializer(_))));
+    }
+}
@@ -14,38 +14,178 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::sync::LazyLock;
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::time::{Duration, SystemTime};
 
 use anyhow::{Error, Result};
 use async_channel::Sender;
 use async_compat::CompatExt;
-use futures_util::StreamExt;
+use futures_lite::AsyncWriteExt;
 use gdk::RGBA;
 use glycin::Loader;
 use glycin_gtk4;
 use glib::clone;
+use gtk::gdk_pixbuf::Pixbuf;
 use gtk::gio::{Cancelled, File};
 use gtk::graphene::Rect;
 use gtk::prelude::TextureExt;
 use gtk::prelude::*;
 use gtk::{gdk, gio, glib, gsk};
+use indexmap::IndexMap;
+use reqwest::{header, StatusCode};
 use url::Url;
 
+use crate::api::client::HTTP_CLIENT;
+use crate::settings::{settings_manager, Key};
 use crate::{config, path};
 
 struct RenderNodeSend(pub gsk::RenderNode);
 
 unsafe impl Send for RenderNodeSend {}
 
-static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
-    reqwest::ClientBuilder::new()
-        .timeout(Duration::from_secs(5))
-        .build()
+/// Covers are not worth waiting for long
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a failed cover download is remembered before it gets retried
+const FAILURE_TTL: Duration = Duration::from_secs(86400);
+
+/// Maximum memory used by decoded cover textures kept in memory
+const MAX_TEXTURE_MEMORY: usize = 64 * 1024 * 1024;
+
+/// How long a cached cover is used before it gets revalidated
+const COVER_TTL: Duration = Duration::from_secs(86400 * 7);
+
+/// HTTP validators of a cached cover, stored as cache entry metadata
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+struct CoverValidators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl CoverValidators {
+    fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+fn texture_size(texture: &gdk::Texture) -> usize {
+    // Covers are rendered as RGBA textures
+    texture.width() as usize * texture.height() as usize * 4
+}
+
+/// Image formats which need special handling, everything else gets loaded with Glycin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CoverFormat {
+    Svg,
+    Ico,
+    Other,
+}
+
+impl CoverFormat {
+    fn detect(data: &[u8]) -> Self {
+        if data.starts_with(&[0, 0, 1, 0]) {
+            return Self::Ico;
+        }
+
+        // SVGs can start with a BOM, an XML declaration, comments or a doctype
+        let head = String::from_utf8_lossy(&data[..data.len().min(1024)]).to_lowercase();
+        let head = head.trim_start_matches('\u{feff}').trim_start();
+        if head.starts_with("<svg")
+            || (head.starts_with('<') && head.contains("<svg") && !head.contains("<html"))
+        {
+            return Self::Svg;
+        }
+
+        Self::Other
+    }
+}
+
+/// Returns the frame of an ICO file with the highest resolution and color depth,
+/// either as PNG or as ICO file which only contains that frame
+fn ico_best_frame(data: &[u8]) -> Result<Vec<u8>> {
+    let u16_at = |pos: usize| Some(u16::from_le_bytes(data.get(pos..pos + 2)?.try_into().ok()?));
+    let u32_at = |pos: usize| Some(u32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?));
+    let invalid = || Error::msg("invalid ICO file");
+
+    let count = u16_at(4).ok_or_else(invalid)? as usize;
+    let entry = (0..count)
+        .map(|i| 6 + i * 16)
+        .filter(|entry| data.len() >= entry + 16)
+        // A width of 0 means 256 pixels
+        .max_by_key(|&entry| {
+            let width = match data[entry] {
+                0 => 256,
+                width => width as u32,
+            };
+            (width, u16_at(entry + 6).unwrap_or_default())
+        })
+        .ok_or_else(invalid)?;
+
+    let size = u32_at(entry + 8).ok_or_else(invalid)? as usize;
+    let offset = u32_at(entry + 12).ok_or_else(invalid)? as usize;
+    let frame = data.get(offset..offset + size).ok_or_else(invalid)?;
+
+    // Newer ICO files embed the larger frames as PNG
+    if frame.starts_with(b"\x89PNG") {
+        return Ok(frame.to_vec());
+    }
+
+    let mut ico = Vec::with_capacity(22 + frame.len());
+    ico.extend_from_slice(&[0, 0, 1, 0, 1, 0]);
+    ico.extend_from_slice(&data[entry..entry + 12]);
+    ico.extend_from_slice(&22u32.to_le_bytes());
+    ico.extend_from_slice(frame);
+
+    Ok(ico)
+}
+
+/// Decodes a downloaded cover. SVGs get rasterized at `size`, or their intrinsic size if
+/// not set, so that they don't end up blurry after scaling.
+async fn decode_cover(
+    file: &gio::File,
+    data: &glib::Bytes,
+    size: Option<i32>,
+) -> Result<gdk::Texture> {
+    let format = CoverFormat::detect(data);
+    let data = match format {
+        CoverFormat::Svg => data.clone(),
+        CoverFormat::Ico => glib::Bytes::from_owned(ico_best_frame(data)?),
+        CoverFormat::Other => {
+            let image = Loader::new(file).load()?;
+            let frame = image.next_frame_future().await?;
+            return Ok(glycin_gtk4::frame_get_texture(&frame));
+        }
+    };
+
+    let stream = gio::MemoryInputStream::from_bytes(&data);
+    let pixbuf = match size {
+        Some(size) if format == CoverFormat::Svg => {
+            Pixbuf::from_stream_at_scale_future(&stream, size, size, true).await?
+        }
+        _ => Pixbuf::from_stream_future(&stream).await?,
+    };
+
+    Ok(gdk::Texture::for_pixbuf(&pixbuf))
+}
+
+fn entry_age(time: u128) -> Duration {
+    let now = SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
-});
+        .as_millis();
+
+    Duration::from_millis(now.saturating_sub(time).try_into().unwrap_or_default())
+}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum CoverPriority {
+    Offscreen,
+    Mapped,
+    Visible,
+}
+
+#[derive(Clone)]
 struct CoverRequest {
     favicon_url: Url,
     size: i32,
@@ -53,9 +193,31 @@ struct CoverRequest {
     cancellable: gio::Cancellable,
     tmp_file: gio::File,
     tmp_stream: gio::FileIOStream,
+    widget: glib::WeakRef<gtk::Widget>,
 }
 
 impl CoverRequest {
+    fn priority(&self) -> CoverPriority {
+        let Some(widget) = self.widget.upgrade() else {
+            return CoverPriority::Offscreen;
+        };
+
+        if !widget.is_mapped() {
+            return CoverPriority::Offscreen;
+        }
+
+        // Check whether the widget is inside the visible area of the scrolled window
+        let Some(viewport) = widget.ancestor(gtk::ScrolledWindow::static_type()) else {
+            return CoverPriority::Visible;
+        };
+
+        let viewport_rect = Rect::new(0.0, 0.0, viewport.width() as f32, viewport.height() as f32);
+        match widget.compute_bounds(&viewport) {
+            Some(bounds) if bounds.intersection(&viewport_rect).is_some() => CoverPriority::Visible,
+            _ => CoverPriority::Mapped,
+        }
+    }
+
     pub async fn handle_request(self) {
         let res = gio::CancellableFuture::new(self.cover_texture(), self.cancellable.clone()).await;
         let msg = match res {
@@ -68,57 +230,160 @@ impl CoverRequest {
     }
 
     async fn cover_texture(&self) -> Result<gdk::Texture> {
-        if let Ok(texture) = self.cached_texture().await {
-            return Ok(texture);
+        let mut validators = None;
+
+        if let Ok(Some(md)) = cacache::metadata(&*path::CACHE, self.cache_key()).await {
+            if let Ok(texture) = self.cached_texture().await {
+                if entry_age(md.time) < COVER_TTL {
+                    return Ok(texture);
+                }
+
+                // The cached cover is stale, check whether it has been changed in the meantime
+                let stale_validators: CoverValidators =
+                    serde_json::from_value(md.metadata).unwrap_or_default();
+                if !stale_validators.is_empty() {
+                    match self.download_tmp_file(Some(&stale_validators)).compat().await {
+                        Ok(None) => {
+                            debug!("Cover {} has not been modified", self.favicon_url);
+                            let data = cacache::read(&*path::CACHE, self.cache_key()).await?;
+                            self.write_cache(&data, &stale_validators).await?;
+                            return Ok(texture);
+                        }
+                        Ok(Some(new_validators)) => validators = Some(new_validators),
+                        Err(err) => {
+                            // Keep using the stale cover, it's better than showing nothing
+                            debug!("Unable to revalidate cover {}: {err}", self.favicon_url);
+                            return Ok(texture);
+                        }
+                    }
+                }
+            }
         }
 
-        self.compute_texture().await
+        // Don't try again to load covers which recently failed, to avoid stalling on known-bad urls
+        if validators.is_none() && self.is_known_failure().await {
+            return Err(Error::msg("cover recently failed to load"));
+        }
+
+        let res = self.compute_texture(validators).await;
+        if let Err(err) = &res {
+            debug!("Unable to load cover {}: {err}", self.favicon_url);
+
+            // Failures while being offline are only temporary
+            if gio::NetworkMonitor::default().is_network_available() {
+                self.store_failure().await;
+            }
+        }
+
+        res
+    }
+
+    async fn is_known_failure(&self) -> bool {
+        let key = format!("failure:{}", self.favicon_url);
+        let Ok(Some(md)) = cacache::metadata(&*path::CACHE, &key).await else {
+            return false;
+        };
+
+        if entry_age(md.time) > FAILURE_TTL {
+            let _ = cacache::remove(&*path::CACHE, &key).await;
+            return false;
+        }
+
+        true
+    }
+
+    async fn store_failure(&self) {
+        let key = format!("failure:{}", self.favicon_url);
+        if let Err(err) = cacache::write(&*path::CACHE, key, b"").await {
+            warn!("Unable to cache cover failure: {err}");
+        }
+    }
+
+    fn cache_key(&self) -> String {
+        format!("{}@{}", self.favicon_url, self.size)
     }
 
     async fn cached_texture(&self) -> Result<gdk::Texture> {
-        let key = format!("{}@{}", self.favicon_url, self.size);
-        let data = cacache::read(&*path::CACHE, key).await?;
+        let data = cacache::read(&*path::CACHE, self.cache_key()).await?;
         let bytes = glib::Bytes::from_owned(data);
 
         Ok(gdk::Texture::from_bytes(&bytes)?)
     }
 
-    async fn compute_texture(&self) -> Result<gdk::Texture> {
-        let (cover_texture, cover_bytes) = self.cover_bytes().await?;
+    async fn write_cache(&self, data: &[u8], validators: &CoverValidators) -> Result<()> {
+        let mut writer = cacache::WriteOpts::new()
+            .algorithm(cacache::Algorithm::Xxh3)
+            .metadata(serde_json::to_value(validators)?)
+            .open(&*path::CACHE, self.cache_key())
+            .await?;
+        writer.write_all(data).await?;
+        writer.commit().await?;
 
-        let key = format!("{}@{}", self.favicon_url, self.size);
-        cacache::write(&*path::CACHE, key, &cover_bytes).await?;
+        Ok(())
+    }
+
+    async fn compute_texture(&self, validators: Option<CoverValidators>) -> Result<gdk::Texture> {
+        // The cover might have been already downloaded while revalidating it
+        let validators = if let Some(validators) = validators {
+            validators
+        } else {
+            self.download_tmp_file(None)
+                .compat()
+                .await?
+                .unwrap_or_default()
+        };
+
+        let (cover_texture, cover_bytes) = self.cover_bytes().await?;
+        self.write_cache(&cover_bytes, &validators).await?;
 
         Ok(cover_texture)
     }
 
     async fn cover_bytes(&self) -> Result<(gdk::Texture, Vec<u8>)> {
-        self.download_tmp_file().compat().await?;
-
-        let loader = Loader::new(&self.tmp_file);
-        let image = loader.load()?;
-        let frame = image.next_frame_future().await?;
-        let texture = glycin_gtk4::frame_get_texture(&frame);
+        let (data, _) = self.tmp_file.load_bytes_future().await?;
+        let texture = decode_cover(&self.tmp_file, &data, Some(self.size)).await?;
 
         let snapshot = gtk::Snapshot::new();
         snapshot_thumbnail(&snapshot, texture, self.size as f32);
-        let node = RenderNodeSend(snapshot.to_node().unwrap());
+        let node = snapshot.to_node().unwrap();
+
+        // Prefer the renderer of the window, which has to be used from the main thread.
+        // Fall back to software rendering in a separate thread if not available.
+        let cover_texture = if let Some(texture) = self.render_native(&node) {
+            texture
+        } else {
+            let node = RenderNodeSend(node);
+            let handle = gio::spawn_blocking(clone!(
+                #[strong(rename_to = size)]
+                self.size,
+                move || Self::render_cairo(size, node)
+            ));
+            handle.await.unwrap()
+        };
 
         let handle = gio::spawn_blocking(clone!(
-            #[strong(rename_to = size)]
-            self.size,
-            move || Self::render(size, node)
+            #[strong]
+            cover_texture,
+            move || cover_texture.save_to_png_bytes().to_vec()
         ));
-        let (cover_texture, cover_bytes) = handle.await.unwrap()?;
-
-        let key = format!("{}@{}", self.favicon_url, self.size);
-        cacache::write_with_algo(cacache::Algorithm::Xxh3, &*path::CACHE, key, &cover_bytes)
-            .await?;
+        let cover_bytes = handle.await.unwrap();
 
         Ok((cover_texture, cover_bytes))
     }
 
-    fn render(size: i32, node: RenderNodeSend) -> Result<(gdk::Texture, Vec<u8>)> {
+    /// Renders the node with the renderer GTK picked for the window of the widget,
+    /// which works regardless of whether it's GL, Vulkan or Cairo based
+    fn render_native(&self, node: &gsk::RenderNode) -> Option<gdk::Texture> {
+        let renderer = self.widget.upgrade()?.native()?.renderer()?;
+        if !renderer.is_realized() {
+            return None;
+        }
+
+        let rect = Rect::new(0.0, 0.0, self.size as f32, self.size as f32);
+        Some(renderer.render_texture(node, Some(&rect)))
+    }
+
+    fn render_cairo(size: i32, node: RenderNodeSend) -> gdk::Texture {
         let renderer = gsk::CairoRenderer::new();
         let display = gdk::Display::default().expect("No default display available");
         renderer
@@ -129,13 +394,45 @@ impl CoverRequest {
         let texture = renderer.render_texture(node.0, Some(&rect));
         renderer.unrealize();
 
-        let png_bytes = texture.save_to_png_bytes().to_vec();
-        Ok((texture, png_bytes))
+        texture
     }
 
-    async fn download_tmp_file(&self) -> Result<()> {
-        let request = HTTP_CLIENT.get(self.favicon_url.as_str()).build()?;
-        let response = HTTP_CLIENT.execute(request).await?;
+    /// Downloads the cover into the temporary file. Returns `None` if the cover
+    /// has not been modified since the passed validators were retrieved.
+    async fn download_tmp_file(
+        &self,
+        validators: Option<&CoverValidators>,
+    ) -> Result<Option<CoverValidators>> {
+        let mut request = HTTP_CLIENT
+            .get(self.favicon_url.as_str())
+            .timeout(REQUEST_TIMEOUT);
+        if let Some(validators) = validators {
+            if let Some(etag) = &validators.etag {
+                request = request.header(header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &validators.last_modified {
+                request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = HTTP_CLIENT.execute(request.build()?).await?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+
+        let response = response.error_for_status()?;
+        let header_value = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let new_validators = CoverValidators {
+            etag: header_value(header::ETAG),
+            last_modified: header_value(header::LAST_MODIFIED),
+        };
+
         let body_bytes = response.bytes().await?;
 
         // We have to write the data to the disk in order to be able to load them using Glycin
@@ -147,7 +444,7 @@ impl CoverRequest {
             .write_bytes_future(&bytes, glib::Priority::LOW)
             .await?;
 
-        Ok(())
+        Ok(Some(new_validators))
     }
 
     async fn delete_tmp_file(&self) -> Result<()> {
@@ -158,23 +455,119 @@ impl CoverRequest {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct CoverLoader {
-    request_sender: Sender<CoverRequest>,
+    queue: Rc<RefCell<Vec<CoverRequest>>>,
+    queue_size: usize,
+    wakeup_sender: Sender<()>,
+    textures: Rc<RefCell<IndexMap<String, gdk::Texture>>>,
 }
 
 impl CoverLoader {
     pub fn new() -> Self {
-        let (request_sender, request_receiver) = async_channel::unbounded::<CoverRequest>();
-        let request_stream = request_receiver
-            .map(|r| r.handle_request())
-            .buffer_unordered(usize::max(glib::num_processors() as usize / 2, 2));
+        // A queue size of 0 means that the queue is unbounded
+        let queue_size = settings_manager::integer(Key::CoverLoaderQueueSize).max(0) as usize;
 
-        glib::spawn_future_local(async move {
-            request_stream.collect::<Vec<_>>().await;
-        });
+        // A concurrency of 0 means that it gets determined by the number of processors
+        let concurrency = settings_manager::integer(Key::CoverLoaderConcurrency);
+        let concurrency = if concurrency > 0 {
+            concurrency as usize
+        } else {
+            usize::max(glib::num_processors() as usize / 2, 2)
+        };
+        debug!("Loading covers with concurrency of {concurrency}, queue size {queue_size}");
+
+        let queue: Rc<RefCell<Vec<CoverRequest>>> = Rc::default();
+        let (wakeup_sender, wakeup_receiver) = async_channel::unbounded::<()>();
+
+        for _ in 0..concurrency {
+            glib::spawn_future_local(clone!(
+                #[strong]
+                queue,
+                #[strong]
+                wakeup_receiver,
+                async move {
+                    loop {
+                        let request = Self::next_request(&queue);
+                        if let Some(request) = request {
+                            request.handle_request().await;
+                        } else if wakeup_receiver.recv().await.is_err() {
+                            break;
+                        }
+                    }
+                }
+            ));
+        }
+
+        Self {
+            queue,
+            queue_size,
+            wakeup_sender,
+            textures: Rc::default(),
+        }
+    }
+
+    /// Returns the number of decoded cover textures kept in memory, and their estimated size in bytes
+    pub fn memory_usage(&self) -> (usize, usize) {
+        let textures = self.textures.borrow();
+        let bytes = textures.values().map(texture_size).sum();
+        (textures.len(), bytes)
+    }
+
+    /// Returns the size of the cover disk cache in bytes
+    pub async fn disk_usage(&self) -> usize {
+        gio::spawn_blocking(|| {
+            cacache::list_sync(&*path::CACHE)
+                .flatten()
+                .map(|md| md.size)
+                .sum()
+        })
+        .await
+        .unwrap_or_default()
+    }
+
+    fn cached_texture(&self, key: &str) -> Option<gdk::Texture> {
+        let mut textures = self.textures.borrow_mut();
+
+        // Move texture to the end, so that it's the most recently used one
+        let texture = textures.shift_remove(key)?;
+        textures.insert(key.to_string(), texture.clone());
+
+        Some(texture)
+    }
+
+    fn insert_texture(&self, key: String, texture: gdk::Texture) {
+        let mut textures = self.textures.borrow_mut();
+        textures.insert(key, texture);
+
+        // Evict least recently used textures
+        let mut bytes: usize = textures.values().map(texture_size).sum();
+        while bytes > MAX_TEXTURE_MEMORY && textures.len() > 1 {
+            if let Some((_, texture)) = textures.shift_remove_index(0) {
+                bytes -= texture_size(&texture);
+            }
+        }
+    }
 
-        Self { request_sender }
+    /// Returns the pending request which should be handled next. Cancelled requests come first
+    /// since they can be finished immediately, followed by the covers which are currently visible.
+    fn next_request(queue: &Rc<RefCell<Vec<CoverRequest>>>) -> Option<CoverRequest> {
+        let mut queue = queue.borrow_mut();
+
+        let index = queue
+            .iter()
+            .position(|r| r.cancellable.is_cancelled())
+            .or_else(|| {
+                // `max_by_key` returns the last max element, so reverse to keep the queue order
+                queue
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .max_by_key(|(_, r)| r.priority())
+                    .map(|(i, _)| i)
+            })?;
+
+        Some(queue.remove(index))
     }
 
     pub async fn prune_cache(&self) {
@@ -184,27 +577,93 @@ impl CoverLoader {
         let _ = std::fs::remove_dir_all(&path);
 
         // Remove cached covers which are older > 30 days
-        let ttl = Duration::from_secs(86400 * 30);
-        for md in cacache::list_sync(&*path::CACHE).flatten() {
-            let now = SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_millis();
-
-            let age = Duration::from_millis((now - md.time).try_into().unwrap_or_default());
-            if age > ttl {
-                let _ = cacache::remove_hash_sync(&*path::CACHE, &md.integrity);
-                let _ = cacache::remove_sync(&*path::CACHE, &md.key);
-            }
+        Self::remove_cache_entries(Duration::from_secs(86400 * 30)).await;
+    }
+
+    /// Removes all covers from the disk cache
+    pub async fn clear_cache(&self) {
+        Self::remove_cache_entries(Duration::ZERO).await;
+    }
+
+    /// Removes everything stored in the cache directory, and drops the covers kept in memory
+    pub async fn purge_cache(&self) {
+        self.textures.borrow_mut().clear();
+
+        let res = gio::spawn_blocking(|| {
+            std::fs::remove_dir_all(&*path::CACHE)?;
+            std::fs::create_dir_all(&*path::CACHE)
+        })
+        .await;
+
+        match res {
+            Ok(Err(err)) => warn!("Unable to purge cache: {err}"),
+            Err(_) => warn!("Unable to purge cache"),
+            _ => (),
         }
     }
 
+    /// Forgets that loading the cover failed recently, so that it gets downloaded again
+    pub async fn forget_failure(&self, favicon_url: &Url) {
+        let key = format!("failure:{favicon_url}");
+        let _ = cacache::remove(&*path::CACHE, &key).await;
+    }
+
+    /// Downloads the cover in its original size, without caching or cropping it
+    pub async fn load_original_cover(&self, favicon_url: &Url) -> Result<gdk::Texture> {
+        let body_bytes = async {
+            let response = HTTP_CLIENT
+                .get(favicon_url.as_str())
+                .timeout(REQUEST_TIMEOUT)
+                .send()
+                .await?;
+            response.error_for_status()?.bytes().await
+        }
+        .compat()
+        .await?;
+
+        // Glycin is only able to load images from files
+        let (tmp_file, tmp_stream) = File::new_tmp_future(
+            Some(&format!("{}-Cover-XXXXXX", config::NAME)),
+            glib::Priority::LOW,
+        )
+        .await?;
+        let bytes = glib::Bytes::from_owned(body_bytes);
+        tmp_stream
+            .output_stream()
+            .write_bytes_future(&bytes, glib::Priority::LOW)
+            .await?;
+        tmp_stream.close_future(glib::Priority::LOW).await?;
+
+        let res = decode_cover(&tmp_file, &bytes, None).await;
+
+        let _ = tmp_file.delete_future(glib::Priority::LOW).await;
+        res
+    }
+
+    async fn remove_cache_entries(max_age: Duration) {
+        let _ = gio::spawn_blocking(move || {
+            for md in cacache::list_sync(&*path::CACHE).flatten() {
+                if entry_age(md.time) >= max_age {
+                    let _ = cacache::remove_hash_sync(&*path::CACHE, &md.integrity);
+                    let _ = cacache::remove_sync(&*path::CACHE, &md.key);
+                }
+            }
+        })
+        .await;
+    }
+
     pub async fn load_cover(
         &mut self,
         favicon_url: &Url,
         size: i32,
         cancellable: gio::Cancellable,
+        widget: &impl IsA<gtk::Widget>,
     ) -> Result<gdk::Texture> {
+        let key = format!("{favicon_url}@{size}");
+        if let Some(texture) = self.cached_texture(&key) {
+            return Ok(texture);
+        }
+
         let (sender, receiver) = async_channel::bounded(1);
 
         let (tmp_file, tmp_stream) = File::new_tmp_future(
@@ -220,13 +679,51 @@ impl CoverLoader {
             cancellable: cancellable.clone(),
             tmp_file,
             tmp_stream,
+            widget: widget.upcast_ref::<gtk::Widget>().downgrade(),
         };
-        self.request_sender
-            .send(request)
+
+        {
+            let mut queue = self.queue.borrow_mut();
+
+            // Make room by dropping the least important pending request. Cancelled requests
+            // don't count, they get finished right away anyway.
+            let pending = queue
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| !r.cancellable.is_cancelled());
+            if self.queue_size > 0 && pending.clone().count() >= self.queue_size {
+                let least_important = pending
+                    .min_by_key(|(_, r)| r.priority())
+                    .map(|(i, r)| (i, r.priority()));
+
+                match least_important {
+                    Some((index, priority)) if priority < request.priority() => {
+                        let dropped = queue.remove(index);
+                        debug!("Cover queue is full, dropping {}", dropped.favicon_url);
+                        dropped.cancellable.cancel();
+                        glib::spawn_future_local(dropped.handle_request());
+                    }
+                    _ => {
+                        drop(queue);
+                        debug!("Cover queue is full, dropping {}", request.favicon_url);
+                        let _ = request.delete_tmp_file().await;
+                        return Err(Error::msg("Cover queue is full"));
+                    }
+                }
+            }
+
+            queue.push(request);
+        }
+
+        self.wakeup_sender
+            .send(())
             .await
             .map_err(|_| Error::msg("Unable to send cover request"))?;
 
-        receiver.recv().await?
+        let texture = receiver.recv().await??;
+        self.insert_texture(key, texture.clone());
+
+        Ok(texture)
     }
 }
 
@@ -34,6 +34,15 @@ pub enum Error {
     #[error("Network error: {0}")]
     Network(#[from] Rc<reqwest::Error>),
 
+    #[error("Keyring error: {0}")]
+    Keyring(#[from] Rc<oo7::Error>),
+
+    #[error("GStreamer pipeline error: {0}")]
+    Pipeline(String),
+
+    #[error("Stream doesn't deliver any data")]
+    NoStreamData,
+
     #[error("Unsupported url scheme")]
     UnsupportedUrlScheme,
 
@@ -0,0 +1,37 @@
+// Shortwave - language.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use inflector::Inflector;
+
+use crate::iso_codes;
+
+/// A language which is used by radio-browser stations
+#[derive(Default, Debug, Clone, PartialEq, Eq, serde_derive::Deserialize)]
+pub struct Language {
+    pub name: String,
+    pub iso_639: Option<String>,
+    pub stationcount: u32,
+}
+
+impl Language {
+    /// Language name in the user's language, falls back to the name provided by radio-browser
+    pub fn localized_name(&self) -> String {
+        self.iso_639
+            .as_deref()
+            .and_then(iso_codes::language_name)
+            .unwrap_or_else(|| self.name.to_title_case())
+    }
+}
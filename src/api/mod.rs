@@ -15,24 +15,38 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 static STATION_SEARCH: &str = "json/stations/search";
+static STATION_BY_UUID: &str = "json/stations/byuuid";
+static STATION_BY_URL: &str = "json/stations/byurl";
+static LANGUAGES: &str = "json/languages";
 static STATS: &str = "json/stats";
+static ACOUSTID_LOOKUP: &str = "https://api.acoustid.org/v2/lookup";
 
+mod acoustid;
 pub mod client;
 mod cover_loader;
 mod error;
-mod http;
+mod language;
+mod playlist_entry;
 mod station;
 mod station_metadata;
 mod station_model;
 mod station_request;
+mod station_section_sorter;
 mod station_sorter;
 mod stats;
+mod stream_info;
 
+pub use acoustid::{AcoustIdArtist, AcoustIdRecording, AcoustIdResponse, AcoustIdResult};
+pub use client::{request_stats, RequestStats};
 pub use cover_loader::CoverLoader;
 pub use error::Error;
+pub use language::Language;
+pub use playlist_entry::PlaylistEntry;
 pub use station::SwStation;
 pub use station_metadata::StationMetadata;
 pub use station_model::SwStationModel;
 pub use station_request::StationRequest;
+pub use station_section_sorter::SwStationSectionSorter;
 pub use station_sorter::{SwStationSorter, SwStationSorting, SwStationSortingType};
 pub use stats::Stats;
+pub use stream_info::StreamInfo;
@@ -0,0 +1,117 @@
+// Shortwave - playlist_entry.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use url::Url;
+
+/// A stream from a playlist file, e.g. M3U / PLS or a plain list of URLs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistEntry {
+    pub name: Option<String>,
+    pub url: Url,
+}
+
+impl PlaylistEntry {
+    /// Parses all streams of a playlist. Lines which are neither a stream URL nor
+    /// playlist metadata get ignored, as well as duplicated URLs.
+    pub fn parse_all(text: &str) -> Vec<Self> {
+        let is_pls = text
+            .lines()
+            .any(|line| line.trim().eq_ignore_ascii_case("[playlist]"));
+
+        let entries = if is_pls {
+            Self::parse_pls(text)
+        } else {
+            Self::parse_m3u(text)
+        };
+
+        let mut urls = Vec::new();
+        entries
+            .into_iter()
+            .filter(|entry| {
+                if urls.contains(&entry.url) {
+                    false
+                } else {
+                    urls.push(entry.url.clone());
+                    true
+                }
+            })
+            .collect()
+    }
+
+    /// M3U playlists, which also covers plain lists with one URL per line
+    fn parse_m3u(text: &str) -> Vec<Self> {
+        let mut entries = Vec::new();
+        let mut name = None;
+
+        for line in text.lines().map(str::trim) {
+            if let Some(info) = line.strip_prefix("#EXTINF:") {
+                // The title follows the duration and attributes, e.g. "-1 tvg-id="",Radio"
+                name = info
+                    .split_once(',')
+                    .map(|(_, title)| title.trim().to_string())
+                    .filter(|title| !title.is_empty());
+            } else if line.is_empty() || line.starts_with('#') {
+                continue;
+            } else if let Some(url) = Self::stream_url(line) {
+                entries.push(Self {
+                    name: name.take(),
+                    url,
+                });
+            }
+        }
+
+        entries
+    }
+
+    /// PLS playlists with numbered `FileN` and `TitleN` keys
+    fn parse_pls(text: &str) -> Vec<Self> {
+        let mut files = Vec::new();
+        let mut titles = HashMap::new();
+
+        for line in text.lines().map(str::trim) {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+
+            if let Some(number) = key.strip_prefix("file") {
+                if let Some(url) = Self::stream_url(value) {
+                    files.push((number.to_string(), url));
+                }
+            } else if let Some(number) = key.strip_prefix("title") {
+                if !value.is_empty() {
+                    titles.insert(number.to_string(), value.to_string());
+                }
+            }
+        }
+
+        files
+            .into_iter()
+            .map(|(number, url)| Self {
+                name: titles.remove(&number),
+                url,
+            })
+            .collect()
+    }
+
+    fn stream_url(text: &str) -> Option<Url> {
+        let url = Url::parse(text).ok()?;
+        matches!(url.scheme(), "http" | "https").then_some(url)
+    }
+}
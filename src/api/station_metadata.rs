@@ -22,6 +22,8 @@ use inflector::Inflector;
 use serde::{Deserialize, Deserializer, Serializer};
 use url::Url;
 
+use crate::iso_codes;
+
 #[derive(glib::Boxed, Default, Debug, Clone, Serialize, Deserialize)]
 #[boxed_type(name = "SwStationMetadata")]
 pub struct StationMetadata {
@@ -69,6 +71,12 @@ pub struct StationMetadata {
 }
 
 impl StationMetadata {
+    /// Whether the stream was reachable during the last check of radio-browser.
+    /// Always `false` for local stations, which don't get checked.
+    pub fn is_online(&self) -> bool {
+        self.lastcheckok == 1
+    }
+
     pub fn formatted_tags(&self) -> String {
         let tags = self.tags.split(',');
         let mut formatted = String::new();
@@ -77,6 +85,26 @@ impl StationMetadata {
         }
         formatted.split_at(2).1.to_string()
     }
+
+    /// Country name in the user's language, falls back to the name provided by radio-browser
+    pub fn localized_country(&self) -> String {
+        iso_codes::country_name(&self.countrycode).unwrap_or_else(|| self.country.to_title_case())
+    }
+
+    /// Language names in the user's language, falls back to the names provided by radio-browser
+    pub fn localized_language(&self) -> String {
+        let names: Option<Vec<String>> = self
+            .languagecodes
+            .split(',')
+            .filter(|code| !code.trim().is_empty())
+            .map(iso_codes::language_name)
+            .collect();
+
+        match names {
+            Some(names) if !names.is_empty() => names.join(", "),
+            _ => self.language.to_title_case(),
+        }
+    }
 }
 
 impl StationMetadata {
@@ -15,6 +15,7 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use std::cell::RefCell;
+use std::collections::HashSet;
 
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
@@ -84,6 +85,63 @@ impl SwStationModel {
         self.items_changed(pos, 0, added);
     }
 
+    /// Replaces the model content with the passed stations, but only applies the minimal
+    /// required changes, so that already existing stations don't have to be recreated.
+    pub fn set_stations(&self, mut stations: Vec<SwStation>) {
+        let imp = self.imp();
+
+        // radio-browser can return the same station more than once
+        let mut new_uuids = HashSet::new();
+        stations.retain(|s| new_uuids.insert(s.uuid()));
+
+        // Remove stations which are no longer included
+        let mut pos = 0;
+        while pos < self.n_items() {
+            let (start, removed) = {
+                let mut map = imp.map.borrow_mut();
+                let start = pos as usize;
+                let mut end = start;
+
+                while end < map.len() && !new_uuids.contains(map.get_index(end).unwrap().0) {
+                    end += 1;
+                }
+
+                map.drain(start..end);
+                (start as u32, (end - start) as u32)
+            };
+
+            if removed > 0 {
+                self.items_changed(start, removed, 0);
+            }
+            pos = start + 1;
+        }
+
+        // Insert new stations or move existing ones to match the new order
+        for (pos, station) in stations.into_iter().enumerate() {
+            let uuid = station.uuid();
+            let current_pos = imp.map.borrow().get_index_of(&uuid);
+
+            match current_pos {
+                Some(current_pos) if current_pos == pos => continue,
+                Some(current_pos) => {
+                    // Everything before `pos` already matches, so the station can only
+                    // move towards the front. Report the move as a single change.
+                    {
+                        imp.map.borrow_mut().move_index(current_pos, pos);
+                    }
+                    let changed = (current_pos - pos + 1) as u32;
+                    self.items_changed(pos as u32, changed, changed);
+                }
+                None => {
+                    {
+                        imp.map.borrow_mut().shift_insert(pos, uuid, station);
+                    }
+                    self.items_changed(pos as u32, 0, 1);
+                }
+            }
+        }
+    }
+
     pub fn remove_station(&self, station: &SwStation) {
         let imp = self.imp();
         let pos = { imp.map.borrow().get_index_of(&station.uuid()) };
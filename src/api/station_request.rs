@@ -27,6 +27,7 @@ pub struct StationRequest {
     pub language_exact: Option<bool>,
     pub tag: Option<String>,
     pub tag_exact: Option<bool>,
+    pub codec: Option<String>,
     pub bitrate_min: Option<u32>,
     pub bitrate_max: Option<u32>,
     pub has_geo_info: Option<bool>,
@@ -69,6 +70,7 @@ impl Default for StationRequest {
             language_exact: None,
             tag: None,
             tag_exact: None,
+            codec: None,
             bitrate_min: None,
             bitrate_max: None,
             has_geo_info: None,
@@ -0,0 +1,239 @@
+// Shortwave - station_section_sorter.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::Cell;
+
+use glib::Properties;
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::api::{SwStation, SwStationSorting, SwStationSortingType};
+use crate::i18n::i18n;
+
+mod imp {
+    use super::*;
+
+    /// Groups stations into sections by the value they get sorted by, to be used as
+    /// section sorter of a [`gtk::SortListModel`], which then provides the sections
+    /// through [`gtk::SectionModel`].
+    #[derive(Debug, Default, Properties)]
+    #[properties(wrapper_type = super::SwStationSectionSorter)]
+    pub struct SwStationSectionSorter {
+        #[property(get, set=Self::set_sorting, builder(SwStationSorting::default()))]
+        pub sorting: Cell<SwStationSorting>,
+        #[property(get, set=Self::set_sorting_type, builder(SwStationSortingType::Ascending))]
+        pub sorting_type: Cell<SwStationSortingType>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwStationSectionSorter {
+        const NAME: &'static str = "SwStationSectionSorter";
+        type Type = super::SwStationSectionSorter;
+        type ParentType = gtk::Sorter;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for SwStationSectionSorter {}
+
+    impl SorterImpl for SwStationSectionSorter {
+        fn order(&self) -> gtk::SorterOrder {
+            if super::is_grouped(self.sorting.get()) {
+                gtk::SorterOrder::Partial
+            } else {
+                gtk::SorterOrder::None
+            }
+        }
+
+        fn compare(&self, item1: &glib::Object, item2: &glib::Object) -> gtk::Ordering {
+            let a = item1.downcast_ref::<SwStation>().unwrap();
+            let b = item2.downcast_ref::<SwStation>().unwrap();
+
+            let sorting = self.sorting.get();
+            let (Some(a), Some(b)) = (
+                super::section_key(a, sorting),
+                super::section_key(b, sorting),
+            ) else {
+                return gtk::Ordering::Equal;
+            };
+
+            // Stations without a value are always grouped last
+            let ordering = match (a.is_empty(), b.is_empty()) {
+                (true, false) => std::cmp::Ordering::Greater,
+                (false, true) => std::cmp::Ordering::Less,
+                _ if self.sorting_type.get() == SwStationSortingType::Descending => b.cmp(&a),
+                _ => a.cmp(&b),
+            };
+
+            ordering.into()
+        }
+    }
+
+    impl SwStationSectionSorter {
+        fn set_sorting(&self, sorting: SwStationSorting) {
+            self.sorting.set(sorting);
+            self.obj().changed(gtk::SorterChange::Different);
+        }
+
+        fn set_sorting_type(&self, sorting_type: SwStationSortingType) {
+            self.sorting_type.set(sorting_type);
+            self.obj().changed(gtk::SorterChange::Different);
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct SwStationSectionSorter(ObjectSubclass<imp::SwStationSectionSorter>) @extends gtk::Sorter;
+}
+
+impl SwStationSectionSorter {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// Whether the stations currently get grouped into sections
+    pub fn is_grouped(&self) -> bool {
+        is_grouped(self.sorting())
+    }
+
+    /// Title of the section which starts with `station`, used for the section headers
+    pub fn section_title(&self, station: &SwStation) -> String {
+        let metadata = station.metadata();
+        let title = match self.sorting() {
+            SwStationSorting::Name => section_key(station, SwStationSorting::Name).unwrap(),
+            SwStationSorting::Country => metadata.country.trim().to_string(),
+            SwStationSorting::Language => metadata.language.trim().to_string(),
+            SwStationSorting::State => metadata.state.trim().to_string(),
+            SwStationSorting::Codec => metadata.codec.trim().to_uppercase(),
+            _ => String::new(),
+        };
+
+        if title.is_empty() {
+            i18n("Unknown")
+        } else {
+            title
+        }
+    }
+}
+
+impl Default for SwStationSectionSorter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether stations get grouped into sections with this sorting
+fn is_grouped(sorting: SwStationSorting) -> bool {
+    matches!(
+        sorting,
+        SwStationSorting::Name
+            | SwStationSorting::Country
+            | SwStationSorting::Language
+            | SwStationSorting::State
+            | SwStationSorting::Codec
+    )
+}
+
+/// Value by which stations get grouped, empty if the station has no such value.
+/// `None` if stations don't get grouped with this sorting.
+fn section_key(station: &SwStation, sorting: SwStationSorting) -> Option<String> {
+    if !is_grouped(sorting) {
+        return None;
+    }
+
+    let metadata = station.metadata();
+
+    let key = match sorting {
+        SwStationSorting::Name => {
+            let first = metadata.name.trim().chars().next();
+            match first {
+                Some(c) if c.is_alphabetic() => c.to_uppercase().collect(),
+                Some(_) => "#".into(),
+                None => String::new(),
+            }
+        }
+        SwStationSorting::Country => metadata.country.trim().to_lowercase(),
+        SwStationSorting::Language => metadata.language.trim().to_lowercase(),
+        SwStationSorting::State => metadata.state.trim().to_string(),
+        SwStationSorting::Codec => metadata.codec.trim().to_uppercase(),
+        _ => unreachable!(),
+    };
+
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::StationMetadata;
+
+    fn station(metadata: StationMetadata) -> SwStation {
+        SwStation::new("uuid", false, metadata, None)
+    }
+
+    #[test]
+    fn name_sections() {
+        let key = |name: &str| {
+            let station = station(StationMetadata {
+                name: name.into(),
+                ..Default::default()
+            });
+            section_key(&station, SwStationSorting::Name)
+        };
+
+        assert_eq!(key("  radio paradise"), Some("R".into()));
+        assert_eq!(key("Ö1"), Some("Ö".into()));
+        assert_eq!(key("1LIVE"), Some("#".into()));
+        assert_eq!(key(""), Some(String::new()));
+    }
+
+    #[test]
+    fn metadata_sections() {
+        let station = station(StationMetadata {
+            country: " Germany".into(),
+            language: "German ".into(),
+            state: "Bavaria".into(),
+            codec: "mp3".into(),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            section_key(&station, SwStationSorting::Country),
+            Some("germany".into())
+        );
+        assert_eq!(
+            section_key(&station, SwStationSorting::Language),
+            Some("german".into())
+        );
+        assert_eq!(
+            section_key(&station, SwStationSorting::State),
+            Some("Bavaria".into())
+        );
+        assert_eq!(
+            section_key(&station, SwStationSorting::Codec),
+            Some("MP3".into())
+        );
+    }
+
+    #[test]
+    fn ungrouped_sortings() {
+        let station = station(StationMetadata::default());
+
+        assert!(!is_grouped(SwStationSorting::Votes));
+        assert_eq!(section_key(&station, SwStationSorting::Default), None);
+        assert_eq!(section_key(&station, SwStationSorting::Random), None);
+    }
+}
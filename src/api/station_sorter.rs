@@ -14,7 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
 
 use glib::{Enum, Properties};
 use gtk::glib;
@@ -22,6 +24,7 @@ use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 
 use crate::api::SwStation;
+use crate::database::StationPlayStats;
 
 mod imp {
     use super::*;
@@ -33,6 +36,12 @@ mod imp {
         pub sorting: Cell<SwStationSorting>,
         #[property(get, set=Self::set_sorting_type, builder(SwStationSortingType::Ascending))]
         pub sorting_type: Cell<SwStationSortingType>,
+        /// Latitude and longitude from which the distance gets measured
+        pub location: Cell<Option<(f64, f64)>>,
+        /// Used for sorting by recently or most played stations
+        pub play_stats: RefCell<HashMap<String, StationPlayStats>>,
+        /// Changes every time random sorting gets selected, for a new order
+        pub shuffle_seed: Cell<u32>,
     }
 
     #[glib::object_subclass]
@@ -58,6 +67,9 @@ mod imp {
                 b,
                 self.sorting.get(),
                 self.sorting_type.get() == SwStationSortingType::Descending,
+                self.location.get(),
+                &self.play_stats.borrow(),
+                self.shuffle_seed.get(),
             )
             .into()
         }
@@ -66,6 +78,9 @@ mod imp {
     impl SwStationSorter {
         fn set_sorting(&self, sorting: SwStationSorting) {
             self.sorting.set(sorting);
+            if sorting == SwStationSorting::Random {
+                self.shuffle_seed.set(glib::random_int());
+            }
             self.obj().changed(gtk::SorterChange::Different);
         }
 
@@ -79,7 +94,31 @@ mod imp {
             b: &SwStation,
             sorting: SwStationSorting,
             descending: bool,
+            location: Option<(f64, f64)>,
+            play_stats: &HashMap<String, StationPlayStats>,
+            shuffle_seed: u32,
         ) -> std::cmp::Ordering {
+            match sorting {
+                SwStationSorting::Nearest => {
+                    return Self::distance_cmp(a, b, descending, location);
+                }
+                SwStationSorting::RecentlyPlayed => {
+                    let last_played =
+                        |s: &SwStation| play_stats.get(&s.uuid()).map(|p| p.last_played);
+                    return Self::play_stats_cmp(last_played(a), last_played(b), descending);
+                }
+                SwStationSorting::MostPlayed => {
+                    let duration =
+                        |s: &SwStation| play_stats.get(&s.uuid()).map(|p| p.total_duration);
+                    return Self::play_stats_cmp(duration(a), duration(b), descending);
+                }
+                SwStationSorting::LastCheck => {
+                    let last_check = |s: &SwStation| s.metadata().lastchecktime_iso8601;
+                    return Self::last_check_cmp(last_check(a), last_check(b), descending);
+                }
+                _ => (),
+            }
+
             let mut station_a = a.clone();
             let mut station_b = b.clone();
 
@@ -111,12 +150,97 @@ mod imp {
                 SwStationSorting::Votes => {
                     station_a.metadata().votes.cmp(&station_b.metadata().votes)
                 }
+                SwStationSorting::ClickCount => station_a
+                    .metadata()
+                    .clickcount
+                    .cmp(&station_b.metadata().clickcount),
                 SwStationSorting::Bitrate => station_a
                     .metadata()
                     .bitrate
                     .cmp(&station_b.metadata().bitrate),
+                SwStationSorting::Random => Self::shuffle_key(&station_a, shuffle_seed)
+                    .cmp(&Self::shuffle_key(&station_b, shuffle_seed)),
+                SwStationSorting::Nearest
+                | SwStationSorting::RecentlyPlayed
+                | SwStationSorting::MostPlayed
+                | SwStationSorting::LastCheck => unreachable!(),
+            }
+        }
+
+        /// Stations without coordinates are always sorted last
+        fn distance_cmp(
+            a: &SwStation,
+            b: &SwStation,
+            descending: bool,
+            location: Option<(f64, f64)>,
+        ) -> std::cmp::Ordering {
+            let Some(location) = location else {
+                return std::cmp::Ordering::Equal;
+            };
+
+            let distance = |station: &SwStation| {
+                let metadata = station.metadata();
+                let coordinates = (f64::from(metadata.geo_lat?), f64::from(metadata.geo_long?));
+                Some(Self::distance(location, coordinates))
+            };
+
+            match (distance(a), distance(b)) {
+                (Some(a), Some(b)) if descending => b.total_cmp(&a),
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+        }
+
+        /// The highest value comes first, unless descending. Stations which
+        /// never have been played are always sorted last.
+        fn play_stats_cmp(a: Option<i64>, b: Option<i64>, descending: bool) -> std::cmp::Ordering {
+            match (a, b) {
+                (Some(a), Some(b)) if descending => a.cmp(&b),
+                (Some(a), Some(b)) => b.cmp(&a),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+        }
+
+        /// The most recently checked stations come first, unless descending.
+        /// Stations which never have been checked, like local ones, are always sorted last.
+        fn last_check_cmp(
+            a: Option<String>,
+            b: Option<String>,
+            descending: bool,
+        ) -> std::cmp::Ordering {
+            // ISO 8601 timestamps in UTC can be compared as strings
+            match (a, b) {
+                (Some(a), Some(b)) if descending => a.cmp(&b),
+                (Some(a), Some(b)) => b.cmp(&a),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
             }
         }
+
+        /// Stable position of the station in the shuffled order
+        fn shuffle_key(station: &SwStation, seed: u32) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            seed.hash(&mut hasher);
+            station.uuid().hash(&mut hasher);
+            hasher.finish()
+        }
+
+        /// Great-circle distance between two coordinates in kilometres
+        fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+            const EARTH_RADIUS: f64 = 6371.0;
+
+            let (lat_a, long_a) = (a.0.to_radians(), a.1.to_radians());
+            let (lat_b, long_b) = (b.0.to_radians(), b.1.to_radians());
+
+            let h = ((lat_b - lat_a) / 2.0).sin().powi(2)
+                + lat_a.cos() * lat_b.cos() * ((long_b - long_a) / 2.0).sin().powi(2);
+            2.0 * EARTH_RADIUS * h.sqrt().asin()
+        }
     }
 }
 
@@ -128,6 +252,22 @@ impl SwStationSorter {
     pub fn new() -> Self {
         glib::Object::new()
     }
+
+    pub fn location(&self) -> Option<(f64, f64)> {
+        self.imp().location.get()
+    }
+
+    /// Sets the location used for sorting by distance
+    pub fn set_location(&self, location: Option<(f64, f64)>) {
+        self.imp().location.set(location);
+        self.changed(gtk::SorterChange::Different);
+    }
+
+    /// Sets the play statistics used for sorting by recently or most played stations
+    pub fn set_play_stats(&self, play_stats: HashMap<String, StationPlayStats>) {
+        *self.imp().play_stats.borrow_mut() = play_stats;
+        self.changed(gtk::SorterChange::Different);
+    }
 }
 
 impl Default for SwStationSorter {
@@ -136,7 +276,7 @@ impl Default for SwStationSorter {
     }
 }
 
-#[derive(Display, Copy, Debug, Clone, EnumString, Eq, PartialEq, Enum)]
+#[derive(Display, Copy, Debug, Clone, EnumString, Eq, PartialEq, Enum, Serialize, Deserialize)]
 #[repr(u32)]
 #[enum_type(name = "SwStationSorting")]
 #[derive(Default)]
@@ -150,9 +290,15 @@ pub enum SwStationSorting {
     Codec,
     Votes,
     Bitrate,
+    Nearest,
+    RecentlyPlayed,
+    MostPlayed,
+    ClickCount,
+    LastCheck,
+    Random,
 }
 
-#[derive(Display, Copy, Debug, Clone, EnumString, Eq, PartialEq, Enum)]
+#[derive(Display, Copy, Debug, Clone, EnumString, Eq, PartialEq, Enum, Serialize, Deserialize)]
 #[repr(u32)]
 #[enum_type(name = "SwStationSortingType")]
 #[derive(Default)]
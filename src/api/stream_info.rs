@@ -0,0 +1,82 @@
+// Shortwave - stream_info.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+use url::Url;
+
+/// Information a stream announces in its response headers, e.g. Icecast / SHOUTcast
+/// `icy-*` headers
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct StreamInfo {
+    pub name: Option<String>,
+    /// Comma separated, in the same notation as the radio-browser tags
+    pub genre: Option<String>,
+    /// Bitrate in kbit/s
+    pub bitrate: Option<i32>,
+    pub homepage: Option<Url>,
+    pub content_type: Option<String>,
+}
+
+impl StreamInfo {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        let genre = header("icy-genre").map(|genre| {
+            genre
+                .split([',', ';', '/'])
+                .map(|tag| tag.trim().to_lowercase())
+                .filter(|tag| !tag.is_empty())
+                .collect::<Vec<_>>()
+                .join(",")
+        });
+
+        // Some servers announce the bitrate for each channel, e.g. "128,128"
+        let bitrate = header("icy-br")
+            .and_then(|br| br.split(',').next().and_then(|br| br.trim().parse().ok()));
+
+        Self {
+            name: header("icy-name"),
+            genre,
+            bitrate,
+            homepage: header("icy-url").and_then(|url| Url::parse(&url).ok()),
+            content_type: header(CONTENT_TYPE.as_str()),
+        }
+    }
+
+    /// Codec name in the notation used by radio-browser
+    pub fn codec(&self) -> Option<String> {
+        let content_type = self.content_type.as_deref()?;
+        let mime = content_type.split(';').next()?.trim().to_lowercase();
+
+        let codec = match mime.as_str() {
+            "audio/mpeg" | "audio/mp3" => "MP3",
+            "audio/aac" => "AAC",
+            "audio/aacp" => "AAC+",
+            "audio/ogg" | "application/ogg" => "OGG",
+            "audio/opus" => "OPUS",
+            "audio/flac" => "FLAC",
+            _ => return None,
+        };
+
+        Some(codec.to_string())
+    }
+}
@@ -26,10 +26,10 @@ use gtk::{gio, glib};
 
 use crate::api::client;
 use crate::api::CoverLoader;
-use crate::audio::{SwPlaybackState, SwPlayer, SwRecordingState, SwTrack};
+use crate::audio::{SwPlaybackState, SwPlayer, SwRecorderModel, SwRecordingState, SwTrack};
 use crate::config;
 use crate::database::SwLibrary;
-use crate::i18n::{i18n, i18n_f};
+use crate::i18n::{i18n, i18n_f, ni18n_f};
 use crate::settings::*;
 use crate::ui::{SwApplicationWindow, SwTrackDialog};
 use crate::utils::is_kde_plasma;
@@ -46,6 +46,8 @@ mod imp {
         #[property(get)]
         player: SwPlayer,
         #[property(get)]
+        recorders: SwRecorderModel,
+        #[property(get)]
         rb_server: RefCell<Option<String>>,
         #[property(get, set = Self::set_background_playback)]
         background_playback: Cell<bool>,
@@ -54,6 +56,7 @@ mod imp {
         pub inhibit_cookie: Cell<u32>,
         pub background_hold: RefCell<Option<gio::ApplicationHoldGuard>>,
         pub background_proxy: OnceCell<BackgroundProxy<'static>>,
+        pub deferred_startup_done: Cell<bool>,
     }
 
     #[glib::object_subclass]
@@ -135,15 +138,26 @@ mod imp {
                 // app.quit
                 gio::ActionEntry::builder("quit")
                     .activate(move |app: &super::SwApplication, _, _| {
-                        app.quit();
+                        glib::spawn_future_local(clone!(
+                            #[weak]
+                            app,
+                            async move {
+                                if app.application_window().confirm_quit().await {
+                                    app.quit();
+                                }
+                            }
+                        ));
                     })
                     .build(),
             ]);
 
-            obj.set_accels_for_action("win.show-preferences", &["<primary>comma"]);
-            obj.set_accels_for_action("app.quit", &["<primary>q"]);
-            obj.set_accels_for_action("window.close", &["<primary>w"]);
-            obj.set_accels_for_action("player.toggle-playback", &["<primary>space"]);
+            // app.playback-shuffle
+            obj.add_action(&settings_manager::create_action(Key::PlaybackShuffle));
+
+            // app.stations-hide-broken
+            obj.add_action(&settings_manager::create_action(Key::StationsHideBroken));
+
+            shortcuts::apply(&*obj);
         }
     }
 
@@ -151,40 +165,71 @@ mod imp {
         fn startup(&self) {
             self.parent_startup();
 
-            let fut = clone!(
-                #[weak(rename_to = imp)]
-                self,
-                async move {
-                    // Find radiobrowser server and update library data
-                    imp.lookup_rb_server().await;
-
-                    // Setup background portal proxy
-                    imp.setup_background_portal_proxy().await;
-                }
-            );
-            glib::spawn_future_local(fut);
-
-            // Restore previously played station / volume
-            self.player.restore_state();
-
             settings_manager::bind_property(
                 Key::BackgroundPlayback,
                 &*self.obj(),
                 "background-playback",
             );
+
+            settings_manager::bind_property(
+                Key::AppearanceColorScheme,
+                &adw::StyleManager::default(),
+                "color-scheme",
+            );
         }
 
         fn activate(&self) {
             self.parent_activate();
 
             debug!("gio::Application -> activate()");
-            self.obj().application_window().present();
+            let window = self.obj().application_window();
+
+            // Stay in the background on the first activation, only possible with background
+            // playback, since otherwise the application would quit right away
+            if !self.deferred_startup_done.get()
+                && settings_manager::boolean(Key::StartupHidden)
+                && self.obj().background_playback()
+            {
+                debug!("Start hidden in background");
+                self.deferred_startup_done.set(true);
+                self.deferred_startup();
+                return;
+            }
+
+            window.present();
+
+            if !self.deferred_startup_done.replace(true) {
+                // Wait until the first frame got drawn, so that the window shows up as fast as
+                // possible, and do the remaining startup work afterwards when idle.
+                window.add_tick_callback(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    #[upgrade_or]
+                    glib::ControlFlow::Break,
+                    move |_, _| {
+                        glib::idle_add_local_once(clone!(
+                            #[weak]
+                            imp,
+                            move || imp.deferred_startup()
+                        ));
+                        glib::ControlFlow::Break
+                    }
+                ));
+            }
         }
 
         fn shutdown(&self) {
             self.parent_shutdown();
             debug!("gio::Application -> shutdown()");
 
+            self.player.finish_listening_session();
+
+            // Recordings which are still around now aren't needed anymore
+            self.player.discard_temporary_recordings();
+            // The main loop isn't running anymore, but the recordings still need to
+            // receive their end-of-stream to be finalized
+            glib::MainContext::default().block_on(self.recorders.stop_all());
+
             glib::spawn_future_local(async {
                 super::SwApplication::default()
                     .cover_loader()
@@ -199,7 +244,10 @@ mod imp {
             self.parent_window_removed(window);
             let obj = self.obj();
 
-            if obj.active_window().is_none() && obj.player().state() != SwPlaybackState::Playing {
+            if obj.active_window().is_none()
+                && obj.player().state() != SwPlaybackState::Playing
+                && obj.recorders().n_active() == 0
+            {
                 debug!("All windows closed, no active playback -> quit application, no need to run in background.");
                 obj.quit();
             }
@@ -209,6 +257,45 @@ mod imp {
     impl AdwApplicationImpl for SwApplication {}
 
     impl SwApplication {
+        fn deferred_startup(&self) {
+            debug!("Run deferred startup tasks");
+
+            // Restore previously played station / volume
+            self.player.restore_state();
+            self.player.restore_past_tracks();
+
+            // Offer recordings which are left over from a crash
+            self.player.recover_recordings();
+            let count = self.player.recovered_tracks().n_items();
+            if count > 0 {
+                let toast = adw::Toast::builder()
+                    .title(ni18n_f(
+                        "{} recording has been recovered",
+                        "{} recordings have been recovered",
+                        count,
+                        &[&count.to_string()],
+                    ))
+                    .button_label(i18n("Show"))
+                    .action_name("win.show-recovered-tracks")
+                    .timeout(0)
+                    .build();
+                self.obj().application_window().add_toast(toast);
+            }
+
+            let fut = clone!(
+                #[weak(rename_to = imp)]
+                self,
+                async move {
+                    // Find radiobrowser server and update library data
+                    imp.lookup_rb_server().await;
+
+                    // Setup background portal proxy
+                    imp.setup_background_portal_proxy().await;
+                }
+            );
+            glib::spawn_future_local(fut);
+        }
+
         fn set_background_playback(&self, enabled: bool) {
             debug!("Enable background playback: {}", enabled);
             self.background_playback.set(enabled);
@@ -0,0 +1,70 @@
+// Shortwave - audio_filter.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt::Debug;
+
+use anyhow::Result;
+use gstreamer::prelude::*;
+use gstreamer::Element;
+
+/// A component which inserts its own elements into the playback path, between the time-shift
+/// buffer and the volume controls
+pub trait AudioFilter: Debug {
+    /// Unique name, used to persist the order and whether the filter is enabled
+    fn name(&self) -> &str;
+
+    /// Creates a new element or bin, which gets inserted every time the filters change
+    fn create_element(&self) -> Result<Element>;
+}
+
+/// Audio filter based on a `gst-launch` style description of one or more elements
+#[derive(Debug, Clone)]
+pub struct SwElementFilter {
+    name: String,
+    description: String,
+}
+
+impl SwElementFilter {
+    pub fn new(name: &str, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+        }
+    }
+
+    /// The filters which are shipped with Shortwave, all disabled by default
+    pub fn builtin() -> Vec<Self> {
+        vec![
+            Self::new("equalizer", "equalizer-10bands"),
+            Self::new("karaoke", "audiokaraoke"),
+            Self::new(
+                "compressor",
+                "audiodynamic characteristics=soft-knee mode=compressor threshold=0.3 ratio=0.5",
+            ),
+        ]
+    }
+}
+
+impl AudioFilter for SwElementFilter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn create_element(&self) -> Result<Element> {
+        let bin = gstreamer::parse::bin_from_description(&self.description, true)?;
+        Ok(bin.upcast())
+    }
+}
@@ -0,0 +1,86 @@
+// Shortwave - audio_info.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::i18n::{i18n, i18n_f, ni18n_f};
+
+/// Format of the currently played stream, as far as it's known
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct StreamAudioInfo {
+    pub codec: Option<String>,
+    /// Bitrate in bits per second
+    pub bitrate: Option<u32>,
+    /// Sample rate in Hz
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u32>,
+}
+
+impl StreamAudioInfo {
+    /// Short, human readable codec name, e.g. "AAC" instead of "MPEG-4 AAC"
+    fn codec_name(&self) -> Option<String> {
+        let codec = self.codec.as_ref()?;
+        let lowercase = codec.to_lowercase();
+
+        let name = if lowercase.contains("aac") {
+            "AAC"
+        } else if lowercase.contains("mp3") || lowercase.contains("layer 3") {
+            "MP3"
+        } else if lowercase.contains("opus") {
+            "Opus"
+        } else if lowercase.contains("vorbis") {
+            "Vorbis"
+        } else if lowercase.contains("flac") {
+            "FLAC"
+        } else {
+            codec
+        };
+
+        Some(name.to_string())
+    }
+
+    /// Formats the info like "AAC 256 kbps 48 kHz stereo"
+    pub fn label(&self) -> String {
+        let mut parts = Vec::new();
+
+        if let Some(codec) = self.codec_name() {
+            parts.push(codec);
+        }
+
+        if let Some(bitrate) = self.bitrate.filter(|b| *b > 0) {
+            parts.push(i18n_f("{} kbps", &[&(bitrate / 1000).to_string()]));
+        }
+
+        if let Some(rate) = self.sample_rate.filter(|r| *r > 0) {
+            let khz = f64::from(rate) / 1000.0;
+            let khz = if rate % 1000 == 0 {
+                format!("{khz:.0}")
+            } else {
+                format!("{khz:.1}")
+            };
+            parts.push(i18n_f("{} kHz", &[&khz]));
+        }
+
+        match self.channels {
+            Some(1) => parts.push(i18n("mono")),
+            Some(2) => parts.push(i18n("stereo")),
+            Some(n) if n > 2 => {
+                parts.push(ni18n_f("{} channel", "{} channels", n, &[&n.to_string()]))
+            }
+            _ => (),
+        }
+
+        parts.join(" ")
+    }
+}
@@ -0,0 +1,45 @@
+// Shortwave - convert_format.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+/// Format into which saved tracks get converted
+#[derive(Display, Copy, Debug, Clone, EnumString, Eq, PartialEq, Default)]
+#[strum(serialize_all = "kebab_case")]
+pub enum SwConvertFormat {
+    #[default]
+    Disabled,
+    Mp3,
+    Flac,
+}
+
+impl SwConvertFormat {
+    /// File extension of the converted tracks, `None` if they don't get converted
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Self::Disabled => None,
+            Self::Mp3 => Some("mp3"),
+            Self::Flac => Some("flac"),
+        }
+    }
+
+    /// GStreamer elements which encode raw audio into this format
+    pub fn encoder(&self) -> Option<&'static str> {
+        match self {
+            Self::Disabled => None,
+            Self::Mp3 => Some("lamemp3enc target=bitrate bitrate=320 cbr=true ! id3v2mux"),
+            Self::Flac => Some("flacenc"),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+// Shortwave - filename_style.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use sanitize_filename::Options;
+
+#[derive(Display, Copy, Debug, Clone, EnumString, Eq, PartialEq, Default)]
+#[strum(serialize_all = "kebab_case")]
+pub enum SwFilenameStyle {
+    #[default]
+    KeepUnicode,
+    WindowsSafe,
+    Ascii,
+}
+
+impl SwFilenameStyle {
+    pub fn sanitize(&self, name: &str) -> String {
+        match self {
+            Self::KeepUnicode => sanitize_filename::sanitize(name),
+            Self::WindowsSafe => Self::sanitize_windows(name),
+            Self::Ascii => Self::sanitize_windows(&deunicode::deunicode(name)),
+        }
+    }
+
+    fn sanitize_windows(name: &str) -> String {
+        let options = Options {
+            windows: true,
+            truncate: true,
+            replacement: "_",
+        };
+
+        sanitize_filename::sanitize_with_options(name, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn keep_unicode_removes_separators() {
+        let style = SwFilenameStyle::KeepUnicode;
+        assert_eq!(
+            style.sanitize("Sigur Rós/Hoppípolla"),
+            "Sigur RósHoppípolla"
+        );
+    }
+
+    #[test]
+    fn windows_safe_replaces_reserved_characters() {
+        let style = SwFilenameStyle::WindowsSafe;
+        assert_eq!(style.sanitize("AC/DC: Live?"), "AC_DC_ Live_");
+        assert_eq!(style.sanitize("CON"), "_");
+    }
+
+    #[test]
+    fn ascii_transliterates() {
+        let style = SwFilenameStyle::Ascii;
+        assert_eq!(
+            style.sanitize("Sigur Rós: Hoppípolla"),
+            "Sigur Ros_ Hoppipolla"
+        );
+    }
+
+    #[test]
+    fn setting_values() {
+        assert_eq!(SwFilenameStyle::WindowsSafe.to_string(), "windows-safe");
+        assert_eq!(
+            SwFilenameStyle::from_str("keep-unicode"),
+            Ok(SwFilenameStyle::KeepUnicode)
+        );
+        assert!(SwFilenameStyle::from_str("unknown").is_err());
+    }
+}
@@ -0,0 +1,67 @@
+// Shortwave - fingerprint.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use futures_util::StreamExt;
+use gstreamer::prelude::*;
+use gstreamer::MessageView;
+use gtk::gio;
+
+use crate::api::Error;
+
+/// Calculates the Chromaprint fingerprint of the recorded `input` file
+pub async fn fingerprint(input: &gio::File) -> Result<Option<String>, Error> {
+    let Some(input) = input.path() else {
+        return Ok(None);
+    };
+
+    let pipeline = gstreamer::parse::launch(
+        "filesrc name=filesrc ! decodebin ! audioconvert ! chromaprint ! fakesink",
+    )?;
+    let pipeline = pipeline.downcast::<gstreamer::Pipeline>().unwrap();
+
+    let filesrc = pipeline.by_name("filesrc").unwrap();
+    filesrc.set_property("location", input.to_string_lossy().to_string());
+
+    pipeline
+        .set_state(gstreamer::State::Playing)
+        .map_err(|err| Error::Pipeline(err.to_string()))?;
+
+    let mut res = Ok(None);
+    let mut messages = pipeline.bus().unwrap().stream();
+    while let Some(message) = messages.next().await {
+        match message.view() {
+            MessageView::Tag(tag) => {
+                let fingerprint = tag
+                    .tags()
+                    .generic("chromaprint-fingerprint")
+                    .and_then(|value| value.get::<String>().ok());
+
+                if fingerprint.is_some() {
+                    res = Ok(fingerprint);
+                }
+            }
+            MessageView::Eos(_) => break,
+            MessageView::Error(err) => {
+                res = Err(Error::GLib(err.error()));
+                break;
+            }
+            _ => (),
+        }
+    }
+
+    let _ = pipeline.set_state(gstreamer::State::Null);
+    res
+}
@@ -0,0 +1,54 @@
+// Shortwave - folder_structure.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::path::PathBuf;
+
+use gtk::glib;
+
+use crate::audio::SwFilenameStyle;
+
+/// How saved tracks get organized in subdirectories of the track directory
+#[derive(Display, Copy, Debug, Clone, EnumString, Eq, PartialEq, Default)]
+#[strum(serialize_all = "kebab_case")]
+pub enum SwFolderStructure {
+    SingleFolder,
+    Station,
+    #[default]
+    StationDate,
+}
+
+impl SwFolderStructure {
+    /// Subdirectory for a track of `station`, which started playing at `date`
+    pub fn subdirectory(
+        &self,
+        station: &str,
+        date: &glib::DateTime,
+        style: SwFilenameStyle,
+    ) -> PathBuf {
+        let mut path = PathBuf::new();
+
+        if *self == Self::Station || *self == Self::StationDate {
+            path.push(style.sanitize(station));
+        }
+
+        if *self == Self::StationDate {
+            let date = date.format("%Y-%m-%d").unwrap();
+            path.push(date.as_str());
+        }
+
+        path
+    }
+}
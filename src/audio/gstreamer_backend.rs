@@ -14,18 +14,31 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::cell::OnceCell;
-use std::path::PathBuf;
+use std::cell::{Cell, OnceCell, RefCell};
+use std::fs;
+use std::future::Future;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use anyhow::{anyhow, bail};
 use async_channel::Sender;
 use glib::clone;
 use gstreamer::prelude::*;
-use gstreamer::{Bin, Element, MessageView, PadProbeReturn, PadProbeType, Pipeline, State};
+use gstreamer::{
+    Bin, Element, MessageView, PadProbeData, PadProbeReturn, PadProbeType, Pipeline, State,
+};
+use gstreamer_app::AppSrc;
 use gstreamer_audio::{StreamVolume, StreamVolumeFormat};
-use gtk::glib;
+use gtk::{gio, glib};
 
-use crate::audio::SwPlaybackState;
+use crate::audio::timeshift_buffer::{self, TimeshiftBuffer};
+use crate::audio::{AudioFilter, StreamAudioInfo, SwPlaybackState};
+use crate::database::HttpSettings;
+use crate::i18n::i18n;
+use crate::path;
 
 #[rustfmt::skip]
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -35,18 +48,52 @@ use crate::audio::SwPlaybackState;
 //                                          |     | -> (  | recorderbin |  )                      //
 //   --------------      --------------     |     |    (   -------------   )                      //
 //  | uridecodebin | -> | audioconvert | -> | tee |                                               //
-//   --------------      --------------     |     |     -------      ---------------------------  //
-//                                          |     | -> | queue | -> | pulsesink | autoaudiosink | //
-//                                           -----      -------      ---------------------------  //
+//   --------------      --------------     |     |     ----------------------------              //
+//                                          |     | -> | queue or timeshift bin (*) | ->          //
+//                                           -----      ----------------------------              //
+//                                                                                                //
+//       --------------     (   ---------   )     ---------      ------      -------              //
+//   -> | audioconvert | -> (  | filters |  ) -> | ducking | -> | fade | -> | queue | ->          //
+//       --------------     (   ---------   )     ---------      ------      -------              //
+//                                                                                                //
+//       -----------------------------------                                                      //
+//   -> | pulsesink | autoaudio | (fakesink) |                                                    //
+//       -----------------------------------                                                      //
+//                                                                                                //
+//  (*) The timeshift bin is only used while the time-shift buffer is enabled:                    //
+//                                                                                                //
+//    -------      ---------------      --------------      ----------      ---------------       //
+//   | queue | -> | audioresample | -> | audioconvert | -> |  queue2  | -> | rawaudioparse |      //
+//   | leaky |     ---------------      --------------     |   ring   |     ---------------       //
+//    -------                                              |  buffer  |                           //
+//                                                         | on disk  |                           //
+//                                                          ----------                            //
 //                                                                                                //
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// Interval in which the volume gets adjusted during a fade
+const FADE_STEP: Duration = Duration::from_millis(50);
+
 #[derive(Clone)]
 pub enum GstreamerChange {
     Title(String),
     PlaybackState(SwPlaybackState),
     Volume(f64),
     Failure(String),
+    /// The stream URL is no longer reachable (e.g. 404 / 410 or a redirect loop)
+    StreamUnavailable(String),
+    /// Whether the audio output is available, or currently requested by another application
+    AudioFocus(bool),
+    /// Playback state of a recorded file
+    FilePlaybackState(SwPlaybackState),
+    /// How much of the stream buffer is filled, in percent
+    Buffering(i32),
+    /// Format of the played stream
+    AudioInfo(StreamAudioInfo),
+    /// Peak and RMS level of the recorded audio, from 0.0 (silent) to 1.0 (full scale)
+    RecordingLevel(f64, f64),
+    /// The headless pipeline stopped, which only affects the recording
+    RecordingFailure(String),
 }
 
 #[derive(Default, Debug)]
@@ -71,9 +118,37 @@ impl BufferingState {
 pub struct GstreamerBackend {
     pipeline: Pipeline,
     recorderbin: Arc<Mutex<Option<Bin>>>,
+    /// Probe which drops the recorded data while the recording is paused,
+    /// and the running time at which it got paused
+    recording_pause: Option<(gstreamer::PadProbeId, u64)>,
     current_title: Arc<Mutex<String>>,
+    audio_info: Arc<Mutex<StreamAudioInfo>>,
     buffering_state: Arc<Mutex<BufferingState>>,
+    timeshift: Arc<Mutex<TimeshiftBuffer>>,
+    /// Probe which blocks the time-shift buffer while playback is paused,
+    /// and the running time at which it got paused
+    timeshift_pause: RefCell<Option<(gstreamer::PadProbeId, u64)>>,
+    /// Element between the tee and the playback path, either a plain queue
+    /// or the bin of the time-shift buffer
+    output_path: Arc<Mutex<Element>>,
+    http_settings: Arc<Mutex<HttpSettings>>,
+    fade_duration: Duration,
+    /// Gets increased with every fade, so that running fades notice when they got superseded
+    fade_generation: Rc<Cell<u32>>,
+    /// Whether the pipeline only runs in the background for recording, while
+    /// another device is playing the station
+    headless: Rc<Cell<bool>>,
+    /// Audio output of the pipeline, gets replaced by `fakesink` while headless
+    audiosink: Element,
+    fakesink: Element,
+    /// Registered audio filters in playback order, and whether they're enabled
+    filters: Vec<(Box<dyn AudioFilter>, bool)>,
+    /// Elements of the enabled filters, which are currently linked into the pipeline
+    filter_elements: Arc<Mutex<Vec<Element>>>,
     bus_watch_guard: OnceCell<gstreamer::bus::BusWatchGuard>,
+    /// Separate pipeline for playing recorded files, independent of the station playback
+    file_playbin: Element,
+    file_bus_watch_guard: OnceCell<gstreamer::bus::BusWatchGuard>,
     sender: Sender<GstreamerChange>,
 }
 
@@ -90,13 +165,23 @@ impl GstreamerBackend {
 
         // create gstreamer pipeline
         let pipeline_launch = format!(
-            "uridecodebin name=uridecodebin use-buffering=true buffer-duration=6000000000 ! audioconvert name=audioconvert ! tee name=tee ! queue ! {audiosink} name={audiosink}"
+            "uridecodebin name=uridecodebin use-buffering=true buffer-duration=6000000000 ! audioconvert name=audioconvert ! tee name=tee ! queue name=direct_queue ! \
+             audioconvert name=filter_convert ! volume name=ducking ! volume name=fade ! queue name=output_queue ! {audiosink} name={audiosink}"
         );
         let pipeline = gstreamer::parse::launch(&pipeline_launch)
             .expect("Unable to create gstreamer pipeline");
         let pipeline = pipeline.downcast::<gstreamer::Pipeline>().unwrap();
         pipeline.set_message_forward(true);
 
+        // Let the sound server know that this is music, so it can duck or cork
+        // the stream e.g. during calls
+        if let Some(pulsesink) = pipeline.by_name("pulsesink") {
+            let properties = gstreamer::Structure::builder("props")
+                .field("media.role", "music")
+                .build();
+            pulsesink.set_property("stream-properties", properties);
+        }
+
         // The recorderbin gets added / removed dynamically to the pipeline
         let recorderbin = Arc::new(Mutex::new(None));
 
@@ -107,12 +192,47 @@ impl GstreamerBackend {
         // Buffering state
         let buffering_state = Arc::new(Mutex::new(BufferingState::default()));
 
+        // Time-shift buffer, which gets linked between decoding and playback once it's enabled
+        let timeshift = Arc::new(Mutex::new(TimeshiftBuffer::default()));
+        let output_path = Arc::new(Mutex::new(pipeline.by_name("direct_queue").unwrap()));
+
+        // Ring buffer files of previous sessions aren't needed anymore
+        let _ = fs::remove_dir_all(Self::timeshift_directory());
+
+        // Nothing gets played locally while headless, so no sound server stream is needed
+        let audiosink = pipeline.by_name(audiosink).unwrap();
+        let fakesink = gstreamer::ElementFactory::make("fakesink")
+            .name("fakesink")
+            .property("sync", true)
+            .build()
+            .expect("Unable to create gstreamer fakesink");
+
+        let file_playbin = gstreamer::ElementFactory::make("playbin")
+            .name("file_playbin")
+            .build()
+            .expect("Unable to create gstreamer playbin");
+
         let mut gstreamer_backend = Self {
             pipeline,
             recorderbin,
+            recording_pause: None,
             current_title,
+            audio_info: Arc::default(),
             buffering_state,
+            timeshift,
+            timeshift_pause: RefCell::default(),
+            output_path,
+            http_settings: Arc::default(),
+            fade_duration: Duration::ZERO,
+            fade_generation: Rc::default(),
+            headless: Rc::default(),
+            audiosink,
+            fakesink,
+            filters: Vec::new(),
+            filter_elements: Arc::default(),
             bus_watch_guard: OnceCell::default(),
+            file_playbin,
+            file_bus_watch_guard: OnceCell::default(),
             sender: gst_sender,
         };
 
@@ -161,34 +281,37 @@ impl GstreamerBackend {
             );
         }
 
-        // dynamically link uridecodebin element with audioconvert element
-        let uridecodebin = self.pipeline.by_name("uridecodebin").unwrap();
+        // The decoded format is only known from the caps
         let audioconvert = self.pipeline.by_name("audioconvert").unwrap();
-        uridecodebin.connect_pad_added(clone!(
-            #[weak]
-            audioconvert,
-            move |_, src_pad| {
-                let sink_pad = audioconvert
-                    .static_pad("sink")
-                    .expect("Failed to get static sink pad from audioconvert");
-                if sink_pad.is_linked() {
-                    return; // We are already linked. Ignoring.
-                }
+        audioconvert.static_pad("sink").unwrap().add_probe(
+            PadProbeType::EVENT_DOWNSTREAM,
+            clone!(
+                #[strong(rename_to = audio_info)]
+                self.audio_info,
+                #[strong(rename_to = sender)]
+                self.sender,
+                move |_, info| {
+                    let Some(gstreamer::PadProbeData::Event(ref event)) = info.data else {
+                        return PadProbeReturn::Ok;
+                    };
 
-                let new_pad_caps = src_pad
-                    .current_caps()
-                    .expect("Failed to get caps of new pad.");
-                let new_pad_struct = new_pad_caps
-                    .structure(0)
-                    .expect("Failed to get first structure of caps.");
-                let new_pad_type = new_pad_struct.name();
+                    if let gstreamer::EventView::Caps(caps) = event.view() {
+                        if let Ok(caps_info) = gstreamer_audio::AudioInfo::from_caps(caps.caps()) {
+                            Self::update_audio_info(&audio_info, &sender, |info| {
+                                info.sample_rate = Some(caps_info.rate());
+                                info.channels = Some(caps_info.channels());
+                            });
+                        }
+                    }
 
-                if new_pad_type.starts_with("audio/x-raw") {
-                    // check if new_pad is audio
-                    let _ = src_pad.link(&sink_pad);
+                    PadProbeReturn::Ok
                 }
-            }
-        ));
+            ),
+        );
+
+        // dynamically link uridecodebin element with audioconvert element
+        let uridecodebin = self.pipeline.by_name("uridecodebin").unwrap();
+        self.connect_uridecodebin(&uridecodebin, false);
 
         // listen for new pipeline / bus messages
         let bus = self.pipeline.bus().expect("Unable to get pipeline bus");
@@ -202,6 +325,10 @@ impl GstreamerBackend {
                 self.buffering_state,
                 #[weak(rename_to = current_title)]
                 self.current_title,
+                #[strong(rename_to = audio_info)]
+                self.audio_info,
+                #[strong(rename_to = headless)]
+                self.headless,
                 #[upgrade_or_panic]
                 move |_, message| {
                     Self::parse_bus_message(
@@ -210,21 +337,118 @@ impl GstreamerBackend {
                         gst_sender.clone(),
                         &buffering_state,
                         current_title,
+                        &audio_info,
+                        headless.get(),
                     );
                     glib::ControlFlow::Continue
                 }
             ))
             .unwrap();
         self.bus_watch_guard.set(guard).unwrap();
+
+        let bus = self.file_playbin.bus().unwrap();
+        let guard = bus
+            .add_watch_local(clone!(
+                #[weak(rename_to = playbin)]
+                self.file_playbin,
+                #[strong(rename_to = sender)]
+                self.sender,
+                #[upgrade_or_panic]
+                move |_, message| {
+                    Self::parse_file_bus_message(&playbin, message, &sender);
+                    glib::ControlFlow::Continue
+                }
+            ))
+            .unwrap();
+        self.file_bus_watch_guard.set(guard).unwrap();
+    }
+
+    /// Applies the station specific HTTP options to the source element of an uridecodebin
+    pub fn apply_http_settings(source: &Element, http_settings: &HttpSettings) {
+        if !http_settings.user_agent.is_empty() && source.has_property("user-agent") {
+            source.set_property("user-agent", &http_settings.user_agent);
+        }
+
+        if !http_settings.headers.is_empty() && source.has_property("extra-headers") {
+            let mut headers = gstreamer::Structure::new_empty("extra-headers");
+            for (name, value) in &http_settings.headers {
+                headers.set(name.as_str(), value);
+            }
+            source.set_property("extra-headers", headers);
+        }
+
+        if let Some(credentials) = &http_settings.credentials {
+            if source.has_property("user-id") && source.has_property("user-pw") {
+                source.set_property("user-id", &credentials.username);
+                source.set_property("user-pw", &credentials.password);
+            }
+        }
+    }
+
+    fn connect_uridecodebin(&self, uridecodebin: &Element, apply_offset: bool) {
+        // Apply the station specific HTTP options to the source element
+        let http_settings = self.http_settings.clone();
+        uridecodebin.connect("source-setup", false, move |args| {
+            let source = args[1].get::<Element>().unwrap();
+            Self::apply_http_settings(&source, &http_settings.lock().unwrap());
+            None
+        });
+
+        let audioconvert = self.pipeline.by_name("audioconvert").unwrap();
+        uridecodebin.connect_pad_added(clone!(
+            #[weak]
+            audioconvert,
+            #[weak(rename_to = pipeline)]
+            self.pipeline,
+            move |_, src_pad| {
+                let sink_pad = audioconvert
+                    .static_pad("sink")
+                    .expect("Failed to get static sink pad from audioconvert");
+                if sink_pad.is_linked() {
+                    return; // We are already linked. Ignoring.
+                }
+
+                let new_pad_caps = src_pad
+                    .current_caps()
+                    .expect("Failed to get caps of new pad.");
+                let new_pad_struct = new_pad_caps
+                    .structure(0)
+                    .expect("Failed to get first structure of caps.");
+                let new_pad_type = new_pad_struct.name();
+
+                if new_pad_type.starts_with("audio/x-raw") {
+                    // A source which got swapped into the running pipeline starts with
+                    // its timestamps at zero, so shift them to the current running time.
+                    // Otherwise the sink would drop all buffers as being too late.
+                    if apply_offset {
+                        let offset = Self::calculate_pipeline_offset(&pipeline);
+                        src_pad.set_offset(offset.try_into().unwrap_or_default());
+                    }
+
+                    // check if new_pad is audio
+                    let _ = src_pad.link(&sink_pad);
+                }
+            }
+        ));
     }
 
     pub fn set_state(&mut self, state: gstreamer::State) {
         debug!("Set playback state: {:?}", state);
 
-        if state == gstreamer::State::Playing {
+        // Don't reset the buffering state if the pipeline is already playing, e.g. because
+        // the source got swapped while playing
+        if state == gstreamer::State::Playing && self.pipeline.current_state() != state {
             debug!("Start pipeline...");
             let mut buffering_state = self.buffering_state.lock().unwrap();
             buffering_state.reset();
+
+            // Stay silent until the playback actually starts and fades in
+            let volume = if self.fade_duration.is_zero() {
+                1.0
+            } else {
+                0.0
+            };
+            self.set_fade_volume(volume);
         }
 
         if state == gstreamer::State::Null {
@@ -233,6 +457,12 @@ impl GstreamerBackend {
                 GstreamerChange::PlaybackState(SwPlaybackState::Stopped),
             );
             *self.current_title.lock().unwrap() = String::new();
+            Self::update_audio_info(&self.audio_info, &self.sender, |info| {
+                *info = StreamAudioInfo::default()
+            });
+
+            self.stop_pipeline();
+            return;
         }
 
         let res = self.pipeline.set_state(state);
@@ -247,7 +477,7 @@ impl GstreamerBackend {
                 &self.sender,
                 GstreamerChange::Failure("Failed to set pipeline to playing".into()),
             );
-            let _ = self.pipeline.set_state(gstreamer::State::Null);
+            self.stop_pipeline();
             return;
         }
 
@@ -261,6 +491,16 @@ impl GstreamerBackend {
         }
     }
 
+    /// Stops the pipeline and removes the ring buffer file of the time-shift buffer,
+    /// queue2 creates a new one once playback starts again
+    fn stop_pipeline(&self) {
+        let location = Self::timeshift_location(&self.output_path.lock().unwrap());
+        let _ = self.pipeline.set_state(State::Null);
+
+        Self::remove_timeshift_file(location);
+        self.reset_timeshift();
+    }
+
     pub fn state(&self) -> SwPlaybackState {
         let state = self
             .pipeline
@@ -272,8 +512,13 @@ impl GstreamerBackend {
         }
     }
 
+    /// The PulseAudio output, also while it's replaced by the fakesink of the headless pipeline
+    fn pulsesink(&self) -> Option<&Element> {
+        (self.audiosink.name() == "pulsesink").then_some(&self.audiosink)
+    }
+
     pub fn volume(&self) -> f64 {
-        let v = if let Some(pulsesink) = self.pipeline.by_name("pulsesink") {
+        let v = if let Some(pulsesink) = self.pulsesink() {
             pulsesink.property("volume")
         } else {
             1.0
@@ -283,38 +528,824 @@ impl GstreamerBackend {
     }
 
     pub fn set_volume(&self, volume: f64) {
-        if let Some(pulsesink) = self.pipeline.by_name("pulsesink") {
+        let pa_volume = StreamVolume::convert_volume(
+            StreamVolumeFormat::Cubic,
+            StreamVolumeFormat::Linear,
+            volume,
+        );
+
+        // Saved tracks are played with the same volume as the stream
+        self.file_playbin.set_property("volume", pa_volume);
+
+        if let Some(pulsesink) = self.pulsesink() {
             if volume != 0.0 {
                 pulsesink.set_property("mute", false);
             }
 
-            let pa_volume = StreamVolume::convert_volume(
-                StreamVolumeFormat::Cubic,
-                StreamVolumeFormat::Linear,
-                volume,
-            );
             pulsesink.set_property("volume", pa_volume);
         } else {
             warn!("PulseAudio is required for changing the volume.")
         }
     }
 
+    /// Lowers the volume temporarily, without changing the volume of the stream itself
+    pub fn set_ducked(&self, ducked: bool) {
+        let ducking = self.pipeline.by_name("ducking").unwrap();
+        ducking.set_property("volume", if ducked { 0.2 } else { 1.0 });
+    }
+
+    pub fn set_fade_duration(&mut self, duration: Duration) {
+        self.fade_duration = duration;
+    }
+
+    /// Ramps up the volume, e.g. after playback has been started
+    pub fn fade_in(&self) {
+        glib::spawn_future_local(self.ramp_fade_volume(1.0));
+    }
+
+    /// Ramps down the volume, the returned future resolves when the stream is silent
+    pub fn fade_out(&self) -> impl Future<Output = ()> {
+        self.ramp_fade_volume(0.0)
+    }
+
+    fn set_fade_volume(&self, volume: f64) {
+        // Stop any running fade
+        self.fade_generation
+            .set(self.fade_generation.get().wrapping_add(1));
+
+        let fade = self.pipeline.by_name("fade").unwrap();
+        fade.set_property("volume", volume);
+    }
+
+    fn ramp_fade_volume(&self, target: f64) -> impl Future<Output = ()> {
+        let generation = self.fade_generation.get().wrapping_add(1);
+        self.fade_generation.set(generation);
+
+        let fade_generation = self.fade_generation.clone();
+        let fade = self.pipeline.by_name("fade").unwrap();
+        let steps = (self.fade_duration.as_millis() / FADE_STEP.as_millis()) as u32;
+
+        async move {
+            let start: f64 = fade.property("volume");
+
+            for step in 1..steps {
+                glib::timeout_future(FADE_STEP).await;
+
+                // Another fade has been started in the meantime
+                if fade_generation.get() != generation {
+                    return;
+                }
+
+                let volume = start + (target - start) * f64::from(step) / f64::from(steps);
+                fade.set_property("volume", volume);
+            }
+
+            fade.set_property("volume", target);
+        }
+    }
+
+    /// Keeps the pipeline silent and stops reporting its playback state, so
+    /// that it can keep recording while another device plays the station
+    pub fn set_headless(&self, headless: bool) {
+        if self.headless.replace(headless) == headless {
+            return;
+        }
+
+        let (old_sink, new_sink) = if headless {
+            (self.audiosink.clone(), self.fakesink.clone())
+        } else {
+            (self.fakesink.clone(), self.audiosink.clone())
+        };
+
+        let pipeline = self.pipeline.clone();
+        let queue = self.pipeline.by_name("output_queue").unwrap();
+        let src_pad = queue.static_pad("src").unwrap();
+
+        // Swap the sinks once no data flows, the probe gets called immediately if the pad is idle
+        let sinks = Mutex::new(Some((old_sink, new_sink)));
+        src_pad.add_probe(PadProbeType::IDLE, move |_, _| {
+            if let Some((old_sink, new_sink)) = sinks.lock().unwrap().take() {
+                let _ = pipeline.remove(&old_sink);
+                let _ = old_sink.set_state(State::Null);
+
+                let res = pipeline.add(&new_sink).and_then(|_| queue.link(&new_sink));
+                match res {
+                    Ok(()) => {
+                        let _ = new_sink.sync_state_with_parent();
+                    }
+                    Err(err) => warn!("Unable to swap audio output: {err}"),
+                }
+            }
+            PadProbeReturn::Remove
+        });
+    }
+
+    pub fn is_headless(&self) -> bool {
+        self.headless.get()
+    }
+
     pub fn set_mute(&self, mute: bool) {
-        if let Some(pulsesink) = self.pipeline.by_name("pulsesink") {
+        self.file_playbin.set_property("mute", mute);
+
+        if let Some(pulsesink) = self.pulsesink() {
             pulsesink.set_property("mute", mute);
         }
     }
 
     pub fn set_source_uri(&mut self, source: &str) {
+        // Tearing down the whole pipeline (and thus the audio sink) takes a while, so if it's
+        // already running only the source gets replaced
+        if self.pipeline.current_state() >= State::Paused {
+            self.swap_source_uri(source);
+            return;
+        }
+
         debug!("Stop pipeline...");
-        let _ = self.pipeline.set_state(State::Null);
+        self.stop_pipeline();
         *self.current_title.lock().unwrap() = String::new();
+        Self::update_audio_info(&self.audio_info, &self.sender, |info| {
+            *info = StreamAudioInfo::default()
+        });
 
         debug!("Set new source URI...");
         let uridecodebin = self.pipeline.by_name("uridecodebin").unwrap();
         uridecodebin.set_property("uri", source);
     }
 
+    fn swap_source_uri(&mut self, source: &str) {
+        debug!("Swap source of running pipeline...");
+        *self.current_title.lock().unwrap() = String::new();
+        Self::update_audio_info(&self.audio_info, &self.sender, |info| {
+            *info = StreamAudioInfo::default()
+        });
+
+        // Don't continue playing buffered audio of the previous station
+        if self.timeshift.lock().unwrap().is_enabled() {
+            self.jump_to_live();
+            self.resume();
+        }
+
+        // Remove the old source, this also unlinks it from audioconvert
+        let old_uridecodebin = self.pipeline.by_name("uridecodebin").unwrap();
+        let _ = old_uridecodebin.set_state(State::Null);
+        let _ = self.pipeline.remove(&old_uridecodebin);
+
+        let uridecodebin = gstreamer::ElementFactory::make("uridecodebin")
+            .name("uridecodebin")
+            .property("use-buffering", true)
+            .property("buffer-duration", 6_000_000_000i64)
+            .property("uri", source)
+            .build()
+            .expect("Unable to create uridecodebin");
+        self.connect_uridecodebin(&uridecodebin, true);
+
+        self.pipeline
+            .add(&uridecodebin)
+            .expect("Unable to add uridecodebin to pipeline");
+
+        // Report loading until the new source finished buffering. Whether the stream is live
+        // gets kept, since the pipeline itself doesn't change its state.
+        {
+            let mut buffering_state = self.buffering_state.lock().unwrap();
+            let is_live = buffering_state.is_live;
+            buffering_state.reset();
+            buffering_state.is_live = is_live;
+            buffering_state.buffering = true;
+        }
+        crate::utils::send(
+            &self.sender,
+            GstreamerChange::PlaybackState(SwPlaybackState::Loading),
+        );
+
+        if uridecodebin.sync_state_with_parent().is_err() {
+            warn!("Failed to start new source");
+            crate::utils::send(
+                &self.sender,
+                GstreamerChange::PlaybackState(SwPlaybackState::Failure),
+            );
+            crate::utils::send(
+                &self.sender,
+                GstreamerChange::Failure("Failed to start new source".into()),
+            );
+            self.stop_pipeline();
+        }
+    }
+
+    pub fn set_http_settings(&self, http_settings: HttpSettings) {
+        *self.http_settings.lock().unwrap() = http_settings;
+    }
+
+    pub fn http_settings(&self) -> HttpSettings {
+        self.http_settings.lock().unwrap().clone()
+    }
+
+    /// Graphviz description of the playback pipeline, for bug reports
+    pub fn pipeline_graph(&self) -> String {
+        self.pipeline
+            .debug_to_dot_data(gstreamer::DebugGraphDetails::all())
+            .to_string()
+    }
+
+    /// Adds a filter to the end of the playback path, it's disabled until it gets enabled with
+    /// [`Self::set_filter_states`]
+    pub fn register_filter(&mut self, filter: Box<dyn AudioFilter>) {
+        if self.filters.iter().any(|(f, _)| f.name() == filter.name()) {
+            warn!("Audio filter {} is already registered", filter.name());
+            return;
+        }
+
+        self.filters.push((filter, false));
+    }
+
+    /// Orders and enables the registered filters according to `states`. Filters which aren't
+    /// listed follow after the listed ones and get disabled.
+    pub fn set_filter_states(&mut self, states: &[(String, bool)]) {
+        let mut remaining = std::mem::take(&mut self.filters);
+
+        for (name, enabled) in states {
+            if let Some(pos) = remaining.iter().position(|(f, _)| f.name() == name) {
+                let (filter, _) = remaining.remove(pos);
+                self.filters.push((filter, *enabled));
+            }
+        }
+        self.filters
+            .extend(remaining.into_iter().map(|(filter, _)| (filter, false)));
+
+        self.update_filter_elements();
+    }
+
+    fn update_filter_elements(&self) {
+        let mut elements = Vec::new();
+        for (filter, _) in self.filters.iter().filter(|(_, enabled)| *enabled) {
+            match filter.create_element() {
+                Ok(element) => elements.push(element),
+                Err(err) => warn!("Unable to create audio filter {}: {err}", filter.name()),
+            }
+        }
+
+        let convert = self.pipeline.by_name("filter_convert").unwrap();
+        let ducking = self.pipeline.by_name("ducking").unwrap();
+        let src_pad = convert.static_pad("src").unwrap();
+
+        // Relink once no data flows, the probe gets called immediately if the pad is idle
+        let pipeline = self.pipeline.clone();
+        let filter_elements = self.filter_elements.clone();
+        let elements = Mutex::new(Some(elements));
+        src_pad.add_probe(PadProbeType::IDLE, move |_, _| {
+            if let Some(elements) = elements.lock().unwrap().take() {
+                let mut filter_elements = filter_elements.lock().unwrap();
+                Self::relink_filters(
+                    &pipeline,
+                    &convert,
+                    &ducking,
+                    &mut filter_elements,
+                    elements,
+                );
+            }
+            PadProbeReturn::Remove
+        });
+    }
+
+    fn relink_filters(
+        pipeline: &Pipeline,
+        convert: &Element,
+        ducking: &Element,
+        current: &mut Vec<Element>,
+        filters: Vec<Element>,
+    ) {
+        // Removing the elements also unlinks them
+        for element in current.drain(..) {
+            let _ = pipeline.remove(&element);
+            let _ = element.set_state(State::Null);
+        }
+        convert.unlink(ducking);
+
+        // Every filter gets followed by an audioconvert, so that the next one can negotiate
+        // its own format
+        for filter in filters {
+            let audioconvert = gstreamer::ElementFactory::make("audioconvert")
+                .build()
+                .unwrap();
+            current.push(filter);
+            current.push(audioconvert);
+        }
+
+        let mut chain = vec![convert.clone()];
+        chain.extend(current.iter().cloned());
+        chain.push(ducking.clone());
+
+        let res = pipeline
+            .add_many(current.iter())
+            .and_then(|_| Element::link_many(&chain));
+
+        if let Err(err) = res {
+            warn!("Unable to link audio filters: {err}");
+            for element in current.drain(..) {
+                let _ = pipeline.remove(&element);
+            }
+            let _ = convert.link(ducking);
+            return;
+        }
+
+        for element in current.iter() {
+            let _ = element.sync_state_with_parent();
+        }
+        debug!("Linked {} audio filter elements", current.len() / 2);
+    }
+
+    pub fn set_timeshift_duration(&self, duration: gstreamer::ClockTime) {
+        {
+            let mut timeshift = self.timeshift.lock().unwrap();
+            if timeshift.ring_size() == TimeshiftBuffer::bytes(duration) {
+                return;
+            }
+            timeshift.set_max_duration(duration);
+        }
+
+        self.update_output_path();
+    }
+
+    /// Links the time-shift buffer between the tee and the playback path if it's enabled,
+    /// otherwise the audio gets played directly through a plain queue
+    fn update_output_path(&self) {
+        let enabled = self.timeshift.lock().unwrap().is_enabled();
+        let is_direct = self.output_path.lock().unwrap().name() == "direct_queue";
+
+        let new_path = if enabled {
+            match self.create_timeshift_bin() {
+                Ok(bin) => bin.upcast::<Element>(),
+                Err(err) => {
+                    warn!("Unable to create time-shift buffer: {err}");
+                    return;
+                }
+            }
+        } else if is_direct {
+            return;
+        } else {
+            gstreamer::ElementFactory::make("queue")
+                .name("direct_queue")
+                .build()
+                .unwrap()
+        };
+
+        // The buffered audio gets lost, so playback can't stay paused
+        let was_paused = self.is_paused();
+        self.reset_timeshift();
+        if was_paused {
+            crate::utils::send(
+                &self.sender,
+                GstreamerChange::PlaybackState(SwPlaybackState::Playing),
+            );
+        }
+
+        let tee = self.pipeline.by_name("tee").unwrap();
+        let convert = self.pipeline.by_name("filter_convert").unwrap();
+        let old_path = self.output_path.lock().unwrap().clone();
+        let Some(tee_srcpad) = old_path.static_pad("sink").and_then(|pad| pad.peer()) else {
+            warn!("Unable to relink playback path: Output isn't linked");
+            return;
+        };
+
+        // Relink once no data flows, the probe gets called immediately if the pad is idle
+        let pipeline = self.pipeline.clone();
+        let output_path = self.output_path.clone();
+        let new_path = Mutex::new(Some(new_path));
+        tee_srcpad.add_probe(PadProbeType::IDLE, move |tee_srcpad, _| {
+            if let Some(new_path) = new_path.lock().unwrap().take() {
+                let mut output_path = output_path.lock().unwrap();
+                Self::relink_output_path(
+                    &pipeline,
+                    &tee,
+                    tee_srcpad,
+                    &convert,
+                    &output_path,
+                    &new_path,
+                );
+                *output_path = new_path;
+            }
+            PadProbeReturn::Remove
+        });
+    }
+
+    fn relink_output_path(
+        pipeline: &Pipeline,
+        tee: &Element,
+        tee_srcpad: &gstreamer::Pad,
+        convert: &Element,
+        old_path: &Element,
+        new_path: &Element,
+    ) {
+        // Removing the element also unlinks it
+        let location = Self::timeshift_location(old_path);
+        let _ = pipeline.remove(old_path);
+        let _ = old_path.set_state(State::Null);
+        tee.release_request_pad(tee_srcpad);
+        Self::remove_timeshift_file(location);
+
+        // The time-shift buffer stamps the audio starting at zero, so shift it to the
+        // current running time. Otherwise the sink would drop it as being too late.
+        if let Some(srcpad) = Self::timeshift_parse_srcpad(new_path) {
+            let offset = pipeline.current_running_time().unwrap_or_default();
+            srcpad.set_offset(offset.nseconds().try_into().unwrap_or_default());
+        }
+
+        let res = pipeline
+            .add(new_path)
+            .and_then(|_| Element::link_many([tee, new_path, convert]));
+
+        match res {
+            Ok(()) => {
+                let _ = new_path.sync_state_with_parent();
+                debug!("Linked playback path {}", new_path.name());
+            }
+            Err(err) => warn!("Unable to link playback path: {err}"),
+        }
+    }
+
+    fn create_timeshift_bin(&self) -> anyhow::Result<Bin> {
+        // The audio gets stored as raw PCM in a fixed format, so that the position in the
+        // ring buffer can be calculated from the amount of data. rawaudioparse reads it
+        // from the ring buffer, and handles the seeks for rewinding.
+        let description = format!(
+            "queue name=timeshift_input leaky=downstream ! audioresample ! audioconvert ! \
+             audio/x-raw,format=S16LE,layout=interleaved,rate={rate},channels={channels} ! \
+             queue2 name=timeshift_queue use-buffering=false temp-remove=false max-size-buffers=0 max-size-bytes=0 max-size-time=0 ! \
+             rawaudioparse name=timeshift_parse use-sink-caps=false format=pcm pcm-format=s16le sample-rate={rate} num-channels={channels}",
+            rate = timeshift_buffer::SAMPLE_RATE,
+            channels = timeshift_buffer::CHANNELS,
+        );
+        let bin = gstreamer::parse::bin_from_description(&description, true)?;
+
+        let directory = Self::timeshift_directory();
+        fs::create_dir_all(&directory)?;
+        let template = directory.join("timeshift-XXXXXX");
+
+        let queue = bin.by_name("timeshift_queue").unwrap();
+        queue.set_property("temp-template", template.to_str().unwrap_or_default());
+        queue.set_property(
+            "ring-buffer-max-size",
+            self.timeshift.lock().unwrap().ring_size(),
+        );
+
+        // Keep track of how much got written into the ring buffer
+        queue.static_pad("sink").unwrap().add_probe(
+            PadProbeType::BUFFER | PadProbeType::BUFFER_LIST,
+            clone!(
+                #[strong(rename_to = timeshift)]
+                self.timeshift,
+                move |_, info| {
+                    let size = match info.data {
+                        Some(PadProbeData::Buffer(ref buffer)) => buffer.size(),
+                        Some(PadProbeData::BufferList(ref list)) => list.calculate_size(),
+                        _ => 0,
+                    };
+                    timeshift.lock().unwrap().push(size as u64);
+                    PadProbeReturn::Ok
+                }
+            ),
+        );
+
+        let srcpad = Self::timeshift_parse_srcpad(bin.upcast_ref::<Element>()).unwrap();
+        srcpad.add_probe(
+            PadProbeType::BUFFER,
+            clone!(
+                #[strong(rename_to = timeshift)]
+                self.timeshift,
+                move |_, info| {
+                    if let Some(PadProbeData::Buffer(ref buffer)) = info.data {
+                        if let Some(pts) = buffer.pts() {
+                            let position = pts + buffer.duration().unwrap_or_default();
+                            timeshift.lock().unwrap().set_position(position);
+                        }
+                    }
+                    PadProbeReturn::Ok
+                }
+            ),
+        );
+
+        // Seeking within the buffer must not reset the running time of the pipeline, the
+        // pad offset already makes the audio continue at the current running time
+        srcpad.add_probe(PadProbeType::EVENT_FLUSH, |_, info| {
+            let resets_time = match info.data {
+                Some(PadProbeData::Event(ref event)) => match event.view() {
+                    gstreamer::EventView::FlushStop(flush_stop) => flush_stop.resets_time(),
+                    _ => false,
+                },
+                _ => false,
+            };
+
+            if resets_time {
+                let event = gstreamer::event::FlushStop::new(false);
+                info.data = Some(PadProbeData::Event(event));
+            }
+            PadProbeReturn::Ok
+        });
+
+        Ok(bin)
+    }
+
+    fn timeshift_directory() -> PathBuf {
+        let mut path = path::CACHE.clone();
+        path.push("timeshift");
+        path
+    }
+
+    /// Location of the ring buffer file, if the output path is the time-shift buffer
+    fn timeshift_location(output_path: &Element) -> Option<String> {
+        output_path
+            .downcast_ref::<Bin>()?
+            .by_name("timeshift_queue")?
+            .property::<Option<String>>("temp-location")
+    }
+
+    fn remove_timeshift_file(location: Option<String>) {
+        if let Some(location) = location {
+            if let Err(err) = fs::remove_file(&location) {
+                debug!("Unable to remove time-shift buffer file {location}: {err}");
+            }
+        }
+    }
+
+    fn timeshift_parse_srcpad(output_path: &Element) -> Option<gstreamer::Pad> {
+        output_path
+            .downcast_ref::<Bin>()?
+            .by_name("timeshift_parse")?
+            .static_pad("src")
+    }
+
+    fn timeshift_srcpad(&self) -> Option<gstreamer::Pad> {
+        Self::timeshift_parse_srcpad(&self.output_path.lock().unwrap())
+    }
+
+    /// Forgets the buffered audio, e.g. because the pipeline got stopped
+    fn reset_timeshift(&self) {
+        let srcpad = self.timeshift_srcpad();
+        if let Some((probe_id, _)) = self.timeshift_pause.take() {
+            if let Some(srcpad) = &srcpad {
+                srcpad.remove_probe(probe_id);
+            }
+        }
+
+        if let Some(srcpad) = srcpad {
+            srcpad.set_offset(0);
+        }
+        self.timeshift.lock().unwrap().clear();
+    }
+
+    /// Pauses playback, while the stream continues to get buffered in background
+    pub fn pause(&self) {
+        if self.timeshift_pause.borrow().is_some() {
+            return;
+        }
+
+        let Some(srcpad) = self.timeshift_srcpad() else {
+            warn!("Unable to pause playback: Time-shift buffer is disabled");
+            return;
+        };
+        let Some(running_time) = self.pipeline.current_running_time() else {
+            warn!("Unable to pause playback: Pipeline isn't running");
+            return;
+        };
+
+        debug!("Pause playback, continue buffering...");
+        let probe_id = srcpad
+            .add_probe(
+                PadProbeType::BLOCK | PadProbeType::BUFFER | PadProbeType::BUFFER_LIST,
+                |_, _| PadProbeReturn::Ok,
+            )
+            .expect("Unable to add time-shift pause probe");
+
+        *self.timeshift_pause.borrow_mut() = Some((probe_id, running_time.nseconds()));
+        self.timeshift.lock().unwrap().set_paused(true);
+        crate::utils::send(
+            &self.sender,
+            GstreamerChange::PlaybackState(SwPlaybackState::Paused),
+        );
+    }
+
+    pub fn resume(&self) {
+        let Some((probe_id, paused_at)) = self.timeshift_pause.take() else {
+            return;
+        };
+
+        debug!("Resume playback from time-shift buffer...");
+        if let Some(srcpad) = self.timeshift_srcpad() {
+            // Shift the timestamps by the length of the pause, so that the buffered
+            // audio continues at the current running time
+            let running_time = self.pipeline.current_running_time().unwrap_or_default();
+            let gap = running_time.nseconds().saturating_sub(paused_at);
+            srcpad.set_offset(srcpad.offset() + i64::try_from(gap).unwrap_or_default());
+            srcpad.remove_probe(probe_id);
+        }
+
+        self.timeshift.lock().unwrap().set_paused(false);
+        crate::utils::send(
+            &self.sender,
+            GstreamerChange::PlaybackState(SwPlaybackState::Playing),
+        );
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.timeshift.lock().unwrap().is_paused()
+    }
+
+    /// Plays the given duration of the time-shift buffer again
+    pub fn rewind(&self, duration: gstreamer::ClockTime) {
+        debug!("Rewind playback by {duration}...");
+        let position = self.timeshift.lock().unwrap().rewind_position(duration);
+        self.seek_timeshift(position);
+    }
+
+    pub fn jump_to_live(&self) {
+        debug!("Jump to live...");
+        let position = self.timeshift.lock().unwrap().live_position();
+        self.seek_timeshift(position);
+    }
+
+    fn seek_timeshift(&self, position: gstreamer::ClockTime) {
+        let Some(srcpad) = self.timeshift_srcpad() else {
+            return;
+        };
+        let Some(running_time) = self.pipeline.current_running_time() else {
+            return;
+        };
+
+        // The audio after the seek continues at the current running time, and a paused
+        // playback resumes from there
+        srcpad.set_offset(running_time.nseconds().try_into().unwrap_or_default());
+        if let Some((_, paused_at)) = self.timeshift_pause.borrow_mut().as_mut() {
+            *paused_at = running_time.nseconds();
+        }
+        self.timeshift.lock().unwrap().set_position(position);
+
+        let seek = gstreamer::event::Seek::new(
+            1.0,
+            gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::ACCURATE,
+            gstreamer::SeekType::Set,
+            position,
+            gstreamer::SeekType::None,
+            gstreamer::ClockTime::NONE,
+        );
+        if !srcpad.send_event(seek) {
+            warn!("Unable to seek within the time-shift buffer");
+        }
+    }
+
+    /// Returns how far playback is behind the live stream, and how much audio is buffered
+    pub fn timeshift_state(&self) -> (gstreamer::ClockTime, gstreamer::ClockTime) {
+        let timeshift = self.timeshift.lock().unwrap();
+        (timeshift.delay(), timeshift.duration())
+    }
+
+    /// Encodes the most recent audio of the time-shift buffer into a file.
+    /// Resolves to the duration of the saved audio.
+    pub fn save_timeshift(
+        &self,
+        duration: gstreamer::ClockTime,
+        path: PathBuf,
+    ) -> impl Future<Output = anyhow::Result<gstreamer::ClockTime>> {
+        let ranges = self.timeshift.lock().unwrap().recent_ranges(duration);
+        let location = Self::timeshift_location(&self.output_path.lock().unwrap());
+        debug!(
+            "Saving {:?} of the time-shift buffer {:?} to {:?}",
+            ranges, location, path
+        );
+
+        async move {
+            gio::spawn_blocking(move || Self::encode_ranges(location, ranges, &path))
+                .await
+                .map_err(|_| anyhow!("Unable to encode the time-shift buffer"))?
+        }
+    }
+
+    fn encode_ranges(
+        location: Option<String>,
+        ranges: Vec<(u64, u64)>,
+        path: &Path,
+    ) -> anyhow::Result<gstreamer::ClockTime> {
+        let Some(location) = location.filter(|_| !ranges.is_empty()) else {
+            bail!(i18n("No audio has been buffered yet"));
+        };
+        let mut file = fs::File::open(location)?;
+
+        let pipeline = gstreamer::parse::launch(
+            "appsrc name=src format=time block=true ! audioconvert ! vorbisenc ! oggmux ! filesink name=filesink",
+        )?
+        .downcast::<Pipeline>()
+        .unwrap();
+
+        let appsrc = pipeline.by_name("src").and_downcast::<AppSrc>().unwrap();
+        appsrc.set_caps(Some(&timeshift_buffer::caps()));
+
+        let filesink = pipeline.by_name("filesink").unwrap();
+        filesink.set_property("location", path.to_str().unwrap_or_default());
+
+        pipeline.set_state(State::Playing)?;
+
+        // The audio gets read in chunks of one second, and stamped to start at zero
+        let chunk_size = TimeshiftBuffer::bytes(gstreamer::ClockTime::SECOND);
+        let mut written = 0;
+        'ranges: for (offset, length) in ranges {
+            if let Err(err) = file.seek(SeekFrom::Start(offset)) {
+                warn!("Unable to read time-shift buffer: {err}");
+                break;
+            }
+
+            let mut remaining = length;
+            while remaining > 0 {
+                let size = remaining.min(chunk_size);
+                let mut data = vec![0; size as usize];
+                if let Err(err) = file.read_exact(&mut data) {
+                    warn!("Unable to read time-shift buffer: {err}");
+                    break 'ranges;
+                }
+
+                let mut buffer = gstreamer::Buffer::from_mut_slice(data);
+                {
+                    let buffer = buffer.get_mut().unwrap();
+                    buffer.set_pts(TimeshiftBuffer::time(written));
+                    buffer.set_duration(TimeshiftBuffer::time(size));
+                }
+                written += size;
+                remaining -= size;
+
+                if appsrc.push_buffer(buffer).is_err() {
+                    break 'ranges;
+                }
+            }
+        }
+        let _ = appsrc.end_of_stream();
+
+        let duration = TimeshiftBuffer::time(written);
+        let bus = pipeline.bus().unwrap();
+        let result = match bus.timed_pop_filtered(
+            gstreamer::ClockTime::NONE,
+            &[gstreamer::MessageType::Eos, gstreamer::MessageType::Error],
+        ) {
+            Some(message) => match message.view() {
+                MessageView::Error(err) => Err(anyhow!(err.error().to_string())),
+                _ => Ok(duration),
+            },
+            None => Ok(duration),
+        };
+
+        let _ = pipeline.set_state(State::Null);
+        result
+    }
+
+    pub fn play_file(&self, uri: &str, volume: f64) {
+        debug!("Play file: {uri}");
+        let _ = self.file_playbin.set_state(State::Null);
+
+        let volume = StreamVolume::convert_volume(
+            StreamVolumeFormat::Cubic,
+            StreamVolumeFormat::Linear,
+            volume,
+        );
+        self.file_playbin.set_property("volume", volume);
+        self.file_playbin.set_property("uri", uri);
+
+        if self.file_playbin.set_state(State::Playing).is_err() {
+            warn!("Unable to play file: {uri}");
+            crate::utils::send(
+                &self.sender,
+                GstreamerChange::FilePlaybackState(SwPlaybackState::Failure),
+            );
+        }
+    }
+
+    pub fn set_file_paused(&self, paused: bool) {
+        let state = if paused {
+            State::Paused
+        } else {
+            State::Playing
+        };
+        let _ = self.file_playbin.set_state(state);
+    }
+
+    pub fn stop_file(&self) {
+        let _ = self.file_playbin.set_state(State::Null);
+        crate::utils::send(
+            &self.sender,
+            GstreamerChange::FilePlaybackState(SwPlaybackState::Stopped),
+        );
+    }
+
+    pub fn seek_file(&self, position: gstreamer::ClockTime) {
+        let flags = gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::KEY_UNIT;
+        if let Err(err) = self.file_playbin.seek_simple(flags, position) {
+            warn!("Unable to seek file: {err}");
+        }
+    }
+
+    pub fn file_position(&self) -> Option<gstreamer::ClockTime> {
+        self.file_playbin.query_position::<gstreamer::ClockTime>()
+    }
+
+    pub fn file_duration(&self) -> Option<gstreamer::ClockTime> {
+        self.file_playbin.query_duration::<gstreamer::ClockTime>()
+    }
+
     pub fn start_recording(&mut self, path: PathBuf) {
         if self.is_recording() {
             warn!("Unable to start recording: Already recording");
@@ -324,7 +1355,7 @@ impl GstreamerBackend {
 
         // Create actual recorderbin
         let description =
-            "queue name=queue ! vorbisenc ! oggmux  ! filesink name=filesink async=false";
+            "queue name=queue ! level interval=100000000 ! vorbisenc ! oggmux  ! filesink name=filesink async=false";
         let recorderbin = gstreamer::parse::bin_from_description(description, true)
             .expect("Unable to create recorderbin");
         recorderbin.set_property("message-forward", true);
@@ -370,15 +1401,73 @@ impl GstreamerBackend {
             .expect("Unable to link tee srcpad with recorderbin sinkpad");
 
         *self.recorderbin.lock().unwrap() = Some(recorderbin);
+        self.recording_pause = None;
         debug!("Started recording to {:?}", path);
     }
 
+    /// Drops the recorded data until the recording gets resumed. The recorderbin
+    /// keeps running, so that everything ends up in the same file.
+    pub fn pause_recording(&mut self) {
+        if self.recording_pause.is_some() {
+            return;
+        }
+
+        let Some(queue_srcpad) = self.recorder_queue_srcpad() else {
+            warn!("Unable to pause recording: No recording running");
+            return;
+        };
+
+        let probe_id = queue_srcpad
+            .add_probe(PadProbeType::BUFFER | PadProbeType::BUFFER_LIST, |_, _| {
+                PadProbeReturn::Drop
+            })
+            .expect("Unable to add recording pause probe");
+        let paused_at = Self::calculate_pipeline_offset(&self.pipeline);
+
+        self.recording_pause = Some((probe_id, paused_at));
+        debug!("Paused recording.");
+    }
+
+    pub fn resume_recording(&mut self) {
+        let Some((probe_id, paused_at)) = self.recording_pause.take() else {
+            return;
+        };
+
+        let Some(queue_srcpad) = self.recorder_queue_srcpad() else {
+            return;
+        };
+
+        // Shift the timestamps by the length of the gap, so that the muxer
+        // continues seamlessly where the recording got paused
+        let gap = Self::calculate_pipeline_offset(&self.pipeline).saturating_sub(paused_at);
+        let offset = queue_srcpad.offset() - i64::try_from(gap).unwrap_or_default();
+        queue_srcpad.set_offset(offset);
+        queue_srcpad.remove_probe(probe_id);
+
+        debug!(
+            "Resumed recording, skipped {} seconds.",
+            gap / 1_000_000_000
+        );
+    }
+
+    fn recorder_queue_srcpad(&self) -> Option<gstreamer::Pad> {
+        self.recorderbin
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|bin| bin.by_name("queue"))
+            .and_then(|queue| queue.static_pad("src"))
+    }
+
     pub fn stop_recording(&mut self, discard_buffered_data: bool) {
         debug!(
             "Stop recording... (Discard buffered data: {:?})",
             &discard_buffered_data
         );
 
+        // The end-of-stream event passes the pause probe, so the file gets finalized anyway
+        self.recording_pause = None;
+
         let recorderbin = self.recorderbin.lock().unwrap().take();
         let recorderbin = match recorderbin {
             None => {
@@ -447,6 +1536,17 @@ impl GstreamerBackend {
         self.recorderbin.lock().unwrap().is_some()
     }
 
+    /// Whether a stopped recording is still being finalized, i.e. the end-of-stream
+    /// event hasn't reached the file sink yet
+    pub fn is_finalizing_recording(&self) -> bool {
+        let current = self.recorderbin.lock().unwrap().clone();
+        self.pipeline.children().iter().any(|child| {
+            child.downcast_ref::<Bin>().is_some_and(|bin| {
+                Some(bin) != current.as_ref() && bin.by_name("filesink").is_some()
+            })
+        })
+    }
+
     pub fn recording_duration(&self) -> u64 {
         let recorderbin: &Option<Bin> = &self.recorderbin.lock().unwrap();
         if let Some(recorderbin) = recorderbin {
@@ -456,7 +1556,11 @@ impl GstreamerBackend {
                 .static_pad("src")
                 .unwrap();
 
-            let running_time = *recorderbin.current_running_time().unwrap_or_default();
+            // The duration doesn't advance while the recording is paused
+            let running_time = match self.recording_pause {
+                Some((_, paused_at)) => paused_at,
+                None => *recorderbin.current_running_time().unwrap_or_default(),
+            };
             let offset = queue_srcpad.offset().unsigned_abs();
 
             trace!("Running time: {running_time}");
@@ -505,15 +1609,78 @@ impl GstreamerBackend {
         pulsesink.is_ok()
     }
 
+    /// Applies a change to the stream audio info, and reports it if something changed
+    fn update_audio_info(
+        audio_info: &Arc<Mutex<StreamAudioInfo>>,
+        sender: &Sender<GstreamerChange>,
+        update: impl FnOnce(&mut StreamAudioInfo),
+    ) {
+        let mut audio_info = audio_info.lock().unwrap();
+        let previous = audio_info.clone();
+        update(&mut audio_info);
+
+        // This also gets called from streaming threads, so don't wait for the receiver
+        if *audio_info != previous {
+            if let Err(err) = sender.try_send(GstreamerChange::AudioInfo(audio_info.clone())) {
+                warn!("Unable to report audio info: {err}");
+            }
+        }
+    }
+
     fn parse_bus_message(
         pipeline: Pipeline,
         message: &gstreamer::Message,
         sender: Sender<GstreamerChange>,
         buffering_state: &Arc<Mutex<BufferingState>>,
         current_title: Arc<Mutex<String>>,
+        audio_info: &Arc<Mutex<StreamAudioInfo>>,
+        headless: bool,
     ) {
+        // The playback state is determined by the remote device, only titles
+        // and the end of recordings are relevant for the headless pipeline
+        if headless
+            && !matches!(
+                message.view(),
+                MessageView::Tag(_) | MessageView::Element(_)
+            )
+        {
+            match message.view() {
+                MessageView::Error(err) => {
+                    warn!("Gstreamer Error in headless pipeline: {}", err.error());
+                    let msg = err.error().to_string();
+                    crate::utils::send(&sender, GstreamerChange::RecordingFailure(msg));
+                }
+                MessageView::Eos(_) => {
+                    let msg = String::from("End of stream");
+                    crate::utils::send(&sender, GstreamerChange::RecordingFailure(msg));
+                }
+                _ => (),
+            }
+            return;
+        }
+
         match message.view() {
             MessageView::Tag(tag) => {
+                let tags = tag.tags();
+                let codec = tags
+                    .get::<gstreamer::tags::AudioCodec>()
+                    .map(|c| c.get().to_string());
+                let bitrate = tags
+                    .get::<gstreamer::tags::NominalBitrate>()
+                    .or_else(|| tags.get::<gstreamer::tags::Bitrate>())
+                    .map(|b| b.get());
+
+                if codec.is_some() || bitrate.is_some() {
+                    Self::update_audio_info(audio_info, &sender, |info| {
+                        if codec.is_some() {
+                            info.codec = codec;
+                        }
+                        if bitrate.is_some() {
+                            info.bitrate = bitrate;
+                        }
+                    });
+                }
+
                 if let Some(t) = tag.tags().get::<gstreamer::tags::Title>() {
                     let new_title = t.get().to_string();
 
@@ -543,6 +1710,7 @@ impl GstreamerBackend {
             MessageView::Buffering(buffering) => {
                 let percent = buffering.percent();
                 debug!("Buffering ({}%)", percent);
+                crate::utils::send(&sender, GstreamerChange::Buffering(percent));
 
                 // Wait until buffering is complete before start/resume playing
                 let mut buffering_state = buffering_state.lock().unwrap();
@@ -590,9 +1758,23 @@ impl GstreamerBackend {
                     }
                 }
             }
+            MessageView::RequestState(request) => {
+                // The sound server asks to cork the stream, e.g. because of a call
+                let has_focus = request.requested_state() == State::Playing;
+                debug!("Audio focus changed: {}", has_focus);
+                crate::utils::send(&sender, GstreamerChange::AudioFocus(has_focus));
+            }
             MessageView::Element(element) => {
-                // Catch the end-of-stream messages from the filesink
+                // Input levels of the recorderbin
                 let structure = element.structure().unwrap();
+                if structure.name() == "level" {
+                    let peak = Self::level_amplitude(structure, "peak");
+                    let rms = Self::level_amplitude(structure, "rms");
+                    crate::utils::send(&sender, GstreamerChange::RecordingLevel(peak, rms));
+                    return;
+                }
+
+                // Catch the end-of-stream messages from the filesink
                 if structure.name() == "GstBinForwarded" {
                     let message: gstreamer::message::Message = structure.get("message").unwrap();
                     if let MessageView::Eos(_) = &message.view() {
@@ -620,6 +1802,16 @@ impl GstreamerBackend {
                 } else {
                     warn!("Gstreamer Error: {}", msg);
                 }
+
+                // Stations move their streams quite often, so let the player try to
+                // resolve a new URL before it reports the failure
+                let is_redirect_loop = err.error().matches(gstreamer::ResourceError::OpenRead)
+                    && msg.to_lowercase().contains("redirect");
+                if err.error().matches(gstreamer::ResourceError::NotFound) || is_redirect_loop {
+                    crate::utils::send(&sender, GstreamerChange::StreamUnavailable(msg));
+                    return;
+                }
+
                 crate::utils::send(
                     &sender,
                     GstreamerChange::PlaybackState(SwPlaybackState::Failure),
@@ -629,4 +1821,171 @@ impl GstreamerBackend {
             _ => (),
         };
     }
+
+    /// Highest level of all channels, converted from dB to a linear amplitude
+    pub fn level_amplitude(structure: &gstreamer::StructureRef, field: &str) -> f64 {
+        let Ok(values) = structure.get::<glib::ValueArray>(field) else {
+            return 0.0;
+        };
+
+        let db = values
+            .iter()
+            .filter_map(|v| v.get::<f64>().ok())
+            .fold(f64::NEG_INFINITY, f64::max);
+        10f64.powf(db / 20.0).clamp(0.0, 1.0)
+    }
+
+    fn parse_file_bus_message(
+        playbin: &Element,
+        message: &gstreamer::Message,
+        sender: &Sender<GstreamerChange>,
+    ) {
+        let state = match message.view() {
+            MessageView::StateChanged(sc) => {
+                if message.src() != Some(playbin.upcast_ref::<gstreamer::Object>()) {
+                    return;
+                }
+
+                match sc.current() {
+                    State::Playing => SwPlaybackState::Playing,
+                    // Pipeline passes the paused state while starting playback
+                    State::Paused if sc.pending() != State::Playing => SwPlaybackState::Paused,
+                    _ => return,
+                }
+            }
+            MessageView::Eos(_) => {
+                let _ = playbin.set_state(State::Null);
+                SwPlaybackState::Stopped
+            }
+            MessageView::Error(err) => {
+                warn!("Gstreamer Error while playing file: {}", err.error());
+                let _ = playbin.set_state(State::Null);
+                SwPlaybackState::Failure
+            }
+            _ => return,
+        };
+
+        crate::utils::send(sender, GstreamerChange::FilePlaybackState(state));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_channel::Receiver;
+    use futures_util::future::{select, Either};
+
+    use super::*;
+    use crate::test_server::{IcecastStream, TestServer};
+
+    const TIMEOUT: Duration = Duration::from_secs(15);
+
+    /// Waits for the first change which matches, and skips the ones before it
+    async fn wait_for(
+        receiver: &Receiver<GstreamerChange>,
+        description: &str,
+        matches: impl Fn(&GstreamerChange) -> bool,
+    ) {
+        let change = async {
+            while let Ok(change) = receiver.recv().await {
+                if matches(&change) {
+                    return;
+                }
+            }
+        };
+
+        let timeout = glib::timeout_future(TIMEOUT);
+        if let Either::Right(_) = select(Box::pin(change), timeout).await {
+            panic!("Timed out waiting for {description}");
+        }
+    }
+
+    fn is_title(change: &GstreamerChange, expected: &str) -> bool {
+        matches!(change, GstreamerChange::Title(title) if title == expected)
+    }
+
+    async fn wait_until(description: &str, condition: impl Fn() -> bool) {
+        let mut waited = Duration::ZERO;
+        while !condition() {
+            assert!(waited < TIMEOUT, "Timed out waiting for {description}");
+            glib::timeout_future(Duration::from_millis(100)).await;
+            waited += Duration::from_millis(100);
+        }
+    }
+
+    #[test]
+    fn play_and_record_stream() {
+        gstreamer::init().unwrap();
+
+        // The stream gets played like by any other application, which needs the usual plugins
+        let elements = ["souphttpsrc", "icydemux", "wavparse", "vorbisenc", "oggmux"];
+        let missing: Vec<_> = elements
+            .into_iter()
+            .filter(|name| gstreamer::ElementFactory::find(name).is_none())
+            .collect();
+        if !missing.is_empty() {
+            eprintln!("Skipping test, missing GStreamer elements: {missing:?}");
+            return;
+        }
+
+        let server = TestServer::start(|request| {
+            IcecastStream::response(request, &["Artist - First Song", "Artist - Second Song"])
+        });
+        let recording =
+            std::env::temp_dir().join(format!("shortwave-test-{}.ogg", std::process::id()));
+
+        // Changes get reported on the thread default main context
+        let context = glib::MainContext::new();
+        context
+            .with_thread_default(|| {
+                context.block_on(async {
+                    let (sender, receiver) = async_channel::unbounded();
+                    let mut backend = GstreamerBackend::new(sender);
+
+                    // Play the stream without a sound server
+                    backend.set_headless(true);
+                    backend.set_source_uri(&server.url("/stream"));
+                    backend.set_state(State::Playing);
+
+                    wait_until("playback", || backend.state() == SwPlaybackState::Playing).await;
+                    wait_for(&receiver, "first title", |change| {
+                        is_title(change, "Artist - First Song")
+                    })
+                    .await;
+
+                    // Record while the next title gets announced
+                    backend.start_recording(recording.clone());
+                    assert!(backend.is_recording());
+                    wait_for(&receiver, "recording level", |change| {
+                        matches!(change, GstreamerChange::RecordingLevel(_, _))
+                    })
+                    .await;
+                    wait_for(&receiver, "second title", |change| {
+                        is_title(change, "Artist - Second Song")
+                    })
+                    .await;
+                    assert!(backend.recording_duration() > 0);
+
+                    backend.stop_recording(false);
+                    assert!(!backend.is_recording());
+                    wait_until("finalized recording", || !backend.is_finalizing_recording()).await;
+
+                    backend.set_state(State::Null);
+                    wait_for(&receiver, "stop", |change| {
+                        matches!(
+                            change,
+                            GstreamerChange::PlaybackState(SwPlaybackState::Stopped)
+                        )
+                    })
+                    .await;
+                    assert_eq!(backend.state(), SwPlaybackState::Stopped);
+                })
+            })
+            .unwrap();
+
+        let size = fs::metadata(&recording)
+            .map(|m| m.len())
+            .unwrap_or_default();
+        let _ = fs::remove_file(&recording);
+        assert!(size > 0, "Recording is empty");
+    }
 }
@@ -0,0 +1,26 @@
+// Shortwave - interruption_behavior.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+/// What happens with playback while another application, e.g. for a call,
+/// requests the audio output
+#[derive(Display, Copy, Debug, Clone, EnumString, Eq, PartialEq, Default)]
+#[strum(serialize_all = "kebab_case")]
+pub enum SwInterruptionBehavior {
+    #[default]
+    Duck,
+    Pause,
+    Ignore,
+}
@@ -0,0 +1,58 @@
+// Shortwave - library_structure.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::path::PathBuf;
+
+use gtk::glib;
+
+use crate::audio::SwFilenameStyle;
+
+/// How exported tracks get organized in the music directory
+#[derive(Display, Copy, Debug, Clone, EnumString, Eq, PartialEq, Default)]
+#[strum(serialize_all = "kebab_case")]
+pub enum SwLibraryStructure {
+    #[default]
+    ArtistAlbum,
+    StationDate,
+}
+
+impl SwLibraryStructure {
+    /// Subdirectory for a track of `artist`, which has been played by `station` at `date`.
+    /// The actual album is unknown, so the station is used as album instead.
+    pub fn subdirectory(
+        &self,
+        artist: Option<&str>,
+        station: &str,
+        date: &glib::DateTime,
+        style: SwFilenameStyle,
+    ) -> PathBuf {
+        let mut path = PathBuf::new();
+
+        match self {
+            Self::ArtistAlbum => {
+                path.push(style.sanitize(artist.unwrap_or(station)));
+                path.push(style.sanitize(station));
+            }
+            Self::StationDate => {
+                path.push(style.sanitize(station));
+                let date = date.format("%Y-%m-%d").unwrap();
+                path.push(date.as_str());
+            }
+        }
+
+        path
+    }
+}
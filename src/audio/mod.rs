@@ -14,20 +14,51 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+mod audio_filter;
+mod audio_info;
+mod convert_format;
+mod filename_style;
+pub mod fingerprint;
+mod folder_structure;
 mod gstreamer_backend;
+mod interruption_behavior;
+mod library_structure;
 mod mpris;
+pub mod music_library;
 mod playback_state;
 mod player;
+mod queue_entry;
+mod queue_model;
+mod recorder;
+mod recorder_model;
+mod recorder_state;
 mod recording_mode;
+pub mod recording_recovery;
 mod recording_state;
+mod search_provider;
+mod timeshift_buffer;
 mod track;
 mod track_model;
+pub mod transcoder;
 
+pub use audio_filter::{AudioFilter, SwElementFilter};
+pub use audio_info::StreamAudioInfo;
+pub use convert_format::SwConvertFormat;
+pub use filename_style::SwFilenameStyle;
+pub use folder_structure::SwFolderStructure;
 pub use gstreamer_backend::{GstreamerBackend, GstreamerChange};
+pub use interruption_behavior::SwInterruptionBehavior;
+pub use library_structure::SwLibraryStructure;
 pub use mpris::MprisServer;
 pub use playback_state::SwPlaybackState;
 pub use player::SwPlayer;
+pub use queue_entry::SwQueueEntry;
+pub use queue_model::SwQueueModel;
+pub use recorder::SwRecorder;
+pub use recorder_model::SwRecorderModel;
+pub use recorder_state::SwRecorderState;
 pub use recording_mode::SwRecordingMode;
 pub use recording_state::SwRecordingState;
+pub use search_provider::SwSearchProvider;
 pub use track::SwTrack;
 pub use track_model::SwTrackModel;
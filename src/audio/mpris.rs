@@ -45,6 +45,7 @@ impl MprisServer {
             .can_go_next(true)
             .can_go_previous(true)
             .can_seek(false)
+            .shuffle(SwApplication::default().player().shuffle())
             .can_set_fullscreen(false)
             .can_raise(true)
             .can_quit(true)
@@ -117,6 +118,20 @@ impl MprisServer {
             }
         ));
 
+        player.connect_shuffle_notify(clone!(
+            #[strong]
+            server,
+            move |_| {
+                glib::spawn_future_local(clone!(
+                    #[strong]
+                    server,
+                    async move {
+                        server.update_mpris_shuffle().await;
+                    }
+                ));
+            }
+        ));
+
         player.connect_volume_notify(clone!(
             #[strong]
             server,
@@ -146,7 +161,12 @@ impl MprisServer {
 
         server.player.connect_pause(|_| {
             glib::spawn_future_local(async move {
-                SwApplication::default().player().stop_playback().await;
+                let player = SwApplication::default().player();
+                if player.can_pause() {
+                    player.pause_playback();
+                } else {
+                    player.stop_playback().await;
+                }
             });
         });
 
@@ -160,6 +180,10 @@ impl MprisServer {
             SwApplication::default().player().set_volume(volume);
         });
 
+        server.player.connect_set_shuffle(|_, shuffle| {
+            SwApplication::default().player().set_shuffle(shuffle);
+        });
+
         server.player.connect_raise(|_| {
             SwApplication::default().activate();
         });
@@ -259,6 +283,7 @@ impl MprisServer {
             SwPlaybackState::Playing => PlaybackStatus::Playing,
             SwPlaybackState::Loading => PlaybackStatus::Playing,
             SwPlaybackState::Failure => PlaybackStatus::Stopped,
+            SwPlaybackState::Paused => PlaybackStatus::Paused,
         };
 
         if let Err(err) = self.player.set_playback_status(playback_status).await {
@@ -276,6 +301,13 @@ impl MprisServer {
         }
     }
 
+    async fn update_mpris_shuffle(&self) {
+        let player = SwApplication::default().player();
+        if let Err(err) = self.player.set_shuffle(player.shuffle()).await {
+            error!("Unable to update mpris shuffle: {:?}", err.to_string())
+        }
+    }
+
     async fn update_mpris_capabilities(&self) {
         let library = SwApplication::default().library();
         
@@ -0,0 +1,158 @@
+// Shortwave - music_library.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use gtk::prelude::*;
+use gtk::{gio, glib};
+
+use crate::api::Error;
+use crate::audio::{transcoder, SwFilenameStyle, SwLibraryStructure, SwTrack};
+use crate::settings::{settings_manager, Key};
+
+/// Copies the saved file of a track into the music directory with tags, so that
+/// it shows up in music players like GNOME Music automatically
+pub async fn export(track: &SwTrack, file: &gio::File) -> Result<gio::File, Error> {
+    let Some(directory) = glib::user_special_dir(glib::UserDirectory::Music) else {
+        return Err(Error::Pipeline(String::from(
+            "No music directory available",
+        )));
+    };
+
+    let style = settings_manager::string(Key::RecordingFilenameStyle);
+    let style = SwFilenameStyle::from_str(&style).unwrap_or_default();
+    let structure = settings_manager::string(Key::RecordingLibraryStructure);
+    let structure = SwLibraryStructure::from_str(&structure).unwrap_or_default();
+
+    let station = track.station().title();
+    let (artist, title) = track.artist_and_title();
+    let date = glib::DateTime::from_unix_local(track.started_at()).unwrap();
+
+    let extension = file
+        .path()
+        .and_then(|p| p.extension().map(|e| e.to_string_lossy().to_string()))
+        .unwrap_or_else(|| String::from("ogg"));
+
+    let mut path = PathBuf::from(directory);
+    path.push(structure.subdirectory(artist.as_deref(), &station, &date, style));
+    let name = style.sanitize(&track.title());
+    path.push(format!("{name}.{extension}"));
+
+    // The saved file itself can already be located in the music directory. Any other
+    // file with the same name must not be overwritten though.
+    let in_place = gio::File::for_path(&path).equal(file);
+    if !in_place {
+        path = unique_path(&path, &name, &extension);
+    }
+    let destination = gio::File::for_path(path);
+
+    if let Some(directory) = destination.parent() {
+        match directory.make_directory_with_parents(gio::Cancellable::NONE) {
+            Err(err) if !err.matches(gio::IOErrorEnum::Exists) => return Err(err.into()),
+            _ => (),
+        }
+    }
+
+    let mut tags = gstreamer::TagList::new();
+    {
+        let tags = tags.get_mut().unwrap();
+        tags.add::<gstreamer::tags::Title>(&title.as_str(), gstreamer::TagMergeMode::Replace);
+        if let Some(artist) = &artist {
+            tags.add::<gstreamer::tags::Artist>(&artist.as_str(), gstreamer::TagMergeMode::Replace);
+        }
+        tags.add::<gstreamer::tags::Album>(&station.as_str(), gstreamer::TagMergeMode::Replace);
+    }
+
+    debug!(
+        "Export track \"{}\" to {:?}",
+        &track.title(),
+        destination.path()
+    );
+    if in_place {
+        // Reading and writing the same file at once would truncate it
+        let mut part = destination.path().unwrap().into_os_string();
+        part.push(".part");
+        let part = gio::File::for_path(PathBuf::from(part));
+
+        if let Err(err) = transcoder::write_tags(file, &part, &tags).await {
+            let _ = part.delete_future(glib::Priority::LOW).await;
+            return Err(err);
+        }
+
+        let (move_future, _) = part.move_future(
+            &destination,
+            gio::FileCopyFlags::OVERWRITE,
+            glib::Priority::LOW,
+        );
+        move_future.await?;
+    } else {
+        transcoder::write_tags(file, &destination, &tags).await?;
+    }
+
+    request_indexing(&destination).await;
+    Ok(destination)
+}
+
+/// Appends a number to the file name as long as a file with that name exists already
+fn unique_path(path: &Path, name: &str, extension: &str) -> PathBuf {
+    let mut unique = path.to_path_buf();
+    let mut number = 2;
+
+    while unique.exists() {
+        unique.set_file_name(format!("{name} ({number}).{extension}"));
+        number += 1;
+    }
+
+    unique
+}
+
+/// Asks the Tracker file miner to index the file right away, instead of waiting
+/// until it notices the change on its own
+async fn request_indexing(file: &gio::File) {
+    let proxy = gio::DBusProxy::for_bus_future(
+        gio::BusType::Session,
+        gio::DBusProxyFlags::DO_NOT_LOAD_PROPERTIES,
+        None,
+        "org.freedesktop.Tracker3.Miner.Files",
+        "/org/freedesktop/Tracker3/Miner/Files/Index",
+        "org.freedesktop.Tracker3.Miner.Files.Index",
+    )
+    .await;
+
+    // Empty graphs and flags index the file with the default settings
+    let args = (
+        file.uri().to_string(),
+        Vec::<String>::new(),
+        Vec::<String>::new(),
+    );
+    let res = match proxy {
+        Ok(proxy) => proxy
+            .call_future(
+                "IndexLocation",
+                Some(&args.to_variant()),
+                gio::DBusCallFlags::NONE,
+                -1,
+            )
+            .await
+            .map(|_| ()),
+        Err(err) => Err(err),
+    };
+
+    if let Err(err) = res {
+        debug!("Unable to request indexing of exported track: {err}");
+    }
+}
@@ -27,4 +27,5 @@ pub enum SwPlaybackState {
     Playing,
     Loading,
     Failure,
+    Paused,
 }
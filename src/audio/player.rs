@@ -16,22 +16,46 @@
 
 use std::cell::{Cell, OnceCell, RefCell};
 use std::fs;
+use std::pin::pin;
+use std::str::FromStr;
+use std::time::Duration;
 
 use adw::prelude::*;
+use async_io::Timer;
+use futures_util::future::{select, Either};
 use glib::clone;
 use glib::subclass::prelude::*;
 use glib::Properties;
 use gtk::{gio, glib};
 
-use crate::api::{StationMetadata, SwStation};
+use crate::api::{client, Error, StationMetadata, SwStation};
 use crate::app::SwApplication;
 use crate::audio::*;
 use crate::config;
-use crate::device::{SwCastSender, SwDevice, SwDeviceDiscovery, SwDeviceKind, SwDlnaSender};
+use crate::database::{
+    AutomationRule, StationCredentials, StationHistory, StationSettings, TrackHistory, WatchList,
+};
+use crate::device::{
+    DeviceError, SwCastSender, SwDevice, SwDeviceDiscovery, SwDeviceKind, SwDlnaSender,
+};
 use crate::i18n::*;
 use crate::path;
 use crate::settings::{settings_manager, Key};
-use crate::ui::DisplayError;
+use crate::ui::{DisplayError, SwApplicationWindow};
+use crate::utils;
+
+/// How many stations may fail in a row before shuffling stops
+const MAX_SHUFFLE_FAILURES: u32 = 5;
+/// How often a station gets tried before switching to its backup station
+const FAILOVER_ATTEMPTS: u32 = 3;
+/// Seconds to wait before a failed station gets retried
+const FAILOVER_RETRY_DELAY: u32 = 5;
+/// How long connecting to a Cast device may take
+const CAST_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// Size of the cover which gets served to DLNA renderers, in pixels
+const DLNA_COVER_SIZE: i32 = 512;
+/// How much of the time-shift buffer gets saved on demand
+const SAVE_RECENT_DURATION: Duration = Duration::from_secs(5 * 60);
 
 mod imp {
     use super::*;
@@ -43,12 +67,16 @@ mod imp {
         Cancelled,
         ReachedMaximumDuration,
         StreamFailure,
+        Quit,
     }
 
     impl RecordingStopReason {
         fn discard_data(&self) -> bool {
-            // Save recorded data only on track save or when track reaches maximum duration
-            *self != Self::TrackChange && *self != Self::ReachedMaximumDuration
+            // Save recorded data only on track save, when track reaches maximum duration
+            // or when it should be kept before quitting
+            *self != Self::TrackChange
+                && *self != Self::ReachedMaximumDuration
+                && *self != Self::Quit
         }
     }
 
@@ -62,6 +90,26 @@ mod imp {
         state: Cell<SwPlaybackState>,
         #[property(get)]
         last_failure: RefCell<String>,
+        /// How much of the stream buffer is filled while loading, in percent
+        #[property(get)]
+        buffering: Cell<i32>,
+        /// Peak level of the recorded audio, from 0.0 to 1.0
+        #[property(get)]
+        recording_peak: Cell<f64>,
+        /// RMS level of the recorded audio, from 0.0 to 1.0
+        #[property(get)]
+        recording_rms: Cell<f64>,
+        /// Whether the recording of the playing track is paused, e.g. during an ad break
+        #[property(get)]
+        recording_paused: Cell<bool>,
+        /// Format of the played stream, e.g. "AAC 256 kbps 48 kHz stereo"
+        #[property(get)]
+        #[property(name="has-audio-format", get=Self::has_audio_format, type=bool)]
+        audio_format: RefCell<String>,
+        /// Whether a new stream URL has already been requested for the current station
+        stream_url_resolved: Cell<bool>,
+        /// Whether playback got ducked or paused because another application uses the audio output
+        interrupted: Cell<bool>,
         #[property(get)]
         #[property(name="has-playing-track", get=Self::has_playing_track, type=bool)]
         playing_track: RefCell<Option<SwTrack>>,
@@ -69,10 +117,79 @@ mod imp {
         previous_track: RefCell<Option<SwTrack>>,
         #[property(get)]
         past_tracks: SwTrackModel,
+        #[property(get)]
+        recovered_tracks: SwTrackModel,
+        /// Stations which get played after the current one
+        #[property(get)]
+        queue: SwQueueModel,
+        /// Seconds until the queue advances to the next station, or 0 if it doesn't advance automatically
+        #[property(get)]
+        queue_remaining: Cell<u64>,
+        /// Whether a random library station gets played on failure or after the shuffle interval
+        #[property(get, set)]
+        shuffle: Cell<bool>,
+        /// Seconds the current station has been played while shuffling
+        shuffle_elapsed: Cell<u64>,
+        /// Number of stations which failed in a row while shuffling
+        shuffle_failures: Cell<u32>,
+        /// How often the current station failed in a row
+        station_failures: Cell<u32>,
+        /// Most recent volume for the DLNA renderer, set while it gets sent
+        dlna_volume_pending: Cell<Option<f64>>,
+        /// Uuids of the stations which failed over to their backup station in a row,
+        /// followed by the station which is tried next
+        failover_chain: RefCell<Vec<String>>,
+        /// Uuid of the station which is listened to, and since when
+        listening_session: RefCell<Option<(String, glib::DateTime)>>,
+        /// Recorded track which gets played from its file
+        #[property(get)]
+        file_track: RefCell<Option<SwTrack>>,
+        #[property(get, builder(SwPlaybackState::default()))]
+        file_state: Cell<SwPlaybackState>,
+        /// Position of the file playback in seconds
+        #[property(get)]
+        file_position: Cell<f64>,
+        /// Duration of the played file in seconds
+        #[property(get)]
+        file_duration: Cell<f64>,
+        /// Whether the recorded track is played on the DLNA renderer instead of locally
+        #[property(get)]
+        file_remote: Cell<bool>,
+        /// Saved track which is queued on the renderer after the played one, and its URI
+        remote_next_track: RefCell<Option<(SwTrack, String)>>,
+        /// Periodic read back of the renderer state while it plays a recorded track
+        remote_file_poll: RefCell<Option<glib::JoinHandle<()>>>,
         #[property(get, set=Self::set_volume)]
         volume: Cell<f64>,
+        /// Whether the output is muted. Only forwarded to DLNA renderers, other
+        /// outputs get silenced by lowering the volume.
+        #[property(get, set=Self::set_muted)]
+        muted: Cell<bool>,
+        /// Recording mode which applies to the current track, automation rules can override
+        /// the configured one
         #[property(get, set=Self::set_recording_mode, builder(SwRecordingMode::default()))]
+        #[property(
+            name="active-recording-mode",
+            get=Self::active_recording_mode,
+            type=SwRecordingMode,
+            builder(SwRecordingMode::default())
+        )]
         recording_mode: Cell<SwRecordingMode>,
+        /// Recording mode set by a matching automation rule
+        rule_recording_mode: Cell<Option<SwRecordingMode>>,
+        /// Duration of the volume ramps when starting or stopping playback, in seconds
+        #[property(get, set=Self::set_fade_duration)]
+        fade_duration: Cell<f64>,
+        /// Maximum time-shift buffer size in minutes
+        #[property(get, set=Self::set_timeshift_duration)]
+        timeshift_duration: Cell<i32>,
+        /// How many seconds playback is behind the live stream
+        #[property(get)]
+        #[property(name="is-timeshifted", get=Self::is_timeshifted, type=bool)]
+        timeshift_delay: Cell<u64>,
+        /// How many seconds of audio can be saved from the time-shift buffer
+        #[property(get)]
+        recent_audio_duration: Cell<u64>,
 
         #[property(get)]
         #[property(name="has-device", get=Self::has_device, type=bool)]
@@ -86,6 +203,8 @@ mod imp {
         pub backend: OnceCell<RefCell<GstreamerBackend>>,
         pub mpris_server: OnceCell<MprisServer>,
         pub gst_sender: OnceCell<async_channel::Sender<GstreamerChange>>,
+        /// Kept around to get notified when the audio filter settings change
+        audio_filter_settings: OnceCell<gio::Settings>,
         
         // Cast FFmpeg proxy state
         pub cast_proxy_active: Cell<bool>,
@@ -107,6 +226,7 @@ mod imp {
             // Setup Gstreamer backend
             let (sender, receiver) = async_channel::bounded(10);
             self.gst_sender.set(sender.clone()).unwrap();
+            self.cast_sender.set_change_sender(sender.clone());
             self.backend
                 .set(RefCell::new(GstreamerBackend::new(sender)))
                 .unwrap();
@@ -154,11 +274,30 @@ mod imp {
                     .handle_error("Unable to start MPRIS media controls")
             });
 
-            // Cleanup temporary recording directory
+            // Keep recordings which are left over after a crash, so that they can be recovered
             let mut path = path::DATA.clone();
             path.push("recording");
+            recording_recovery::collect_orphaned_recordings(&path);
+
+            // Cleanup temporary recording directory. It gets renamed first, so that
+            // the actual deletion can happen in the background without interfering
+            // with new recordings.
+            let mut old_path = path::DATA.clone();
+            old_path.push("recording.old");
+
             if path.exists() {
-                fs::remove_dir_all(path).expect("Could not delete recording directory.");
+                if let Err(err) = fs::rename(&path, &old_path) {
+                    warn!("Unable to move old recording directory: {err}");
+                }
+            }
+
+            if old_path.exists() {
+                glib::spawn_future_local(async move {
+                    let res = gio::spawn_blocking(move || fs::remove_dir_all(old_path)).await;
+                    if !matches!(res, Ok(Ok(()))) {
+                        warn!("Could not delete old recording directory.");
+                    }
+                });
             }
 
             // Ensure temporary recording directory gsetting is set
@@ -175,12 +314,49 @@ mod imp {
             }
 
             // Set how many tracks will be saved before they are replaced with newer recordings
-            let max_count = settings_manager::integer(Key::PlaybackPastTracksCount) as u32;
-            self.past_tracks.set_max_count(max_count);
+            settings_manager::bind_property(
+                Key::PlaybackPastTracksCount,
+                &self.past_tracks,
+                "max-count",
+            );
+            self.recovered_tracks.set_max_count(u32::MAX);
+
+            // Bind shuffle setting
+            settings_manager::bind_property(Key::PlaybackShuffle, &*self.obj(), "shuffle");
 
             // Bind recording mode setting
             settings_manager::bind_property(Key::RecordingMode, &*self.obj(), "recording-mode");
 
+            // Bind fade setting
+            settings_manager::bind_property(
+                Key::PlaybackFadeDuration,
+                &*self.obj(),
+                "fade-duration",
+            );
+
+            // Bind time-shift buffer setting
+            settings_manager::bind_property(
+                Key::PlaybackTimeshiftDuration,
+                &*self.obj(),
+                "timeshift-duration",
+            );
+
+            // Audio filters, their order and whether they're enabled is stored in the settings
+            for filter in SwElementFilter::builtin() {
+                self.obj().register_audio_filter(Box::new(filter));
+            }
+
+            let settings = settings_manager::settings();
+            settings.connect_changed(
+                Some(Key::PlaybackAudioFilters.to_string().as_str()),
+                clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move |_, _| imp.apply_audio_filters()
+                ),
+            );
+            self.audio_filter_settings.set(settings).unwrap();
+
             glib::timeout_add_seconds_local(
                 1,
                 clone!(
@@ -206,6 +382,55 @@ mod imp {
                         if stop_recording {
                             imp.stop_recording(RecordingStopReason::ReachedMaximumDuration);
                         }
+
+                        if imp.file_state.get() == SwPlaybackState::Playing {
+                            imp.update_file_position();
+                        }
+
+                        // Play a random station after the shuffle interval elapsed
+                        if imp.shuffle.get() && imp.state.get() == SwPlaybackState::Playing {
+                            let elapsed = imp.shuffle_elapsed.get() + 1;
+                            imp.shuffle_elapsed.set(elapsed);
+
+                            let interval = settings_manager::integer(Key::PlaybackShuffleInterval);
+                            if interval > 0 && elapsed >= interval as u64 * 60 {
+                                imp.shuffle_elapsed.set(0);
+                                let obj = imp.obj().clone();
+                                glib::spawn_future_local(async move {
+                                    obj.shuffle_station().await;
+                                });
+                            }
+                        }
+
+                        // Advance station queue
+                        let remaining = imp.queue_remaining.get();
+                        if remaining > 0 && imp.state.get() == SwPlaybackState::Playing {
+                            imp.queue_remaining.set(remaining - 1);
+                            imp.obj().notify_queue_remaining();
+
+                            if remaining == 1 {
+                                let obj = imp.obj().clone();
+                                glib::spawn_future_local(async move {
+                                    obj.play_next().await;
+                                });
+                            }
+                        }
+
+                        // Update time-shift buffer indicator
+                        let (delay, buffered) =
+                            imp.backend.get().unwrap().borrow().timeshift_state();
+                        if imp.timeshift_delay.get() != delay.seconds() {
+                            imp.timeshift_delay.set(delay.seconds());
+                            imp.obj().notify_timeshift_delay();
+                            imp.obj().notify_is_timeshifted();
+                        }
+
+                        let recent = buffered.seconds().min(SAVE_RECENT_DURATION.as_secs());
+                        if imp.recent_audio_duration.get() != recent {
+                            imp.recent_audio_duration.set(recent);
+                            imp.obj().notify_recent_audio_duration();
+                        }
+
                         glib::ControlFlow::Continue
                     }
                 ),
@@ -222,10 +447,24 @@ mod imp {
             self.obj().playing_track().is_some()
         }
 
+        fn has_audio_format(&self) -> bool {
+            !self.audio_format.borrow().is_empty()
+        }
+
+        fn is_timeshifted(&self) -> bool {
+            self.timeshift_delay.get() > 0
+        }
+
         fn has_device(&self) -> bool {
             self.obj().device().is_some()
         }
 
+        pub fn active_recording_mode(&self) -> SwRecordingMode {
+            self.rule_recording_mode
+                .get()
+                .unwrap_or(self.recording_mode.get())
+        }
+
         pub fn set_volume(&self, volume: f64) {
             if self.volume.get() != volume {
                 debug!("Set volume: {}", &volume);
@@ -249,14 +488,13 @@ mod imp {
                 } else if let Some(device) = self.obj().device() {
                     // Handle device-specific volume control
                     match device.kind() {
+                        SwDeviceKind::Dlna if self.muted.get() && volume == 0.0 => {
+                            // The renderer is muted, so it can keep its own volume
+                            debug!("Not lowering volume of muted DLNA device");
+                        }
                         SwDeviceKind::Dlna => {
                             debug!("Setting DLNA device volume: {}", volume);
-                            if let Err(e) = self.obj().dlna_sender().set_volume_dlna(volume) {
-                                warn!("Failed to set DLNA volume: {}", e);
-                            } else {
-                                // Only save volume if DLNA device accepted it
-                                settings_manager::set_double(volume_key, volume);
-                            }
+                            self.set_dlna_volume(volume);
                         }
                         SwDeviceKind::Cast => {
                             debug!("Setting Cast device volume: {}", volume);
@@ -273,6 +511,115 @@ mod imp {
             }
         }
 
+        /// Sends the volume to the DLNA renderer. Only one request is sent at a time, and
+        /// volume changes in the meantime (e.g. while dragging the slider) only send the
+        /// most recent value afterwards.
+        fn set_dlna_volume(&self, volume: f64) {
+            if self.dlna_volume_pending.replace(Some(volume)).is_some() {
+                return;
+            }
+
+            let sender = self.obj().dlna_sender().clone();
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                async move {
+                    while let Some(volume) = imp.dlna_volume_pending.get() {
+                        if let Err(e) = sender.set_volume_dlna(volume).await {
+                            warn!("Failed to set DLNA volume: {}", e);
+                        } else if imp.dlna_volume_pending.get() == Some(volume) {
+                            // Only save volume if DLNA device accepted it
+                            settings_manager::set_double(Key::PlaybackVolumeDlna, volume);
+                        }
+
+                        // Stop unless the volume has been changed during the request
+                        if imp.dlna_volume_pending.get() == Some(volume) {
+                            imp.dlna_volume_pending.set(None);
+                        }
+                    }
+                }
+            ));
+        }
+
+        pub fn set_muted(&self, muted: bool) {
+            if self.muted.replace(muted) == muted {
+                return;
+            }
+
+            debug!("Set muted: {}", muted);
+            let device = self.obj().device();
+            if device.is_some_and(|d| d.kind() == SwDeviceKind::Dlna) {
+                let sender = self.obj().dlna_sender().clone();
+                if sender.muted() != muted {
+                    glib::spawn_future_local(async move {
+                        if let Err(e) = sender.set_mute_dlna(muted).await {
+                            warn!("Failed to set DLNA mute: {}", e);
+                        }
+                    });
+                }
+            }
+
+            self.obj().notify_muted();
+        }
+
+        /// Takes over volume and mute changes reported by the DLNA renderer,
+        /// without sending them back to it
+        fn dlna_state_changed(&self, sender: &SwDlnaSender) {
+            let device = self.obj().device();
+            if !device.is_some_and(|d| d.kind() == SwDeviceKind::Dlna) {
+                return;
+            }
+
+            if self.muted.get() != sender.muted() {
+                self.muted.set(sender.muted());
+                self.obj().notify_muted();
+            }
+
+            // While muted the volume control stays at zero
+            let volume = sender.volume();
+            if !self.muted.get() && self.volume.get() != volume {
+                debug!("DLNA device volume changed to {}", volume);
+                self.volume.set(volume);
+                settings_manager::set_double(Key::PlaybackVolumeDlna, volume);
+                self.obj().notify_volume();
+            }
+        }
+
+        pub fn set_fade_duration(&self, seconds: f64) {
+            self.fade_duration.set(seconds);
+
+            let duration = std::time::Duration::from_secs_f64(seconds.max(0.0));
+            self.backend
+                .get()
+                .unwrap()
+                .borrow_mut()
+                .set_fade_duration(duration);
+        }
+
+        pub fn set_timeshift_duration(&self, minutes: i32) {
+            self.timeshift_duration.set(minutes);
+
+            let duration = gstreamer::ClockTime::from_seconds(minutes.max(0) as u64 * 60);
+            self.backend
+                .get()
+                .unwrap()
+                .borrow()
+                .set_timeshift_duration(duration);
+        }
+
+        fn apply_audio_filters(&self) {
+            let states: Vec<(String, bool)> = settings_manager::settings()
+                .value(&Key::PlaybackAudioFilters.to_string())
+                .get()
+                .unwrap_or_default();
+
+            self.backend
+                .get()
+                .unwrap()
+                .borrow_mut()
+                .set_filter_states(&states);
+        }
+
         pub fn set_recording_mode(&self, mode: SwRecordingMode) {
             if self.recording_mode.get() != mode {
                 debug!(
@@ -281,6 +628,7 @@ mod imp {
                     &mode
                 );
                 self.recording_mode.set(mode);
+                self.obj().notify_active_recording_mode();
 
                 if mode == SwRecordingMode::Nothing {
                     self.obj().cancel_recording();
@@ -294,6 +642,15 @@ mod imp {
                 GstreamerChange::PlaybackState(state) => self.gst_playback_change(&state),
                 GstreamerChange::Volume(volume) => self.gst_volume_change(volume),
                 GstreamerChange::Failure(f) => self.gst_failure(&f),
+                GstreamerChange::RecordingFailure(f) => self.gst_recording_failure(&f),
+                GstreamerChange::StreamUnavailable(f) => self.gst_stream_unavailable(f),
+                GstreamerChange::AudioFocus(has_focus) => self.gst_audio_focus(has_focus),
+                GstreamerChange::FilePlaybackState(state) => self.gst_file_playback_change(state),
+                GstreamerChange::Buffering(percent) => self.gst_buffering_change(percent),
+                GstreamerChange::AudioInfo(info) => self.gst_audio_info_change(&info),
+                GstreamerChange::RecordingLevel(peak, rms) => {
+                    self.gst_recording_level_change(peak, rms)
+                }
             }
 
             glib::ControlFlow::Continue
@@ -301,7 +658,18 @@ mod imp {
 
         fn gst_title_change(&self, title: &str) {
             debug!("Stream title has changed to: {}", title);
-            let track = SwTrack::new(title, &self.obj().station().unwrap());
+            let station = self.obj().station().unwrap();
+
+            // Normalize the title, in case the station uses an unusual format
+            let settings = StationSettings::load(&station.uuid());
+            let title = settings.title_parsing.apply(title);
+
+            // Rules can change the recording mode, which is already needed to create the track
+            let hour = glib::DateTime::now_local().unwrap().hour() as u32;
+            let rules = AutomationRule::matching(&station, &title, hour);
+            self.set_rule_recording_mode(rules.iter().find_map(|rule| rule.recording_mode));
+
+            let track = SwTrack::new(&title, &station);
 
             // Stop recording of old track
             self.stop_recording(RecordingStopReason::TrackChange);
@@ -310,7 +678,7 @@ mod imp {
             let mut is_playing_track_from_beginning = false;
             if let Some(track) = self.playing_track.borrow_mut().take() {
                 if track.state().include_in_past_tracks() {
-                    self.past_tracks.add_track(&track);
+                    self.add_past_track(&track);
                 }
 
                 *self.previous_track.borrow_mut() = Some(track);
@@ -318,7 +686,9 @@ mod imp {
                 is_playing_track_from_beginning = true;
             }
 
-            if self.obj().recording_mode() != SwRecordingMode::Nothing {
+            // Watched tracks get recorded regardless of the recording mode
+            let is_watched = WatchList::contains(&track.title());
+            if self.obj().active_recording_mode() != SwRecordingMode::Nothing || is_watched {
                 // If there is no previous track, we know that the current track is the
                 // first track we play from that station. This means that it would be
                 // incomplete, as we couldn't record it completely from the beginning.
@@ -333,16 +703,39 @@ mod imp {
                 }
             }
 
+            if is_watched && track.state() == SwRecordingState::Recording {
+                debug!("Track {:?} is on the watch list, save it when recorded.", track.title());
+                track.set_save_when_recorded(true);
+                track.connect_is_saved_notify(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move |track| imp.watched_track_saved(track)
+                ));
+            }
+
+            for rule in &rules {
+                self.apply_rule(rule, &track);
+            }
+
             // Set new track
             *self.playing_track.borrow_mut() = Some(track.clone());
             self.obj().notify_playing_track();
             self.obj().notify_has_playing_track();
 
             // Show desktop notification
-            if settings_manager::boolean(Key::Notifications) {
-                let id = format!("{}.TrackNotification", config::APP_ID);
-                SwApplication::default()
-                    .send_notification(Some(&id), &self.track_notification(&track));
+            if settings_manager::boolean(Key::Notifications) && !settings.mute_notifications {
+                let notification = self.track_notification(&track);
+                glib::spawn_future_local(async move {
+                    if !settings_manager::boolean(Key::NotificationsDoNotDisturb)
+                        && utils::do_not_disturb().await
+                    {
+                        debug!("Do not disturb is enabled, not showing track notification.");
+                        return;
+                    }
+
+                    let id = format!("{}.TrackNotification", config::APP_ID);
+                    SwApplication::default().send_notification(Some(&id), &notification);
+                });
             }
         }
 
@@ -356,13 +749,173 @@ mod imp {
                 }
             }
 
+            // The pipeline keeps running while playback is paused, so it may still
+            // report state changes, which shouldn't end the paused state.
+            let is_paused = self.backend.get().unwrap().borrow().is_paused();
+            let state = if is_paused && *state != SwPlaybackState::Failure {
+                &SwPlaybackState::Paused
+            } else {
+                state
+            };
+
+            if *state == SwPlaybackState::Playing && self.state.get() != SwPlaybackState::Playing {
+                self.backend.get().unwrap().borrow().fade_in();
+            }
+
+            if *state == SwPlaybackState::Stopped {
+                self.gst_buffering_change(0);
+            }
+
+            // Don't keep playback ducked for the next time it gets started
+            if *state == SwPlaybackState::Stopped && self.interrupted.replace(false) {
+                self.backend.get().unwrap().borrow().set_ducked(false);
+            }
+
             self.state.set(*state);
             self.obj().notify_state();
 
+            if *state == SwPlaybackState::Playing {
+                self.shuffle_failures.set(0);
+                self.station_failures.set(0);
+                self.failover_chain.borrow_mut().clear();
+            } else if *state == SwPlaybackState::Failure {
+                self.handle_failure();
+            }
+
+            self.update_listening_session(*state);
+
             // Inhibit session suspend when playback is active
             SwApplication::default().set_inhibit(state == &SwPlaybackState::Playing);
         }
 
+        /// Records how long stations get listened to in the station history
+        pub fn update_listening_session(&self, state: SwPlaybackState) {
+            let uuid = self
+                .obj()
+                .station()
+                .map(|station| station.uuid())
+                .filter(|_| state == SwPlaybackState::Playing);
+
+            let is_same_station = self
+                .listening_session
+                .borrow()
+                .as_ref()
+                .map(|(session_uuid, _)| Some(session_uuid) == uuid.as_ref());
+
+            // Loading doesn't interrupt the session of the current station
+            if is_same_station == Some(true) || state == SwPlaybackState::Loading {
+                return;
+            }
+
+            let now = glib::DateTime::now_local().unwrap();
+            if let Some((session_uuid, started_at)) = self.listening_session.take() {
+                let duration = now.difference(&started_at).as_seconds();
+                StationHistory::record(&session_uuid, &started_at, duration.max(0) as u64);
+            }
+
+            if let Some(uuid) = uuid {
+                *self.listening_session.borrow_mut() = Some((uuid, now));
+            }
+        }
+
+        /// Retries a failed station and switches to its backup station,
+        /// or to a random station when shuffling
+        fn handle_failure(&self) {
+            let library = SwApplication::default().library();
+            let Some(station) = self.obj().station() else {
+                return;
+            };
+
+            let backup = StationSettings::load(&station.uuid())
+                .backup_station
+                .and_then(|uuid| library.station_by_uuid(&uuid));
+
+            if let Some(backup) = backup {
+                let failures = self.station_failures.get() + 1;
+                self.station_failures.set(failures);
+
+                let obj = self.obj().clone();
+                if failures < FAILOVER_ATTEMPTS {
+                    info!("PLAYER: Retrying {:?} ({failures}/{FAILOVER_ATTEMPTS})", station.title());
+                    glib::spawn_future_local(async move {
+                        glib::timeout_future_seconds(FAILOVER_RETRY_DELAY).await;
+
+                        // Playback could have been changed in the meantime
+                        if obj.station() == Some(station) && obj.state() == SwPlaybackState::Failure {
+                            obj.start_playback().await;
+                        }
+                    });
+                } else if self.failover_chain.borrow().contains(&backup.uuid()) {
+                    // Backup stations referring to each other would be switched endlessly
+                    warn!("PLAYER: All backup stations of {:?} failed", station.title());
+                } else {
+                    let mut chain = self.failover_chain.borrow_mut();
+                    if chain.is_empty() {
+                        chain.push(station.uuid());
+                    }
+                    chain.push(backup.uuid());
+                    drop(chain);
+
+                    info!("PLAYER: Switching to backup station {:?}", backup.title());
+                    if let Some(window) = SwApplication::default().active_window() {
+                        let window = window.downcast::<SwApplicationWindow>().unwrap();
+                        window.show_notification(&i18n_f(
+                            "{} is unavailable, switched to {}",
+                            &[&station.title(), &backup.title()],
+                        ));
+                    }
+
+                    glib::spawn_future_local(async move {
+                        obj.set_station(backup).await;
+                    });
+                }
+            } else if self.shuffle.get() {
+                // Give up after several failures in a row, e.g. when the network is down
+                let failures = self.shuffle_failures.get() + 1;
+                self.shuffle_failures.set(failures);
+
+                if failures <= MAX_SHUFFLE_FAILURES {
+                    let obj = self.obj().clone();
+                    glib::spawn_future_local(async move {
+                        obj.shuffle_station().await;
+                    });
+                } else {
+                    warn!("PLAYER: Too many failed stations in a row, not shuffling anymore");
+                }
+            }
+        }
+
+        fn gst_audio_info_change(&self, info: &StreamAudioInfo) {
+            let format = info.label();
+            debug!("Stream audio format: {}", format);
+
+            *self.audio_format.borrow_mut() = format;
+            self.obj().notify_audio_format();
+            self.obj().notify_has_audio_format();
+        }
+
+        fn gst_buffering_change(&self, percent: i32) {
+            if self.buffering.get() != percent {
+                self.buffering.set(percent);
+                self.obj().notify_buffering();
+            }
+        }
+
+        fn set_recording_paused(&self, paused: bool) {
+            if self.recording_paused.get() != paused {
+                self.recording_paused.set(paused);
+                self.obj().notify_recording_paused();
+            }
+        }
+
+        fn gst_recording_level_change(&self, peak: f64, rms: f64) {
+            // Listeners only need to observe the peak, the rms is already updated by then
+            self.recording_rms.set(rms);
+            self.recording_peak.set(peak);
+            self.obj().notify_recording_rms();
+            self.obj().notify_recording_peak();
+        }
+
         fn gst_volume_change(&self, volume: f64) {
             if self.obj().device().is_some() {
                 return;
@@ -380,16 +933,168 @@ mod imp {
             }
         }
 
+        fn gst_audio_focus(&self, has_focus: bool) {
+            let obj = self.obj();
+            let backend = self.backend.get().unwrap().borrow();
+
+            if has_focus {
+                if !self.interrupted.replace(false) {
+                    return;
+                }
+
+                debug!("Audio output is available again, restore playback");
+                backend.set_ducked(false);
+                if obj.state() == SwPlaybackState::Paused {
+                    backend.resume();
+                }
+                return;
+            }
+
+            let behavior = settings_manager::string(Key::PlaybackInterruptionBehavior);
+            let behavior = SwInterruptionBehavior::from_str(&behavior).unwrap_or_default();
+            if behavior == SwInterruptionBehavior::Ignore || obj.state() != SwPlaybackState::Playing
+            {
+                return;
+            }
+
+            // Playback can only be paused with the time-shift buffer, duck it otherwise
+            if behavior == SwInterruptionBehavior::Pause && obj.can_pause() {
+                debug!("Audio output is requested by another application, pause playback");
+                backend.pause();
+            } else {
+                debug!("Audio output is requested by another application, duck playback");
+                backend.set_ducked(true);
+            }
+            self.interrupted.set(true);
+        }
+
+        pub fn gst_file_playback_change(&self, state: SwPlaybackState) {
+            if state == SwPlaybackState::Failure {
+                let title = i18n("Unable to play recorded track");
+                if let Some(window) = SwApplication::default().active_window() {
+                    let window = window.downcast::<SwApplicationWindow>().unwrap();
+                    window.show_notification(&title);
+                }
+            }
+
+            self.file_state.set(state);
+            self.obj().notify_file_state();
+            self.update_file_position();
+
+            if matches!(state, SwPlaybackState::Stopped | SwPlaybackState::Failure) {
+                *self.file_track.borrow_mut() = None;
+                self.obj().notify_file_track();
+
+                // Ends the poll of the renderer state as well
+                self.remote_next_track.take();
+                if self.file_remote.replace(false) {
+                    self.obj().notify_file_remote();
+                }
+            }
+        }
+
+        pub fn update_file_position(&self) {
+            // The renderer position gets read back by the remote file poll
+            if self.file_remote.get() {
+                return;
+            }
+
+            let backend = self.backend.get().unwrap().borrow();
+            let position = backend.file_position().unwrap_or_default();
+            let duration = backend.file_duration().unwrap_or_default();
+
+            self.file_position.set(position.mseconds() as f64 / 1000.0);
+            self.file_duration.set(duration.mseconds() as f64 / 1000.0);
+            self.obj().notify_file_position();
+            self.obj().notify_file_duration();
+        }
+
         fn gst_failure(&self, failure: &str) {
             *self.last_failure.borrow_mut() = failure.to_string();
             self.obj().notify_last_failure();
         }
 
+        /// The headless pipeline failed, while the device may still be playing fine
+        fn gst_recording_failure(&self, failure: &str) {
+            warn!("Recording stopped: {failure}");
+
+            // The track has not been recorded completely
+            if self.backend.get().unwrap().borrow().is_recording() {
+                self.stop_recording(RecordingStopReason::StreamFailure);
+            }
+            self.obj().stop_headless_pipeline();
+        }
+
+        fn gst_stream_unavailable(&self, failure: String) {
+            let station = self
+                .obj()
+                .station()
+                .filter(|station| !station.is_local() && self.obj().device().is_none());
+
+            let Some(station) = station.filter(|_| !self.stream_url_resolved.get()) else {
+                self.gst_playback_change(&SwPlaybackState::Failure);
+                self.gst_failure(&failure);
+                return;
+            };
+
+            info!(
+                "PLAYER: Stream of {} is unavailable, requesting updated station metadata",
+                station.title()
+            );
+            self.stream_url_resolved.set(true);
+
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                async move {
+                    let result = client::station_metadata_by_uuid(&station.uuid()).await;
+
+                    // The station could have been changed in the meantime
+                    if imp.obj().station().as_ref() != Some(&station) {
+                        return;
+                    }
+
+                    let old_url = station.stream_url();
+                    match result {
+                        Ok(Some(metadata)) => {
+                            station.set_metadata(metadata);
+                            SwApplication::default().library().update_station(&station);
+
+                            let new_url = station
+                                .stream_url()
+                                .filter(|url| Some(url) != old_url.as_ref());
+
+                            if let Some(url) = new_url {
+                                info!("PLAYER: Retrying playback with updated stream URL: {url}");
+                                let mut backend = imp.backend.get().unwrap().borrow_mut();
+                                backend.set_source_uri(url.as_ref());
+                                backend.set_state(gstreamer::State::Playing);
+                                return;
+                            }
+
+                            debug!("Station metadata contains no new stream URL");
+                        }
+                        Ok(None) => debug!("Station is no longer available in the directory"),
+                        Err(err) => warn!("Unable to update station metadata: {}", err.to_string()),
+                    }
+
+                    imp.gst_playback_change(&SwPlaybackState::Failure);
+                    imp.gst_failure(&failure);
+                }
+            ));
+        }
+
+        /// Adds a finished track to the past played tracks, which are kept across sessions
+        fn add_past_track(&self, track: &SwTrack) {
+            self.past_tracks.add_track(track);
+            TrackHistory::record(track, self.past_tracks.max_count());
+        }
+
         /// Unsets the current playing track and adds it to the past played tracks history
         pub fn reset_track(&self) {
             if let Some(track) = self.playing_track.borrow_mut().take() {
                 if track.state().include_in_past_tracks() {
-                    self.past_tracks.add_track(&track);
+                    self.add_past_track(&track);
                 }
             }
 
@@ -404,6 +1109,14 @@ mod imp {
             fs::create_dir_all(path.parent().unwrap())
                 .expect("Could not create path for recording");
 
+            // Only recordings in the data directory get recovered, the ones recorded directly
+            // into the track directory are left there as `.part` files
+            let mut temp_path = path::DATA.clone();
+            temp_path.push("recording");
+            if path.starts_with(temp_path) {
+                recording_recovery::store_recording_info(track);
+            }
+
             track.set_state(SwRecordingState::Recording);
             self.backend
                 .get()
@@ -413,6 +1126,7 @@ mod imp {
         }
 
         pub fn stop_recording(&self, reason: RecordingStopReason) {
+            self.set_recording_paused(false);
             let backend = &mut self.backend.get().unwrap().borrow_mut();
 
             if !backend.is_recording() {
@@ -426,7 +1140,7 @@ mod imp {
                 return;
             };
 
-            let mode = self.obj().recording_mode();
+            let mode = self.obj().active_recording_mode();
             let minimum_duration = settings_manager::integer(Key::RecordingMinimumDuration);
 
             let mut duration = backend.recording_duration();
@@ -458,7 +1172,7 @@ mod imp {
             // Check whether recorded track should be saved immediately
             let save_track = mode == SwRecordingMode::Everything || track.save_when_recorded();
             if track.state().is_recorded() && save_track {
-                track.save().handle_error("Unable to save track");
+                track.save_in_background();
             }
 
             debug!(
@@ -473,12 +1187,67 @@ mod imp {
 
             if discard_data {
                 debug!("Discard recorded data: {}", track.file().parse_name());
-                if let Err(err) = track.file().delete(gio::Cancellable::NONE) {
-                    warn!("Unable to discard recorded data: {}", err.to_string());
-                }
+                let file = track.file();
+                glib::spawn_future_local(async move {
+                    if let Err(err) = file.delete_future(glib::Priority::LOW).await {
+                        warn!("Unable to discard recorded data: {}", err.to_string());
+                    }
+                });
+            }
+        }
+
+        fn set_rule_recording_mode(&self, mode: Option<SwRecordingMode>) {
+            if self.rule_recording_mode.get() != mode {
+                debug!("Set recording mode of automation rules: {:?}", mode);
+                self.rule_recording_mode.set(mode);
+                self.obj().notify_active_recording_mode();
             }
         }
 
+        /// Executes the actions of a matching automation rule for the new track
+        fn apply_rule(&self, rule: &AutomationRule, track: &SwTrack) {
+            debug!(
+                "Automation rule {:?} matches track {:?}",
+                rule.name,
+                track.title()
+            );
+
+            if rule.save && track.state() == SwRecordingState::Recording {
+                track.set_save_when_recorded(true);
+            }
+
+            if rule.notify {
+                let notification = gio::Notification::new(&track.title());
+                notification.set_body(Some(&i18n_f("Matched rule “{}”", &[&rule.name])));
+
+                let icon = gio::ThemedIcon::new("emblem-music-symbolic");
+                notification.set_icon(&icon);
+
+                let target: glib::Variant = track.uuid().into();
+                notification.set_default_action_and_target_value("app.show-track", Some(&target));
+
+                let id = format!("{}.RuleNotification", config::APP_ID);
+                SwApplication::default().send_notification(Some(&id), &notification);
+            }
+        }
+
+        /// Removes the watched track from the watch list, and lets the user know it got captured
+        fn watched_track_saved(&self, track: &SwTrack) {
+            WatchList::remove(&track.title());
+
+            let notification = gio::Notification::new(&i18n("Watched Track Recorded"));
+            notification.set_body(Some(&track.title()));
+
+            let icon = gio::ThemedIcon::new("emblem-music-symbolic");
+            notification.set_icon(&icon);
+
+            let target: glib::Variant = track.uuid().into();
+            notification.set_default_action_and_target_value("app.show-track", Some(&target));
+
+            let id = format!("{}.WatchedTrackNotification", config::APP_ID);
+            SwApplication::default().send_notification(Some(&id), &notification);
+        }
+
         fn track_notification(&self, track: &SwTrack) -> gio::Notification {
             let notification = gio::Notification::new(&track.title());
             notification.set_body(Some(&track.station().title()));
@@ -490,7 +1259,7 @@ mod imp {
             notification.set_default_action_and_target_value("app.show-track", Some(&target));
 
             if track.state() == SwRecordingState::Recording {
-                if self.obj().recording_mode() == SwRecordingMode::Decide {
+                if self.obj().active_recording_mode() == SwRecordingMode::Decide {
                     notification.add_button_with_target_value(
                         &i18n("Save Track"),
                         "app.save-track",
@@ -498,8 +1267,8 @@ mod imp {
                     );
                 }
 
-                if self.obj().recording_mode() == SwRecordingMode::Everything
-                    || self.obj().recording_mode() == SwRecordingMode::Decide
+                if self.obj().active_recording_mode() == SwRecordingMode::Everything
+                    || self.obj().active_recording_mode() == SwRecordingMode::Decide
                 {
                     notification.add_button_with_target_value(
                         &i18n("Don't Record"),
@@ -524,7 +1293,23 @@ impl SwPlayer {
     }
 
     fn dlna_sender(&self) -> &SwDlnaSender {
-        self.imp().dlna_sender.get_or_init(|| SwDlnaSender::new())
+        self.imp().dlna_sender.get_or_init(|| {
+            let sender = SwDlnaSender::new();
+
+            // Reflect changes done on the renderer itself
+            sender.connect_volume_notify(clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |sender| obj.imp().dlna_state_changed(sender)
+            ));
+            sender.connect_muted_notify(clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |sender| obj.imp().dlna_state_changed(sender)
+            ));
+
+            sender
+        })
     }
 
     pub async fn set_station(&self, station: SwStation) {
@@ -539,6 +1324,17 @@ impl SwPlayer {
         debug!("Set station: {} (start_playback: {})", station.title(), start_playback);
         let imp = self.imp();
 
+        // A manually selected station gets played until it's changed again
+        imp.queue_remaining.set(0);
+        self.notify_queue_remaining();
+        imp.shuffle_elapsed.set(0);
+
+        // Only a backup station continues the failover chain of the previous station
+        imp.station_failures.set(0);
+        if imp.failover_chain.borrow().last() != Some(&station.uuid()) {
+            imp.failover_chain.borrow_mut().clear();
+        }
+
         // Check Chromecast compatibility BEFORE updating station metadata
         if let Some(url) = station.stream_url() {
             let url_str = url.to_string();
@@ -549,9 +1345,29 @@ impl SwPlayer {
 
         // If we get here, the station is compatible or no Chromecast is connected
         // Sequence for play-new: stop current playback -> update station UI -> load/start output.
-        self.stop_playback_for_station_change().await;
+        // Keep the local pipeline running, so that only its source needs to be swapped
+        let keep_pipeline = start_playback && self.device().is_none();
+        self.stop_playback_for_station_change(keep_pipeline).await;
+
+        // Credentials are stored in the keyring, so only request them when needed
+        let mut http_settings = StationSettings::load(&station.uuid()).http;
+        if http_settings.authentication {
+            let credentials = StationCredentials::load(&station.uuid()).await;
+            credentials.handle_error_toast(i18n("Unable to load station credentials"));
+
+            if let Ok(Some(credentials)) = credentials {
+                http_settings = http_settings.with_credentials(credentials);
+            }
+        }
 
         *imp.station.borrow_mut() = Some(station.clone());
+        imp.stream_url_resolved.set(false);
+
+        imp.backend
+            .get()
+            .unwrap()
+            .borrow()
+            .set_http_settings(http_settings);
         self.notify_station();
         self.notify_has_station();
 
@@ -565,11 +1381,9 @@ impl SwPlayer {
             // Only start local GStreamer audio if no remote device is selected
             if self.device().is_none() {
                 info!("PLAYER: No remote device selected - starting local audio playback");
-                imp.backend
-                    .get()
-                    .unwrap()
-                    .borrow_mut()
-                    .set_source_uri(url.as_ref());
+                let backend = imp.backend.get().unwrap();
+                backend.borrow().set_headless(false);
+                backend.borrow_mut().set_source_uri(url.as_ref());
                 
                 // Reapply saved volume after setting URI to ensure it's properly set in the audio system
                 let device_kind = self.device().map(|d| d.kind());
@@ -640,7 +1454,7 @@ impl SwPlayer {
                                         info!("PLAYER: Cast rejected new station - attempting FFmpeg proxy");
                                         
                                         // Try FFmpeg proxy
-                                        match self.dlna_sender().start_ffmpeg_with_wrapper(url.as_ref(), &title) {
+                                        match self.dlna_sender().start_ffmpeg_with_wrapper(url.as_ref(), &title, self.imp().backend.get().unwrap().borrow().http_settings(), self.device().map(|d| d.output_format()).unwrap_or_default()) {
                                             Ok(proxy_url) => {
                                                 info!("PLAYER: FFmpeg proxy started: {}", proxy_url);
                                                 *self.imp().cast_proxy_url.borrow_mut() = Some(proxy_url.clone());
@@ -718,6 +1532,7 @@ impl SwPlayer {
                 if let Some(device) = self.device() {
                     if device.kind() == SwDeviceKind::Cast && self.imp().cast_proxy_active.get() {
                         info!("PLAYER: Cast proxy already active, skipping redundant start_playback()");
+                        self.start_headless_pipeline();
                         // Set playback state to Playing since media is already loaded
                         if let Some(sender) = self.imp().gst_sender.get() {
                             let _ = sender.send_blocking(GstreamerChange::PlaybackState(SwPlaybackState::Playing));
@@ -751,6 +1566,15 @@ impl SwPlayer {
         if self.station().is_none() {
             return;
         }
+
+        // Only one thing at a time should be audible
+        self.stop_track_playback();
+
+        // Continue where playback got paused
+        if self.state() == SwPlaybackState::Paused {
+            self.resume_playback();
+            return;
+        }
         
         // Test Cast device connection before starting playback (handles suspend/resume)
         if let Some(device) = self.device() {
@@ -793,12 +1617,9 @@ impl SwPlayer {
         // Only start local GStreamer playback if no remote device is selected
         if self.device().is_none() {
             info!("PLAYER: Starting local GStreamer playback");
-            self.imp()
-                .backend
-                .get()
-                .unwrap()
-                .borrow_mut()
-                .set_state(gstreamer::State::Playing);
+            let backend = self.imp().backend.get().unwrap();
+            backend.borrow().set_headless(false);
+            backend.borrow_mut().set_state(gstreamer::State::Playing);
             
             // Set volume AFTER state transition to prevent GStreamer from resetting it
             info!("PLAYER: Setting volume {} after GStreamer state transition", saved_volume);
@@ -812,6 +1633,7 @@ impl SwPlayer {
         } else {
             info!("PLAYER: Remote device active - setting volume for remote device");
             self.set_volume(saved_volume);
+            self.start_headless_pipeline();
         }
 
         // Handle remote device playback
@@ -861,8 +1683,8 @@ impl SwPlayer {
                                 } else if is_compatibility_error {
                                     info!("PLAYER: Cast device rejected stream - attempting FFmpeg proxy transcoding");
                                     
-                                    // Try to start FFmpeg proxy to transcode to MP3
-                                    match self.dlna_sender().start_ffmpeg_with_wrapper(url.as_ref(), &title) {
+                                    // Try to start FFmpeg proxy to transcode into a format the device supports
+                                    match self.dlna_sender().start_ffmpeg_with_wrapper(url.as_ref(), &title, self.imp().backend.get().unwrap().borrow().http_settings(), self.device().map(|d| d.output_format()).unwrap_or_default()) {
                                         Ok(proxy_url) => {
                                             info!("PLAYER: FFmpeg proxy started successfully: {}", proxy_url);
                                             *self.imp().cast_proxy_url.borrow_mut() = Some(proxy_url.clone());
@@ -921,7 +1743,7 @@ impl SwPlayer {
                                     let title = station.title();
                                     let cover_url = station.custom_cover().map(|_| "".to_string()).unwrap_or_default();
                                     
-                                    match self.dlna_sender().start_ffmpeg_with_wrapper(url.as_ref(), &title) {
+                                    match self.dlna_sender().start_ffmpeg_with_wrapper(url.as_ref(), &title, self.imp().backend.get().unwrap().borrow().http_settings(), self.device().map(|d| d.output_format()).unwrap_or_default()) {
                                         Ok(proxy_url) => {
                                             info!("PLAYER: FFmpeg proxy started successfully: {}", proxy_url);
                                             *self.imp().cast_proxy_url.borrow_mut() = Some(proxy_url.clone());
@@ -956,9 +1778,10 @@ impl SwPlayer {
                                                 return;
                                             }
                                             
+                                            // The receiver reports when playback actually started
                                             info!("PLAYER: ✅ Cast playback started successfully with FFmpeg proxy");
                                             if let Some(sender) = self.imp().gst_sender.get() {
-                                                let _ = sender.send_blocking(GstreamerChange::PlaybackState(SwPlaybackState::Playing));
+                                                let _ = sender.send_blocking(GstreamerChange::PlaybackState(SwPlaybackState::Loading));
                                             }
                                         }
                                         Err(ffmpeg_err) => {
@@ -984,10 +1807,10 @@ impl SwPlayer {
                             Err::<(), cast_sender::Error>(e).handle_error("Unable to start Google Cast playback");
                         }
                     } else {
-                        // Set Playing state after Cast command completes
-                        info!("PLAYER: Setting Chromecast playback state to Playing");
+                        // The receiver reports when playback actually started, until then it's loading
+                        info!("PLAYER: Cast playback requested, waiting for the receiver");
                         if let Some(sender) = self.imp().gst_sender.get() {
-                            let _ = sender.send_blocking(GstreamerChange::PlaybackState(SwPlaybackState::Playing));
+                            let _ = sender.send_blocking(GstreamerChange::PlaybackState(SwPlaybackState::Loading));
                         }
                     }
                 }
@@ -1091,13 +1914,13 @@ impl SwPlayer {
         });
     }
     
-    async fn execute_dlna_playback_with_yields(&self, saved_volume: f64) -> Result<(), Box<dyn std::error::Error>> {
+    async fn execute_dlna_playback_with_yields(&self, saved_volume: f64) -> Result<(), DeviceError> {
         let dlna_sender = self.dlna_sender();
         let station = self.station().unwrap();
         
         // Step 1: Apply saved volume to DLNA device
         info!("PLAYER: Step 1 - Setting DLNA device volume to {}", saved_volume);
-        if let Err(e) = dlna_sender.set_volume_dlna(saved_volume) {
+        if let Err(e) = dlna_sender.set_volume_dlna(saved_volume).await {
             warn!("PLAYER: ⚠️ Failed to set DLNA volume: {}", e);
         } else {
             info!("PLAYER: ✅ Volume set successfully");
@@ -1122,7 +1945,7 @@ impl SwPlayer {
             info!("PLAYER: ✅ FFmpeg proxy already running - sending play command only");
             // Only send play command if proxy is already running
             info!("PLAYER: Step 3 - Sending Play command to DLNA device");
-            dlna_sender.start_playback()?;
+            dlna_sender.start_playback().await?;
             info!("PLAYER: ✅ Step 3 COMPLETE - Play command sent successfully");
         } else {
             info!("PLAYER: ℹ️ FFmpeg proxy not running - starting full setup");
@@ -1137,7 +1960,9 @@ impl SwPlayer {
                 
                 // Yield before starting FFmpeg to allow UI updates
                 Self::yield_to_ui().await;
-                
+
+                self.load_dlna_cover(&station).await;
+                let http_settings = self.imp().backend.get().unwrap().borrow().http_settings();
                 dlna_sender.load_media(
                     url.as_ref(),
                     &station
@@ -1146,7 +1971,8 @@ impl SwPlayer {
                         .map(|u| u.to_string())
                         .unwrap_or_default(),
                     &station.title(),
-                )?;
+                    http_settings,
+                ).await?;
                 info!("PLAYER: ✅ Step 3 COMPLETE - FFmpeg proxy started and URL sent to device");
             } else {
                 error!("PLAYER: ❌ No stream URL available for station");
@@ -1157,16 +1983,46 @@ impl SwPlayer {
         Ok(())
     }
     
+    /// Loads the station cover, so that it can be served to the DLNA renderer
+    async fn load_dlna_cover(&self, station: &SwStation) {
+        let Some(favicon_url) = station.metadata().favicon else {
+            self.dlna_sender().set_cover(None);
+            return;
+        };
+
+        // The cover loader prioritizes requests by the visibility of the widget
+        let widget: gtk::Widget = SwApplication::default()
+            .active_window()
+            .map(|window| window.upcast())
+            .unwrap_or_else(|| gtk::Image::new().upcast());
+
+        let mut cover_loader = SwApplication::default().cover_loader();
+        let res = cover_loader
+            .load_cover(&favicon_url, DLNA_COVER_SIZE, gio::Cancellable::new(), &widget)
+            .await;
+
+        match res {
+            Ok(texture) => {
+                let png = texture.save_to_png_bytes();
+                self.dlna_sender().set_cover(Some(png.to_vec()));
+            }
+            Err(e) => {
+                debug!("PLAYER: Unable to load cover for DLNA device: {}", e);
+                self.dlna_sender().set_cover(None);
+            }
+        }
+    }
+
     async fn yield_to_ui() {
         // Create a small delay to allow UI updates
         // This is a workaround since yield_yield is not available
         glib::timeout_future(std::time::Duration::from_millis(10)).await;
     }
 
-    async fn start_dlna_playback_sequence(&self, saved_volume: f64) -> Result<(), Box<dyn std::error::Error>> {
+    async fn start_dlna_playback_sequence(&self, saved_volume: f64) -> Result<(), DeviceError> {
         // Apply saved volume to DLNA device
         info!("PLAYER: Step 1 - Setting DLNA device volume to {}", saved_volume);
-        if let Err(e) = self.dlna_sender().set_volume_dlna(saved_volume) {
+        if let Err(e) = self.dlna_sender().set_volume_dlna(saved_volume).await {
             warn!("PLAYER: ⚠️ Failed to set DLNA volume: {}", e);
         } else {
             info!("PLAYER: ✅ Volume set successfully");
@@ -1189,7 +2045,7 @@ impl SwPlayer {
             info!("PLAYER: ✅ FFmpeg proxy already running - sending play command only");
             // Only send play command if proxy is already running
             info!("PLAYER: Step 3 - Sending Play command to DLNA device");
-            dlna_sender.start_playback()?;
+            dlna_sender.start_playback().await?;
             info!("PLAYER: ✅ Step 3 COMPLETE - Play command sent successfully");
         } else {
             info!("PLAYER: ℹ️ FFmpeg proxy not running - starting full setup");
@@ -1202,7 +2058,9 @@ impl SwPlayer {
                 
                 if let Some(url) = station.stream_url() {
                     info!("PLAYER: Original Stream URL: {}", url);
-                    
+
+                    self.load_dlna_cover(&station).await;
+                    let http_settings = self.imp().backend.get().unwrap().borrow().http_settings();
                     dlna_sender.load_media(
                         url.as_ref(),
                         &station
@@ -1211,7 +2069,8 @@ impl SwPlayer {
                             .map(|u| u.to_string())
                             .unwrap_or_default(),
                         &station.title(),
-                    )?;
+                        http_settings,
+                    ).await?;
                     info!("PLAYER: ✅ Step 3 COMPLETE - FFmpeg proxy started and URL sent to device");
                 } else {
                     error!("PLAYER: ❌ No stream URL available for station");
@@ -1233,7 +2092,11 @@ impl SwPlayer {
             println!("🔵 TOGGLE: Current state: {:?}", self.state());
         }
         
-        if self.state() == SwPlaybackState::Playing || self.state() == SwPlaybackState::Loading {
+        if self.state() == SwPlaybackState::Playing && self.can_pause() {
+            self.pause_playback();
+        } else if self.state() == SwPlaybackState::Paused {
+            self.resume_playback();
+        } else if self.state() == SwPlaybackState::Playing || self.state() == SwPlaybackState::Loading {
             #[cfg(feature = "dlna-debug")]
             println!("🔵 TOGGLE: State is Playing/Loading - calling stop_playback()");
             self.stop_playback().await;
@@ -1249,7 +2112,454 @@ impl SwPlayer {
         println!("🔵 TOGGLE: toggle_playback() completed");
     }
 
-    pub async fn stop_playback_for_station_change(&self) {
+    /// Adds a station to the queue, which gets played for `duration` minutes
+    pub fn enqueue_station(&self, station: &SwStation, duration: u32) {
+        info!("PLAYER: Add {:?} to the queue", station.title());
+        self.queue().add_entry(&SwQueueEntry::new(station, duration));
+    }
+
+    /// Switches to the next station of the queue
+    pub async fn play_next(&self) {
+        let Some(entry) = self.queue().pop_front() else {
+            return;
+        };
+
+        info!("PLAYER: Play next station from queue");
+        self.set_station(entry.station()).await;
+
+        self.imp().queue_remaining.set(u64::from(entry.duration()) * 60);
+        self.notify_queue_remaining();
+    }
+
+    /// Switches to a random library station
+    pub async fn shuffle_station(&self) {
+        let Some(station) = SwApplication::default().library().random_station() else {
+            debug!("No library station available for shuffling");
+            return;
+        };
+
+        info!("PLAYER: Shuffle to station {:?}", station.title());
+        self.set_station(station).await;
+    }
+
+    /// Plays a library station which fits the listening history at the current time of day
+    pub async fn play_suggestion(&self) {
+        let library = SwApplication::default().library();
+        let current = self.station().map(|station| station.uuid());
+
+        let mut stations = library.stations();
+        stations.retain(|station| Some(station.uuid()) != current);
+
+        let Some(station) = StationHistory::suggest(&stations).or_else(|| library.random_station())
+        else {
+            debug!("No library station available for a suggestion");
+            return;
+        };
+
+        info!("PLAYER: Play suggested station {:?}", station.title());
+        self.set_station(station).await;
+    }
+
+    /// Plays a saved track from its file, live playback gets paused meanwhile
+    pub async fn play_track(&self, track: &SwTrack) {
+        let Some(file) = track.saved_to() else {
+            debug!("Track not saved, not able to play it.");
+            return;
+        };
+
+        if self.state() == SwPlaybackState::Playing {
+            if self.can_pause() {
+                self.pause_playback();
+            } else {
+                self.stop_playback().await;
+            }
+        }
+
+        info!("PLAYER: Play recorded track \"{}\"", track.title());
+        *self.imp().file_track.borrow_mut() = Some(track.clone());
+        self.notify_file_track();
+
+        if self
+            .device()
+            .is_some_and(|d| d.kind() == SwDeviceKind::Dlna)
+        {
+            self.play_track_remote(track, &file).await;
+            return;
+        }
+
+        let backend = self.imp().backend.get().unwrap().borrow();
+        backend.set_mute(self.muted());
+        backend.play_file(&file.uri(), self.volume());
+    }
+
+    /// Plays the recorded track on the DLNA renderer, which fetches the file from us
+    async fn play_track_remote(&self, track: &SwTrack, file: &gio::File) {
+        let imp = self.imp();
+        let Some(path) = file.path() else {
+            return;
+        };
+
+        // The previous track must not be reported as stopped while the new one gets loaded
+        self.stop_remote_file_poll();
+        imp.gst_file_playback_change(SwPlaybackState::Loading);
+
+        if let Err(e) = self.dlna_sender().load_file(&path, &track.title()).await {
+            warn!("Unable to play recorded track on DLNA device: {}", e);
+            imp.gst_file_playback_change(SwPlaybackState::Failure);
+            return;
+        }
+
+        imp.file_remote.set(true);
+        self.notify_file_remote();
+        imp.file_position.set(0.0);
+        imp.file_duration.set(track.duration() as f64);
+        imp.gst_file_playback_change(SwPlaybackState::Playing);
+
+        self.queue_next_remote_track().await;
+        self.start_remote_file_poll();
+    }
+
+    /// Queues the saved track which follows the played one on the renderer
+    async fn queue_next_remote_track(&self) {
+        let Some((track, path)) = self
+            .next_saved_track()
+            .and_then(|track| Some((track.clone(), track.saved_to()?.path()?)))
+        else {
+            self.imp().remote_next_track.take();
+            return;
+        };
+
+        match self.dlna_sender().queue_file(&path, &track.title()).await {
+            Ok(uri) => *self.imp().remote_next_track.borrow_mut() = Some((track, uri)),
+            Err(e) => {
+                // Not all renderers support queuing, `play_next_track()` loads it directly then
+                debug!("Unable to queue next track on DLNA device: {}", e);
+                self.imp().remote_next_track.take();
+            }
+        }
+    }
+
+    /// Reads back position and transport state of the renderer while it plays a
+    /// recorded track, and follows when it advances to the queued track
+    fn start_remote_file_poll(&self) {
+        self.stop_remote_file_poll();
+
+        let obj = self.downgrade();
+        let handle = glib::spawn_future_local(async move {
+            // Renderers report "STOPPED" for a moment until they start playing
+            let mut started = false;
+
+            loop {
+                glib::timeout_future_seconds(1).await;
+
+                let Some(sender) = obj
+                    .upgrade()
+                    .filter(|player| player.file_remote())
+                    .map(|player| player.dlna_sender().clone())
+                else {
+                    break;
+                };
+
+                let state = sender.transport_state().await;
+                let position = sender.position_info().await;
+
+                let Some(player) = obj.upgrade().filter(|player| player.file_remote()) else {
+                    break;
+                };
+                let imp = player.imp();
+
+                let state = match state.as_deref() {
+                    Ok("PLAYING") => SwPlaybackState::Playing,
+                    Ok("PAUSED_PLAYBACK") => SwPlaybackState::Paused,
+                    Ok("STOPPED") | Ok("NO_MEDIA_PRESENT") if started => {
+                        imp.gst_file_playback_change(SwPlaybackState::Stopped);
+                        break;
+                    }
+                    Ok(_) => imp.file_state.get(),
+                    Err(e) => {
+                        debug!("Unable to read DLNA transport state: {}", e);
+                        continue;
+                    }
+                };
+                started |= state == SwPlaybackState::Playing;
+
+                if let Ok(position) = position {
+                    let next = imp.remote_next_track.borrow().clone();
+                    if let Some((track, _)) = next.filter(|(_, uri)| *uri == position.uri) {
+                        info!(
+                            "PLAYER: DLNA device advanced to track \"{}\"",
+                            track.title()
+                        );
+                        *imp.file_track.borrow_mut() = Some(track);
+                        player.notify_file_track();
+                        player.queue_next_remote_track().await;
+                    }
+
+                    imp.file_position.set(position.position);
+                    if position.duration > 0.0 {
+                        imp.file_duration.set(position.duration);
+                    }
+                    player.notify_file_position();
+                    player.notify_file_duration();
+                }
+
+                if imp.file_state.get() != state {
+                    imp.file_state.set(state);
+                    player.notify_file_state();
+                }
+            }
+        });
+
+        *self.imp().remote_file_poll.borrow_mut() = Some(handle);
+    }
+
+    fn stop_remote_file_poll(&self) {
+        if let Some(handle) = self.imp().remote_file_poll.borrow_mut().take() {
+            handle.abort();
+        }
+    }
+
+    /// Saved track which follows the played one in the list of past tracks
+    fn next_saved_track(&self) -> Option<SwTrack> {
+        let current = self.file_track()?;
+        let tracks = self.past_tracks().snapshot();
+        let position = tracks
+            .iter()
+            .position(|track| track.downcast_ref::<SwTrack>() == Some(&current))?;
+
+        tracks
+            .into_iter()
+            .skip(position + 1)
+            .filter_map(|track| track.downcast::<SwTrack>().ok())
+            .find(|track| track.is_saved())
+    }
+
+    /// Whether there's a saved track which can be played with `play_next_track()`
+    pub fn has_next_track(&self) -> bool {
+        self.file_remote() && self.next_saved_track().is_some()
+    }
+
+    /// Advances to the next saved track while playing recorded tracks on the DLNA renderer
+    pub async fn play_next_track(&self) {
+        if !self.file_remote() {
+            return;
+        }
+
+        let queued = self.imp().remote_next_track.borrow().clone();
+        if queued.is_some() {
+            // The poll follows once the renderer reports the queued track
+            match self.dlna_sender().next().await {
+                Ok(()) => return,
+                Err(e) => debug!("DLNA device is unable to advance to the next track: {}", e),
+            }
+        }
+
+        if let Some(track) = self.next_saved_track() {
+            self.play_track(&track).await;
+        }
+    }
+
+    pub fn toggle_track_playback(&self) {
+        let paused = self.file_state() == SwPlaybackState::Playing;
+
+        if self.file_remote() {
+            let obj = self.clone();
+            glib::spawn_future_local(async move {
+                let sender = obj.dlna_sender();
+                let res = if paused {
+                    sender.pause_playback().await
+                } else {
+                    sender.start_playback().await
+                };
+
+                match res {
+                    Ok(()) if paused => obj.imp().gst_file_playback_change(SwPlaybackState::Paused),
+                    Ok(()) => obj.imp().gst_file_playback_change(SwPlaybackState::Playing),
+                    Err(e) => warn!("Unable to toggle track playback on DLNA device: {}", e),
+                }
+            });
+            return;
+        }
+
+        self.imp()
+            .backend
+            .get()
+            .unwrap()
+            .borrow()
+            .set_file_paused(paused);
+    }
+
+    /// Ends the playback of the recorded track. On a DLNA renderer the track keeps playing
+    /// until the station media replaces it.
+    pub fn stop_track_playback(&self) {
+        if self.file_remote() {
+            self.stop_remote_file_poll();
+            self.imp()
+                .gst_file_playback_change(SwPlaybackState::Stopped);
+        } else if self.file_track().is_some() {
+            self.imp().backend.get().unwrap().borrow().stop_file();
+        }
+    }
+
+    /// Seeks to the given position in seconds
+    pub fn seek_track(&self, position: f64) {
+        if self.file_remote() {
+            self.imp().file_position.set(position);
+            self.notify_file_position();
+
+            let sender = self.dlna_sender().clone();
+            glib::spawn_future_local(async move {
+                if let Err(e) = sender.seek(position).await {
+                    warn!("Unable to seek on DLNA device: {}", e);
+                }
+            });
+            return;
+        }
+
+        let position = gstreamer::ClockTime::from_mseconds((position * 1000.0) as u64);
+        self.imp().backend.get().unwrap().borrow().seek_file(position);
+        self.imp().update_file_position();
+    }
+
+    /// Whether playback can be paused using the time-shift buffer
+    pub fn can_pause(&self) -> bool {
+        self.device().is_none() && self.timeshift_duration() > 0
+    }
+
+    pub fn pause_playback(&self) {
+        if !self.can_pause() {
+            return;
+        }
+
+        info!("PLAYER: Pause playback");
+        self.imp().backend.get().unwrap().borrow().pause();
+    }
+
+    pub fn resume_playback(&self) {
+        info!("PLAYER: Resume playback");
+        self.stop_track_playback();
+        self.imp().backend.get().unwrap().borrow().resume();
+    }
+
+    /// Plays the last 30 seconds again
+    pub fn replay(&self) {
+        if self.device().is_some() || self.timeshift_duration() <= 0 {
+            return;
+        }
+
+        info!("PLAYER: Replay last 30 seconds");
+        let backend = self.imp().backend.get().unwrap().borrow();
+        backend.rewind(gstreamer::ClockTime::from_seconds(30));
+
+        if backend.is_paused() {
+            backend.resume();
+        }
+    }
+
+    /// Saves the last minutes of the stream from the time-shift buffer into the
+    /// track directory, e.g. to keep a track which has already been played.
+    /// Returns the file together with the duration of the saved audio in seconds.
+    pub async fn save_recent_audio(&self) -> anyhow::Result<(gio::File, u64)> {
+        let Some(station) = self.station() else {
+            anyhow::bail!(i18n("No station is playing"));
+        };
+
+        if self.device().is_some() || self.timeshift_duration() <= 0 {
+            anyhow::bail!(i18n("The time-shift buffer is disabled"));
+        }
+
+        let path = SwRecorder::destination_path(&station);
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+
+        let duration = gstreamer::ClockTime::from_seconds(SAVE_RECENT_DURATION.as_secs());
+        let future = self
+            .imp()
+            .backend
+            .get()
+            .unwrap()
+            .borrow()
+            .save_timeshift(duration, path.clone());
+        let saved = future.await?;
+
+        info!(
+            "PLAYER: Saved the last {} seconds to {:?}",
+            saved.seconds(),
+            path
+        );
+        Ok((gio::File::for_path(path), saved.seconds()))
+    }
+
+    /// Registers a filter which can insert its own elements into the playback path
+    pub fn register_audio_filter(&self, filter: Box<dyn AudioFilter>) {
+        let imp = self.imp();
+        imp.backend.get().unwrap().borrow_mut().register_filter(filter);
+        imp.apply_audio_filters();
+    }
+
+    /// Graphviz description of the playback pipeline, for bug reports
+    pub fn pipeline_graph(&self) -> String {
+        self.imp().backend.get().unwrap().borrow().pipeline_graph()
+    }
+
+    /// Address of the FFmpeg proxy which streams to the connected device, if it's running
+    pub fn device_proxy_url(&self) -> Option<String> {
+        match self.device()?.kind() {
+            SwDeviceKind::Cast => self
+                .imp()
+                .cast_proxy_url
+                .borrow()
+                .clone()
+                .filter(|_| self.imp().cast_proxy_active.get()),
+            SwDeviceKind::Dlna => self.dlna_sender().proxy_url(),
+        }
+    }
+
+    pub fn jump_to_live(&self) {
+        info!("PLAYER: Jump to live");
+        let backend = self.imp().backend.get().unwrap().borrow();
+        backend.jump_to_live();
+
+        if backend.is_paused() {
+            backend.resume();
+        }
+    }
+
+    /// Keeps receiving the stream locally while a device plays it, so that
+    /// tracks still get recorded. The local audio output stays silent.
+    fn start_headless_pipeline(&self) {
+        if self.imp().active_recording_mode() == SwRecordingMode::Nothing {
+            return;
+        }
+
+        let Some(url) = self.station().and_then(|station| station.stream_url()) else {
+            return;
+        };
+
+        info!("PLAYER: Starting headless local pipeline for recording");
+        let mut backend = self.imp().backend.get().unwrap().borrow_mut();
+        backend.set_headless(true);
+        backend.set_source_uri(url.as_ref());
+        backend.set_state(gstreamer::State::Playing);
+    }
+
+    fn stop_headless_pipeline(&self) {
+        let imp = self.imp();
+        if !imp.backend.get().unwrap().borrow().is_headless() {
+            return;
+        }
+
+        info!("PLAYER: Stopping headless local pipeline");
+        imp.stop_recording(imp::RecordingStopReason::StoppedPlayback);
+        imp.reset_track();
+
+        let mut backend = imp.backend.get().unwrap().borrow_mut();
+        backend.set_state(gstreamer::State::Null);
+        backend.set_headless(false);
+    }
+
+    pub async fn stop_playback_for_station_change(&self, keep_pipeline: bool) {
         #[cfg(feature = "dlna-debug")]
         {
             println!("=== STOP FOR STATION CHANGE ===");
@@ -1265,6 +2575,12 @@ impl SwPlayer {
         println!("🔴 STOP: Device type: {:?}", device_kind);
         info!("PLAYER: Device before stop: {:?}", device_kind);
 
+        // Avoid an abrupt cut when stopping local playback
+        if device_before_stop.is_none() && self.state() == SwPlaybackState::Playing {
+            let fade_out = imp.backend.get().unwrap().borrow().fade_out();
+            fade_out.await;
+        }
+
         // Discard recorded data when the stream stops
         #[cfg(feature = "dlna-debug")]
         println!("🔴 STOP: Stopping recording and resetting track");
@@ -1272,13 +2588,15 @@ impl SwPlayer {
         imp.reset_track();
 
         // Stop GStreamer backend
-        #[cfg(feature = "dlna-debug")]
-        println!("🔴 STOP: Setting GStreamer to Null state");
-        imp.backend
-            .get()
-            .unwrap()
-            .borrow_mut()
-            .set_state(gstreamer::State::Null);
+        if !keep_pipeline {
+            #[cfg(feature = "dlna-debug")]
+            println!("🔴 STOP: Setting GStreamer to Null state");
+            imp.backend
+                .get()
+                .unwrap()
+                .borrow_mut()
+                .set_state(gstreamer::State::Null);
+        }
 
         // NOTE: Don't send stop to Cast device when changing stations
         // This prevents "Invalid Request" errors when nothing is playing
@@ -1384,7 +2702,7 @@ impl SwPlayer {
                     }
                     info!("PLAYER: Stopping DLNA playback");
                     
-                    if let Err(e) = self.dlna_sender().stop_playback() {
+                    if let Err(e) = self.dlna_sender().stop_playback().await {
                         #[cfg(feature = "dlna-debug")]
                         println!("🔴 STOP: ❌ Failed to stop DLNA playback: {}", e);
                         warn!("PLAYER: Failed to stop DLNA playback: {}", e);
@@ -1416,11 +2734,98 @@ impl SwPlayer {
         info!("PLAYER: stop_playback() completed");
     }
 
+    /// Restores the past played tracks of previous sessions
+    pub fn restore_past_tracks(&self) {
+        let tracks = TrackHistory::load(self.past_tracks().max_count());
+
+        // Oldest first, so that the most recent track ends up at the top
+        for track in tracks.iter().rev() {
+            self.past_tracks().add_track(track);
+        }
+    }
+
+    /// Makes recordings from a previous session which didn't shut down properly available
+    pub fn recover_recordings(&self) {
+        for track in recording_recovery::recovered_tracks() {
+            info!("Recovered recording {:?}", track.title());
+            self.recovered_tracks().add_track(&track);
+        }
+    }
+
+    /// Removes the temporary recording directory, needs to be called on a clean shutdown.
+    /// Otherwise the recordings are considered orphaned on the next start.
+    /// Stores the listening period of the current station in the station history
+    pub fn finish_listening_session(&self) {
+        self.imp().update_listening_session(SwPlaybackState::Stopped);
+    }
+
+    pub fn discard_temporary_recordings(&self) {
+        let mut path = path::DATA.clone();
+        path.push("recording");
+
+        if path.exists() {
+            if let Err(err) = fs::remove_dir_all(path) {
+                warn!("Unable to remove temporary recording directory: {err}");
+            }
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.imp().backend.get().unwrap().borrow().is_recording()
+    }
+
     pub fn cancel_recording(&self) {
         let imp = self.imp();
         imp.stop_recording(imp::RecordingStopReason::Cancelled);
     }
 
+    /// Skips a part of the track, e.g. an ad break. The recording continues in the
+    /// same file after it got resumed.
+    pub fn pause_recording(&self) {
+        let imp = self.imp();
+        if !self.is_recording() {
+            return;
+        }
+
+        imp.backend.get().unwrap().borrow_mut().pause_recording();
+        imp.set_recording_paused(true);
+    }
+
+    pub fn resume_recording(&self) {
+        let imp = self.imp();
+        imp.backend.get().unwrap().borrow_mut().resume_recording();
+        imp.set_recording_paused(false);
+    }
+
+    /// Stops the current recording and saves the data recorded so far, even if the
+    /// track hasn't been played completely yet
+    pub async fn save_recording(&self) -> Result<(), Error> {
+        let Some(track) = self.playing_track() else {
+            return Ok(());
+        };
+
+        self.imp().stop_recording(imp::RecordingStopReason::Quit);
+
+        // Wait until the end of the recording got written, otherwise the file gets truncated
+        let backend = self.imp().backend.get().unwrap();
+        for _ in 0..50 {
+            if !backend.borrow().is_finalizing_recording() {
+                break;
+            }
+            glib::timeout_future(Duration::from_millis(100)).await;
+        }
+
+        // The track may be already getting saved in background (e.g. "Everything" mode)
+        if track.is_saving() {
+            while track.is_saving() {
+                glib::timeout_future(std::time::Duration::from_millis(100)).await;
+            }
+            Ok(())
+        } else {
+            track.save().await
+        }
+    }
+
     pub fn restore_state(&self) {
         let imp = self.imp();
 
@@ -1479,7 +2884,8 @@ impl SwPlayer {
                     #[weak]
                     station,
                     async move {
-                        obj.set_station_with_playback(station, false).await;
+                        let resume = settings_manager::boolean(Key::StartupResumePlayback);
+                        obj.set_station_with_playback(station, resume).await;
                     }
                 ));
             }
@@ -1487,7 +2893,7 @@ impl SwPlayer {
         }
     }
 
-    pub async fn connect_device(&self, device: &SwDevice) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn connect_device(&self, device: &SwDevice) -> Result<(), DeviceError> {
         // Note: Cast compatibility is determined by device response, not by checking URL
         // This allows the device itself to determine what formats it supports
         
@@ -1526,12 +2932,21 @@ impl SwPlayer {
 
         let result = match device.kind() {
             SwDeviceKind::Cast => {
-                if let Err(e) = self.cast_sender()
-                    .connect(&device.address())
-                    .await
-                {
-                    return Err(Box::new(e) as Box<dyn std::error::Error>);
+                let connect = self.cast_sender().connect(&device.address());
+                let timeout = Timer::after(CAST_CONNECT_TIMEOUT);
+                match select(pin!(connect), pin!(timeout)).await {
+                    Either::Left((Ok(()), _)) => (),
+                    Either::Left((Err(e), _)) => return Err(e.into()),
+                    Either::Right(_) => {
+                        warn!("PLAYER: Connecting to Cast device timed out");
+                        self.cast_sender().disconnect().await;
+                        return Err(DeviceError::Timeout);
+                    }
                 }
+                device.set_supported_formats(self.cast_sender().supported_formats());
+
+                // Announce a proxy address which is reachable by the receiver
+                self.dlna_sender().update_local_ip(&device.address()).await;
                 
                 // Stop any existing playback to prevent previous station from auto-playing
                 info!("PLAYER: Stopping any existing Cast playback");
@@ -1559,10 +2974,11 @@ impl SwPlayer {
                 self.dlna_sender().stop_ffmpeg_server();
                 
                 info!("PLAYER: Step 2 - Connecting to DLNA device to fetch service URLs");
-                match self.dlna_sender().connect(&device.address()) {
+                match self.dlna_sender().connect(&device.address()).await {
                     Ok(_) => {
                         info!("PLAYER: ✅ Step 2 COMPLETE - DLNA device connected successfully");
                         info!("PLAYER: Service URLs fetched and stored");
+                        device.set_supported_formats(self.dlna_sender().supported_formats());
                         
                         // Show current DLNA sender state
                         let dlna_sender = self.dlna_sender();
@@ -1635,7 +3051,7 @@ impl SwPlayer {
                                         info!("PLAYER: Cast rejected stream during auto-play - attempting FFmpeg proxy");
                                         
                                         // Try FFmpeg proxy
-                                        match self.dlna_sender().start_ffmpeg_with_wrapper(url.as_ref(), &title) {
+                                        match self.dlna_sender().start_ffmpeg_with_wrapper(url.as_ref(), &title, self.imp().backend.get().unwrap().borrow().http_settings(), self.device().map(|d| d.output_format()).unwrap_or_default()) {
                                             Ok(proxy_url) => {
                                                 info!("PLAYER: FFmpeg proxy started: {}", proxy_url);
                                                 *self.imp().cast_proxy_url.borrow_mut() = Some(proxy_url.clone());
@@ -1665,7 +3081,7 @@ impl SwPlayer {
                                                     } else {
                                                         info!("PLAYER: ✅ Cast playback started with FFmpeg proxy");
                                                         if let Some(sender) = self.imp().gst_sender.get() {
-                                                            let _ = sender.send_blocking(GstreamerChange::PlaybackState(SwPlaybackState::Playing));
+                                                            let _ = sender.send_blocking(GstreamerChange::PlaybackState(SwPlaybackState::Loading));
                                                         }
                                                     }
                                                 }
@@ -1688,7 +3104,7 @@ impl SwPlayer {
                                     } else {
                                         info!("PLAYER: ✅ Cast playback started");
                                         if let Some(sender) = self.imp().gst_sender.get() {
-                                            let _ = sender.send_blocking(GstreamerChange::PlaybackState(SwPlaybackState::Playing));
+                                            let _ = sender.send_blocking(GstreamerChange::PlaybackState(SwPlaybackState::Loading));
                                         }
                                     }
                                 }
@@ -1699,6 +3115,8 @@ impl SwPlayer {
                         // Load media and start playback on DLNA device
                         if let Some(station) = self.station() {
                             if let Some(url) = station.stream_url() {
+                                self.load_dlna_cover(&station).await;
+                                let http_settings = self.imp().backend.get().unwrap().borrow().http_settings();
                                 if let Err(e) = self.dlna_sender()
                                     .load_media(
                                         url.as_ref(),
@@ -1708,12 +3126,14 @@ impl SwPlayer {
                                             .map(|u| u.to_string())
                                             .unwrap_or_default(),
                                         &station.title(),
+                                        http_settings,
                                     )
+                                    .await
                                 {
                                     error!("PLAYER: Failed to load DLNA media: {}", e);
                                 } else {
                                     // Start DLNA playback if media loaded successfully
-                                    if let Err(e) = self.dlna_sender().start_playback() {
+                                    if let Err(e) = self.dlna_sender().start_playback().await {
                                         error!("PLAYER: Failed to start DLNA playback: {}", e);
                                     } else {
                                         info!("PLAYER: ✅ DLNA playback started");
@@ -1740,7 +3160,7 @@ impl SwPlayer {
     }
 
     // Test Cast device connection and reconnect if needed (for suspend/resume scenarios)
-    pub async fn test_and_reconnect_cast(&self) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn test_and_reconnect_cast(&self) -> Result<(), DeviceError> {
         if let Some(device) = self.device() {
             if device.kind() != SwDeviceKind::Cast {
                 return Ok(());
@@ -1768,7 +3188,7 @@ impl SwPlayer {
                 }
                 Err(e) => {
                     error!("PLAYER: Failed to reconnect to Cast device: {}", e);
-                    return Err(format!("Failed to reconnect to Cast device: {}", e).into());
+                    return Err(e.into());
                 }
             }
         }
@@ -1784,6 +3204,11 @@ impl SwPlayer {
                 println!("🟡 DISCONNECT: Device type: {:?}", device.kind());
             }
             info!("PLAYER: Disconnecting device: {:?}", device.kind());
+            self.stop_headless_pipeline();
+
+            if self.file_remote() {
+                self.stop_track_playback();
+            }
             
             // Stop playback on the device first
             match device.kind() {
@@ -1819,7 +3244,7 @@ impl SwPlayer {
                     }
                     info!("PLAYER: Stopping DLNA playback and FFmpeg proxy");
                     
-                    if let Err(e) = self.dlna_sender().stop_playback() {
+                    if let Err(e) = self.dlna_sender().stop_playback().await {
                         #[cfg(feature = "dlna-debug")]
                         println!("🟡 DISCONNECT: ❌ Failed to stop DLNA playback: {}", e);
                         warn!("PLAYER: Failed to stop DLNA playback: {}", e);
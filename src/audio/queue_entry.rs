@@ -0,0 +1,71 @@
+// Shortwave - queue_entry.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::{Cell, OnceCell};
+
+use glib::Properties;
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::api::SwStation;
+use crate::i18n::i18n;
+use crate::utils;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, Properties)]
+    #[properties(wrapper_type = super::SwQueueEntry)]
+    pub struct SwQueueEntry {
+        #[property(get, set, construct_only)]
+        station: OnceCell<SwStation>,
+        /// How many minutes the station gets played before the queue advances,
+        /// or 0 if it should be played until the next station is requested manually
+        #[property(get, set, construct_only)]
+        duration: Cell<u32>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwQueueEntry {
+        const NAME: &'static str = "SwQueueEntry";
+        type Type = super::SwQueueEntry;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for SwQueueEntry {}
+}
+
+glib::wrapper! {
+    pub struct SwQueueEntry(ObjectSubclass<imp::SwQueueEntry>);
+}
+
+impl SwQueueEntry {
+    pub fn new(station: &SwStation, duration: u32) -> Self {
+        glib::Object::builder()
+            .property("station", station)
+            .property("duration", duration)
+            .build()
+    }
+
+    pub fn duration_label(&self) -> String {
+        if self.duration() == 0 {
+            i18n("Until Skipped")
+        } else {
+            utils::format_duration(u64::from(self.duration()) * 60, false)
+        }
+    }
+}
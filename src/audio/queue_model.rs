@@ -0,0 +1,116 @@
+// Shortwave - queue_model.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::RefCell;
+
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use crate::audio::SwQueueEntry;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct SwQueueModel {
+        pub vec: RefCell<Vec<SwQueueEntry>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwQueueModel {
+        const NAME: &'static str = "SwQueueModel";
+        type Type = super::SwQueueModel;
+        type Interfaces = (gio::ListModel,);
+    }
+
+    impl ObjectImpl for SwQueueModel {}
+
+    impl ListModelImpl for SwQueueModel {
+        fn item_type(&self) -> glib::Type {
+            SwQueueEntry::static_type()
+        }
+
+        fn n_items(&self) -> u32 {
+            self.vec.borrow().len() as u32
+        }
+
+        fn item(&self, position: u32) -> Option<glib::Object> {
+            self.vec
+                .borrow()
+                .get(position as usize)
+                .map(|o| o.clone().upcast::<glib::Object>())
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct SwQueueModel(ObjectSubclass<imp::SwQueueModel>) @implements gio::ListModel;
+}
+
+impl SwQueueModel {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub fn add_entry(&self, entry: &SwQueueEntry) {
+        let pos = {
+            let mut vec = self.imp().vec.borrow_mut();
+            vec.push(entry.clone());
+            vec.len() - 1
+        };
+
+        self.items_changed(pos as u32, 0, 1);
+    }
+
+    pub fn remove_entry(&self, entry: &SwQueueEntry) {
+        let pos = {
+            let mut vec = self.imp().vec.borrow_mut();
+            let Some(pos) = vec.iter().position(|e| e == entry) else {
+                return;
+            };
+            vec.remove(pos);
+            pos
+        };
+
+        self.items_changed(pos as u32, 1, 0);
+    }
+
+    /// Removes the first entry from the queue and returns it
+    pub fn pop_front(&self) -> Option<SwQueueEntry> {
+        let entry = {
+            let mut vec = self.imp().vec.borrow_mut();
+            if vec.is_empty() {
+                return None;
+            }
+            vec.remove(0)
+        };
+
+        self.items_changed(0, 1, 0);
+        Some(entry)
+    }
+
+    pub fn clear(&self) {
+        let removed = std::mem::take(&mut *self.imp().vec.borrow_mut());
+        self.items_changed(0, removed.len() as u32, 0);
+    }
+}
+
+impl Default for SwQueueModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,280 @@
+// Shortwave - recorder.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::{Cell, OnceCell, RefCell};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use glib::{clone, Properties};
+use gstreamer::prelude::*;
+use gstreamer::{Element, MessageView, Pipeline};
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use crate::api::SwStation;
+use crate::audio::{GstreamerBackend, SwFilenameStyle, SwRecorderState};
+use crate::database::HttpSettings;
+use crate::i18n::i18n;
+use crate::settings::{settings_manager, Key};
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, Properties)]
+    #[properties(wrapper_type = super::SwRecorder)]
+    pub struct SwRecorder {
+        #[property(get, set, construct_only)]
+        station: OnceCell<SwStation>,
+        #[property(get, builder(SwRecorderState::default()))]
+        state: Cell<SwRecorderState>,
+        /// Title of the currently recorded track, as reported by the stream
+        #[property(get)]
+        title: RefCell<String>,
+        /// Recorded duration in seconds
+        #[property(get)]
+        duration: Cell<u64>,
+        #[property(get)]
+        file: OnceCell<gio::File>,
+        #[property(get)]
+        error: RefCell<String>,
+
+        pipeline: OnceCell<Pipeline>,
+        bus_watch_guard: OnceCell<gstreamer::bus::BusWatchGuard>,
+        duration_source: RefCell<Option<glib::SourceId>>,
+        /// State which gets applied once the pipeline has been finalized
+        stop_state: Cell<SwRecorderState>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwRecorder {
+        const NAME: &'static str = "SwRecorder";
+        type Type = super::SwRecorder;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for SwRecorder {
+        fn dispose(&self) {
+            if let Some(source) = self.duration_source.take() {
+                source.remove();
+            }
+
+            if let Some(pipeline) = self.pipeline.get() {
+                let _ = pipeline.set_state(gstreamer::State::Null);
+            }
+        }
+    }
+
+    impl SwRecorder {
+        pub(super) fn start(&self, http_settings: HttpSettings) -> Result<()> {
+            let station = self.obj().station();
+            let url = station
+                .stream_url()
+                .with_context(|| i18n("Station cannot be streamed. URL is not valid."))?;
+
+            let path = super::SwRecorder::destination_path(&station);
+            if let Some(directory) = path.parent() {
+                std::fs::create_dir_all(directory)?;
+            }
+
+            // Same encoding as the recordings of the player, but without audio output
+            let pipeline = gstreamer::parse::launch(
+                "uridecodebin name=uridecodebin ! audioconvert ! audioresample ! queue ! vorbisenc ! oggmux ! filesink name=filesink",
+            )?
+            .downcast::<Pipeline>()
+            .unwrap();
+
+            let uridecodebin = pipeline.by_name("uridecodebin").unwrap();
+            uridecodebin.set_property("uri", url.as_str());
+            uridecodebin.connect("source-setup", false, move |args| {
+                let source = args[1].get::<Element>().unwrap();
+                GstreamerBackend::apply_http_settings(&source, &http_settings);
+                None
+            });
+
+            let filesink = pipeline.by_name("filesink").unwrap();
+            filesink.set_property("location", path.to_str().unwrap_or_default());
+
+            let guard = pipeline.bus().unwrap().add_watch_local(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move |_, message| {
+                    imp.parse_bus_message(message);
+                    glib::ControlFlow::Continue
+                }
+            ))?;
+
+            pipeline.set_state(gstreamer::State::Playing)?;
+            debug!(
+                "Started background recording of {:?} to {:?}",
+                station.title(),
+                path
+            );
+
+            let source = glib::timeout_add_seconds_local(
+                1,
+                clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    #[upgrade_or]
+                    glib::ControlFlow::Break,
+                    move || {
+                        imp.update_duration();
+                        glib::ControlFlow::Continue
+                    }
+                ),
+            );
+
+            self.file.set(gio::File::for_path(path)).unwrap();
+            self.pipeline.set(pipeline).unwrap();
+            self.bus_watch_guard.set(guard).unwrap();
+            *self.duration_source.borrow_mut() = Some(source);
+
+            Ok(())
+        }
+
+        /// Finalizes the file by sending an end-of-stream event through the pipeline
+        pub(super) fn stop(&self, stop_state: SwRecorderState) {
+            if self.state.get() != SwRecorderState::Recording {
+                return;
+            }
+
+            self.stop_state.set(stop_state);
+            self.set_state(SwRecorderState::Stopping);
+
+            if let Some(pipeline) = self.pipeline.get() {
+                pipeline.send_event(gstreamer::event::Eos::new());
+            }
+        }
+
+        fn finish(&self, state: SwRecorderState) {
+            if let Some(source) = self.duration_source.take() {
+                source.remove();
+            }
+
+            if let Some(pipeline) = self.pipeline.get() {
+                let _ = pipeline.set_state(gstreamer::State::Null);
+            }
+
+            debug!(
+                "Background recording of {:?} finished: {state}",
+                self.obj().station().title()
+            );
+            self.set_state(state);
+        }
+
+        fn set_state(&self, state: SwRecorderState) {
+            self.state.set(state);
+            self.obj().notify_state();
+        }
+
+        fn parse_bus_message(&self, message: &gstreamer::Message) {
+            match message.view() {
+                MessageView::Tag(tag) => {
+                    if let Some(title) = tag.tags().get::<gstreamer::tags::Title>() {
+                        let title = title.get().to_string();
+                        if *self.title.borrow() != title {
+                            *self.title.borrow_mut() = title;
+                            self.obj().notify_title();
+                        }
+                    }
+                }
+                MessageView::Eos(_) => self.finish(self.stop_state.get()),
+                MessageView::Error(err) => {
+                    warn!("Background recording failed: {}", err.error());
+                    *self.error.borrow_mut() = err.error().to_string();
+                    self.obj().notify_error();
+                    self.finish(SwRecorderState::Failed);
+                }
+                _ => (),
+            }
+        }
+
+        fn update_duration(&self) {
+            if self.state.get() != SwRecorderState::Recording {
+                return;
+            }
+
+            let position = self
+                .pipeline
+                .get()
+                .and_then(|pipeline| pipeline.query_position::<gstreamer::ClockTime>());
+            if let Some(position) = position {
+                self.duration.set(position.seconds());
+                self.obj().notify_duration();
+            }
+
+            let maximum_duration = settings_manager::integer(Key::RecordersMaximumDuration);
+            if self.duration.get() >= u64::try_from(maximum_duration).unwrap_or_default() * 60 {
+                self.stop(SwRecorderState::StoppedReachedMaxDuration);
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    /// Records a station in the background, independent of the player
+    pub struct SwRecorder(ObjectSubclass<imp::SwRecorder>);
+}
+
+impl SwRecorder {
+    pub fn new(station: &SwStation) -> Self {
+        glib::Object::builder().property("station", station).build()
+    }
+
+    pub fn start(&self, http_settings: HttpSettings) -> Result<()> {
+        self.imp().start(http_settings)
+    }
+
+    pub fn stop(&self) {
+        self.imp().stop(SwRecorderState::Stopped);
+    }
+
+    /// Stops the recording, and waits until the end of the file got written,
+    /// otherwise the file gets truncated
+    pub async fn stop_future(&self) {
+        self.stop();
+
+        for _ in 0..50 {
+            if self.state() != SwRecorderState::Stopping {
+                break;
+            }
+            glib::timeout_future(Duration::from_millis(100)).await;
+        }
+    }
+
+    /// Location in the track directory for a recording of the station,
+    /// which is named after the station and the current time
+    pub fn destination_path(station: &SwStation) -> PathBuf {
+        let directory = settings_manager::string(Key::RecordingTrackDirectory);
+        let style = settings_manager::string(Key::RecordingFilenameStyle);
+        let style = SwFilenameStyle::from_str(&style).unwrap_or_default();
+
+        let date = glib::DateTime::now_local()
+            .and_then(|date| date.format("%F %H-%M-%S"))
+            .map(|date| date.to_string())
+            .unwrap_or_default();
+        let filename = style.sanitize(&format!("{} {date}", station.title())) + ".ogg";
+
+        let mut path = PathBuf::from(directory);
+        path.push(filename);
+        path
+    }
+}
@@ -0,0 +1,166 @@
+// Shortwave - recorder_model.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::RefCell;
+
+use anyhow::{anyhow, bail, Result};
+use futures_util::future::join_all;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use crate::api::SwStation;
+use crate::app::SwApplication;
+use crate::audio::SwRecorder;
+use crate::database::{StationCredentials, StationSettings};
+use crate::i18n::{i18n, ni18n_f};
+use crate::settings::{settings_manager, Key};
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct SwRecorderModel {
+        pub vec: RefCell<Vec<SwRecorder>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwRecorderModel {
+        const NAME: &'static str = "SwRecorderModel";
+        type Type = super::SwRecorderModel;
+        type Interfaces = (gio::ListModel,);
+    }
+
+    impl ObjectImpl for SwRecorderModel {}
+
+    impl ListModelImpl for SwRecorderModel {
+        fn item_type(&self) -> glib::Type {
+            SwRecorder::static_type()
+        }
+
+        fn n_items(&self) -> u32 {
+            self.vec.borrow().len() as u32
+        }
+
+        fn item(&self, position: u32) -> Option<glib::Object> {
+            self.vec
+                .borrow()
+                .get(position as usize)
+                .map(|o| o.clone().upcast::<glib::Object>())
+        }
+    }
+}
+
+glib::wrapper! {
+    /// Background recorder sessions, which run independently of the player
+    pub struct SwRecorderModel(ObjectSubclass<imp::SwRecorderModel>) @implements gio::ListModel;
+}
+
+impl SwRecorderModel {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// Starts recording the station in the background, as long as the
+    /// maximum number of concurrent sessions isn't reached yet
+    pub async fn start_recorder(&self, station: &SwStation) -> Result<SwRecorder> {
+        if self.is_recording(station) {
+            bail!(i18n("The station is already being recorded"));
+        }
+
+        let maximum = settings_manager::integer(Key::RecordersMaximumSessions);
+        if self.n_active() >= u32::try_from(maximum).unwrap_or_default() {
+            bail!(ni18n_f(
+                "Only {} station can be recorded at the same time",
+                "Only {} stations can be recorded at the same time",
+                maximum as u32,
+                &[&maximum.to_string()],
+            ));
+        }
+
+        // Credentials are stored in the keyring, so only request them when needed
+        let mut http_settings = StationSettings::load(&station.uuid()).http;
+        if http_settings.authentication {
+            http_settings.credentials = StationCredentials::load(&station.uuid())
+                .await
+                .map_err(|err| anyhow!("{}: {err}", i18n("Unable to load station credentials")))?;
+        }
+
+        let recorder = SwRecorder::new(station);
+        recorder.start(http_settings)?;
+
+        let pos = {
+            let mut vec = self.imp().vec.borrow_mut();
+            vec.push(recorder.clone());
+            vec.len() - 1
+        };
+
+        self.items_changed(pos as u32, 0, 1);
+        Ok(recorder)
+    }
+
+    /// Stops the recorder if needed, and removes it from the list. The application
+    /// keeps running until the recording has been finalized.
+    pub fn remove_recorder(&self, recorder: &SwRecorder) {
+        let hold = SwApplication::default().hold();
+        let finalizing = recorder.clone();
+        glib::spawn_future_local(async move {
+            finalizing.stop_future().await;
+            drop(hold);
+        });
+
+        let pos = {
+            let mut vec = self.imp().vec.borrow_mut();
+            let Some(pos) = vec.iter().position(|r| r == recorder) else {
+                return;
+            };
+            vec.remove(pos);
+            pos
+        };
+
+        self.items_changed(pos as u32, 1, 0);
+    }
+
+    /// Stops all recorders, and waits until their recordings have been finalized
+    pub async fn stop_all(&self) {
+        let recorders = self.imp().vec.borrow().clone();
+        join_all(recorders.iter().map(|recorder| recorder.stop_future())).await;
+    }
+
+    pub fn is_recording(&self, station: &SwStation) -> bool {
+        self.imp()
+            .vec
+            .borrow()
+            .iter()
+            .any(|r| r.state().is_active() && r.station().uuid() == station.uuid())
+    }
+
+    /// Number of sessions which are currently recording
+    pub fn n_active(&self) -> u32 {
+        self.imp()
+            .vec
+            .borrow()
+            .iter()
+            .filter(|r| r.state().is_active())
+            .count() as u32
+    }
+}
+
+impl Default for SwRecorderModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,51 @@
+// Shortwave - recorder_state.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use gtk::glib;
+use gtk::glib::Enum;
+
+use crate::i18n::i18n;
+
+#[derive(Display, Copy, Debug, Clone, EnumString, Eq, PartialEq, Enum)]
+#[repr(u32)]
+#[enum_type(name = "SwRecorderState")]
+#[derive(Default)]
+pub enum SwRecorderState {
+    #[default]
+    Recording,
+    Stopping,
+    Stopped,
+    StoppedReachedMaxDuration,
+    Failed,
+}
+
+impl SwRecorderState {
+    pub fn is_active(&self) -> bool {
+        *self == Self::Recording || *self == Self::Stopping
+    }
+
+    pub fn title(&self) -> String {
+        match self {
+            SwRecorderState::Recording => i18n("Recording…"),
+            SwRecorderState::Stopping => i18n("Stopping…"),
+            SwRecorderState::Stopped => i18n("Recorded"),
+            SwRecorderState::StoppedReachedMaxDuration => {
+                i18n("Recorded, maximum duration reached")
+            }
+            SwRecorderState::Failed => i18n("Failed"),
+        }
+    }
+}
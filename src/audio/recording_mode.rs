@@ -17,7 +17,7 @@
 use gtk::glib;
 use gtk::glib::Enum;
 
-#[derive(Display, Copy, Debug, Clone, EnumString, Eq, PartialEq, Enum)]
+#[derive(Display, Copy, Debug, Clone, EnumString, Eq, PartialEq, Enum, Serialize, Deserialize)]
 #[repr(u32)]
 #[enum_type(name = "SwRecordingMode")]
 #[derive(Default)]
@@ -0,0 +1,219 @@
+// Shortwave - recording_recovery.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use gtk::prelude::*;
+use gtk::{gio, glib};
+
+use crate::api::{StationMetadata, SwStation};
+use crate::audio::{SwRecordingState, SwTrack};
+use crate::path;
+
+// Temporary recordings get stored together with a small information file, so that
+// they can be recovered in case Shortwave didn't shut down properly.
+#[derive(Serialize, Deserialize, Debug)]
+struct RecordingInfo {
+    title: String,
+    station_uuid: String,
+    station_is_local: bool,
+    station: StationMetadata,
+}
+
+fn info_path(recording: &Path) -> PathBuf {
+    recording.with_extension("json")
+}
+
+/// Directory which contains the recordings recovered from a previous session
+pub fn recovery_dir() -> PathBuf {
+    let mut path = path::DATA.clone();
+    path.push("recovered");
+    path
+}
+
+/// Stores the information which is needed to recover the recording of `track`
+pub fn store_recording_info(track: &SwTrack) {
+    let Some(path) = track.file().path() else {
+        return;
+    };
+
+    let station = track.station();
+    let info = RecordingInfo {
+        title: track.title(),
+        station_uuid: station.uuid(),
+        station_is_local: station.is_local(),
+        station: station.metadata(),
+    };
+
+    let res = serde_json::to_string(&info)
+        .map_err(|err| err.to_string())
+        .and_then(|json| fs::write(info_path(&path), json).map_err(|err| err.to_string()));
+
+    if let Err(err) = res {
+        warn!("Unable to store recording information: {err}");
+    }
+}
+
+/// Removes the recording information of a recording which got saved or discarded
+pub fn remove_recording_info(recording: &gio::File) {
+    let Some(path) = recording.path() else {
+        return;
+    };
+
+    let file = gio::File::for_path(info_path(&path));
+    glib::spawn_future_local(async move {
+        let _ = file.delete_future(glib::Priority::LOW).await;
+    });
+}
+
+/// Moves all recordings of `directory` which can be recovered into the recovery directory
+pub fn collect_orphaned_recordings(directory: &Path) {
+    move_orphaned_recordings(directory, &recovery_dir());
+}
+
+fn move_orphaned_recordings(directory: &Path, recovery_dir: &Path) {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let info = entry.path();
+        let recording = info.with_extension("ogg");
+        if info.extension().and_then(|ext| ext.to_str()) != Some("json") || !recording.exists() {
+            continue;
+        }
+
+        if let Err(err) = fs::create_dir_all(recovery_dir) {
+            warn!("Unable to create recovery directory: {err}");
+            return;
+        }
+
+        let file_name = entry.file_name();
+        let res = fs::rename(
+            &recording,
+            recovery_dir.join(&file_name).with_extension("ogg"),
+        )
+        .and_then(|_| fs::rename(&info, recovery_dir.join(&file_name)));
+
+        match res {
+            Ok(()) => info!("Found orphaned recording {:?}", recording),
+            Err(err) => warn!("Unable to move orphaned recording {:?}: {err}", recording),
+        }
+    }
+}
+
+/// Creates tracks for all recordings in the recovery directory
+pub fn recovered_tracks() -> Vec<SwTrack> {
+    let Ok(entries) = fs::read_dir(recovery_dir()) else {
+        return Vec::new();
+    };
+
+    let mut tracks = Vec::new();
+    for entry in entries.flatten() {
+        let info = entry.path();
+        if info.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+
+        // The recording got already saved or discarded
+        let recording = info.with_extension("ogg");
+        if !recording.exists() {
+            let _ = fs::remove_file(&info);
+            continue;
+        }
+
+        let info: RecordingInfo = match fs::read_to_string(&info)
+            .map_err(|err| err.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|err| err.to_string()))
+        {
+            Ok(info) => info,
+            Err(err) => {
+                warn!("Unable to read recording information {:?}: {err}", info);
+                continue;
+            }
+        };
+
+        let station = SwStation::new(
+            &info.station_uuid,
+            info.station_is_local,
+            info.station,
+            None,
+        );
+        let file = gio::File::for_path(recording);
+        let track = SwTrack::recovered(&info.title, &station, &file);
+        track.set_state(SwRecordingState::RecordedRecovered);
+
+        tracks.push(track);
+    }
+
+    tracks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tmp_dir() -> PathBuf {
+        let path = std::env::temp_dir().join(format!("shortwave-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn info_path_replaces_extension() {
+        assert_eq!(
+            info_path(Path::new("/tmp/recording/track.ogg")),
+            PathBuf::from("/tmp/recording/track.json")
+        );
+    }
+
+    #[test]
+    fn moves_recordings_with_info() {
+        let directory = tmp_dir();
+        let recovery_dir = directory.join("recovered");
+
+        fs::write(directory.join("complete.ogg"), "audio").unwrap();
+        fs::write(directory.join("complete.json"), "{}").unwrap();
+        fs::write(directory.join("without-info.ogg"), "audio").unwrap();
+        fs::write(directory.join("without-audio.json"), "{}").unwrap();
+
+        move_orphaned_recordings(&directory, &recovery_dir);
+
+        assert!(recovery_dir.join("complete.ogg").exists());
+        assert!(recovery_dir.join("complete.json").exists());
+        assert!(!directory.join("complete.ogg").exists());
+
+        // Recordings without information can't be recovered, and are left untouched
+        assert!(directory.join("without-info.ogg").exists());
+        assert!(directory.join("without-audio.json").exists());
+        assert!(!recovery_dir.join("without-info.ogg").exists());
+        assert!(!recovery_dir.join("without-audio.json").exists());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn missing_directory_is_ignored() {
+        let directory = tmp_dir();
+        let recovery_dir = directory.join("recovered");
+
+        move_orphaned_recordings(&directory.join("missing"), &recovery_dir);
+        assert!(!recovery_dir.exists());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -34,10 +34,12 @@ pub enum SwRecordingState {
     Recording,
     Recorded,
     RecordedReachedMaxDuration,
+    RecordedRecovered,
 
     // Discarded
     DiscardedBelowMinDuration,
     DiscardedCancelled,
+    DiscardedNotSaved,
 }
 
 impl SwRecordingState {
@@ -46,7 +48,9 @@ impl SwRecordingState {
     }
 
     pub fn is_recorded(&self) -> bool {
-        *self == Self::Recorded || *self == Self::RecordedReachedMaxDuration
+        *self == Self::Recorded
+            || *self == Self::RecordedReachedMaxDuration
+            || *self == Self::RecordedRecovered
     }
 
     pub fn title(&self) -> String {
@@ -58,9 +62,11 @@ impl SwRecordingState {
             SwRecordingState::Recording => i18n("Recording…"),
             SwRecordingState::Recorded => i18n("Recorded"),
             SwRecordingState::RecordedReachedMaxDuration => i18n("Recorded"),
+            SwRecordingState::RecordedRecovered => i18n("Recovered"),
 
             SwRecordingState::DiscardedBelowMinDuration => i18n("Below Threshold"),
             SwRecordingState::DiscardedCancelled => i18n("Cancelled"),
+            SwRecordingState::DiscardedNotSaved => i18n("Not Saved"),
         }
     }
 
@@ -80,10 +86,16 @@ impl SwRecordingState {
             SwRecordingState::RecordedReachedMaxDuration => {
                 i18n("The maximum recording duration has been reached")
             }
+            SwRecordingState::RecordedRecovered => {
+                i18n("The track has been recovered after Shortwave quit unexpectedly")
+            }
             SwRecordingState::DiscardedBelowMinDuration => {
                 i18n("The track has been discarded as the duration was below the set threshold")
             }
             SwRecordingState::DiscardedCancelled => i18n("Recording has been cancelled"),
+            SwRecordingState::DiscardedNotSaved => {
+                i18n("The recording has been discarded as it wasn't saved before Shortwave quit")
+            }
         }
     }
 }
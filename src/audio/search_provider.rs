@@ -0,0 +1,66 @@
+// Shortwave - search_provider.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use url::Url;
+
+#[derive(Display, Copy, Debug, Clone, EnumString, Eq, PartialEq)]
+#[strum(serialize_all = "kebab_case")]
+pub enum SwSearchProvider {
+    Youtube,
+    Bandcamp,
+    Musicbrainz,
+    Web,
+}
+
+impl SwSearchProvider {
+    pub fn search_url(&self, artist: Option<&str>, title: &str) -> Url {
+        let query = if let Some(artist) = artist {
+            format!("{artist} {title}")
+        } else {
+            title.to_string()
+        };
+
+        let res = match self {
+            Self::Youtube => Url::parse_with_params(
+                "https://www.youtube.com/results",
+                &[("search_query", query)],
+            ),
+            Self::Bandcamp => {
+                Url::parse_with_params("https://bandcamp.com/search", &[("q", query)])
+            }
+            Self::Web => Url::parse_with_params("https://duckduckgo.com/", &[("q", query)]),
+            Self::Musicbrainz => {
+                // MusicBrainz supports Lucene syntax, which allows a more precise match
+                let query = if let Some(artist) = artist {
+                    format!("recording:\"{title}\" AND artist:\"{artist}\"")
+                } else {
+                    format!("recording:\"{title}\"")
+                };
+
+                Url::parse_with_params(
+                    "https://musicbrainz.org/search",
+                    &[
+                        ("query", query.as_str()),
+                        ("type", "recording"),
+                        ("method", "advanced"),
+                    ],
+                )
+            }
+        };
+
+        res.expect("Unable to build search url")
+    }
+}
@@ -0,0 +1,284 @@
+// Shortwave - timeshift_buffer.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use gstreamer::ClockTime;
+use gstreamer_audio::{AudioFormat, AudioInfo};
+
+/// The audio gets converted into this format before it's written into the buffer,
+/// so that positions in the buffer can be calculated from the amount of data
+pub const SAMPLE_RATE: u32 = 48_000;
+pub const CHANNELS: u32 = 2;
+/// Signed 16 bit samples
+const BYTES_PER_FRAME: u64 = CHANNELS as u64 * 2;
+const BYTES_PER_SECOND: u64 = SAMPLE_RATE as u64 * BYTES_PER_FRAME;
+
+/// The oldest audio of a full buffer gets overwritten next, so it's not used for
+/// rewinding or saving
+const OVERWRITE_MARGIN: ClockTime = ClockTime::from_seconds(2);
+
+/// Caps of the audio in the buffer
+pub fn caps() -> gstreamer::Caps {
+    AudioInfo::builder(AudioFormat::S16le, SAMPLE_RATE, CHANNELS)
+        .build()
+        .unwrap()
+        .to_caps()
+        .unwrap()
+}
+
+/// Keeps track of the time-shift buffer, a ring buffer on disk which is managed by a
+/// queue2 element. Playback reads from it, so that it can be paused and resumed later,
+/// or jump back a few seconds.
+#[derive(Debug, Default)]
+pub struct TimeshiftBuffer {
+    max_duration: ClockTime,
+    /// Amount of data which got written into the buffer since playback started
+    written: u64,
+    /// Position of the most recently played audio
+    position: ClockTime,
+    paused: bool,
+}
+
+impl TimeshiftBuffer {
+    pub fn set_max_duration(&mut self, duration: ClockTime) {
+        self.max_duration = duration;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.max_duration.is_zero()
+    }
+
+    /// Size of the ring buffer in bytes
+    pub fn ring_size(&self) -> u64 {
+        Self::bytes(self.max_duration)
+    }
+
+    pub fn push(&mut self, bytes: u64) {
+        self.written += bytes;
+    }
+
+    /// Position of the live stream in the buffer
+    pub fn live_position(&self) -> ClockTime {
+        Self::time(self.written)
+    }
+
+    /// Amount of audio which is available in the buffer
+    pub fn duration(&self) -> ClockTime {
+        Self::time(self.written.min(self.ring_size()))
+    }
+
+    pub fn set_position(&mut self, position: ClockTime) {
+        self.position = position;
+    }
+
+    pub fn position(&self) -> ClockTime {
+        self.position
+    }
+
+    /// How far playback is behind the live stream
+    pub fn delay(&self) -> ClockTime {
+        self.live_position()
+            .saturating_sub(self.position)
+            .min(self.duration())
+    }
+
+    /// Position of the oldest audio which can still be played
+    pub fn oldest_position(&self) -> ClockTime {
+        if self.written <= self.ring_size() {
+            return ClockTime::ZERO;
+        }
+
+        let oldest = self.live_position().saturating_sub(self.duration()) + OVERWRITE_MARGIN;
+        oldest.min(self.live_position())
+    }
+
+    /// Position which lies the given duration before the played audio, without going
+    /// past the oldest available audio
+    pub fn rewind_position(&self, duration: ClockTime) -> ClockTime {
+        self.position
+            .saturating_sub(duration)
+            .max(self.oldest_position())
+    }
+
+    /// Offsets and lengths of the parts of the ring buffer which contain the most
+    /// recent audio, for at most the given duration
+    pub fn recent_ranges(&self, duration: ClockTime) -> Vec<(u64, u64)> {
+        let ring_size = self.ring_size();
+        let start = self
+            .live_position()
+            .saturating_sub(duration)
+            .max(self.oldest_position());
+        let start = Self::bytes(start);
+        if ring_size == 0 || start >= self.written {
+            return Vec::new();
+        }
+
+        // The data wraps around at the end of the ring buffer
+        let offset = start % ring_size;
+        let length = self.written - start;
+        if offset + length <= ring_size {
+            vec![(offset, length)]
+        } else {
+            let first = ring_size - offset;
+            vec![(offset, first), (0, length - first)]
+        }
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn clear(&mut self) {
+        self.written = 0;
+        self.position = ClockTime::ZERO;
+        self.paused = false;
+    }
+
+    /// Amount of data for the given duration, in whole frames
+    pub fn bytes(time: ClockTime) -> u64 {
+        let bytes = u128::from(time.nseconds()) * u128::from(BYTES_PER_SECOND) / 1_000_000_000;
+        (bytes as u64) / BYTES_PER_FRAME * BYTES_PER_FRAME
+    }
+
+    /// Duration of the given amount of data
+    pub fn time(bytes: u64) -> ClockTime {
+        let nseconds = u128::from(bytes) * 1_000_000_000 / u128::from(BYTES_PER_SECOND);
+        ClockTime::from_nseconds(nseconds as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filled(max_seconds: u64, written_seconds: u64) -> TimeshiftBuffer {
+        let mut buffer = TimeshiftBuffer::default();
+        buffer.set_max_duration(ClockTime::from_seconds(max_seconds));
+        buffer.push(TimeshiftBuffer::bytes(ClockTime::from_seconds(
+            written_seconds,
+        )));
+        buffer
+    }
+
+    #[test]
+    fn converts_between_bytes_and_time() {
+        assert_eq!(
+            TimeshiftBuffer::bytes(ClockTime::from_seconds(1)),
+            BYTES_PER_SECOND
+        );
+        assert_eq!(
+            TimeshiftBuffer::time(BYTES_PER_SECOND * 60),
+            ClockTime::from_seconds(60)
+        );
+
+        // Partial frames are never used
+        assert_eq!(
+            TimeshiftBuffer::bytes(ClockTime::from_nseconds(30_000)) % BYTES_PER_FRAME,
+            0
+        );
+    }
+
+    #[test]
+    fn disabled_without_duration() {
+        let buffer = filled(0, 10);
+
+        assert!(!buffer.is_enabled());
+        assert_eq!(buffer.ring_size(), 0);
+        assert_eq!(buffer.duration(), ClockTime::ZERO);
+    }
+
+    #[test]
+    fn duration_is_limited_by_ring_size() {
+        assert_eq!(filled(60, 10).duration(), ClockTime::from_seconds(10));
+        assert_eq!(filled(60, 100).duration(), ClockTime::from_seconds(60));
+    }
+
+    #[test]
+    fn delay_from_position() {
+        let mut buffer = filled(60, 100);
+
+        buffer.set_position(ClockTime::from_seconds(70));
+        assert_eq!(buffer.delay(), ClockTime::from_seconds(30));
+        buffer.set_position(ClockTime::from_seconds(100));
+        assert_eq!(buffer.delay(), ClockTime::ZERO);
+
+        // Playback can't be further behind than the buffer is long
+        buffer.set_position(ClockTime::ZERO);
+        assert_eq!(buffer.delay(), ClockTime::from_seconds(60));
+    }
+
+    #[test]
+    fn rewind_stops_at_oldest_audio() {
+        let mut buffer = filled(60, 30);
+        buffer.set_position(ClockTime::from_seconds(20));
+        assert_eq!(
+            buffer.rewind_position(ClockTime::from_seconds(30)),
+            ClockTime::ZERO
+        );
+
+        // The oldest audio of a full buffer is about to get overwritten
+        let mut buffer = filled(60, 100);
+        buffer.set_position(ClockTime::from_seconds(50));
+        assert_eq!(
+            buffer.rewind_position(ClockTime::from_seconds(30)),
+            ClockTime::from_seconds(42)
+        );
+        buffer.set_position(ClockTime::from_seconds(90));
+        assert_eq!(
+            buffer.rewind_position(ClockTime::from_seconds(30)),
+            ClockTime::from_seconds(60)
+        );
+    }
+
+    #[test]
+    fn recent_ranges_wrap_around() {
+        let second = BYTES_PER_SECOND;
+
+        // Nothing wrapped yet
+        let buffer = filled(60, 30);
+        assert_eq!(
+            buffer.recent_ranges(ClockTime::from_seconds(10)),
+            vec![(20 * second, 10 * second)]
+        );
+
+        // The most recent 30 seconds got written at 40 - 60 and 0 - 10
+        let buffer = filled(60, 70);
+        assert_eq!(
+            buffer.recent_ranges(ClockTime::from_seconds(30)),
+            vec![(40 * second, 20 * second), (0, 10 * second)]
+        );
+
+        assert!(filled(0, 0)
+            .recent_ranges(ClockTime::from_seconds(30))
+            .is_empty());
+    }
+
+    #[test]
+    fn clear_resets_buffer() {
+        let mut buffer = filled(60, 30);
+        buffer.set_position(ClockTime::from_seconds(20));
+        buffer.set_paused(true);
+        buffer.clear();
+
+        assert_eq!(buffer.duration(), ClockTime::ZERO);
+        assert_eq!(buffer.live_position(), ClockTime::ZERO);
+        assert_eq!(buffer.position(), ClockTime::ZERO);
+        assert!(!buffer.is_paused());
+    }
+}
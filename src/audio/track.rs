@@ -15,21 +15,36 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use std::cell::{Cell, OnceCell, RefCell};
-use std::fs;
 use std::path::PathBuf;
-use std::rc::Rc;
+use std::str::FromStr;
 
 use adw::prelude::*;
+use futures_util::{future, StreamExt};
 use glib::subclass::prelude::*;
 use glib::{clone, Properties};
-use gtk::{gio, glib};
+use gtk::{gdk, gio, glib};
 use uuid::Uuid;
 
-use crate::api::{Error, SwStation};
+use crate::api::{client, Error, SwStation};
 use crate::app::SwApplication;
-use crate::audio::SwRecordingState;
+use crate::audio::{
+    fingerprint, music_library, recording_recovery, transcoder, SwConvertFormat, SwFilenameStyle,
+    SwFolderStructure, SwRecordingMode, SwRecordingState, SwSearchProvider,
+};
+use crate::database::WatchList;
+use crate::i18n::i18n;
 use crate::settings::{settings_manager, Key};
-use crate::ui::DisplayError;
+use crate::ui::{DisplayError, SwStationDialog, SwTrimDialog};
+
+/// Stream titles which don't name the playing track, lowercase
+const PLACEHOLDER_TITLES: [&str; 6] = [
+    "live",
+    "live stream",
+    "livestream",
+    "on air",
+    "unknown",
+    "untitled",
+];
 
 mod imp {
     use super::*;
@@ -38,17 +53,19 @@ mod imp {
     #[properties(wrapper_type = super::SwTrack)]
     pub struct SwTrack {
         #[property(get)]
-        uuid: RefCell<String>,
-        #[property(get, set, construct_only)]
-        title: OnceCell<String>,
+        pub uuid: RefCell<String>,
+        #[property(get, set)]
+        title: RefCell<String>,
         #[property(get, set, construct_only)]
         station: OnceCell<SwStation>,
-        #[property(get)]
+        #[property(get, set, construct_only)]
         file: OnceCell<gio::File>,
         #[property(get, set, builder(SwRecordingState::default()))]
         state: Cell<SwRecordingState>,
         #[property(get, set)]
         duration: Cell<u64>,
+        /// Unix timestamp of the playback start
+        pub started_at: Cell<i64>,
 
         // Meaningless for SwRecordingMode != "Decide"
         #[property(get, set)]
@@ -56,8 +73,14 @@ mod imp {
         #[property(get)]
         #[property(name="is-saved", get=Self::is_saved, type=bool)]
         pub saved_to: RefCell<Option<gio::File>>,
+        #[property(get)]
+        pub is_saving: Cell<bool>,
+        #[property(get)]
+        pub save_progress: Cell<f64>,
 
         pub actions: OnceCell<gio::SimpleActionGroup>,
+        /// Number of open dialogs which show this track
+        pub users: Cell<u32>,
     }
 
     #[glib::object_subclass]
@@ -75,12 +98,31 @@ mod imp {
             let uuid = Uuid::new_v4().to_string();
             *self.uuid.borrow_mut() = uuid;
 
-            // track path
-            let mut path = crate::path::DATA.clone();
-            path.push("recording");
-            path.push(self.obj().uuid().to_string() + ".ogg");
-
-            self.file.set(gio::File::for_path(path)).unwrap();
+            let now = glib::DateTime::now_utc().unwrap();
+            self.started_at.set(now.to_unix());
+
+            // track path, which is already set for recovered tracks
+            if self.file.get().is_none() {
+                let mode = SwApplication::default().player().active_recording_mode();
+                let file = if mode == SwRecordingMode::Everything
+                    && settings_manager::boolean(Key::RecordingWriteDirectly)
+                {
+                    // Track gets saved anyway, so record it next to the final location. A
+                    // unique name ensures that an existing file with the same title never
+                    // gets overwritten or deleted, the recording gets renamed when saved.
+                    let destination = self.obj().destination_file().path().unwrap();
+                    let mut path = destination.into_os_string();
+                    path.push(format!(".{}.part", self.obj().uuid()));
+                    gio::File::for_path(PathBuf::from(path))
+                } else {
+                    let mut path = crate::path::DATA.clone();
+                    path.push("recording");
+                    path.push(self.obj().uuid().to_string() + ".ogg");
+                    gio::File::for_path(path)
+                };
+
+                self.file.set(file).unwrap();
+            }
 
             // actions
             let actions = gio::SimpleActionGroup::new();
@@ -101,11 +143,33 @@ mod imp {
             cancel_action.set_enabled(false);
             actions.add_action(&cancel_action);
 
+            let pause_action = gio::SimpleAction::new("toggle-recording-pause", None);
+            pause_action.connect_activate(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_, _| {
+                    let player = SwApplication::default().player();
+                    if let Some(track) = player.playing_track() {
+                        if track.uuid() != imp.obj().uuid() {
+                            return;
+                        }
+
+                        if player.recording_paused() {
+                            player.resume_recording();
+                        } else {
+                            player.pause_recording();
+                        }
+                    }
+                }
+            ));
+            pause_action.set_enabled(false);
+            actions.add_action(&pause_action);
+
             let save_action = gio::SimpleAction::new("save", None);
             save_action.connect_activate(clone!(
                 #[weak(rename_to = imp)]
                 self,
-                move |_, _| imp.obj().save().handle_error("Unable to save track")
+                move |_, _| imp.obj().save_in_background()
             ));
             save_action.set_enabled(false);
             actions.add_action(&save_action);
@@ -115,9 +179,20 @@ mod imp {
                 save_action,
                 #[weak]
                 cancel_action,
+                #[weak]
+                pause_action,
                 move |track| {
-                    save_action.set_enabled(track.state().is_recorded());
+                    save_action.set_enabled(track.state().is_recorded() && !track.is_saving());
                     cancel_action.set_enabled(track.state() == SwRecordingState::Recording);
+                    pause_action.set_enabled(track.state() == SwRecordingState::Recording);
+                }
+            ));
+
+            self.obj().connect_is_saving_notify(clone!(
+                #[weak]
+                save_action,
+                move |track| {
+                    save_action.set_enabled(track.state().is_recorded() && !track.is_saving());
                 }
             ));
 
@@ -138,15 +213,100 @@ mod imp {
                 }
             ));
 
+            let trim_action = gio::SimpleAction::new("trim", None);
+            trim_action.connect_activate(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_, _| imp.obj().show_trim_dialog()
+            ));
+            trim_action.set_enabled(false);
+            actions.add_action(&trim_action);
+
+            self.obj().connect_is_saved_notify(clone!(
+                #[weak]
+                trim_action,
+                move |track| {
+                    trim_action.set_enabled(track.is_saved() && !track.is_saving());
+                }
+            ));
+
+            self.obj().connect_is_saving_notify(clone!(
+                #[weak]
+                trim_action,
+                move |track| {
+                    trim_action.set_enabled(track.is_saved() && !track.is_saving());
+                }
+            ));
+
+            let search_action = gio::SimpleAction::new("search", Some(glib::VariantTy::STRING));
+            search_action.connect_activate(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_, target| {
+                    let provider = target
+                        .and_then(|v| v.str())
+                        .and_then(|p| SwSearchProvider::from_str(p).ok());
+
+                    if let Some(provider) = provider {
+                        imp.obj().search(provider);
+                    }
+                }
+            ));
+            actions.add_action(&search_action);
+
+            // Record the track the next time it gets played
+            let is_watched = WatchList::contains(&self.obj().title());
+            let watch_action =
+                gio::SimpleAction::new_stateful("watch", None, &is_watched.to_variant());
+            watch_action.connect_change_state(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |action, state| {
+                    let Some(watch) = state.and_then(|s| s.get::<bool>()) else {
+                        return;
+                    };
+
+                    if watch {
+                        WatchList::add(&imp.obj().title());
+                    } else {
+                        WatchList::remove(&imp.obj().title());
+                    }
+                    action.set_state(&watch.to_variant());
+                }
+            ));
+            actions.add_action(&watch_action);
+
+            self.obj().connect_is_saved_notify(clone!(
+                #[weak]
+                watch_action,
+                move |track| {
+                    watch_action.set_enabled(!track.is_saved());
+                }
+            ));
+
+            let copy_title_action = gio::SimpleAction::new("copy-title", None);
+            copy_title_action.connect_activate(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_, _| imp.obj().copy_title()
+            ));
+            actions.add_action(&copy_title_action);
+
+            let show_station_action = gio::SimpleAction::new("show-station", None);
+            show_station_action.connect_activate(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_, _| imp.obj().show_station()
+            ));
+            actions.add_action(&show_station_action);
+
             self.actions.set(actions).unwrap();
         }
 
         fn dispose(&self) {
-            if self.obj().state().is_recorded() {
-                self.obj()
-                    .file()
-                    .delete(gio::Cancellable::NONE)
-                    .handle_error("Unable to delete temporary recorded file")
+            // Saved tracks got moved to their destination already
+            if self.obj().state().is_recorded() && !self.obj().is_saved() {
+                self.obj().delete_recorded_file();
             }
         }
     }
@@ -170,45 +330,439 @@ impl SwTrack {
             .build()
     }
 
+    /// Creates a track for a recording which is left over from a previous session
+    pub fn recovered(title: &str, station: &SwStation, file: &gio::File) -> Self {
+        glib::Object::builder()
+            .property("title", title)
+            .property("station", station)
+            .property("file", file)
+            .build()
+    }
+
+    /// Creates a track for an entry of the track history from a previous session
+    pub fn restored(
+        uuid: &str,
+        title: &str,
+        station: &SwStation,
+        started_at: i64,
+        saved_to: Option<gio::File>,
+    ) -> Self {
+        let track = match &saved_to {
+            Some(file) => Self::recovered(title, station, file),
+            None => Self::new(title, station),
+        };
+
+        *track.imp().uuid.borrow_mut() = uuid.to_string();
+        track.imp().started_at.set(started_at);
+        if let Some(file) = saved_to {
+            track.set_saved_to(file);
+        }
+
+        track
+    }
+
+    pub fn started_at(&self) -> i64 {
+        self.imp().started_at.get()
+    }
+
     pub fn insert_actions<W: IsA<gtk::Widget>>(&self, widget: &W) {
         widget.insert_action_group("track", Some(self.imp().actions.get().unwrap()));
     }
 
-    pub fn save(&self) -> Result<(), Error> {
+    pub async fn save(&self) -> Result<(), Error> {
         if !self.state().is_recorded() {
             debug!("Track not recorded, not able to save it.");
             return Ok(());
         }
 
+        if self.is_saving() {
+            debug!("Track is already being saved.");
+            return Ok(());
+        }
+
+        self.set_save_progress(0.0);
+        self.imp().is_saving.set(true);
+        self.notify_is_saving();
+
+        // The title is part of the file name, so it has to be known before
+        self.identify().await;
+
         debug!("Save track \"{}\"", &self.title());
+        let destination = self.destination_file();
+        let res = self.move_to(&destination).await;
+
+        // Converting is optional, the track counts as saved either way
+        let res = match res {
+            Ok(()) => Ok(self.convert(destination).await),
+            Err(err) => Err(err),
+        };
+
+        // Same for adding the loudness information
+        let res = match res {
+            Ok(file) => Ok(self.normalize(file).await),
+            Err(err) => Err(err),
+        };
+
+        // And for the copy in the music library
+        if let Ok(file) = &res {
+            if settings_manager::boolean(Key::RecordingLibraryExport) {
+                music_library::export(self, file)
+                    .await
+                    .handle_error_toast(i18n("Unable to export track to music library"));
+            }
+        }
+
+        self.imp().is_saving.set(false);
+        self.notify_is_saving();
 
+        self.set_saved_to(res?);
+        Ok(())
+    }
+
+    /// Replaces placeholder titles like "Live Stream" with the title AcoustID
+    /// identifies based on the fingerprint of the recording
+    async fn identify(&self) {
+        let api_key = settings_manager::string(Key::RecordingAcoustidApiKey);
+        if api_key.trim().is_empty() || !self.has_placeholder_title() {
+            return;
+        }
+
+        debug!("Identify track \"{}\"", &self.title());
+        let res = match fingerprint::fingerprint(&self.file()).await {
+            Ok(Some(fingerprint)) => {
+                client::acoustid_lookup(api_key.trim(), &fingerprint, self.duration()).await
+            }
+            Ok(None) => Ok(None),
+            Err(err) => Err(err),
+        };
+
+        match res {
+            Ok(Some(recording)) => {
+                let title = recording.track_title();
+                debug!("Identified track \"{}\" as \"{}\"", &self.title(), &title);
+                self.set_title(title);
+            }
+            Ok(None) => debug!("Unable to find a match for track \"{}\"", &self.title()),
+            Err(err) => warn!("Unable to identify track: {err}"),
+        }
+    }
+
+    fn has_placeholder_title(&self) -> bool {
+        let title = self.title().trim().to_lowercase();
+        let station = self.station().title().trim().to_lowercase();
+
+        title.is_empty() || title == station || PLACEHOLDER_TITLES.contains(&title.as_str())
+    }
+
+    async fn move_to(&self, destination: &gio::File) -> Result<(), Error> {
+        // The destination can be located in a subdirectory which doesn't exist yet
+        if let Some(directory) = destination.parent() {
+            match directory.make_directory_with_parents(gio::Cancellable::NONE) {
+                Err(err) if !err.matches(gio::IOErrorEnum::Exists) => return Err(err.into()),
+                _ => (),
+            }
+        }
+
+        // Moving falls back to copy and delete if the destination is located on another device
+        let (move_future, progress) = self.file().move_future(
+            destination,
+            gio::FileCopyFlags::OVERWRITE,
+            glib::Priority::LOW,
+        );
+        let progress = progress.for_each(|(current, total)| {
+            if total > 0 {
+                self.set_save_progress(current as f64 / total as f64);
+            }
+            future::ready(())
+        });
+        let (res, _) = future::join(move_future, progress).await;
+        res?;
+
+        recording_recovery::remove_recording_info(&self.file());
+        Ok(())
+    }
+
+    /// Converts the saved track into the format chosen in the preferences. Returns the
+    /// converted file, or the original one if converting is disabled or failed.
+    async fn convert(&self, file: gio::File) -> gio::File {
+        let format = settings_manager::string(Key::RecordingConvertFormat);
+        let format = SwConvertFormat::from_str(&format).unwrap_or_default();
+        let Some(extension) = format.extension() else {
+            return file;
+        };
+
+        let Some(path) = file.path() else {
+            return file;
+        };
+        let converted = gio::File::for_path(path.with_extension(extension));
+
+        debug!("Convert track \"{}\" to {}", &self.title(), format);
+        self.set_save_progress(0.0);
+
+        let res = transcoder::transcode(
+            &file,
+            &converted,
+            format,
+            clone!(
+                #[weak(rename_to = track)]
+                self,
+                move |progress| track.set_save_progress(progress)
+            ),
+        )
+        .await;
+        res.handle_error_toast(i18n("Unable to convert track"));
+
+        // Keep only one of the files, depending on whether converting succeeded
+        let (kept, obsolete) = if res.is_ok() {
+            (converted, file)
+        } else {
+            (file, converted)
+        };
+
+        match obsolete.delete_future(glib::Priority::LOW).await {
+            Err(err) if !err.matches(gio::IOErrorEnum::NotFound) => {
+                warn!("Unable to delete file after converting track: {err}");
+            }
+            _ => (),
+        }
+
+        kept
+    }
+
+    /// Adds ReplayGain tags to the saved track, so that music players can play
+    /// all saved tracks with the same loudness. Returns the tagged file.
+    async fn normalize(&self, file: gio::File) -> gio::File {
+        if !settings_manager::boolean(Key::RecordingNormalizeLoudness) {
+            return file;
+        }
+
+        let Some(tagged) = Self::part_file(&file) else {
+            return file;
+        };
+
+        debug!("Normalize loudness of track \"{}\"", &self.title());
+        self.set_save_progress(0.0);
+
+        let res = async {
+            let tags = transcoder::analyze_loudness(
+                &file,
+                clone!(
+                    #[weak(rename_to = track)]
+                    self,
+                    move |progress| track.set_save_progress(progress)
+                ),
+            )
+            .await?;
+            transcoder::write_tags(&file, &tagged, &tags).await?;
+
+            let (move_future, _) =
+                tagged.move_future(&file, gio::FileCopyFlags::OVERWRITE, glib::Priority::LOW);
+            move_future.await?;
+            Ok::<_, Error>(())
+        }
+        .await;
+
+        if res.is_err() {
+            match tagged.delete_future(glib::Priority::LOW).await {
+                Err(err) if !err.matches(gio::IOErrorEnum::NotFound) => {
+                    warn!("Unable to delete file after normalizing track: {err}");
+                }
+                _ => (),
+            }
+        }
+        res.handle_error_toast(i18n("Unable to normalize track loudness"));
+
+        file
+    }
+
+    /// Cuts the saved track down to the part between `start` and `end`, e.g. to remove the
+    /// announcements at the beginning or the end of the track
+    pub async fn trim(
+        &self,
+        start: gstreamer::ClockTime,
+        end: gstreamer::ClockTime,
+    ) -> Result<(), Error> {
+        let Some(file) = self.saved_to() else {
+            debug!("Track not saved, not able to trim it.");
+            return Ok(());
+        };
+        let Some(trimmed) = Self::part_file(&file) else {
+            return Ok(());
+        };
+
+        debug!("Trim track \"{}\" to {} - {}", &self.title(), start, end);
+        let res = transcoder::trim(&file, &trimmed, start, end).await;
+
+        if let Err(err) = res {
+            match trimmed.delete_future(glib::Priority::LOW).await {
+                Err(err) if !err.matches(gio::IOErrorEnum::NotFound) => {
+                    warn!("Unable to delete file after trimming track: {err}");
+                }
+                _ => (),
+            }
+            return Err(err);
+        }
+
+        let (move_future, _) =
+            trimmed.move_future(&file, gio::FileCopyFlags::OVERWRITE, glib::Priority::LOW);
+        move_future.await?;
+
+        self.set_duration((end - start).seconds());
+        Ok(())
+    }
+
+    /// Temporary file next to the given one, for writing a modified version of it
+    fn part_file(file: &gio::File) -> Option<gio::File> {
+        let mut path = file.path()?.into_os_string();
+        path.push(".part");
+        Some(gio::File::for_path(PathBuf::from(path)))
+    }
+
+    fn set_save_progress(&self, progress: f64) {
+        self.imp().save_progress.set(progress);
+        self.notify_save_progress();
+    }
+
+    /// Discards the recording, so that the track can't be saved anymore
+    pub fn discard(&self) {
+        if !self.state().is_recorded() || self.is_saved() || self.is_saving() {
+            return;
+        }
+
+        debug!("Discard recorded track \"{}\"", &self.title());
+        self.delete_recorded_file();
+        self.set_state(SwRecordingState::DiscardedCancelled);
+    }
+
+    /// Marks the track as shown somewhere, so that its recorded data doesn't get
+    /// deleted while it's still possible to save it
+    pub fn hold(&self) {
+        let users = &self.imp().users;
+        users.set(users.get() + 1);
+    }
+
+    pub fn release(&self) {
+        let users = &self.imp().users;
+        users.set(users.get().saturating_sub(1));
+    }
+
+    pub fn is_in_use(&self) -> bool {
+        self.imp().users.get() > 0
+    }
+
+    /// Deletes the temporarily recorded data of this track
+    pub fn delete_recorded_file(&self) {
+        let file = self.file();
+        recording_recovery::remove_recording_info(&file);
+
+        glib::spawn_future_local(async move {
+            match file.delete_future(glib::Priority::LOW).await {
+                Err(err) if !err.matches(gio::IOErrorEnum::NotFound) => {
+                    warn!("Unable to delete temporary recorded file: {err}");
+                }
+                _ => (),
+            }
+        });
+    }
+
+    fn set_saved_to(&self, file: gio::File) {
+        *self.imp().saved_to.borrow_mut() = Some(file);
+        self.notify_saved_to();
+        self.notify_is_saved();
+    }
+
+    fn destination_file(&self) -> gio::File {
         let directory = settings_manager::string(Key::RecordingTrackDirectory);
-        let filename = sanitize_filename::sanitize(self.title()) + ".ogg";
+        let style = settings_manager::string(Key::RecordingFilenameStyle);
+        let style = SwFilenameStyle::from_str(&style).unwrap_or_default();
+        let filename = style.sanitize(&self.title()) + ".ogg";
 
         let mut path = PathBuf::from(directory);
+
+        // Saving all tracks would clutter a single directory quickly
+        let mode = SwApplication::default().player().active_recording_mode();
+        if mode == SwRecordingMode::Everything {
+            let structure = settings_manager::string(Key::RecordingFolderStructure);
+            let structure = SwFolderStructure::from_str(&structure).unwrap_or_default();
+            let date = glib::DateTime::from_unix_local(self.started_at()).unwrap();
+            path.push(structure.subdirectory(&self.station().title(), &date, style));
+        }
+
         path.push(filename);
+        gio::File::for_path(path)
+    }
 
-        fs::copy(self.file().path().unwrap(), &path).map_err(Rc::new)?;
+    /// Saves the track without blocking, errors are reported using a toast
+    pub fn save_in_background(&self) {
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = track)]
+            self,
+            async move {
+                track
+                    .save()
+                    .await
+                    .handle_error_toast("Unable to save track");
+            }
+        ));
+    }
 
-        *self.imp().saved_to.borrow_mut() = Some(gio::File::for_path(path));
-        self.notify_saved_to();
-        self.notify_is_saved();
+    /// Splits the stream title into artist and title, based on the common "Artist - Title" notation
+    pub fn artist_and_title(&self) -> (Option<String>, String) {
+        let title = self.title();
 
-        Ok(())
+        if let Some((artist, song)) = title.split_once(" - ") {
+            let artist = artist.trim();
+            let song = song.trim();
+
+            if !artist.is_empty() && !song.is_empty() {
+                return (Some(artist.to_string()), song.to_string());
+            }
+        }
+
+        (None, title.trim().to_string())
     }
 
+    pub fn search(&self, provider: SwSearchProvider) {
+        let (artist, title) = self.artist_and_title();
+        let url = provider.search_url(artist.as_deref(), &title);
+        debug!("Search track \"{}\" on {}", &self.title(), provider);
+
+        if let Some(win) = SwApplication::default().active_window() {
+            let launcher = gtk::UriLauncher::new(url.as_str());
+            launcher.launch(Some(&win), gio::Cancellable::NONE, |res| {
+                res.handle_error("Unable to open web browser");
+            });
+        }
+    }
+
+    pub fn copy_title(&self) {
+        let display = gdk::Display::default().unwrap();
+        display.clipboard().set_text(&self.title());
+
+        let window = SwApplication::default().application_window();
+        window.show_notification(&i18n("Copied"));
+    }
+
+    /// Shows the details of the station which played the track
+    pub fn show_station(&self) {
+        let window = SwApplication::default().application_window();
+        SwStationDialog::new(&self.station()).present(Some(&window));
+    }
+
+    /// Shows the editor for cutting off the beginning or the end of the saved track
+    pub fn show_trim_dialog(&self) {
+        let window = SwApplication::default().application_window();
+        SwTrimDialog::new(self).present(Some(&window));
+    }
+
+    /// Plays the saved track within the app
     pub fn play(&self) {
-        if let Some(file) = self.saved_to() {
-            debug!("Play track \"{}\"", &self.title());
-
-            if let Some(win) = SwApplication::default().active_window() {
-                let launcher = gtk::FileLauncher::new(Some(&file));
-                launcher.launch(Some(&win), gio::Cancellable::NONE, |res| {
-                    res.handle_error("Unable to play track");
-                });
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = track)]
+            self,
+            async move {
+                SwApplication::default().player().play_track(&track).await;
             }
-        } else {
-            debug!("Track not saved, not able to play it.");
-        }
+        ));
     }
 }
@@ -30,7 +30,7 @@ mod imp {
     #[derive(Debug, Properties, Default)]
     #[properties(wrapper_type = super::SwTrackModel)]
     pub struct SwTrackModel {
-        #[property(get, set)]
+        #[property(get, set=Self::set_max_count)]
         max_count: Cell<u32>,
 
         pub map: RefCell<IndexMap<String, SwTrack>>,
@@ -64,21 +64,39 @@ mod imp {
     }
 
     impl SwTrackModel {
+        fn set_max_count(&self, max_count: u32) {
+            self.max_count.set(max_count);
+            self.purge_tracks();
+        }
+
         pub fn purge_tracks(&self) {
             let removed = {
                 let mut map = self.map.borrow_mut();
 
                 if map.len() > self.obj().max_count() as usize {
-                    let len: usize = map.split_off((self.obj().max_count()) as usize).len();
-                    len
+                    map.split_off((self.obj().max_count()) as usize)
                 } else {
-                    0
+                    IndexMap::new()
                 }
             };
 
-            if removed > 0 {
+            if !removed.is_empty() {
                 self.obj()
-                    .items_changed(self.obj().max_count(), removed as u32, 0);
+                    .items_changed(self.obj().max_count(), removed.len() as u32, 0);
+            }
+
+            // Don't wait until the track objects get disposed, since they might be still
+            // referenced somewhere else. Tracks shown in an open track dialog can still
+            // be saved, their data gets deleted once the dialog is gone.
+            for track in removed.values() {
+                if track.state().is_recorded()
+                    && !track.is_saved()
+                    && !track.is_saving()
+                    && !track.is_in_use()
+                {
+                    debug!("Delete recorded data of purged track {:?}", track.title());
+                    track.delete_recorded_file();
+                }
             }
         }
     }
@@ -125,6 +143,23 @@ impl SwTrackModel {
         self.imp().purge_tracks();
     }
 
+    /// Removes all tracks, and deletes their recorded data if it hasn't been saved
+    pub fn clear(&self) {
+        let removed = std::mem::take(&mut *self.imp().map.borrow_mut());
+        self.items_changed(0, removed.len() as u32, 0);
+
+        for track in removed.values() {
+            if track.state().is_recorded()
+                && !track.is_saved()
+                && !track.is_saving()
+                && !track.is_in_use()
+            {
+                debug!("Delete recorded data of removed track {:?}", track.title());
+                track.delete_recorded_file();
+            }
+        }
+    }
+
     pub fn track_by_uuid(&self, uuid: &str) -> Option<SwTrack> {
         self.imp().map.borrow().get(uuid).cloned()
     }
@@ -0,0 +1,376 @@
+// Shortwave - transcoder.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::path::Path;
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use glib::clone;
+use gstreamer::prelude::*;
+use gstreamer::MessageView;
+use gtk::{gio, glib};
+
+use crate::api::Error;
+use crate::audio::{GstreamerBackend, SwConvertFormat};
+
+/// Converts the recorded `input` file into `output` using a separate pipeline.
+/// The progress gets reported as fraction between 0.0 and 1.0.
+pub async fn transcode(
+    input: &gio::File,
+    output: &gio::File,
+    format: SwConvertFormat,
+    progress: impl Fn(f64) + 'static,
+) -> Result<(), Error> {
+    let (Some(input), Some(output), Some(encoder)) =
+        (input.path(), output.path(), format.encoder())
+    else {
+        return Err(Error::Pipeline(String::from("Unsupported file or format")));
+    };
+
+    let pipeline = gstreamer::parse::launch(&format!(
+        "filesrc name=filesrc ! decodebin ! audioconvert ! audioresample ! {encoder} ! filesink name=filesink"
+    ))?;
+    let pipeline = pipeline.downcast::<gstreamer::Pipeline>().unwrap();
+
+    let filesrc = pipeline.by_name("filesrc").unwrap();
+    filesrc.set_property("location", input.to_string_lossy().to_string());
+    let filesink = pipeline.by_name("filesink").unwrap();
+    filesink.set_property("location", output.to_string_lossy().to_string());
+
+    pipeline
+        .set_state(gstreamer::State::Playing)
+        .map_err(|err| Error::Pipeline(err.to_string()))?;
+
+    let progress_source = report_progress(&pipeline, progress);
+    let res = wait_for_eos(&pipeline).await;
+
+    progress_source.remove();
+    let _ = pipeline.set_state(gstreamer::State::Null);
+
+    res
+}
+
+/// Analyzes the loudness of the `input` file, and returns the ReplayGain tags
+/// (track gain, track peak and reference level) for it.
+/// The progress gets reported as fraction between 0.0 and 1.0.
+pub async fn analyze_loudness(
+    input: &gio::File,
+    progress: impl Fn(f64) + 'static,
+) -> Result<gstreamer::TagList, Error> {
+    let Some(input) = input.path() else {
+        return Err(Error::Pipeline(String::from("Unsupported file")));
+    };
+
+    let pipeline = gstreamer::parse::launch(
+        "filesrc name=filesrc ! decodebin ! audioconvert ! audioresample ! rganalysis ! fakesink sync=false",
+    )?;
+    let pipeline = pipeline.downcast::<gstreamer::Pipeline>().unwrap();
+
+    let filesrc = pipeline.by_name("filesrc").unwrap();
+    filesrc.set_property("location", input.to_string_lossy().to_string());
+
+    pipeline
+        .set_state(gstreamer::State::Playing)
+        .map_err(|err| Error::Pipeline(err.to_string()))?;
+
+    let progress_source = report_progress(&pipeline, progress);
+
+    // The results get posted as tags once the whole file has been analyzed
+    let mut tags = gstreamer::TagList::new();
+    let mut res = Ok(());
+    let mut messages = pipeline.bus().unwrap().stream();
+    while let Some(message) = messages.next().await {
+        match message.view() {
+            MessageView::Tag(tag) => {
+                let tag = tag.tags();
+                if tag.get::<gstreamer::tags::TrackGain>().is_some() {
+                    tags = tag;
+                }
+            }
+            MessageView::Eos(_) => break,
+            MessageView::Error(err) => {
+                res = Err(Error::GLib(err.error()));
+                break;
+            }
+            _ => (),
+        }
+    }
+
+    progress_source.remove();
+    let _ = pipeline.set_state(gstreamer::State::Null);
+    res?;
+
+    if tags.get::<gstreamer::tags::TrackGain>().is_none() {
+        return Err(Error::Pipeline(String::from(
+            "No loudness information available",
+        )));
+    }
+
+    Ok(tags)
+}
+
+/// Copies the saved `input` file to `output` with the given tags, without re-encoding it.
+/// Existing tags of the file are kept, unless they get replaced by the given ones.
+pub async fn write_tags(
+    input: &gio::File,
+    output: &gio::File,
+    tags: &gstreamer::TagList,
+) -> Result<(), Error> {
+    let (Some(input), Some(output)) = (input.path(), output.path()) else {
+        return Err(Error::Pipeline(String::from("Unsupported file")));
+    };
+
+    let pipeline = gstreamer::parse::launch(&format!(
+        "filesrc name=filesrc ! {} ! filesink name=filesink",
+        remuxer(&input)?
+    ))?;
+    let pipeline = pipeline.downcast::<gstreamer::Pipeline>().unwrap();
+
+    let filesrc = pipeline.by_name("filesrc").unwrap();
+    filesrc.set_property("location", input.to_string_lossy().to_string());
+    let filesink = pipeline.by_name("filesink").unwrap();
+    filesink.set_property("location", output.to_string_lossy().to_string());
+
+    let tagger = pipeline
+        .by_name("tagger")
+        .and_dynamic_cast::<gstreamer::TagSetter>()
+        .unwrap();
+    tagger.merge_tags(tags, gstreamer::TagMergeMode::Replace);
+
+    pipeline
+        .set_state(gstreamer::State::Playing)
+        .map_err(|err| Error::Pipeline(err.to_string()))?;
+
+    let res = wait_for_eos(&pipeline).await;
+    let _ = pipeline.set_state(gstreamer::State::Null);
+
+    res
+}
+
+/// Determines the exact duration of the `input` file
+pub async fn duration(input: &gio::File) -> Result<gstreamer::ClockTime, Error> {
+    let Some(input) = input.path() else {
+        return Err(Error::Pipeline(String::from("Unsupported file")));
+    };
+
+    let pipeline = gstreamer::parse::launch("filesrc name=filesrc ! decodebin ! fakesink")?;
+    let pipeline = pipeline.downcast::<gstreamer::Pipeline>().unwrap();
+
+    let filesrc = pipeline.by_name("filesrc").unwrap();
+    filesrc.set_property("location", input.to_string_lossy().to_string());
+
+    // The duration is known as soon as the pipeline prerolled
+    pipeline
+        .set_state(gstreamer::State::Paused)
+        .map_err(|err| Error::Pipeline(err.to_string()))?;
+
+    let mut res = Err(Error::Pipeline(String::from("Unknown duration")));
+    let mut messages = pipeline.bus().unwrap().stream();
+    while let Some(message) = messages.next().await {
+        match message.view() {
+            MessageView::AsyncDone(_) => {
+                if let Some(duration) = pipeline.query_duration::<gstreamer::ClockTime>() {
+                    res = Ok(duration);
+                }
+                break;
+            }
+            MessageView::Error(err) => {
+                res = Err(Error::GLib(err.error()));
+                break;
+            }
+            _ => (),
+        }
+    }
+
+    let _ = pipeline.set_state(gstreamer::State::Null);
+    res
+}
+
+/// Calculates the peak levels of the `input` file for drawing a waveform. The file gets split
+/// into the given number of sections, with one level between 0.0 and 1.0 for each of them.
+pub async fn waveform(
+    input: &gio::File,
+    duration: gstreamer::ClockTime,
+    sections: u64,
+) -> Result<Vec<f64>, Error> {
+    let Some(input) = input.path() else {
+        return Err(Error::Pipeline(String::from("Unsupported file")));
+    };
+
+    let interval =
+        (duration.nseconds() / sections.max(1)).max(gstreamer::ClockTime::MSECOND.nseconds());
+    let pipeline = gstreamer::parse::launch(&format!(
+        "filesrc name=filesrc ! decodebin ! audioconvert ! level interval={interval} ! fakesink sync=false"
+    ))?;
+    let pipeline = pipeline.downcast::<gstreamer::Pipeline>().unwrap();
+
+    let filesrc = pipeline.by_name("filesrc").unwrap();
+    filesrc.set_property("location", input.to_string_lossy().to_string());
+
+    pipeline
+        .set_state(gstreamer::State::Playing)
+        .map_err(|err| Error::Pipeline(err.to_string()))?;
+
+    let mut peaks = Vec::new();
+    let mut res = Ok(());
+    let mut messages = pipeline.bus().unwrap().stream();
+    while let Some(message) = messages.next().await {
+        match message.view() {
+            MessageView::Element(element) => {
+                let structure = element.structure().unwrap();
+                if structure.name() == "level" {
+                    peaks.push(GstreamerBackend::level_amplitude(structure, "peak"));
+                }
+            }
+            MessageView::Eos(_) => break,
+            MessageView::Error(err) => {
+                res = Err(Error::GLib(err.error()));
+                break;
+            }
+            _ => (),
+        }
+    }
+
+    let _ = pipeline.set_state(gstreamer::State::Null);
+    res.map(|_| peaks)
+}
+
+/// Copies the part between `start` and `end` of the saved `input` file to `output`.
+/// The audio doesn't get re-encoded, so the cut happens at the nearest frame boundaries.
+pub async fn trim(
+    input: &gio::File,
+    output: &gio::File,
+    start: gstreamer::ClockTime,
+    end: gstreamer::ClockTime,
+) -> Result<(), Error> {
+    let (Some(input), Some(output)) = (input.path(), output.path()) else {
+        return Err(Error::Pipeline(String::from("Unsupported file")));
+    };
+
+    let pipeline = gstreamer::parse::launch(&format!(
+        "filesrc name=filesrc ! {} ! filesink name=filesink",
+        remuxer(&input)?
+    ))?;
+    let pipeline = pipeline.downcast::<gstreamer::Pipeline>().unwrap();
+
+    let filesrc = pipeline.by_name("filesrc").unwrap();
+    filesrc.set_property("location", input.to_string_lossy().to_string());
+    let filesink = pipeline.by_name("filesink").unwrap();
+    filesink.set_property("location", output.to_string_lossy().to_string());
+
+    // Seeking is only possible after the pipeline prerolled
+    pipeline
+        .set_state(gstreamer::State::Paused)
+        .map_err(|err| Error::Pipeline(err.to_string()))?;
+
+    let mut res = Ok(());
+    let mut seeked = false;
+    let mut messages = pipeline.bus().unwrap().stream();
+    while let Some(message) = messages.next().await {
+        match message.view() {
+            MessageView::AsyncDone(_) => {
+                // The seek itself posts another AsyncDone, which must not trigger a new seek
+                if seeked || message.src() != Some(pipeline.upcast_ref::<gstreamer::Object>()) {
+                    continue;
+                }
+                seeked = true;
+
+                let result = pipeline.seek(
+                    1.0,
+                    gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::ACCURATE,
+                    gstreamer::SeekType::Set,
+                    start,
+                    gstreamer::SeekType::Set,
+                    end,
+                );
+                if let Err(err) = result {
+                    res = Err(Error::Pipeline(err.to_string()));
+                    break;
+                }
+
+                if let Err(err) = pipeline.set_state(gstreamer::State::Playing) {
+                    res = Err(Error::Pipeline(err.to_string()));
+                    break;
+                }
+            }
+            MessageView::Eos(_) => break,
+            MessageView::Error(err) => {
+                res = Err(Error::GLib(err.error()));
+                break;
+            }
+            _ => (),
+        }
+    }
+
+    let _ = pipeline.set_state(gstreamer::State::Null);
+    res
+}
+
+/// Pipeline description for demuxing and muxing the file again without re-encoding it.
+/// The element which can be used for setting tags is named `tagger`.
+fn remuxer(path: &Path) -> Result<&'static str, Error> {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "ogg" => Ok("oggdemux ! vorbisparse ! vorbistag name=tagger ! oggmux"),
+        "mp3" => Ok("id3demux ! mpegaudioparse ! id3v2mux name=tagger"),
+        "flac" => Ok("flacparse ! flactag name=tagger"),
+        _ => Err(Error::Pipeline(String::from("Unsupported file format"))),
+    }
+}
+
+fn report_progress(
+    pipeline: &gstreamer::Pipeline,
+    progress: impl Fn(f64) + 'static,
+) -> glib::SourceId {
+    glib::timeout_add_local(
+        Duration::from_millis(250),
+        clone!(
+            #[weak]
+            pipeline,
+            #[upgrade_or]
+            glib::ControlFlow::Break,
+            move || {
+                let position = pipeline.query_position::<gstreamer::ClockTime>();
+                let duration = pipeline.query_duration::<gstreamer::ClockTime>();
+
+                if let (Some(position), Some(duration)) = (position, duration) {
+                    if duration.nseconds() > 0 {
+                        progress(position.nseconds() as f64 / duration.nseconds() as f64);
+                    }
+                }
+
+                glib::ControlFlow::Continue
+            }
+        ),
+    )
+}
+
+async fn wait_for_eos(pipeline: &gstreamer::Pipeline) -> Result<(), Error> {
+    let mut messages = pipeline.bus().unwrap().stream();
+    while let Some(message) = messages.next().await {
+        match message.view() {
+            MessageView::Eos(_) => return Ok(()),
+            MessageView::Error(err) => return Err(Error::GLib(err.error())),
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
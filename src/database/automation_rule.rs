@@ -0,0 +1,171 @@
+// Shortwave - automation_rule.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::Mutex;
+
+use regex::{Regex, RegexBuilder};
+
+use super::models::AutomationRuleEntry;
+use super::queries;
+use crate::api::SwStation;
+use crate::audio::SwRecordingMode;
+
+/// Enabled rules with their compiled title pattern. Loaded when the first track gets
+/// matched, and dropped again as soon as a rule gets changed.
+static ENABLED_RULES: Mutex<Option<Vec<(AutomationRule, Option<Regex>)>>> = Mutex::new(None);
+
+/// Actions which get executed automatically for tracks that match all conditions of the rule,
+/// e.g. "when station is X between 18 and 20 o'clock, save all tracks"
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct AutomationRule {
+    #[serde(skip)]
+    pub name: String,
+    pub enabled: bool,
+
+    /// UUID of the station, the rule applies to all stations if empty
+    pub station_uuid: String,
+    /// Only used to describe the rule, the station may not be in the library anymore
+    pub station_title: String,
+    /// Hours in which the rule applies, from the start until before the end hour. Wraps around
+    /// midnight if the end is before the start.
+    pub hours: Option<(u32, u32)>,
+    /// Case insensitive regular expression for the track title, matches all titles if empty
+    pub title_pattern: String,
+
+    /// Recording mode which gets used instead of the configured one
+    pub recording_mode: Option<SwRecordingMode>,
+    pub notify: bool,
+    pub save: bool,
+}
+
+impl AutomationRule {
+    pub fn all() -> Vec<Self> {
+        let entries = queries::automation_rules().unwrap_or_else(|err| {
+            warn!("Unable to load automation rules: {err}");
+            Vec::new()
+        });
+
+        entries
+            .into_iter()
+            .filter_map(|entry| match serde_json::from_str::<Self>(&entry.data) {
+                Ok(rule) => Some(Self {
+                    name: entry.name,
+                    ..rule
+                }),
+                Err(err) => {
+                    warn!("Unable to parse automation rule {}: {err}", entry.name);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Enabled rules whose conditions match the track which is currently played
+    pub fn matching(station: &SwStation, title: &str, hour: u32) -> Vec<Self> {
+        let mut enabled_rules = ENABLED_RULES.lock().unwrap();
+        enabled_rules
+            .get_or_insert_with(Self::compile_enabled)
+            .iter()
+            .filter(|(rule, regex)| {
+                let title_matches = match regex {
+                    Some(regex) => regex.is_match(title),
+                    None => true,
+                };
+                title_matches && rule.matches(station, hour)
+            })
+            .map(|(rule, _)| rule.clone())
+            .collect()
+    }
+
+    /// Loads the enabled rules and compiles their title patterns, rules with an
+    /// invalid pattern never match
+    fn compile_enabled() -> Vec<(Self, Option<Regex>)> {
+        Self::all()
+            .into_iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| {
+                if rule.title_pattern.is_empty() {
+                    return Some((rule, None));
+                }
+
+                match Self::title_regex(&rule.title_pattern) {
+                    Ok(regex) => Some((rule, Some(regex))),
+                    Err(err) => {
+                        warn!(
+                            "Invalid title pattern of automation rule {}: {err}",
+                            rule.name
+                        );
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn invalidate_cache() {
+        *ENABLED_RULES.lock().unwrap() = None;
+    }
+
+    /// Stores the rule, replacing any rule with the same name
+    pub fn store(&self) {
+        let res = queries::update_automation_rule(AutomationRuleEntry {
+            name: self.name.clone(),
+            data: serde_json::to_string(self).unwrap(),
+        });
+
+        if let Err(err) = res {
+            warn!("Unable to store automation rule: {err}");
+        }
+        Self::invalidate_cache();
+    }
+
+    pub fn delete(name: &str) {
+        if let Err(err) = queries::delete_automation_rule(name) {
+            warn!("Unable to delete automation rule: {err}");
+        }
+        Self::invalidate_cache();
+    }
+
+    /// Whether the station and hour conditions match, the title pattern is matched separately
+    fn matches(&self, station: &SwStation, hour: u32) -> bool {
+        if !self.station_uuid.is_empty() && self.station_uuid != station.uuid() {
+            return false;
+        }
+
+        if let Some((start, end)) = self.hours {
+            let in_hours = if start <= end {
+                (start..end).contains(&hour)
+            } else {
+                hour >= start || hour < end
+            };
+
+            if !in_hours {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    pub fn is_valid_pattern(pattern: &str) -> bool {
+        Self::title_regex(pattern).is_ok()
+    }
+
+    fn title_regex(pattern: &str) -> Result<Regex, regex::Error> {
+        RegexBuilder::new(pattern).case_insensitive(true).build()
+    }
+}
@@ -22,10 +22,13 @@ use gtk::{
     prelude::*,
     subclass::prelude::*,
 };
+use rand::prelude::IndexedRandom;
 
 use crate::{
     api::{SwStation, SwStationModel, SwStationSorter},
-    database::{models::StationEntry, queries, SwLibraryStatus},
+    database::{
+        models::StationEntry, queries, StationCredentials, StationSettings, SwLibraryStatus,
+    },
     settings::{settings_manager, Key},
 };
 
@@ -174,6 +177,16 @@ impl SwLibrary {
         for station in &stations {
             imp.model.remove_station(station);
             queries::delete_station(&station.uuid()).unwrap();
+
+            // Credentials are not part of the database, so they have to be removed separately
+            if StationSettings::load(&station.uuid()).http.authentication {
+                let uuid = station.uuid();
+                glib::spawn_future_local(async move {
+                    if let Err(err) = StationCredentials::delete(&uuid).await {
+                        warn!("Unable to remove station credentials: {err}");
+                    }
+                });
+            }
         }
 
         // Update status
@@ -186,6 +199,27 @@ impl SwLibrary {
         self.notify("status");
     }
 
+    /// Stores updated station metadata, if the station is part of the library
+    pub fn update_station(&self, station: &SwStation) {
+        let imp = imp::SwLibrary::from_obj(self);
+        let Some(library_station) = imp
+            .stations
+            .borrow()
+            .iter()
+            .find(|s| s.uuid() == station.uuid())
+            .cloned()
+        else {
+            return;
+        };
+
+        if &library_station != station {
+            library_station.set_metadata(station.metadata());
+        }
+
+        let entry = StationEntry::for_station(&library_station);
+        queries::update_station(entry).unwrap();
+    }
+
     pub fn contains_station(&self, station: &SwStation) -> bool {
         let imp = imp::SwLibrary::from_obj(self);
         imp.stations
@@ -194,6 +228,20 @@ impl SwLibrary {
             .any(|s| s.uuid() == station.uuid())
     }
 
+    pub fn station_by_uuid(&self, uuid: &str) -> Option<SwStation> {
+        let imp = imp::SwLibrary::from_obj(self);
+        imp.stations
+            .borrow()
+            .iter()
+            .find(|s| s.uuid() == uuid)
+            .cloned()
+    }
+
+    pub fn stations(&self) -> Vec<SwStation> {
+        let imp = imp::SwLibrary::from_obj(self);
+        imp.stations.borrow().clone()
+    }
+
     pub fn get_next_favorite(&self) -> Option<SwStation> {
         let imp = imp::SwLibrary::from_obj(self);
         if let Some(model) = imp.sorted_model.borrow().as_ref() {
@@ -282,6 +330,25 @@ impl SwLibrary {
         }
     }
 
+    /// Returns a random library station, other than the currently played one
+    pub fn random_station(&self) -> Option<SwStation> {
+        let imp = imp::SwLibrary::from_obj(self);
+        let current_uuid = crate::app::SwApplication::default()
+            .player()
+            .station()
+            .map(|station| station.uuid());
+
+        let stations: Vec<SwStation> = imp
+            .stations
+            .borrow()
+            .iter()
+            .filter(|station| Some(station.uuid()) != current_uuid)
+            .cloned()
+            .collect();
+
+        stations.choose(&mut rand::rng()).cloned()
+    }
+
     pub fn sorted_model(&self) -> Option<gtk::SortListModel> {
         let imp = imp::SwLibrary::from_obj(self);
         imp.sorted_model.borrow().clone()
@@ -14,12 +14,28 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+mod automation_rule;
 mod connection;
 mod library;
 mod library_status;
 mod models;
 mod queries;
+mod saved_search;
 mod schema;
+mod station_credentials;
+mod station_history;
+mod station_popularity;
+mod station_settings;
+mod track_history;
+mod watch_list;
 
+pub use automation_rule::AutomationRule;
 pub use library::SwLibrary;
 pub use library_status::SwLibraryStatus;
+pub use saved_search::SavedSearch;
+pub use station_credentials::StationCredentials;
+pub use station_history::{StationHistory, StationPlayStats};
+pub use station_popularity::StationPopularity;
+pub use station_settings::{HttpSettings, StationSettings, TitleParsingRules};
+pub use track_history::TrackHistory;
+pub use watch_list::WatchList;
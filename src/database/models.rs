@@ -54,3 +54,122 @@ impl StationEntry {
         }
     }
 }
+
+/// Settings of an individual station, which can also be stored for stations
+/// which aren't part of the library.
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = station_settings)]
+pub struct StationSettingsEntry {
+    /// Unique ID of the station.
+    pub uuid: String,
+
+    /// Serialized station settings.
+    pub data: String,
+}
+
+/// A rule which automates actions, saved under a name.
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = automation_rules)]
+pub struct AutomationRuleEntry {
+    /// Name under which the rule got saved.
+    pub name: String,
+
+    /// Serialized rule.
+    pub data: String,
+}
+
+/// A search which got saved under a name.
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = saved_searches)]
+pub struct SavedSearchEntry {
+    /// Name under which the search got saved.
+    pub name: String,
+
+    /// Serialized search.
+    pub data: String,
+}
+
+/// A period of time in which a station has been listened to.
+#[derive(Queryable, Debug, Clone)]
+#[diesel(table_name = station_history)]
+pub struct StationHistoryEntry {
+    pub id: i32,
+
+    /// Unique ID of the station.
+    pub uuid: String,
+
+    /// Unix timestamp of the playback start.
+    pub started_at: i64,
+
+    /// Playback duration in seconds.
+    pub duration: i32,
+}
+
+/// A new history entry, the ID gets assigned by the database.
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = station_history)]
+pub struct NewStationHistoryEntry {
+    pub uuid: String,
+    pub started_at: i64,
+    pub duration: i32,
+}
+
+/// A track which has been played, including the outcome of its recording.
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = track_history)]
+pub struct TrackHistoryEntry {
+    /// Unique ID of the track.
+    pub uuid: String,
+
+    /// Title of the track, as announced by the station.
+    pub title: String,
+
+    /// Unique ID of the station which played the track.
+    pub station_uuid: String,
+
+    /// Whether the station is local.
+    pub station_is_local: bool,
+
+    /// Serialized station metadata.
+    pub station_data: String,
+
+    /// Unix timestamp of the playback start.
+    pub started_at: i64,
+
+    /// Duration of the track in seconds.
+    pub duration: i64,
+
+    /// Recording state of the track.
+    pub state: String,
+
+    /// Path of the recording, if it got saved.
+    pub saved_to: Option<String>,
+}
+
+/// A track which should be recorded the next time it gets played.
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = watched_tracks)]
+pub struct WatchedTrackEntry {
+    /// Title of the track, as announced by the station.
+    pub title: String,
+
+    /// Unix timestamp of when the track got added.
+    pub added_at: i64,
+}
+
+/// Popularity of a station on a day, as reported by radio-browser.
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = station_popularity)]
+pub struct StationPopularityEntry {
+    /// Unique ID of the station.
+    pub uuid: String,
+
+    /// Days since the Unix epoch.
+    pub day: i64,
+
+    /// Number of clicks within the last 24 hours.
+    pub clickcount: i32,
+
+    /// Number of votes.
+    pub votes: i32,
+}
@@ -14,8 +14,15 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use super::models::StationEntry;
-use super::schema::library;
+use super::models::{
+    AutomationRuleEntry, NewStationHistoryEntry, SavedSearchEntry, StationEntry,
+    StationHistoryEntry, StationPopularityEntry, StationSettingsEntry, TrackHistoryEntry,
+    WatchedTrackEntry,
+};
+use super::schema::{
+    automation_rules, library, saved_searches, station_history, station_popularity,
+    station_settings, track_history, watched_tracks,
+};
 use crate::database;
 use crate::diesel::prelude::*;
 
@@ -52,3 +59,181 @@ pub fn delete_station(uuid: &str) -> Result<(), diesel::result::Error> {
     diesel::delete(library::table.filter(library::uuid.eq(uuid))).execute(&mut *con)?;
     Ok(())
 }
+
+pub fn station_settings(uuid: &str) -> Result<Option<StationSettingsEntry>, diesel::result::Error> {
+    let mut con = connect_db!();
+    let entry = station_settings::table
+        .filter(station_settings::uuid.eq(uuid))
+        .first::<StationSettingsEntry>(&mut con)
+        .optional()?;
+    Ok(entry)
+}
+
+pub fn update_station_settings(entry: StationSettingsEntry) -> Result<(), diesel::result::Error> {
+    let mut con = connect_db!();
+    diesel::replace_into(station_settings::table)
+        .values(entry)
+        .execute(&mut *con)?;
+    Ok(())
+}
+
+pub fn delete_station_settings(uuid: &str) -> Result<(), diesel::result::Error> {
+    let mut con = connect_db!();
+    diesel::delete(station_settings::table.filter(station_settings::uuid.eq(uuid)))
+        .execute(&mut *con)?;
+    Ok(())
+}
+
+pub fn station_history(since: i64) -> Result<Vec<StationHistoryEntry>, diesel::result::Error> {
+    let mut con = connect_db!();
+    let entries = station_history::table
+        .filter(station_history::started_at.ge(since))
+        .load::<StationHistoryEntry>(&mut con)?;
+    Ok(entries)
+}
+
+pub fn insert_station_history(entry: NewStationHistoryEntry) -> Result<(), diesel::result::Error> {
+    let mut con = connect_db!();
+    diesel::insert_into(station_history::table)
+        .values(entry)
+        .execute(&mut *con)?;
+    Ok(())
+}
+
+pub fn purge_station_history(before: i64) -> Result<(), diesel::result::Error> {
+    let mut con = connect_db!();
+    diesel::delete(station_history::table.filter(station_history::started_at.lt(before)))
+        .execute(&mut *con)?;
+    Ok(())
+}
+
+pub fn station_popularity(
+    uuid: &str,
+    since: i64,
+) -> Result<Vec<StationPopularityEntry>, diesel::result::Error> {
+    let mut con = connect_db!();
+    let entries = station_popularity::table
+        .filter(station_popularity::uuid.eq(uuid))
+        .filter(station_popularity::day.ge(since))
+        .order(station_popularity::day.asc())
+        .load::<StationPopularityEntry>(&mut con)?;
+    Ok(entries)
+}
+
+pub fn update_station_popularity(
+    entry: StationPopularityEntry,
+) -> Result<(), diesel::result::Error> {
+    let mut con = connect_db!();
+    diesel::replace_into(station_popularity::table)
+        .values(entry)
+        .execute(&mut *con)?;
+    Ok(())
+}
+
+pub fn purge_station_popularity(before: i64) -> Result<(), diesel::result::Error> {
+    let mut con = connect_db!();
+    diesel::delete(station_popularity::table.filter(station_popularity::day.lt(before)))
+        .execute(&mut *con)?;
+    Ok(())
+}
+
+pub fn saved_searches() -> Result<Vec<SavedSearchEntry>, diesel::result::Error> {
+    let mut con = connect_db!();
+    let entries = saved_searches::table
+        .order(saved_searches::name.asc())
+        .load::<SavedSearchEntry>(&mut con)?;
+    Ok(entries)
+}
+
+pub fn update_saved_search(entry: SavedSearchEntry) -> Result<(), diesel::result::Error> {
+    let mut con = connect_db!();
+    diesel::replace_into(saved_searches::table)
+        .values(entry)
+        .execute(&mut *con)?;
+    Ok(())
+}
+
+pub fn delete_saved_search(name: &str) -> Result<(), diesel::result::Error> {
+    let mut con = connect_db!();
+    diesel::delete(saved_searches::table.filter(saved_searches::name.eq(name)))
+        .execute(&mut *con)?;
+    Ok(())
+}
+
+pub fn automation_rules() -> Result<Vec<AutomationRuleEntry>, diesel::result::Error> {
+    let mut con = connect_db!();
+    let entries = automation_rules::table
+        .order(automation_rules::name.asc())
+        .load::<AutomationRuleEntry>(&mut con)?;
+    Ok(entries)
+}
+
+pub fn update_automation_rule(entry: AutomationRuleEntry) -> Result<(), diesel::result::Error> {
+    let mut con = connect_db!();
+    diesel::replace_into(automation_rules::table)
+        .values(entry)
+        .execute(&mut *con)?;
+    Ok(())
+}
+
+pub fn delete_automation_rule(name: &str) -> Result<(), diesel::result::Error> {
+    let mut con = connect_db!();
+    diesel::delete(automation_rules::table.filter(automation_rules::name.eq(name)))
+        .execute(&mut *con)?;
+    Ok(())
+}
+
+pub fn track_history(limit: i64) -> Result<Vec<TrackHistoryEntry>, diesel::result::Error> {
+    let mut con = connect_db!();
+    let entries = track_history::table
+        .order(track_history::started_at.desc())
+        .limit(limit)
+        .load::<TrackHistoryEntry>(&mut con)?;
+    Ok(entries)
+}
+
+pub fn update_track_history(entry: TrackHistoryEntry) -> Result<(), diesel::result::Error> {
+    let mut con = connect_db!();
+    diesel::replace_into(track_history::table)
+        .values(entry)
+        .execute(&mut *con)?;
+    Ok(())
+}
+
+/// Deletes all except the `keep` most recent history entries
+pub fn purge_track_history(keep: i64) -> Result<(), diesel::result::Error> {
+    let mut con = connect_db!();
+    let recent = track_history::table
+        .select(track_history::uuid)
+        .order(track_history::started_at.desc())
+        .limit(keep)
+        .load::<String>(&mut con)?;
+
+    diesel::delete(track_history::table.filter(track_history::uuid.ne_all(recent)))
+        .execute(&mut *con)?;
+    Ok(())
+}
+
+pub fn watched_track(title: &str) -> Result<Option<WatchedTrackEntry>, diesel::result::Error> {
+    let mut con = connect_db!();
+    let entry = watched_tracks::table
+        .filter(watched_tracks::title.eq(title))
+        .first::<WatchedTrackEntry>(&mut con)
+        .optional()?;
+    Ok(entry)
+}
+
+pub fn insert_watched_track(entry: WatchedTrackEntry) -> Result<(), diesel::result::Error> {
+    let mut con = connect_db!();
+    diesel::replace_into(watched_tracks::table)
+        .values(entry)
+        .execute(&mut *con)?;
+    Ok(())
+}
+
+pub fn delete_watched_track(title: &str) -> Result<(), diesel::result::Error> {
+    let mut con = connect_db!();
+    diesel::delete(watched_tracks::table.filter(watched_tracks::title.eq(title)))
+        .execute(&mut *con)?;
+    Ok(())
+}
@@ -0,0 +1,74 @@
+// Shortwave - saved_search.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use super::models::SavedSearchEntry;
+use super::queries;
+use crate::api::{StationRequest, SwStationSorting, SwStationSortingType};
+
+/// A search query including its filters and sorting, saved under a name so
+/// that it can be executed again later
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct SavedSearch {
+    #[serde(skip)]
+    pub name: String,
+    pub request: StationRequest,
+    pub sorting: SwStationSorting,
+    pub sorting_type: SwStationSortingType,
+    /// Whether the results get shown as own section on the discover page
+    pub show_in_discover: bool,
+}
+
+impl SavedSearch {
+    pub fn all() -> Vec<Self> {
+        let entries = queries::saved_searches().unwrap_or_else(|err| {
+            warn!("Unable to load saved searches: {err}");
+            Vec::new()
+        });
+
+        entries
+            .into_iter()
+            .filter_map(|entry| match serde_json::from_str::<Self>(&entry.data) {
+                Ok(search) => Some(Self {
+                    name: entry.name,
+                    ..search
+                }),
+                Err(err) => {
+                    warn!("Unable to parse saved search {}: {err}", entry.name);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Stores the search, replacing any saved search with the same name
+    pub fn store(&self) {
+        let res = queries::update_saved_search(SavedSearchEntry {
+            name: self.name.clone(),
+            data: serde_json::to_string(self).unwrap(),
+        });
+
+        if let Err(err) = res {
+            warn!("Unable to store saved search: {err}");
+        }
+    }
+
+    pub fn delete(name: &str) {
+        if let Err(err) = queries::delete_saved_search(name) {
+            warn!("Unable to delete saved search: {err}");
+        }
+    }
+}
@@ -14,6 +14,13 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+table! {
+    automation_rules (name) {
+        name -> Text,
+        data -> Text,
+    }
+}
+
 table! {
     library (uuid) {
         uuid -> Text,
@@ -23,4 +30,66 @@ table! {
     }
 }
 
-allow_tables_to_appear_in_same_query!(library,);
+table! {
+    saved_searches (name) {
+        name -> Text,
+        data -> Text,
+    }
+}
+
+table! {
+    station_history (id) {
+        id -> Integer,
+        uuid -> Text,
+        started_at -> BigInt,
+        duration -> Integer,
+    }
+}
+
+table! {
+    station_popularity (uuid, day) {
+        uuid -> Text,
+        day -> BigInt,
+        clickcount -> Integer,
+        votes -> Integer,
+    }
+}
+
+table! {
+    station_settings (uuid) {
+        uuid -> Text,
+        data -> Text,
+    }
+}
+
+table! {
+    track_history (uuid) {
+        uuid -> Text,
+        title -> Text,
+        station_uuid -> Text,
+        station_is_local -> Bool,
+        station_data -> Text,
+        started_at -> BigInt,
+        duration -> BigInt,
+        state -> Text,
+        saved_to -> Nullable<Text>,
+    }
+}
+
+table! {
+    watched_tracks (title) {
+        title -> Text,
+        added_at -> BigInt,
+    }
+}
+
+allow_tables_to_appear_in_same_query!(
+    automation_rules,
+    library,
+    saved_searches,
+    station_history,
+    station_popularity,
+    station_settings,
+    track_history,
+    watched_tracks,
+);
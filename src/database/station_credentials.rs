@@ -0,0 +1,119 @@
+// Shortwave - station_credentials.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt;
+use std::rc::Rc;
+
+use gtk::glib;
+
+use super::StationSettings;
+use crate::api::Error;
+use crate::config;
+
+/// Username and password of a station with a protected stream. They get
+/// stored in the keyring, and not in the database.
+#[derive(Clone, PartialEq)]
+pub struct StationCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+// Keep the password out of log output
+impl fmt::Debug for StationCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StationCredentials")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StationCredentials {
+    pub async fn load(uuid: &str) -> Result<Option<Self>, Error> {
+        let keyring = oo7::Keyring::new().await.map_err(Rc::new)?;
+        let items = keyring
+            .search_items(&Self::attributes(uuid))
+            .await
+            .map_err(Rc::new)?;
+
+        let Some(item) = items.first() else {
+            return Ok(None);
+        };
+
+        let attributes = item.attributes().await.map_err(Rc::new)?;
+        let secret = item.secret().await.map_err(Rc::new)?;
+
+        Ok(Some(Self {
+            username: attributes.get("username").cloned().unwrap_or_default(),
+            password: String::from_utf8_lossy(secret.as_bytes()).to_string(),
+        }))
+    }
+
+    pub async fn store(&self, uuid: &str, station_name: &str) -> Result<(), Error> {
+        let keyring = oo7::Keyring::new().await.map_err(Rc::new)?;
+        let mut attributes = Self::attributes(uuid);
+        attributes.push(("username", self.username.as_str()));
+
+        keyring
+            .create_item(station_name, &attributes, self.password.as_str(), true)
+            .await
+            .map_err(Rc::new)?;
+        Ok(())
+    }
+
+    pub async fn delete(uuid: &str) -> Result<(), Error> {
+        let keyring = oo7::Keyring::new().await.map_err(Rc::new)?;
+        keyring
+            .delete(&Self::attributes(uuid))
+            .await
+            .map_err(Rc::new)?;
+        Ok(())
+    }
+
+    /// Stores the credentials of a station, or removes them if both username and password are empty
+    pub async fn update(
+        uuid: &str,
+        station_name: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<(), Error> {
+        let authentication = !username.is_empty() || !password.is_empty();
+        if authentication {
+            let credentials = Self {
+                username: username.to_string(),
+                password: password.to_string(),
+            };
+            credentials.store(uuid, station_name).await?;
+        } else {
+            Self::delete(uuid).await?;
+        }
+
+        let mut settings = StationSettings::load(uuid);
+        settings.http.authentication = authentication;
+        settings.store(uuid);
+
+        Ok(())
+    }
+
+    /// Value of the `Authorization` header for HTTP basic authentication
+    pub fn authorization_header(&self) -> String {
+        let credentials = format!("{}:{}", self.username, self.password);
+        format!("Basic {}", glib::base64_encode(credentials.as_bytes()))
+    }
+
+    fn attributes(uuid: &str) -> Vec<(&str, &str)> {
+        vec![("application", config::APP_ID), ("station", uuid)]
+    }
+}
@@ -0,0 +1,138 @@
+// Shortwave - station_history.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use gtk::glib;
+use rand::prelude::IndexedRandom;
+
+use super::models::NewStationHistoryEntry;
+use super::queries;
+use crate::api::SwStation;
+
+/// Listening periods which are shorter than this don't get recorded
+const MIN_DURATION: u64 = 60;
+/// How many days of history are considered for suggestions
+const MAX_AGE_DAYS: i64 = 60;
+/// Entries older than this many days get removed, they also don't matter for the play statistics
+const MAX_HISTORY_DAYS: i64 = 365;
+/// Stations listened to within this many hours of the current time of day are preferred
+const HOUR_WINDOW: f64 = 4.0;
+/// After how many days the weight of a history entry is halved
+const HALF_LIFE_DAYS: f64 = 14.0;
+
+/// How recently and how long a station has been listened to in total
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StationPlayStats {
+    /// Unix timestamp of the last playback start
+    pub last_played: i64,
+    /// Total playback duration in seconds
+    pub total_duration: i64,
+}
+
+/// Keeps track of how long stations are listened to, so that fitting stations
+/// can be suggested depending on the time of day
+pub struct StationHistory;
+
+impl StationHistory {
+    pub fn record(uuid: &str, started_at: &glib::DateTime, duration: u64) {
+        if duration < MIN_DURATION {
+            return;
+        }
+
+        let entry = NewStationHistoryEntry {
+            uuid: uuid.to_string(),
+            started_at: started_at.to_unix(),
+            duration: duration.try_into().unwrap_or(i32::MAX),
+        };
+
+        if let Err(err) = queries::insert_station_history(entry) {
+            warn!("Unable to store station history: {err}");
+        }
+
+        let before = started_at.to_unix() - MAX_HISTORY_DAYS * 24 * 60 * 60;
+        if let Err(err) = queries::purge_station_history(before) {
+            warn!("Unable to purge station history: {err}");
+        }
+    }
+
+    /// Play statistics of all stations which have been listened to, by their UUID
+    pub fn play_stats() -> HashMap<String, StationPlayStats> {
+        let entries = match queries::station_history(0) {
+            Ok(entries) => entries,
+            Err(err) => {
+                warn!("Unable to load station history: {err}");
+                return HashMap::new();
+            }
+        };
+
+        let mut stats: HashMap<String, StationPlayStats> = HashMap::new();
+        for entry in entries {
+            let station_stats = stats.entry(entry.uuid).or_default();
+            station_stats.last_played = station_stats.last_played.max(entry.started_at);
+            station_stats.total_duration += i64::from(entry.duration);
+        }
+
+        stats
+    }
+
+    /// Picks one of the `stations`, preferring the ones which usually get
+    /// listened to around the current time of day
+    pub fn suggest(stations: &[SwStation]) -> Option<SwStation> {
+        let now = glib::DateTime::now_local().ok()?;
+        let since = now.to_unix() - MAX_AGE_DAYS * 24 * 60 * 60;
+
+        let entries = match queries::station_history(since) {
+            Ok(entries) => entries,
+            Err(err) => {
+                warn!("Unable to load station history: {err}");
+                return None;
+            }
+        };
+
+        let mut scores: HashMap<String, f64> = HashMap::new();
+        for entry in entries {
+            let Ok(started_at) = glib::DateTime::from_unix_local(entry.started_at) else {
+                continue;
+            };
+
+            // Distance between the times of day, e.g. 23:00 and 01:00 are 2 hours apart
+            let hours = (started_at.hour() - now.hour()).abs() as f64;
+            let hours = hours.min(24.0 - hours);
+            let time_weight = (1.0 - hours / HOUR_WINDOW).max(0.0);
+
+            let age_days = (now.to_unix() - entry.started_at) as f64 / (24.0 * 60.0 * 60.0);
+            let age_weight = 0.5_f64.powf(age_days / HALF_LIFE_DAYS);
+
+            let minutes = f64::from(entry.duration) / 60.0;
+            *scores.entry(entry.uuid).or_default() += minutes * time_weight * age_weight;
+        }
+
+        let candidates: Vec<(&SwStation, f64)> = stations
+            .iter()
+            .filter_map(|station| {
+                let score = scores.get(&station.uuid()).copied().unwrap_or_default();
+                (score > 0.0).then_some((station, score))
+            })
+            .collect();
+
+        // Weighted random choice, so that not always the same station gets suggested
+        candidates
+            .choose_weighted(&mut rand::rng(), |(_, score)| *score)
+            .ok()
+            .map(|(station, _)| (*station).clone())
+    }
+}
@@ -0,0 +1,65 @@
+// Shortwave - station_popularity.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use gtk::glib;
+
+use super::models::StationPopularityEntry;
+use super::queries;
+use crate::api::StationMetadata;
+
+/// How many days of popularity samples are kept
+const MAX_AGE_DAYS: i64 = 30;
+
+/// Keeps daily samples of the click counts reported by radio-browser, so that
+/// the popularity of a station can be followed over time
+pub struct StationPopularity;
+
+impl StationPopularity {
+    /// Stores the click count and votes of the station for today
+    pub fn record(uuid: &str, metadata: &StationMetadata) {
+        let today = Self::today();
+        let entry = StationPopularityEntry {
+            uuid: uuid.to_string(),
+            day: today,
+            clickcount: metadata.clickcount,
+            votes: metadata.votes,
+        };
+
+        if let Err(err) = queries::update_station_popularity(entry) {
+            warn!("Unable to store station popularity: {err}");
+        }
+
+        if let Err(err) = queries::purge_station_popularity(today - MAX_AGE_DAYS) {
+            warn!("Unable to purge station popularity: {err}");
+        }
+    }
+
+    /// Daily click counts of the station, starting with the oldest one
+    pub fn click_counts(uuid: &str) -> Vec<i32> {
+        let entries = queries::station_popularity(uuid, Self::today() - MAX_AGE_DAYS)
+            .unwrap_or_else(|err| {
+                warn!("Unable to load station popularity: {err}");
+                Vec::new()
+            });
+
+        entries.into_iter().map(|entry| entry.clickcount).collect()
+    }
+
+    /// Days since the Unix epoch
+    fn today() -> i64 {
+        glib::DateTime::now_utc().unwrap().to_unix() / (24 * 60 * 60)
+    }
+}
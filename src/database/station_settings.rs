@@ -0,0 +1,187 @@
+// Shortwave - station_settings.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use super::models::StationSettingsEntry;
+use super::{queries, StationCredentials};
+
+/// Settings which are stored for individual stations, identified by their uuid
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct StationSettings {
+    pub title_parsing: TitleParsingRules,
+    pub http: HttpSettings,
+    /// Uuid of the library station which gets played when this station fails repeatedly
+    pub backup_station: Option<String>,
+    /// Whether no desktop notifications are shown for new tracks, e.g. for news loops
+    pub mute_notifications: bool,
+}
+
+impl StationSettings {
+    pub fn load(uuid: &str) -> Self {
+        match queries::station_settings(uuid) {
+            Ok(Some(entry)) => serde_json::from_str(&entry.data).unwrap_or_default(),
+            Ok(None) => Self::default(),
+            Err(err) => {
+                warn!("Unable to load station settings: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn store(&self, uuid: &str) {
+        let res = if *self == Self::default() {
+            queries::delete_station_settings(uuid)
+        } else {
+            queries::update_station_settings(StationSettingsEntry {
+                uuid: uuid.to_string(),
+                data: serde_json::to_string(self).unwrap(),
+            })
+        };
+
+        if let Err(err) = res {
+            warn!("Unable to store station settings: {err}");
+        }
+    }
+}
+
+/// HTTP options which are used when requesting the stream of a station,
+/// for streams which require e.g. a specific referer or user agent
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct HttpSettings {
+    /// Replaces the default user agent, if not empty
+    pub user_agent: String,
+    /// Additional request headers
+    pub headers: BTreeMap<String, String>,
+    /// Whether credentials for this station are stored in the keyring
+    pub authentication: bool,
+    /// Credentials loaded from the keyring, they never get stored in the database
+    #[serde(skip)]
+    pub credentials: Option<StationCredentials>,
+}
+
+impl HttpSettings {
+    pub fn with_credentials(mut self, credentials: StationCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Parses headers in the "Name: Value" notation, one per line. Values may contain
+    /// colons and semicolons themselves, e.g. URLs or cookies.
+    pub fn parse_headers(text: &str) -> BTreeMap<String, String> {
+        text.lines()
+            .filter_map(|header| header.split_once(':'))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .filter(|(name, value)| !name.is_empty() && !value.is_empty())
+            .collect()
+    }
+
+    pub fn format_headers(headers: &BTreeMap<String, String>) -> String {
+        headers
+            .iter()
+            .map(|(name, value)| format!("{name}: {value}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Describes how the stream title of a station is made up, so that it can be
+/// normalized to the "Artist - Title" notation
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct TitleParsingRules {
+    /// Prefixes which get removed, e.g. "Now playing:"
+    pub strip_prefixes: Vec<String>,
+    /// Separator between artist and title
+    pub separator: String,
+    /// Whether the title comes before the artist
+    pub swap_artist_title: bool,
+}
+
+impl Default for TitleParsingRules {
+    fn default() -> Self {
+        Self {
+            strip_prefixes: Vec::new(),
+            separator: String::from(" - "),
+            swap_artist_title: false,
+        }
+    }
+}
+
+impl TitleParsingRules {
+    pub fn apply(&self, title: &str) -> String {
+        let mut title = title.trim();
+
+        for prefix in &self.strip_prefixes {
+            let prefix = prefix.trim();
+            if prefix.is_empty() {
+                continue;
+            }
+
+            if let Some(stripped) = title
+                .get(..prefix.len())
+                .filter(|start| start.eq_ignore_ascii_case(prefix))
+                .map(|_| &title[prefix.len()..])
+            {
+                title = stripped.trim_start();
+            }
+        }
+
+        let split = if self.separator.is_empty() {
+            None
+        } else {
+            title.split_once(self.separator.as_str())
+        };
+
+        match split {
+            Some((first, second)) if !first.trim().is_empty() && !second.trim().is_empty() => {
+                let (artist, song) = if self.swap_artist_title {
+                    (second.trim(), first.trim())
+                } else {
+                    (first.trim(), second.trim())
+                };
+                format!("{artist} - {song}")
+            }
+            _ => title.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headers_are_parsed_per_line() {
+        let headers = HttpSettings::parse_headers(
+            "Origin: https://example.org\nCookie: a=1; b=2\n\nInvalid\nEmpty:",
+        );
+
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["Origin"], "https://example.org");
+        assert_eq!(headers["Cookie"], "a=1; b=2");
+    }
+
+    #[test]
+    fn formatted_headers_parse_again() {
+        let headers = HttpSettings::parse_headers("Origin: https://example.org\nX-Token: abc");
+        let text = HttpSettings::format_headers(&headers);
+
+        assert_eq!(HttpSettings::parse_headers(&text), headers);
+    }
+}
@@ -0,0 +1,119 @@
+// Shortwave - track_history.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::str::FromStr;
+
+use gtk::gio;
+use gtk::prelude::*;
+
+use super::models::TrackHistoryEntry;
+use super::queries;
+use crate::api::{StationMetadata, SwStation};
+use crate::app::SwApplication;
+use crate::audio::{SwRecordingState, SwTrack};
+
+/// Keeps the played tracks, so that they are still available after a restart
+pub struct TrackHistory;
+
+impl TrackHistory {
+    /// Stores the finished `track`, only the `max_count` most recent tracks are kept
+    pub fn record(track: &SwTrack, max_count: u32) {
+        Self::store(track);
+
+        // The track can still be saved afterwards
+        track.connect_is_saved_notify(Self::store);
+
+        if let Err(err) = queries::purge_track_history(max_count.into()) {
+            warn!("Unable to purge track history: {err}");
+        }
+    }
+
+    /// Loads the `limit` most recent tracks, starting with the most recent one
+    pub fn load(limit: u32) -> Vec<SwTrack> {
+        let entries = queries::track_history(limit.into()).unwrap_or_else(|err| {
+            warn!("Unable to load track history: {err}");
+            Vec::new()
+        });
+
+        let library = SwApplication::default().library();
+        entries
+            .into_iter()
+            .filter_map(|entry| {
+                let station = match library.station_by_uuid(&entry.station_uuid) {
+                    Some(station) => station,
+                    None => {
+                        let metadata = serde_json::from_str::<StationMetadata>(&entry.station_data)
+                            .inspect_err(|err| {
+                                warn!("Unable to parse station of track {}: {err}", entry.title)
+                            })
+                            .ok()?;
+                        SwStation::new(&entry.station_uuid, entry.station_is_local, metadata, None)
+                    }
+                };
+
+                let saved_to = entry
+                    .saved_to
+                    .map(gio::File::for_path)
+                    .filter(|file| file.query_exists(gio::Cancellable::NONE));
+
+                // Temporary recordings get deleted when quitting
+                let mut state = SwRecordingState::from_str(&entry.state).unwrap_or_default();
+                if (state.is_recorded() || state == SwRecordingState::Recording)
+                    && saved_to.is_none()
+                {
+                    state = SwRecordingState::DiscardedNotSaved;
+                }
+
+                let track = SwTrack::restored(
+                    &entry.uuid,
+                    &entry.title,
+                    &station,
+                    entry.started_at,
+                    saved_to,
+                );
+                track.set_state(state);
+                track.set_duration(entry.duration.try_into().unwrap_or_default());
+                track.connect_is_saved_notify(Self::store);
+
+                Some(track)
+            })
+            .collect()
+    }
+
+    fn store(track: &SwTrack) {
+        let station = track.station();
+        let saved_to = track
+            .saved_to()
+            .and_then(|file| file.path())
+            .map(|path| path.to_string_lossy().to_string());
+
+        let res = queries::update_track_history(TrackHistoryEntry {
+            uuid: track.uuid(),
+            title: track.title(),
+            station_uuid: station.uuid(),
+            station_is_local: station.is_local(),
+            station_data: serde_json::to_string(&station.metadata()).unwrap(),
+            started_at: track.started_at(),
+            duration: track.duration().try_into().unwrap_or(i64::MAX),
+            state: track.state().to_string(),
+            saved_to,
+        });
+
+        if let Err(err) = res {
+            warn!("Unable to store track history: {err}");
+        }
+    }
+}
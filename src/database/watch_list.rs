@@ -0,0 +1,52 @@
+// Shortwave - watch_list.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use gtk::glib;
+
+use super::models::WatchedTrackEntry;
+use super::queries;
+
+/// Track titles which get recorded and saved the next time they are played
+pub struct WatchList;
+
+impl WatchList {
+    pub fn contains(title: &str) -> bool {
+        match queries::watched_track(title) {
+            Ok(entry) => entry.is_some(),
+            Err(err) => {
+                warn!("Unable to load watched track: {err}");
+                false
+            }
+        }
+    }
+
+    pub fn add(title: &str) {
+        let entry = WatchedTrackEntry {
+            title: title.to_string(),
+            added_at: glib::DateTime::now_utc().unwrap().to_unix(),
+        };
+
+        if let Err(err) = queries::insert_watched_track(entry) {
+            warn!("Unable to store watched track: {err}");
+        }
+    }
+
+    pub fn remove(title: &str) {
+        if let Err(err) = queries::delete_watched_track(title) {
+            warn!("Unable to delete watched track: {err}");
+        }
+    }
+}
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::cell::{Cell, RefCell};
+use std::cell::{Cell, OnceCell, RefCell};
 
 use adw::prelude::*;
 use cast_sender::namespace::media::*;
@@ -24,7 +24,33 @@ use glib::subclass::prelude::*;
 use glib::Properties;
 use gtk::glib;
 
+use super::SwCastReceiver;
+use crate::audio::{GstreamerChange, SwPlaybackState};
+use crate::i18n::i18n;
+use crate::settings::{settings_manager, Key};
 use crate::ui::DisplayError;
+use crate::utils;
+
+/// Receiver application made for Shortwave
+const SHORTWAVE_APP_ID: &str = "E3F31F9F";
+
+/// Receiver application which is available on every Cast device
+const DEFAULT_MEDIA_RECEIVER_APP_ID: &str = "CC1AD845";
+
+// Cast receivers don't report their codecs, but every receiver has to support
+// the audio formats listed in the Google Cast supported media documentation
+const SUPPORTED_FORMATS: &[&str] = &[
+    "audio/mpeg",
+    "audio/aac",
+    "audio/mp4",
+    "audio/ogg",
+    "audio/opus",
+    "audio/flac",
+    "audio/wav",
+];
+
+/// How often the media status gets requested from the receiver, in seconds
+const STATUS_POLL_INTERVAL: u32 = 2;
 
 mod imp {
     use super::*;
@@ -42,10 +68,21 @@ mod imp {
         pub volume: Cell<f64>,
         #[property(get)]
         pub is_connected: Cell<bool>,
+        #[property(get, set, builder(SwCastReceiver::default()))]
+        pub receiver_app: Cell<SwCastReceiver>,
+        /// Used with [`SwCastReceiver::Custom`]
+        #[property(get, set)]
+        pub custom_app_id: RefCell<String>,
 
         pub receiver: cast_sender::Receiver,
         pub app: RefCell<Option<cast_sender::App>>,
         pub media_controller: RefCell<Option<MediaController>>,
+
+        /// Channel of the player, which receives the playback state reported by the receiver
+        pub changes: OnceCell<async_channel::Sender<GstreamerChange>>,
+        /// Last forwarded playback state, so that only changes get forwarded
+        pub playback_state: Cell<Option<SwPlaybackState>>,
+        pub status_poll: RefCell<Option<glib::JoinHandle<()>>>,
     }
 
     #[glib::object_subclass]
@@ -55,7 +92,14 @@ mod imp {
     }
 
     #[glib::derived_properties]
-    impl ObjectImpl for SwCastSender {}
+    impl ObjectImpl for SwCastSender {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            settings_manager::bind_property(Key::DeviceCastReceiver, &*self.obj(), "receiver-app");
+            settings_manager::bind_property(Key::DeviceCastAppId, &*self.obj(), "custom-app-id");
+        }
+    }
 
     impl SwCastSender {
         fn set_volume(&self, volume: f64) {
@@ -118,12 +162,50 @@ mod imp {
                     ..Default::default()
                 };
 
+                // Forward the state of the new media, even if it matches the previous one
+                self.playback_state.set(None);
                 media_controller.load(media_info).await?;
             }
 
             Ok(())
         }
 
+        /// Forwards the media status of the receiver to the player
+        fn media_status_changed(&self, status: &MediaStatus) {
+            let Some(changes) = self.changes.get() else {
+                return;
+            };
+
+            let state = match status.player_state {
+                PlayerState::Playing => SwPlaybackState::Playing,
+                PlayerState::Buffering => SwPlaybackState::Loading,
+                // Playback on a device can't be paused in Shortwave, it gets stopped instead
+                PlayerState::Paused => SwPlaybackState::Stopped,
+                PlayerState::Idle => match status.idle_reason {
+                    Some(IdleReason::Error) => SwPlaybackState::Failure,
+                    Some(_) => SwPlaybackState::Stopped,
+                    // No media loaded yet
+                    None => return,
+                },
+            };
+
+            if self.playback_state.replace(Some(state)) == Some(state) {
+                return;
+            }
+
+            debug!("Cast receiver playback state changed to {:?}", state);
+            match state {
+                SwPlaybackState::Failure => {
+                    let failure = i18n("The Cast device is unable to play the stream");
+                    utils::send(changes, GstreamerChange::Failure(failure));
+                }
+                SwPlaybackState::Loading => utils::send(changes, GstreamerChange::Buffering(0)),
+                SwPlaybackState::Playing => utils::send(changes, GstreamerChange::Buffering(100)),
+                _ => (),
+            }
+            utils::send(changes, GstreamerChange::PlaybackState(state));
+        }
+
         pub fn media_controller(&self) -> Option<MediaController> {
             self.media_controller.borrow().clone()
         }
@@ -147,7 +229,7 @@ impl SwCastSender {
         receiver.connect(ip).await?;
 
         let app = receiver
-            .launch_app(AppId::Custom("E3F31F9F".into()))
+            .launch_app(AppId::Custom(self.app_id()))
             .await?;
         let media_controller = MediaController::new(app.clone(), receiver.clone())?;
 
@@ -159,15 +241,85 @@ impl SwCastSender {
 
         self.imp().is_connected.set(true);
         self.notify_is_connected();
+        self.start_status_poll();
 
         Ok(())
     }
 
+    /// Sets the channel which receives the playback state reported by the receiver
+    pub fn set_change_sender(&self, sender: async_channel::Sender<GstreamerChange>) {
+        self.imp().changes.set(sender).unwrap();
+    }
+
+    /// Periodically requests the media status, so that the player follows the
+    /// receiver, including changes done by other senders
+    fn start_status_poll(&self) {
+        self.stop_status_poll();
+        self.imp().playback_state.set(None);
+
+        let obj = self.downgrade();
+        let handle = glib::spawn_future_local(async move {
+            loop {
+                glib::timeout_future_seconds(STATUS_POLL_INTERVAL).await;
+
+                let Some(media_controller) = obj
+                    .upgrade()
+                    .filter(|sender| sender.is_connected())
+                    .and_then(|sender| sender.imp().media_controller())
+                else {
+                    break;
+                };
+
+                let status = media_controller.status().await;
+
+                match (obj.upgrade(), status) {
+                    (Some(sender), Ok(status)) if sender.is_connected() => {
+                        sender.imp().media_status_changed(&status)
+                    }
+                    (Some(sender), Err(e)) if sender.is_connected() => {
+                        debug!("Unable to request Cast media status: {}", e)
+                    }
+                    _ => break,
+                }
+            }
+        });
+
+        *self.imp().status_poll.borrow_mut() = Some(handle);
+    }
+
+    fn stop_status_poll(&self) {
+        if let Some(handle) = self.imp().status_poll.borrow_mut().take() {
+            handle.abort();
+        }
+    }
+
+    /// The ID of the receiver application which gets launched on connect
+    fn app_id(&self) -> String {
+        let custom_app_id = self.custom_app_id();
+
+        match self.receiver_app() {
+            SwCastReceiver::Shortwave => SHORTWAVE_APP_ID.into(),
+            SwCastReceiver::Default => DEFAULT_MEDIA_RECEIVER_APP_ID.into(),
+            SwCastReceiver::Custom if !custom_app_id.trim().is_empty() => custom_app_id.trim().into(),
+            SwCastReceiver::Custom => {
+                warn!("No custom Cast receiver app ID set, using the Shortwave receiver");
+                SHORTWAVE_APP_ID.into()
+            }
+        }
+    }
+
+    /// Mime types the receiver is able to play
+    pub fn supported_formats(&self) -> Vec<String> {
+        SUPPORTED_FORMATS.iter().map(|f| f.to_string()).collect()
+    }
+
     pub async fn disconnect(&self) {
         if !self.is_connected() {
             return;
         }
 
+        self.stop_status_poll();
+
         let app = { self.imp().app.borrow_mut().take() };
         if let Some(app) = app {
             let _ = self.imp().receiver.stop_app(&app).await;
@@ -0,0 +1,272 @@
+// Shortwave - cover_server.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use tiny_http::{Header, Request, Response, Server, StatusCode};
+
+use super::{network, DeviceError};
+
+/// Port on which the cover gets served, next to the FFmpeg stream on 8080
+pub const COVER_SERVER_PORT: u16 = 8081;
+
+#[derive(Default)]
+struct Cover {
+    png: Option<Vec<u8>>,
+    // Changes with every cover, so that renderers don't show a cached one
+    revision: u32,
+}
+
+/// Number of track files which stay available, the played one and the one queued after it
+const MAX_SERVED_TRACKS: usize = 2;
+
+#[derive(Default)]
+struct Tracks {
+    files: Vec<(u32, PathBuf)>,
+    next_id: u32,
+}
+
+/// Small HTTP server providing the station cover as PNG under `/cover`, and
+/// saved tracks under `/track/<id>`. Many renderers are unable to fetch remote
+/// HTTPS favicons on their own.
+#[derive(Default)]
+pub struct CoverServer {
+    cover: Arc<Mutex<Cover>>,
+    tracks: Arc<Mutex<Tracks>>,
+    server: Option<Arc<Server>>,
+    thread_handle: Option<JoinHandle<()>>,
+}
+
+impl CoverServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts listening, does nothing if the server is already running
+    pub fn start(&mut self) -> Result<(), DeviceError> {
+        if self.server.is_some() {
+            return Ok(());
+        }
+
+        let listen_host = if network::has_ipv6() {
+            "[::]"
+        } else {
+            "0.0.0.0"
+        };
+        let server = Server::http(format!("{listen_host}:{COVER_SERVER_PORT}"))
+            .map_err(|e| format!("Unable to start cover server: {e}"))?;
+        let server = Arc::new(server);
+
+        let cover = self.cover.clone();
+        let tracks = self.tracks.clone();
+        let handle = thread::spawn({
+            let server = server.clone();
+            move || Self::serve(&server, &cover, &tracks)
+        });
+
+        info!("DLNA: Cover server listening on port {}", COVER_SERVER_PORT);
+        self.server = Some(server);
+        self.thread_handle = Some(handle);
+        Ok(())
+    }
+
+    fn serve(server: &Server, cover: &Mutex<Cover>, tracks: &Mutex<Tracks>) {
+        for request in server.incoming_requests() {
+            let path = request.url().split('?').next().unwrap_or_default();
+
+            if let Some(name) = path.strip_prefix("/track/") {
+                let id = name.split('.').next().and_then(|id| id.parse::<u32>().ok());
+                let file = tracks
+                    .lock()
+                    .unwrap()
+                    .files
+                    .iter()
+                    .find(|(track_id, _)| Some(*track_id) == id)
+                    .map(|(_, file)| file.clone());
+
+                let res = match file {
+                    Some(file) => Self::respond_file(request, &file),
+                    None => request.respond(Response::empty(404)),
+                };
+
+                if let Err(e) = res {
+                    debug!("DLNA: Unable to send track: {}", e);
+                }
+                continue;
+            }
+
+            let png = if path == "/cover" {
+                cover.lock().unwrap().png.clone()
+            } else {
+                None
+            };
+
+            let res = match png {
+                Some(png) => {
+                    let content_type = Header::from_bytes("Content-Type", "image/png").unwrap();
+                    request.respond(Response::from_data(png).with_header(content_type))
+                }
+                None => request.respond(Response::empty(404)),
+            };
+
+            if let Err(e) = res {
+                debug!("DLNA: Unable to send cover: {}", e);
+            }
+        }
+    }
+
+    /// Sends the file, or the part of it the renderer asked for with a `Range` header
+    fn respond_file(request: Request, path: &Path) -> io::Result<()> {
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) => {
+                warn!("DLNA: Unable to open track {:?}: {}", path, e);
+                return request.respond(Response::empty(404));
+            }
+        };
+        let size = file.metadata()?.len();
+
+        let range = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Range"))
+            .and_then(|header| parse_range(header.value.as_str(), size));
+
+        let mut headers = vec![
+            Header::from_bytes("Content-Type", track_mime_type(path)).unwrap(),
+            Header::from_bytes("Accept-Ranges", "bytes").unwrap(),
+        ];
+
+        let (status, start, length) = match range {
+            Some((start, end)) => {
+                let value = format!("bytes {start}-{end}/{size}");
+                headers.push(Header::from_bytes("Content-Range", value).unwrap());
+                (206, start, end - start + 1)
+            }
+            None => (200, 0, size),
+        };
+
+        file.seek(SeekFrom::Start(start))?;
+        let data = file.take(length);
+        let response = Response::new(
+            StatusCode(status),
+            headers,
+            data,
+            Some(length as usize),
+            None,
+        );
+        request.respond(response)
+    }
+
+    /// Replaces the served cover, `None` if the station has no cover
+    pub fn set_cover(&self, png: Option<Vec<u8>>) {
+        let mut cover = self.cover.lock().unwrap();
+        cover.png = png;
+        cover.revision = cover.revision.wrapping_add(1);
+    }
+
+    /// Path under which the current cover can be fetched, `None` if there's no cover
+    pub fn cover_path(&self) -> Option<String> {
+        let cover = self.cover.lock().unwrap();
+        cover
+            .png
+            .as_ref()
+            .map(|_| format!("/cover?{}", cover.revision))
+    }
+
+    /// Makes the file available to the renderer and returns the path under which it can be
+    /// fetched. Only the most recently added tracks are kept.
+    pub fn add_track(&self, file: PathBuf) -> String {
+        let mut tracks = self.tracks.lock().unwrap();
+        let id = tracks.next_id;
+        tracks.next_id = id.wrapping_add(1);
+
+        let extension = file
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_string();
+        tracks.files.push((id, file));
+        if tracks.files.len() > MAX_SERVED_TRACKS {
+            tracks.files.remove(0);
+        }
+
+        format!("/track/{id}.{extension}")
+    }
+
+    pub fn clear_tracks(&self) {
+        self.tracks.lock().unwrap().files.clear();
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.server.is_some()
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(server) = self.server.take() {
+            server.unblock();
+        }
+
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl fmt::Debug for CoverServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CoverServer")
+            .field("running", &self.is_running())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for CoverServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Mime type which gets announced for a saved track, based on its file extension
+pub fn track_mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("mp3") => "audio/mpeg",
+        Some("flac") => "audio/flac",
+        Some("m4a") | Some("aac") => "audio/mp4",
+        _ => "audio/ogg",
+    }
+}
+
+/// Parses a `bytes=<start>-[<end>]` range into inclusive offsets within the file
+fn parse_range(value: &str, size: u64) -> Option<(u64, u64)> {
+    let (start, end) = value.trim().strip_prefix("bytes=")?.split_once('-')?;
+    let start = start.trim().parse::<u64>().ok()?;
+    if start >= size {
+        return None;
+    }
+
+    let end = match end.trim() {
+        "" => size - 1,
+        end => end.parse::<u64>().ok()?.min(size - 1),
+    };
+
+    (start <= end).then_some((start, end))
+}
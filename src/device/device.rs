@@ -14,14 +14,14 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::cell::{Cell, OnceCell};
+use std::cell::{Cell, OnceCell, RefCell};
 
 use adw::prelude::*;
 use glib::subclass::prelude::*;
 use glib::Properties;
 use gtk::glib;
 
-use super::SwDeviceKind;
+use super::{OutputFormat, SwDeviceKind};
 
 mod imp {
     use super::*;
@@ -39,6 +39,16 @@ mod imp {
         model: OnceCell<String>,
         #[property(get, set, construct_only)]
         address: OnceCell<String>,
+        #[property(get, set)]
+        manufacturer: RefCell<String>,
+        /// Remote URL of an image representing the device, as announced by the device itself
+        #[property(get, set)]
+        icon_url: RefCell<String>,
+
+        /// Monotonic time in microseconds when the device responded to a discovery the last time
+        pub last_seen: Cell<i64>,
+        /// Mime types the device reported as playable, empty if they are unknown
+        pub supported_formats: RefCell<Vec<String>>,
     }
 
     #[glib::object_subclass]
@@ -65,4 +75,29 @@ impl SwDevice {
             .property("address", address)
             .build()
     }
+
+    /// Marks the device as reachable, since it just responded to a discovery
+    pub fn touch(&self) {
+        self.imp().last_seen.set(glib::monotonic_time());
+    }
+
+    /// Whether the device didn't respond to discoveries within the given duration
+    pub fn is_expired(&self, max_age: std::time::Duration) -> bool {
+        let age = glib::monotonic_time() - self.imp().last_seen.get();
+        age > max_age.as_micros() as i64
+    }
+
+    pub fn supported_formats(&self) -> Vec<String> {
+        self.imp().supported_formats.borrow().clone()
+    }
+
+    pub fn set_supported_formats(&self, formats: Vec<String>) {
+        debug!("Device \"{}\" supports: {:?}", self.name(), formats);
+        *self.imp().supported_formats.borrow_mut() = formats;
+    }
+
+    /// The format streams get transcoded into before they're sent to the device
+    pub fn output_format(&self) -> OutputFormat {
+        OutputFormat::from_mime_types(&self.imp().supported_formats.borrow())
+    }
 }
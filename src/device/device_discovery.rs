@@ -14,24 +14,45 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::pin::pin;
-use std::net::{SocketAddr, UdpSocket};
+use std::net::{IpAddr, SocketAddr, UdpSocket};
 use std::time::Duration;
 
 use adw::prelude::*;
 use async_io::Timer;
-use futures_util::future::{select, Either};
+use async_compat::Compat;
+use futures_util::future::{join, join_all, select, Either};
 use glib::subclass::prelude::*;
 use glib::{clone, Properties};
-use gtk::glib;
-use mdns_sd::{Error, ServiceDaemon, ServiceEvent};
-use tokio::sync::oneshot;
+use gtk::{gio, glib};
+use mdns_sd::{Error, IfKind, ServiceDaemon, ServiceEvent};
 
+use super::network::{self, NetworkInterface};
 use super::{SwDevice, SwDeviceKind, SwDeviceModel};
+use crate::api::client::HTTP_CLIENT;
 use crate::i18n::i18n;
+use crate::settings::{settings_manager, Key};
 
-fn parse_ssdp_response(response: &str) -> Option<(String, String, String, String)> {
+/// How often the network gets scanned for devices when background discovery is enabled
+const DISCOVERY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Devices which haven't responded to discoveries for this long get removed
+const DEVICE_EXPIRY: Duration = Duration::from_secs(60 * 3);
+
+/// Information about a DLNA device, taken from its description XML
+#[derive(Debug, Clone, Default)]
+struct DeviceDescription {
+    udn: String,
+    friendly_name: String,
+    device_type: String,
+    manufacturer: String,
+    model_name: String,
+    icon_url: String,
+}
+
+/// Returns the location of the device description and the host of an SSDP response
+fn parse_ssdp_response(response: &str) -> Option<(String, String)> {
     debug!("DLNA: Parsing SSDP response...");
     
     let mut location = None;
@@ -57,57 +78,191 @@ fn parse_ssdp_response(response: &str) -> Option<(String, String, String, String
     let location = location?;
     let host = host.unwrap_or_else(|| "unknown".to_string());
     
-    // Fetch device description XML to get proper friendlyName and device type
-    let (friendly_name, device_type) = fetch_device_info(&location).unwrap_or_else(|_| {
-        // Fallback to a generic name with IP if fetch fails
-        (format!("DLNA Device ({})", host), "unknown".to_string())
-    });
-    
-    debug!("DLNA: Parsed device - Location: {}, Name: {}, Type: {}, Host: {}", location, friendly_name, device_type, host);
+    debug!("DLNA: Parsed device - Location: {}, Host: {}", location, host);
     
-    Some((location, friendly_name, device_type, host))
+    Some((location, host))
 }
 
-fn fetch_device_info(location: &str) -> Result<(String, String), Box<dyn std::error::Error>> {
+// Returns the trimmed content of the first element with the given name
+fn xml_value(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&format!("</{}>", tag))?;
+    Some(xml[start..end].trim().to_string())
+}
+
+// Fetch device description XML to get proper friendlyName and device type
+async fn device_description(location: &str, host: &str) -> DeviceDescription {
+    fetch_device_info(location).await.unwrap_or_else(|e| {
+        debug!("DLNA: Unable to fetch device description from {}: {}", location, e);
+        // Fallback to a generic name with IP if fetch fails
+        DeviceDescription {
+            friendly_name: format!("DLNA Device ({})", host),
+            device_type: "unknown".to_string(),
+            ..Default::default()
+        }
+    })
+}
+
+async fn fetch_device_info(location: &str) -> Result<DeviceDescription, reqwest::Error> {
     debug!("DLNA: Fetching device description from {}", location);
     
-    // Use blocking HTTP client in the background thread
-    let client = reqwest::blocking::Client::builder()
-        .timeout(std::time::Duration::from_secs(3))
-        .build()?;
-    
-    let response = client.get(location).send()?;
-    let xml_content = response.text()?;
+    // reqwest needs a tokio runtime, which gets provided by async-compat
+    let request = HTTP_CLIENT.get(location).timeout(Duration::from_secs(3));
+    let xml_content = Compat::new(async move { request.send().await?.text().await }).await?;
     
     debug!("DLNA: Got device description XML ({} bytes)", xml_content.len());
     
-    // Parse XML to extract friendlyName
-    let friendly_name = if let Some(start) = xml_content.find("<friendlyName>") {
-        if let Some(end) = xml_content.find("</friendlyName>") {
-            let name = xml_content[start + 13..end].trim().to_string();
-            debug!("DLNA: Extracted friendlyName: {}", name);
-            name
-        } else {
-            "Unknown Device".to_string()
-        }
-    } else {
-        "Unknown Device".to_string()
+    // Only look at the root device, embedded devices come after it
+    let device_xml = xml_content
+        .find("<deviceList>")
+        .map(|pos| &xml_content[..pos])
+        .unwrap_or(&xml_content);
+
+    // Prefer icons which can be displayed, some devices only announce bmp files
+    let base_url = xml_value(&xml_content, "URLBase").unwrap_or_else(|| location.to_string());
+    let icon_url = device_xml
+        .split("<icon>")
+        .skip(1)
+        .filter(|icon| {
+            xml_value(icon, "mimetype")
+                .is_some_and(|mime| mime == "image/png" || mime == "image/jpeg")
+        })
+        .filter_map(|icon| xml_value(icon, "url"))
+        .next()
+        .and_then(|url| url::Url::parse(&base_url).ok()?.join(&url).ok())
+        .map(|url| url.to_string())
+        .unwrap_or_default();
+
+    let description = DeviceDescription {
+        udn: xml_value(device_xml, "UDN").unwrap_or_default(),
+        friendly_name: xml_value(device_xml, "friendlyName").unwrap_or_else(|| "Unknown Device".to_string()),
+        device_type: xml_value(device_xml, "deviceType").unwrap_or_else(|| "unknown".to_string()),
+        manufacturer: xml_value(device_xml, "manufacturer").unwrap_or_default(),
+        model_name: xml_value(device_xml, "modelName").unwrap_or_default(),
+        icon_url,
     };
+    debug!("DLNA: Extracted device description: {:?}", description);
+
+    Ok(description)
+}
+
+/// Sends SSDP M-SEARCH requests on the given interfaces and collects the responding devices
+fn ssdp_search(ipv6: bool, interfaces: &[NetworkInterface]) -> Result<Vec<(String, String)>, String> {
+    // Raw SSDP implementation with pa-dlna improvements
+    debug!("DLNA: Creating SSDP M-SEARCH request (IPv6: {})...", ipv6);
+
+    // Only use interfaces of the matching address family
+    let interfaces: Vec<_> = interfaces
+        .iter()
+        .filter(|iface| iface.ip.is_ipv6() == ipv6)
+        .collect();
+    if ipv6 && interfaces.is_empty() {
+        debug!("DLNA: No IPv6 interfaces, skipping IPv6 search");
+        return Ok(Vec::new());
+    }
     
-    // Parse XML to extract deviceType
-    let device_type = if let Some(start) = xml_content.find("<deviceType>") {
-        if let Some(end) = xml_content.find("</deviceType>") {
-            let dev_type = xml_content[start + 12..end].trim().to_string();
-            debug!("DLNA: Extracted deviceType: {}", dev_type);
-            dev_type
-        } else {
-            "unknown".to_string()
+    // Create UDP socket for multicast
+    let bind_addr = if ipv6 { "[::]:0" } else { "0.0.0.0:0" };
+    let socket = match UdpSocket::bind(bind_addr) {
+        Ok(socket) => {
+            debug!("DLNA: UDP socket created successfully");
+            socket
+        }
+        Err(e) => {
+            error!("DLNA: Failed to create UDP socket: {}", e);
+            return Err(format!("Socket creation failed: {}", e));
         }
-    } else {
-        "unknown".to_string()
     };
     
-    Ok((friendly_name, device_type))
+    socket.set_read_timeout(Some(Duration::from_secs(5))).ok();
+    
+    // SSDP M-SEARCH message for root devices (pa-dlna approach)
+    let multicast_host = if ipv6 { "[FF02::C]:1900" } else { "239.255.255.250:1900" };
+    let search_msg = format!(
+        "M-SEARCH * HTTP/1.1\r\n\
+         HOST: {}\r\n\
+         MAN: \"ssdp:discover\"\r\n\
+         ST: upnp:rootdevice\r\n\
+         MX: 2\r\n\r\n",
+        multicast_host
+    );
+    
+    debug!("DLNA: Using upnp:rootdevice search target (pa-dlna approach)");
+    
+    // Send to SSDP multicast address
+    let multicast_addr: SocketAddr = multicast_host.parse().unwrap();
+    
+    // Send multiple M-SEARCH requests like pa-dlna (3 requests with 0.2s intervals)
+    for i in 0..3 {
+        debug!("DLNA: Sending M-SEARCH request #{}", i + 1);
+        if interfaces.is_empty() {
+            if let Err(e) = socket.send_to(search_msg.as_bytes(), multicast_addr) {
+                error!("DLNA: Failed to send M-SEARCH #{}: {}", i + 1, e);
+                return Err(format!("Send failed: {}", e));
+            }
+        }
+
+        // Search on every interface, otherwise only the one of the default route gets used
+        let mut searched_indexes = Vec::new();
+        for iface in &interfaces {
+            let res = match iface.ip {
+                IpAddr::V4(ip) => network::set_multicast_interface(&socket, ip),
+                IpAddr::V6(_) => {
+                    // Multicast is sent per interface, not per address
+                    let Some(index) = iface.index.filter(|i| !searched_indexes.contains(i)) else {
+                        continue;
+                    };
+                    searched_indexes.push(index);
+                    network::set_multicast_interface_v6(&socket, index)
+                }
+            }
+            .and_then(|_| socket.send_to(search_msg.as_bytes(), multicast_addr));
+            if let Err(e) = res {
+                warn!("DLNA: Failed to send M-SEARCH #{} on {}: {}", i + 1, iface.name, e);
+            }
+        }
+        
+        // Wait 0.2 seconds between requests (pa-dlna approach)
+        if i < 2 {
+            std::thread::sleep(Duration::from_millis(200));
+        }
+    }
+    
+    debug!("DLNA: All M-SEARCH requests sent, waiting for responses...");
+    
+    let mut device_infos = Vec::new();
+    let mut buffer = [0u8; 4096];
+    let mut device_count = 0;
+    
+    // Listen for responses
+    loop {
+        match socket.recv_from(&mut buffer) {
+            Ok((bytes_read, src_addr)) => {
+                device_count += 1;
+                let response = String::from_utf8_lossy(&buffer[..bytes_read]);
+                debug!("DLNA: Received response #{} from {}", device_count, src_addr);
+                debug!("DLNA: Response preview: {}", &response[..response.len().min(200)]);
+                
+                // Parse SSDP response
+                if let Some(device_info) = parse_ssdp_response(&response) {
+                    // Devices respond to each of the repeated M-SEARCH requests
+                    if !device_infos.iter().any(|(location, _)| *location == device_info.0) {
+                        device_infos.push(device_info);
+                    }
+                } else {
+                    debug!("DLNA: Failed to parse device response");
+                }
+            }
+            Err(e) => {
+                debug!("DLNA: Stopping listening: {}", e);
+                break;
+            }
+        }
+    }
+    
+    debug!("DLNA: Discovery completed, found {} valid devices", device_infos.len());
+    Ok(device_infos)
 }
 
 mod imp {
@@ -122,6 +277,12 @@ mod imp {
         devices: SwDeviceModel,
         #[property(get)]
         pub is_scanning: Cell<bool>,
+        /// Whether the network is periodically scanned while the app runs, so that devices are
+        /// already known when the device dialog gets opened
+        #[property(get, set=Self::set_background_discovery)]
+        background_discovery: Cell<bool>,
+
+        background_source: RefCell<Option<glib::SourceId>>,
     }
 
     #[glib::object_subclass]
@@ -135,18 +296,78 @@ mod imp {
         fn constructed(&self) {
             self.parent_constructed();
             // Remove automatic scan to prevent scanning notifications during station selection
-            // Users can manually scan when needed via the device dialog
+            // Users can manually scan when needed via the device dialog, or enable the
+            // background discovery.
+            settings_manager::bind_property(
+                Key::DeviceBackgroundDiscovery,
+                &*self.obj(),
+                "background-discovery",
+            );
         }
     }
 
     impl SwDeviceDiscovery {
+        fn set_background_discovery(&self, enabled: bool) {
+            self.background_discovery.set(enabled);
+
+            if let Some(source) = self.background_source.take() {
+                source.remove();
+            }
+
+            if !enabled {
+                return;
+            }
+
+            debug!("Enable background device discovery");
+            let scan = clone!(
+                #[weak(rename_to = imp)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || {
+                    glib::spawn_future_local(clone!(
+                        #[weak]
+                        imp,
+                        async move { imp.obj().scan().await }
+                    ));
+                    glib::ControlFlow::Continue
+                }
+            );
+
+            let interval = DISCOVERY_INTERVAL.as_secs() as u32;
+            let source = glib::timeout_add_seconds_local(interval, scan);
+            *self.background_source.borrow_mut() = Some(source);
+
+            // Don't wait for the first interval to pass
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                async move { imp.obj().scan().await }
+            ));
+        }
+
         pub async fn discover_cast_devices(&self) -> Result<(), Error> {
             let mdns = ServiceDaemon::new()?;
+            if let Some(name) = network::selected_interface_name() {
+                debug!("Limiting Cast discovery to interface {}", name);
+                mdns.disable_interface(IfKind::All)?;
+                mdns.enable_interface(IfKind::Name(name))?;
+            }
+
             let receiver = mdns.browse(CAST_SERVICE)?;
 
             while let Ok(event) = receiver.recv_async().await {
                 if let ServiceEvent::ServiceResolved(info) = event {
-                    let host = info.get_addresses().iter().next().unwrap().to_string();
+                    // Prefer IPv4, but also support receivers only reachable via IPv6
+                    let addresses = info.get_addresses();
+                    let Some(host) = addresses
+                        .iter()
+                        .find(|addr| addr.is_ipv4())
+                        .or_else(|| addresses.iter().next())
+                        .map(|addr| addr.to_string())
+                    else {
+                        continue;
+                    };
 
                     let device = SwDevice::new(
                         info.get_property("id")
@@ -161,6 +382,12 @@ mod imp {
                             .unwrap_or(&i18n("Unknown Model")),
                         &host,
                     );
+
+                    // The receiver serves its icon on the port of the setup API
+                    if let Some(icon_path) = info.get_property("ic").map(|txt| txt.val_str()) {
+                        device.set_icon_url(format!("http://{}:8008{}", network::url_host(&host), icon_path));
+                    }
+
                     self.devices.add_device(&device);
                 }
             }
@@ -171,130 +398,91 @@ mod imp {
         pub async fn discover_dlna_devices(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
             debug!("Starting DLNA device discovery using raw SSDP with pa-dlna improvements...");
             
-            // Use tokio oneshot channel for truly async communication
-            let (sender, receiver) = oneshot::channel::<Result<Vec<(String, String, String, String)>, String>>();
-            
-            std::thread::spawn(move || {
-                debug!("DLNA discovery thread started");
-                
-                let result = std::thread::spawn(move || {
-                    // Raw SSDP implementation with pa-dlna improvements
-                    debug!("DLNA: Creating SSDP M-SEARCH request...");
-                    
-                    // Create UDP socket for multicast
-                    let socket = match UdpSocket::bind("0.0.0.0:0") {
-                        Ok(socket) => {
-                            debug!("DLNA: UDP socket created successfully");
-                            socket
-                        }
-                        Err(e) => {
-                            error!("DLNA: Failed to create UDP socket: {}", e);
-                            return Err(format!("Socket creation failed: {}", e));
-                        }
-                    };
-                    
-                    socket.set_read_timeout(Some(Duration::from_secs(5))).ok();
-                    
-                    // SSDP M-SEARCH message for root devices (pa-dlna approach)
-                    let search_msg = format!(
-                        "M-SEARCH * HTTP/1.1\r\n\
-                         HOST: 239.255.255.250:1900\r\n\
-                         MAN: \"ssdp:discover\"\r\n\
-                         ST: upnp:rootdevice\r\n\
-                         MX: 2\r\n\r\n"
-                    );
-                    
-                    debug!("DLNA: Using upnp:rootdevice search target (pa-dlna approach)");
-                    
-                    // Send to SSDP multicast address
-                    let multicast_addr: SocketAddr = "239.255.255.250:1900".parse().unwrap();
-                    
-                    // Send multiple M-SEARCH requests like pa-dlna (3 requests with 0.2s intervals)
-                    for i in 0..3 {
-                        debug!("DLNA: Sending M-SEARCH request #{}", i + 1);
-                        if let Err(e) = socket.send_to(search_msg.as_bytes(), multicast_addr) {
-                            error!("DLNA: Failed to send M-SEARCH #{}: {}", i + 1, e);
-                            return Err(format!("Send failed: {}", e));
-                        }
-                        
-                        // Wait 0.2 seconds between requests (pa-dlna approach)
-                        if i < 2 {
-                            std::thread::sleep(Duration::from_millis(200));
-                        }
+            let interfaces = network::discovery_interfaces();
+            let ipv6_interfaces = interfaces.clone();
+
+            // The SSDP search uses blocking sockets, so it runs in the thread pool of gio.
+            // IPv4 and IPv6 get searched at the same time.
+            let search = async move {
+                let (ipv4_result, ipv6_result) = join(
+                    gio::spawn_blocking(move || ssdp_search(false, &interfaces)),
+                    gio::spawn_blocking(move || ssdp_search(true, &ipv6_interfaces)),
+                )
+                .await;
+                let ipv4_result = ipv4_result.unwrap_or_else(|_| Err("Search panicked".to_string()));
+                let ipv6_result = ipv6_result.unwrap_or_else(|_| Err("Search panicked".to_string()));
+
+                match (ipv4_result, ipv6_result) {
+                    (Ok(mut device_infos), Ok(ipv6_infos)) => {
+                        device_infos.extend(ipv6_infos);
+                        Ok(device_infos)
                     }
-                    
-                    debug!("DLNA: All M-SEARCH requests sent, waiting for responses...");
-                    
-                    let mut device_infos = Vec::new();
-                    let mut buffer = [0u8; 4096];
-                    let mut device_count = 0;
-                    
-                    // Listen for responses
-                    loop {
-                        match socket.recv_from(&mut buffer) {
-                            Ok((bytes_read, src_addr)) => {
-                                device_count += 1;
-                                let response = String::from_utf8_lossy(&buffer[..bytes_read]);
-                                debug!("DLNA: Received response #{} from {}", device_count, src_addr);
-                                debug!("DLNA: Response preview: {}", &response[..response.len().min(200)]);
-                                
-                                // Parse SSDP response
-                                if let Some(device_info) = parse_ssdp_response(&response) {
-                                    debug!("DLNA: Parsed device - URL: {}, Name: {}", device_info.0, device_info.1);
-                                    device_infos.push(device_info);
-                                } else {
-                                    debug!("DLNA: Failed to parse device response");
-                                }
-                            }
-                            Err(e) => {
-                                debug!("DLNA: Stopping listening: {}", e);
-                                break;
-                            }
-                        }
+                    (Ok(device_infos), Err(e)) => {
+                        debug!("DLNA: IPv6 discovery failed: {}", e);
+                        Ok(device_infos)
                     }
-                    
-                    debug!("DLNA: Discovery completed, found {} valid devices", device_infos.len());
-                    Ok(device_infos)
-                }).join().unwrap_or_else(|_| Err("Thread panicked".to_string()));
-                
-                let _ = sender.send(result);
-            });
+                    (Err(e), Ok(device_infos)) => {
+                        debug!("DLNA: IPv4 discovery failed: {}", e);
+                        Ok(device_infos)
+                    }
+                    (Err(e), Err(_)) => Err(e),
+                }
+            };
 
             // Set up timeout to check for results
             let timeout = Timer::after(Duration::from_secs(12));
             
-            match select(pin!(receiver), pin!(timeout)).await {
-                Either::Left((Ok(Ok(device_infos)), _)) => {
+            match select(pin!(search), pin!(timeout)).await {
+                Either::Left((Ok(locations), _)) => {
                     debug!("DLNA: Discovery completed successfully");
+                    let descriptions = join_all(
+                        locations
+                            .iter()
+                            .map(|(location, host)| device_description(location, host)),
+                    )
+                    .await;
+
+                    // Devices reachable via both protocols are only added once, preferring IPv4
+                    let mut device_infos: Vec<(String, DeviceDescription)> = Vec::new();
+                    for ((url, _host), description) in locations.into_iter().zip(descriptions) {
+                        if !device_infos.iter().any(|(_, d)| !d.udn.is_empty() && d.udn == description.udn) {
+                            device_infos.push((url, description));
+                        }
+                    }
+
                     // Add devices to glib model on main thread
-                    for (url, name, device_type, host) in device_infos {
+                    for (url, description) in device_infos {
+                        let device_type = &description.device_type;
                         // Filter for only media renderer devices
                         if device_type.contains("MediaRenderer") {
-                            // Extract device type name for model field
-                            let device_type_name = device_type.split(':').nth(3).unwrap_or("MediaRenderer");
-                            let device_name = name.trim_start_matches('>');
+                            // Use the model name if available, otherwise the device type name
+                            let model = if description.model_name.is_empty() {
+                                let device_type_name = device_type.split(':').nth(3).unwrap_or("MediaRenderer");
+                                format!("DLNA {}", device_type_name)
+                            } else {
+                                description.model_name.clone()
+                            };
+                            let device_name = &description.friendly_name;
                             debug!("DLNA: Adding media renderer device: {} ({})", device_name, device_type);
                             let device = SwDevice::new(
                                 &url,  // Use the full discovery URL as address
                                 SwDeviceKind::Dlna,
                                 device_name,  // Device name only
-                                &format!("DLNA {}", device_type_name),  // Model as subtitle to match Cast styling
+                                &model,  // Model as subtitle to match Cast styling
                                 &url,  // Use the full discovery URL as address
                             );
+                            device.set_manufacturer(description.manufacturer);
+                            device.set_icon_url(description.icon_url);
                             self.devices.add_device(&device);
                         } else {
-                            debug!("DLNA: Skipping non-renderer device: {} ({})", name, device_type);
+                            debug!("DLNA: Skipping non-renderer device: {} ({})", description.friendly_name, device_type);
                         }
                     }
                 }
-                Either::Left((Ok(Err(e)), _)) => {
+                Either::Left((Err(e), _)) => {
                     error!("DLNA discovery failed: {}", e);
                     return Err(e.into());
                 }
-                Either::Left((Err(_), _)) => {
-                    error!("DLNA discovery communication failed");
-                    return Err("Communication failed".into());
-                }
                 Either::Right(_) => {
                     debug!("DLNA discovery timeout reached");
                     warn!("DLNA discovery timed out");
@@ -325,8 +513,11 @@ impl SwDeviceDiscovery {
         self.imp().is_scanning.set(true);
         self.notify_is_scanning();
 
-        self.devices().clear();
-        
+        // Keep the known devices with background discovery, they get removed once they expire
+        if !self.background_discovery() {
+            self.devices().clear();
+        }
+
         // Run both Cast and DLNA discovery in parallel
         let cast_discovery = self.imp().discover_cast_devices();
         let dlna_discovery = self.imp().discover_dlna_devices();
@@ -349,6 +540,8 @@ impl SwDeviceDiscovery {
             }
         }
 
+        self.devices().remove_expired(DEVICE_EXPIRY);
+
         debug!("Device scan ended!");
         self.imp().is_scanning.set(false);
         self.notify_is_scanning();
@@ -359,7 +552,10 @@ impl SwDeviceDiscovery {
             debug!("Stopping device discovery scan...");
             self.imp().is_scanning.set(false);
             self.notify_is_scanning();
-            self.devices().clear();
+
+            if !self.background_discovery() {
+                self.devices().clear();
+            }
             debug!("Device discovery stopped and cleared");
         }
     }
@@ -370,3 +566,39 @@ impl Default for SwDeviceDiscovery {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures_lite::future::block_on;
+
+    use super::*;
+    use crate::test_server;
+
+    #[test]
+    fn renderer_description() {
+        let renderer = test_server::dlna_renderer(40, false);
+        let location = renderer.url("/description.xml");
+
+        let description = block_on(fetch_device_info(&location)).unwrap();
+        assert_eq!(description.udn, "uuid:5f9ec1b3-ed59-4f4b-8b8e-4f3a1c0ad001");
+        assert_eq!(description.friendly_name, "Living Room");
+        assert_eq!(
+            description.device_type,
+            "urn:schemas-upnp-org:device:MediaRenderer:1"
+        );
+        assert_eq!(description.manufacturer, "Test Audio");
+        assert_eq!(description.model_name, "Renderer 1");
+
+        // The bmp icon can't be displayed
+        assert_eq!(description.icon_url, renderer.url("/icon.png"));
+    }
+
+    #[test]
+    fn unreachable_renderer() {
+        let renderer = test_server::dlna_renderer(40, false);
+        let location = renderer.url("/description.xml");
+        drop(renderer);
+
+        assert!(block_on(fetch_device_info(&location)).is_err());
+    }
+}
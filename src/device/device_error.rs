@@ -0,0 +1,160 @@
+// Shortwave - device_error.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::io;
+use std::num::ParseFloatError;
+
+use thiserror::Error;
+
+use crate::i18n::i18n;
+
+/// Why connecting to a device, or loading media on it, failed
+#[derive(Clone, Error, Debug)]
+pub enum DeviceError {
+    #[error("Device is not reachable: {0}")]
+    Unreachable(String),
+
+    #[error("Device does not support the request: {0}")]
+    Unsupported(String),
+
+    #[error("Device refused the connection: {0}")]
+    Auth(String),
+
+    #[error("Device did not respond in time")]
+    Timeout,
+
+    #[error("Device sent an invalid response: {0}")]
+    InvalidResponse(String),
+
+    #[error("FFmpeg error: {0}")]
+    Ffmpeg(String),
+}
+
+impl DeviceError {
+    /// Classifies an error message of the Cast / DLNA implementations, which
+    /// don't provide any more details about the failure reason
+    fn from_message(message: String) -> Self {
+        let lowercase = message.to_lowercase();
+
+        if lowercase.contains("timed out") || lowercase.contains("timeout") {
+            Self::Timeout
+        } else if lowercase.contains("invalid request")
+            || lowercase.contains("load failed")
+            || lowercase.contains("media channel error")
+            || lowercase.contains("not found")
+            || lowercase.contains("launch")
+        {
+            Self::Unsupported(message)
+        } else if lowercase.contains("denied")
+            || lowercase.contains("unauthorized")
+            || lowercase.contains("forbidden")
+            || lowercase.contains("certificate")
+        {
+            Self::Auth(message)
+        } else {
+            Self::Unreachable(message)
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self {
+            Self::Unreachable(_) => i18n("Device Not Reachable"),
+            Self::Unsupported(_) => i18n("Device Not Supported"),
+            Self::Auth(_) => i18n("Connection Refused"),
+            Self::Timeout => i18n("Device Not Responding"),
+            Self::InvalidResponse(_) => i18n("Unexpected Device Response"),
+            Self::Ffmpeg(_) => i18n("Unable to Convert Stream"),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Self::Unreachable(_) => i18n(
+                "Make sure that the device is switched on and connected to the same network.",
+            ),
+            Self::Unsupported(_) => {
+                i18n("The device does not support the functionality required for playback.")
+            }
+            Self::Auth(_) => {
+                i18n("The device did not allow the connection. Check its sharing settings.")
+            }
+            Self::Timeout => i18n("The device did not respond in time. It might be busy."),
+            Self::InvalidResponse(_) => {
+                i18n("The device responded in an unexpected way. It might not be fully compatible.")
+            }
+            Self::Ffmpeg(_) => i18n(
+                "The stream has to be converted for this device. Make sure that FFmpeg is installed.",
+            ),
+        }
+    }
+}
+
+impl From<cast_sender::Error> for DeviceError {
+    fn from(err: cast_sender::Error) -> Self {
+        Self::from_message(err.to_string())
+    }
+}
+
+impl From<reqwest::Error> for DeviceError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            return Self::Timeout;
+        } else if err.is_connect() {
+            return Self::Unreachable(err.to_string());
+        } else if let Some(status) = err.status() {
+            if status == reqwest::StatusCode::UNAUTHORIZED
+                || status == reqwest::StatusCode::FORBIDDEN
+            {
+                return Self::Auth(err.to_string());
+            }
+        }
+
+        Self::from_message(err.to_string())
+    }
+}
+
+impl From<io::Error> for DeviceError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::TimedOut => Self::Timeout,
+            _ => Self::Unreachable(err.to_string()),
+        }
+    }
+}
+
+impl From<url::ParseError> for DeviceError {
+    fn from(err: url::ParseError) -> Self {
+        Self::InvalidResponse(err.to_string())
+    }
+}
+
+impl From<ParseFloatError> for DeviceError {
+    fn from(err: ParseFloatError) -> Self {
+        Self::InvalidResponse(err.to_string())
+    }
+}
+
+impl From<String> for DeviceError {
+    fn from(message: String) -> Self {
+        Self::from_message(message)
+    }
+}
+
+impl From<&str> for DeviceError {
+    fn from(message: &str) -> Self {
+        Self::from_message(message.to_string())
+    }
+}
@@ -15,6 +15,7 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use std::cell::RefCell;
+use std::time::Duration;
 
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
@@ -70,10 +71,12 @@ impl SwDeviceModel {
     pub(super) fn add_device(&self, device: &SwDevice) {
         let pos = {
             let mut map = self.imp().map.borrow_mut();
-            if map.contains_key(&device.id()) {
+            if let Some(existing) = map.get(&device.id()) {
+                existing.touch();
                 return;
             }
 
+            device.touch();
             map.insert(device.id(), device.clone());
             (map.len() - 1) as u32
         };
@@ -81,6 +84,27 @@ impl SwDeviceModel {
         self.items_changed(pos, 0, 1);
     }
 
+    /// Removes all devices which didn't respond to discoveries within the given duration
+    pub(super) fn remove_expired(&self, max_age: Duration) {
+        loop {
+            let pos = self
+                .imp()
+                .map
+                .borrow()
+                .values()
+                .position(|d| d.is_expired(max_age));
+
+            let Some(pos) = pos else {
+                break;
+            };
+
+            if let Some((_, device)) = self.imp().map.borrow_mut().shift_remove_index(pos) {
+                debug!("Device {:?} expired", device.name());
+            }
+            self.items_changed(pos as u32, 1, 0);
+        }
+    }
+
     pub(super) fn clear(&self) {
         let len = self.n_items();
         self.imp().map.borrow_mut().clear();
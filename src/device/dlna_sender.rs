@@ -15,46 +15,69 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use std::cell::{Cell, RefCell};
-use std::error::Error;
 use std::net;
+use std::path::Path;
 use std::sync::mpsc;
 use std::thread;
 use std::thread::JoinHandle;
 use std::time::Duration;
 use adw::prelude::*;
+use async_compat::Compat;
 use glib::clone;
 use glib::subclass::prelude::*;
 use glib::Properties;
-use gtk::glib;
+use gtk::{gio, glib};
 use log::{debug, error, info, warn};
 use url::Url;
 use uuid::Uuid;
-use super::{FfmpegWrapper, FfmpegCommand};
+use super::cover_server::{self, CoverServer, COVER_SERVER_PORT};
+use super::{network, DeviceError, FfmpegWrapper, FfmpegCommand, OutputFormat};
+use crate::api::client::HTTP_CLIENT;
+use crate::database::HttpSettings;
 
 // Helper function to get local IP address that can reach the DLNA device
-pub fn get_local_ip_for_device(device_url: &str) -> Result<String, Box<dyn Error>> {
+pub fn get_local_ip_for_device(device_url: &str) -> Result<String, DeviceError> {
     // Parse device URL to get device IP
     let parsed_url = Url::parse(device_url)?;
-    let device_ip = parsed_url.host_str().ok_or("Invalid device URL")?;
-    
-    // Create a UDP socket to determine the best local interface
-    let socket = std::net::UdpSocket::bind("0.0.0.0:0")?;
-    socket.connect(format!("{}:80", device_ip))?;
+    let device_ip = match parsed_url.host().ok_or("Invalid device URL")? {
+        url::Host::Ipv4(ip) => net::IpAddr::V4(ip),
+        url::Host::Ipv6(ip) => net::IpAddr::V6(ip),
+        url::Host::Domain(domain) => {
+            // Resolve the device address, to determine the best local interface
+            net::ToSocketAddrs::to_socket_addrs(&(domain, 80))?
+                .next()
+                .ok_or("Unable to resolve device address")?
+                .ip()
+        }
+    };
     
     // Get the local address that would be used to connect to the device
-    let local_addr = socket.local_addr()?;
-    let local_ip = local_addr.ip().to_string();
+    let local_ip = network::local_ip_for(device_ip)?.to_string();
     
     info!("DLNA: Detected local IP {} for device at {}", local_ip, device_ip);
     Ok(local_ip)
 }
 
+// Resolving the device address blocks, so it's done on the gio thread pool
+async fn local_ip_for_device(device_url: String) -> Result<String, DeviceError> {
+    gio::spawn_blocking(move || get_local_ip_for_device(&device_url))
+        .await
+        .unwrap_or_else(|_| Err("Unable to detect local address".into()))
+}
+
+/// Sends a request to the device, and returns the status and body of the response.
+/// reqwest needs a tokio runtime, which gets provided by async-compat.
+async fn send_request(request: reqwest::RequestBuilder) -> Result<(reqwest::StatusCode, String), DeviceError> {
+    Compat::new(async move {
+        let response = request.send().await?;
+        let status = response.status();
+        Ok((status, response.text().await?))
+    })
+    .await
+}
+
 // Helper function to send SOAP actions to DLNA devices
-fn soap_action(control_url: &str, service_type: &str, action: &str, body: &str) -> Result<String, Box<dyn Error>> {
-    let client = reqwest::blocking::Client::builder()
-        .timeout(Duration::from_secs(5))
-        .build()?;
-    
+async fn soap_action(control_url: &str, service_type: &str, action: &str, body: &str) -> Result<String, DeviceError> {
     let soap_envelope = format!(
         r#"<?xml version="1.0" encoding="utf-8"?>
 <s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/">
@@ -67,17 +90,18 @@ fn soap_action(control_url: &str, service_type: &str, action: &str, body: &str)
         action, service_type, body, action
     );
     
-    let response = client
+    let request = HTTP_CLIENT
         .post(control_url)
+        .timeout(Duration::from_secs(5))
         .header("Content-Type", "text/xml; charset=utf-8")
         .header("SOAPAction", format!("\"{}#{}\"", service_type, action))
-        .body(soap_envelope)
-        .send()?;
+        .body(soap_envelope);
+    let (status, response) = send_request(request).await?;
     
-    if response.status().is_success() {
-        Ok(response.text()?)
+    if status.is_success() {
+        Ok(response)
     } else {
-        Err(format!("SOAP action failed: {}", response.status()).into())
+        Err(format!("SOAP action failed: {}", status).into())
     }
 }
 
@@ -94,14 +118,68 @@ fn extract_soap_value(response: &str, tag: &str) -> Option<String> {
     None
 }
 
+// Helper function to escape text which gets embedded into a SOAP body
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Helper function to format seconds as "H:MM:SS" time, as used by AVTransport
+fn format_time(seconds: f64) -> String {
+    let seconds = seconds.max(0.0) as u64;
+    format!("{}:{:02}:{:02}", seconds / 3600, (seconds / 60) % 60, seconds % 60)
+}
+
+// Helper function to parse "H:MM:SS[.F]" time into seconds, `None` for "NOT_IMPLEMENTED"
+fn parse_time(time: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in time.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
+/// Playback position as reported by the renderer
+#[derive(Debug, Clone, Default)]
+pub struct TransportPosition {
+    /// URI of the media which is currently played
+    pub uri: String,
+    /// Position in seconds
+    pub position: f64,
+    /// Duration of the media in seconds, 0 if unknown
+    pub duration: f64,
+}
+
+/// How often volume and mute state get read back from the renderer, in seconds
+const STATE_POLL_INTERVAL: u32 = 5;
+
+// Helper function to read volume (0.0 - 1.0) and mute state from the RenderingControl service
+async fn fetch_rendering_state(control_url: &str) -> (Option<f64>, Option<bool>) {
+    let service = "urn:schemas-upnp-org:service:RenderingControl:1";
+    let body = "<InstanceID>0</InstanceID><Channel>Master</Channel>";
+
+    let volume = soap_action(control_url, service, "GetVolume", body)
+        .await
+        .ok()
+        .and_then(|response| extract_soap_value(&response, "CurrentVolume"))
+        .and_then(|value| value.parse::<f64>().ok())
+        .map(|volume| (volume / 100.0).clamp(0.0, 1.0));
+
+    let muted = soap_action(control_url, service, "GetMute", body)
+        .await
+        .ok()
+        .and_then(|response| extract_soap_value(&response, "CurrentMute"))
+        .map(|value| value == "1" || value.eq_ignore_ascii_case("true"));
+
+    (volume, muted)
+}
+
 // Helper function to fetch device description and extract service URLs
-fn fetch_device_services(device_url: &str) -> Result<(String, String), Box<dyn Error>> {
-    let client = reqwest::blocking::Client::builder()
-        .timeout(Duration::from_secs(5))
-        .build()?;
-    
-    let response = client.get(device_url).send()?;
-    let xml_content = response.text()?;
+async fn fetch_device_services(device_url: &str) -> Result<(String, String), DeviceError> {
+    let request = HTTP_CLIENT.get(device_url).timeout(Duration::from_secs(5));
+    let (_, xml_content) = send_request(request).await?;
     
     debug!("DLNA: Device description XML: {}", xml_content);
     
@@ -182,6 +260,54 @@ fn fetch_device_services(device_url: &str) -> Result<(String, String), Box<dyn E
     }
 }
 
+// Helper function to query the mime types the renderer is able to play,
+// using the Sink value of the ConnectionManager GetProtocolInfo action
+async fn fetch_sink_protocol_info(device_url: &str) -> Result<Vec<String>, DeviceError> {
+    let request = HTTP_CLIENT.get(device_url).timeout(Duration::from_secs(5));
+    let (_, xml_content) = send_request(request).await?;
+
+    let service_start = xml_content
+        .find("urn:schemas-upnp-org:service:ConnectionManager:1")
+        .ok_or("ConnectionManager service not found")?;
+    let service_block_end = xml_content[service_start..].find("</service>")
+        .map(|pos| service_start + pos)
+        .unwrap_or(xml_content.len());
+    let service_block = &xml_content[service_start..service_block_end];
+
+    let url_start = service_block.find("<controlURL>").ok_or_else(|| DeviceError::InvalidResponse("ConnectionManager has no controlURL".into()))?;
+    let url_end = service_block.find("</controlURL>").ok_or_else(|| DeviceError::InvalidResponse("ConnectionManager has no controlURL".into()))?;
+    let url = service_block[url_start + 12..url_end].trim();
+    let control_url = Url::parse(device_url)?.join(url)?;
+    debug!("DLNA: Found ConnectionManager service at: {}", control_url);
+
+    let response = soap_action(
+        control_url.as_str(),
+        "urn:schemas-upnp-org:service:ConnectionManager:1",
+        "GetProtocolInfo",
+        "",
+    )
+    .await?;
+    let sink = extract_soap_value(&response, "Sink").unwrap_or_default();
+
+    // Each entry has the form "<protocol>:<network>:<contentFormat>:<additionalInfo>"
+    let mut formats: Vec<String> = Vec::new();
+    for entry in sink.split(',') {
+        let mut fields = entry.trim().split(':');
+        if fields.next() != Some("http-get") {
+            continue;
+        }
+
+        if let Some(mime) = fields.nth(1) {
+            let mime = mime.trim().to_string();
+            if !mime.is_empty() && !formats.contains(&mime) {
+                formats.push(mime);
+            }
+        }
+    }
+
+    Ok(formats)
+}
+
 pub mod imp {
     use super::*;
 
@@ -197,6 +323,8 @@ pub mod imp {
         #[property(get, set, type = f64)]
         pub volume: Cell<f64>,
         #[property(get)]
+        pub muted: Cell<bool>,
+        #[property(get)]
         pub is_connected: Cell<bool>,
         
         // FFmpeg process for streaming
@@ -217,6 +345,16 @@ pub mod imp {
         
         // FFmpeg wrapper for session management
         pub ffmpeg_wrapper: RefCell<Option<FfmpegWrapper>>,
+
+        // Mime types reported by the renderer, and the format which is streamed to it
+        pub supported_formats: RefCell<Vec<String>>,
+        pub output_format: RefCell<OutputFormat>,
+
+        // Periodic read back of the renderer volume and mute state
+        pub state_poll: RefCell<Option<glib::JoinHandle<()>>>,
+
+        // Serves the station cover to the renderer
+        pub cover_server: RefCell<CoverServer>,
     }
 
     #[glib::object_subclass]
@@ -247,14 +385,15 @@ pub mod imp {
             }
         }
 
-        async fn set_volume_internal(&self, volume: f64) -> Result<(), Box<dyn Error>> {
-            if let Some(ref rc_url) = *self.rendering_control_url.borrow() {
+        async fn set_volume_internal(&self, volume: f64) -> Result<(), DeviceError> {
+            let rc_url = self.rendering_control_url.borrow().clone();
+            if let Some(rc_url) = rc_url {
                 let volume_percent = (volume * 100.0) as u32;
                 let body = format!(
                     "<InstanceID>0</InstanceID><Channel>Master</Channel><DesiredVolume>{}</DesiredVolume>",
                     volume_percent
                 );
-                soap_action(rc_url, "urn:schemas-upnp-org:service:RenderingControl:1", "SetVolume", &body)?;
+                soap_action(&rc_url, "urn:schemas-upnp-org:service:RenderingControl:1", "SetVolume", &body).await?;
             }
 
             Ok(())
@@ -272,7 +411,7 @@ impl SwDlnaSender {
     }
 
     /// Initialize the FFmpeg wrapper thread
-    fn init_ffmpeg_wrapper(&self) -> Result<(), Box<dyn Error>> {
+    fn init_ffmpeg_wrapper(&self) -> Result<(), DeviceError> {
         let mut wrapper_ref = self.imp().ffmpeg_wrapper.borrow_mut();
         if wrapper_ref.is_none() {
             info!("DLNA: Initializing FFmpeg wrapper");
@@ -285,7 +424,7 @@ impl SwDlnaSender {
     }
 
     /// Start FFmpeg streaming using the wrapper thread
-    pub fn start_ffmpeg_with_wrapper(&self, stream_url: &str, title: &str) -> Result<String, Box<dyn Error>> {
+    pub fn start_ffmpeg_with_wrapper(&self, stream_url: &str, title: &str, http_settings: HttpSettings, output_format: OutputFormat) -> Result<String, DeviceError> {
         info!("DLNA: === STARTING FFMPEG WITH WRAPPER ===");
         info!("DLNA: Starting FFmpeg with wrapper for URL: {}", stream_url);
         
@@ -333,27 +472,31 @@ impl SwDlnaSender {
         // Get wrapper reference
         let wrapper_ref = imp.ffmpeg_wrapper.borrow();
         let wrapper = wrapper_ref.as_ref()
-            .ok_or("FFmpeg wrapper not initialized")?;
+            .ok_or_else(|| DeviceError::Ffmpeg("FFmpeg wrapper not initialized".into()))?;
         
         // Generate stream ID
         let stream_id = Uuid::new_v4().to_string();
         
         // Send start command
+        let extension = output_format.extension();
+        imp.output_format.replace(output_format.clone());
         wrapper.send_command(FfmpegCommand::StartStream {
             stream_url: stream_url.to_string(),
             stream_id: stream_id.clone(),
+            http_settings,
+            output_format,
             force_restart: false,
         })?;
         
-        // Return the proxy URL with .mp3 extension for better content type recognition
-        let proxy_url = format!("http://{}:{}/stream.mp3", local_ip, port);
+        // Return the proxy URL with a matching extension for better content type recognition
+        let proxy_url = format!("http://{}:{}/stream.{}", network::url_host(&local_ip), port, extension);
         info!("DLNA: FFmpeg wrapper started, proxy URL: {}", proxy_url);
         
         Ok(proxy_url)
     }
 
     // Start FFmpeg streaming server asynchronously
-    fn start_ffmpeg_server(&self) -> Result<(), Box<dyn Error>> {
+    fn start_ffmpeg_server(&self) -> Result<(), DeviceError> {
         warn!("DLNA: *** OLD FFMPEG START METHOD CALLED ***");
         let imp = self.imp();
         
@@ -459,7 +602,7 @@ impl SwDlnaSender {
             
             // Poll metadata every 30 seconds
             loop {
-                if let Ok(title) = fetch_icy_metadata(&stream_url_for_metadata) {
+                if let Ok(title) = fetch_icy_metadata(&stream_url_for_metadata).await {
                     // Update local UI title if it changed
                     if !title.is_empty() && title != last_title {
                         info!("DLNA: New track detected: {}", title);
@@ -470,7 +613,7 @@ impl SwDlnaSender {
                         // Update DLNA device metadata if it's different from last sent
                         if !title.is_empty() && title != last_dlna_title {
                             info!("DLNA: Updating device metadata to: {}", title);
-                            if let Err(e) = metadata_sender.update_track_metadata(&title) {
+                            if let Err(e) = metadata_sender.update_track_metadata(&title).await {
                                 warn!("DLNA: Failed to update device metadata: {}", e);
                             } else {
                                 info!("DLNA: ✅ Device metadata updated successfully");
@@ -556,7 +699,7 @@ impl SwDlnaSender {
             }
             Err(e) => {
                 error!("DLNA: Failed to receive FFmpeg process handle: {}", e);
-                return Err("Failed to receive FFmpeg process handle".into());
+                return Err(DeviceError::Ffmpeg("Failed to receive FFmpeg process handle".into()));
             }
         }
         
@@ -643,7 +786,7 @@ impl SwDlnaSender {
     }
 
     // Force restart FFmpeg server (used when device selection changes)
-    pub fn restart_ffmpeg_server(&self) -> Result<(), Box<dyn Error>> {
+    pub fn restart_ffmpeg_server(&self) -> Result<(), DeviceError> {
         info!("DLNA: Force restarting FFmpeg server for fresh instance");
         
         // Stop existing FFmpeg server
@@ -681,7 +824,7 @@ impl SwDlnaSender {
         }
     }
 
-        pub fn connect(&self, address: &str) -> Result<(), Box<dyn Error>> {
+    pub async fn connect(&self, address: &str) -> Result<(), DeviceError> {
         if self.is_connected() {
             self.disconnect();
         }
@@ -690,35 +833,98 @@ impl SwDlnaSender {
         let device_url = if address.starts_with("http") {
             address.to_string()
         } else {
-            format!("http://{}", address)
+            format!("http://{}", network::url_host(address))
         };
 
         // Fetch device description and extract service URLs
-        let (av_transport_url, rendering_control_url) = fetch_device_services(&device_url)?;
+        let (av_transport_url, rendering_control_url) = fetch_device_services(&device_url).await?;
         
         // Store the URLs
         *self.imp().device.borrow_mut() = Some(device_url.clone());
         *self.imp().av_transport_url.borrow_mut() = Some(av_transport_url);
         *self.imp().rendering_control_url.borrow_mut() = Some(rendering_control_url);
+
+        // Probe which formats the renderer is able to play
+        let formats = fetch_sink_protocol_info(&device_url).await.unwrap_or_else(|e| {
+            warn!("DLNA: Unable to query supported formats: {}", e);
+            Vec::new()
+        });
+        info!("DLNA: Device supports: {:?}", formats);
+        *self.imp().supported_formats.borrow_mut() = formats;
         
         self.imp().is_connected.set(true);
         self.notify_is_connected();
         
-        // Get current volume from device
-        if let Some(ref rc_url) = *self.imp().rendering_control_url.borrow() {
-            let body = "<InstanceID>0</InstanceID><Channel>Master</Channel>";
-            if let Ok(response) = soap_action(rc_url, "urn:schemas-upnp-org:service:RenderingControl:1", "GetVolume", body) {
-                if let Some(volume_str) = extract_soap_value(&response, "CurrentVolume") {
-                    if let Ok(volume) = volume_str.parse::<f64>() {
-                        let normalized_volume = volume / 100.0;
-                        self.imp().volume.set(normalized_volume);
-                        self.notify_volume();
+        // Get current volume and mute state from device
+        let rc_url = self.imp().rendering_control_url.borrow().clone();
+        if let Some(rc_url) = rc_url {
+            let (volume, muted) = fetch_rendering_state(&rc_url).await;
+            self.update_rendering_state(volume, muted);
+        }
+
+        self.start_state_poll();
+
+        Ok(())
+    }
+
+    /// Periodically reads back volume and mute state, so that changes done
+    /// on the renderer itself (remote control, other apps) get picked up
+    fn start_state_poll(&self) {
+        self.stop_state_poll();
+
+        let obj = self.downgrade();
+        let handle = glib::spawn_future_local(async move {
+            loop {
+                glib::timeout_future_seconds(STATE_POLL_INTERVAL).await;
+
+                let Some(rc_url) = obj
+                    .upgrade()
+                    .and_then(|sender| sender.imp().rendering_control_url.borrow().clone())
+                else {
+                    break;
+                };
+
+                let (volume, muted) = fetch_rendering_state(&rc_url).await;
+
+                match obj.upgrade() {
+                    Some(sender) if sender.is_connected() => {
+                        sender.update_rendering_state(volume, muted)
                     }
+                    _ => break,
                 }
             }
+        });
+
+        *self.imp().state_poll.borrow_mut() = Some(handle);
+    }
+
+    fn stop_state_poll(&self) {
+        if let Some(handle) = self.imp().state_poll.borrow_mut().take() {
+            handle.abort();
         }
+    }
 
-        Ok(())
+    /// Replaces the locally known volume and mute state with the one reported
+    /// by the renderer, and notifies if they differ
+    fn update_rendering_state(&self, volume: Option<f64>, muted: Option<bool>) {
+        let imp = self.imp();
+
+        // The renderer only knows whole percent steps
+        if let Some(volume) = volume {
+            if (imp.volume.get() * 100.0).round() != (volume * 100.0).round() {
+                debug!("DLNA: Renderer reported volume change to {}", volume);
+                imp.volume.set(volume);
+                self.notify_volume();
+            }
+        }
+
+        if let Some(muted) = muted {
+            if imp.muted.get() != muted {
+                debug!("DLNA: Renderer reported mute change to {}", muted);
+                imp.muted.set(muted);
+                self.notify_muted();
+            }
+        }
     }
 
     pub fn disconnect(&self) {
@@ -727,6 +933,7 @@ impl SwDlnaSender {
         }
 
         info!("DLNA: Disconnecting device - performing full cleanup");
+        self.stop_state_poll();
 
         // Perform the same thorough cleanup as stop_ffmpeg_server()
         // This ensures no FFmpeg processes are left running when disconnecting
@@ -736,6 +943,13 @@ impl SwDlnaSender {
         *self.imp().device.borrow_mut() = None;
         *self.imp().av_transport_url.borrow_mut() = None;
         *self.imp().rendering_control_url.borrow_mut() = None;
+        self.imp().supported_formats.borrow_mut().clear();
+        self.imp().muted.set(false);
+        self.notify_muted();
+
+        self.imp().cover_server.borrow_mut().stop();
+        self.imp().cover_server.borrow().set_cover(None);
+        self.imp().cover_server.borrow().clear_tracks();
 
         self.imp().is_connected.set(false);
         self.notify_is_connected();
@@ -743,7 +957,60 @@ impl SwDlnaSender {
         info!("DLNA: Device disconnected and all processes cleaned up");
     }
 
-    pub fn load_media(&self, stream_url: &str, cover_url: &str, title: &str) -> Result<(), Box<dyn Error>> {
+    /// Detects the local address which gets announced to the device for the FFmpeg proxy
+    pub async fn update_local_ip(&self, address: &str) {
+        let device_url = if address.starts_with("http") {
+            address.to_string()
+        } else {
+            format!("http://{}", network::url_host(address))
+        };
+
+        match local_ip_for_device(device_url).await {
+            Ok(ip) => *self.imp().local_ip.borrow_mut() = ip,
+            Err(e) => warn!("DLNA: Failed to detect local IP for {}: {}", address, e),
+        }
+    }
+
+    /// Sets the PNG encoded cover which gets announced to the renderer with the next media
+    pub fn set_cover(&self, png: Option<Vec<u8>>) {
+        self.imp().cover_server.borrow().set_cover(png);
+    }
+
+    /// URL under which the renderer can fetch the current cover. Starts the cover
+    /// server if needed, `None` if there's no cover.
+    fn album_art_url(&self, local_ip: &str) -> Option<String> {
+        let path = self.imp().cover_server.borrow().cover_path()?;
+
+        if let Err(e) = self.imp().cover_server.borrow_mut().start() {
+            warn!("DLNA: {}", e);
+            return None;
+        }
+
+        Some(format!("http://{}:{}{}", network::url_host(local_ip), COVER_SERVER_PORT, path))
+    }
+
+    /// Address of the FFmpeg proxy the renderer streams from, if it's running
+    pub fn proxy_url(&self) -> Option<String> {
+        let imp = self.imp();
+        let running = imp.ffmpeg_process.borrow().is_some()
+            || imp.ffmpeg_wrapper.borrow().as_ref().is_some_and(|w| w.has_active_session());
+
+        running.then(|| {
+            format!(
+                "http://{}:{}/stream.{}",
+                network::url_host(&imp.local_ip.borrow()),
+                imp.ffmpeg_port.get(),
+                imp.output_format.borrow().extension()
+            )
+        })
+    }
+
+    /// Mime types the connected renderer reported as playable, empty if they are unknown
+    pub fn supported_formats(&self) -> Vec<String> {
+        self.imp().supported_formats.borrow().clone()
+    }
+
+    pub async fn load_media(&self, stream_url: &str, cover_url: &str, title: &str, http_settings: HttpSettings) -> Result<(), DeviceError> {
         *self.imp().stream_url.borrow_mut() = stream_url.to_string();
         *self.imp().cover_url.borrow_mut() = cover_url.to_string();
         *self.imp().title.borrow_mut() = title.to_string();
@@ -759,10 +1026,11 @@ impl SwDlnaSender {
             info!("DLNA: Step 1: Load URL to DLNA device");
             
             // Fetch service info on first use if not already done
+            let device_url = self.imp().device.borrow().clone();
             if self.imp().av_transport_url.borrow().is_none() {
-                if let Some(device_url) = self.imp().device.borrow().as_ref() {
+                if let Some(device_url) = device_url.as_ref() {
                     info!("DLNA: Fetching service info on first use");
-                    let (av_url, rc_url) = fetch_device_services(device_url)?;
+                    let (av_url, rc_url) = fetch_device_services(device_url).await?;
                     *self.imp().av_transport_url.borrow_mut() = Some(av_url);
                     *self.imp().rendering_control_url.borrow_mut() = Some(rc_url);
                 }
@@ -770,8 +1038,8 @@ impl SwDlnaSender {
             
             // Step 1: Load the URL to DLNA device first
             let imp = self.imp();
-            let local_ip = if let Some(device_url) = imp.device.borrow().as_ref() {
-                match get_local_ip_for_device(device_url) {
+            let local_ip = if let Some(device_url) = device_url {
+                match local_ip_for_device(device_url).await {
                     Ok(ip) => ip,
                     Err(e) => {
                         warn!("DLNA: Failed to detect local IP: {}, using fallback", e);
@@ -785,14 +1053,21 @@ impl SwDlnaSender {
             
             let port = 8080u16;
             imp.ffmpeg_port.set(port);
-            let ffmpeg_url = format!("http://{}:{}/stream.mp3", local_ip, port);
+            let output_format = OutputFormat::from_mime_types(&imp.supported_formats.borrow());
+            let ffmpeg_url = format!("http://{}:{}/stream.{}", network::url_host(&local_ip), port, output_format.extension());
             
-            if let Some(ref av_url) = *imp.av_transport_url.borrow() {
+            let album_art = self
+                .album_art_url(&local_ip)
+                .map(|url| format!("&lt;upnp:albumArtURI dlna:profileID=\"PNG_LRG\"&gt;{}&lt;/upnp:albumArtURI&gt;", url))
+                .unwrap_or_default();
+
+            let av_url = imp.av_transport_url.borrow().clone();
+            if let Some(av_url) = av_url {
                 // Create metadata using actual station title from Shortwave's radio data
                 let escaped_title = title.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
                 let metadata = format!(
-                    "&lt;DIDL-Lite xmlns:dc=\"http://purl.org/dc/elements/1.1/\" xmlns:upnp=\"urn:schemas-upnp-org:metadata-1-0/upnp/\" xmlns=\"urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/\"&gt;&lt;item id=\"0\" parentID=\"-1\" restricted=\"0\"&gt;&lt;dc:title&gt;{} *LIVE&lt;/dc:title&gt;&lt;upnp:class&gt;object.item.audioItem.musicTrack&lt;/upnp:class&gt;&lt;res protocolInfo=\"http-get:*:audio/mpeg:*\"&gt;{}&lt;/res&gt;&lt;/item&gt;&lt;/DIDL-Lite&gt;",
-                    escaped_title, ffmpeg_url
+                    "&lt;DIDL-Lite xmlns:dc=\"http://purl.org/dc/elements/1.1/\" xmlns:upnp=\"urn:schemas-upnp-org:metadata-1-0/upnp/\" xmlns:dlna=\"urn:schemas-dlna-org:metadata-1-0/\" xmlns=\"urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/\"&gt;&lt;item id=\"0\" parentID=\"-1\" restricted=\"0\"&gt;&lt;dc:title&gt;{} *LIVE&lt;/dc:title&gt;&lt;upnp:class&gt;object.item.audioItem.musicTrack&lt;/upnp:class&gt;{}&lt;res protocolInfo=\"http-get:*:{}:*\"&gt;{}&lt;/res&gt;&lt;/item&gt;&lt;/DIDL-Lite&gt;",
+                    escaped_title, album_art, output_format.mime_type(), ffmpeg_url
                 );
                 
                 let body = format!(
@@ -827,27 +1102,21 @@ impl SwDlnaSender {
                 info!("DLNA: {}", soap_envelope);
                 info!("DLNA: === END SETAVTRANSPORTURI REQUEST ===");
                 
-                let client = reqwest::blocking::Client::builder()
+                let request = HTTP_CLIENT
+                    .post(&av_url)
                     .timeout(Duration::from_secs(10))
-                    .connect_timeout(Duration::from_secs(5))
-                    .build()?;
-                
-                let response = match client
-                    .post(av_url)
                     .header("SOAPAction", "\"urn:schemas-upnp-org:service:AVTransport:1#SetAVTransportURI\"")
                     .header("Content-Type", "text/xml; charset=\"utf-8\"")
                     .header("Content-Length", soap_envelope.len().to_string())
-                    .body(soap_envelope)
-                    .send() {
-                        Ok(resp) => resp,
-                        Err(e) => {
-                            error!("DLNA: HTTP request failed: {}", e);
-                            return Err(format!("HTTP request failed: {}", e).into());
-                        }
-                    };
+                    .body(soap_envelope);
                 
-                let status = response.status();
-                let response_text = response.text().unwrap_or_default();
+                let (status, response_text) = match send_request(request).await {
+                    Ok(response) => response,
+                    Err(e) => {
+                        error!("DLNA: HTTP request failed: {}", e);
+                        return Err(e);
+                    }
+                };
                 
                 info!("DLNA: Response status: {}", status);
                 info!("DLNA: Response body: {}", response_text);
@@ -871,7 +1140,7 @@ impl SwDlnaSender {
                 info!("DLNA: Original stream URL: {}", original_url);
                 
                 // Start FFmpeg using wrapper
-                let proxy_url = self.start_ffmpeg_with_wrapper(&original_url, title)?;
+                let proxy_url = self.start_ffmpeg_with_wrapper(&original_url, title, http_settings, output_format)?;
                 
                 info!("DLNA: FFmpeg server started on {}:{}", local_ip, port);
                 info!("DLNA: Replacing external URL with FFmpeg URL: {}", proxy_url);
@@ -881,7 +1150,7 @@ impl SwDlnaSender {
                 
                 // Wait for FFmpeg to be ready before sending Play command
                 info!("DLNA: Waiting 2 seconds for FFmpeg server to be ready...");
-                std::thread::sleep(Duration::from_secs(2));
+                glib::timeout_future(Duration::from_secs(2)).await;
                 info!("DLNA: FFmpeg should be ready now");
                 
                 let play_body = "<InstanceID>0</InstanceID><Speed>1</Speed>";
@@ -907,22 +1176,21 @@ impl SwDlnaSender {
                 info!("DLNA: {}", play_soap_envelope);
                 info!("DLNA: === END PLAY REQUEST ===");
                 
-                let play_response = match client
-                    .post(av_url)
+                let play_request = HTTP_CLIENT
+                    .post(&av_url)
+                    .timeout(Duration::from_secs(10))
                     .header("SOAPAction", "\"urn:schemas-upnp-org:service:AVTransport:1#Play\"")
                     .header("Content-Type", "text/xml; charset=\"utf-8\"")
                     .header("Content-Length", play_soap_envelope.len().to_string())
-                    .body(play_soap_envelope)
-                    .send() {
-                        Ok(resp) => resp,
-                        Err(e) => {
-                            error!("DLNA: Play HTTP request failed: {}", e);
-                            return Err(format!("Play HTTP request failed: {}", e).into());
-                        }
-                    };
+                    .body(play_soap_envelope);
                 
-                let play_status = play_response.status();
-                let play_response_text = play_response.text().unwrap_or_default();
+                let (play_status, play_response_text) = match send_request(play_request).await {
+                    Ok(response) => response,
+                    Err(e) => {
+                        error!("DLNA: Play HTTP request failed: {}", e);
+                        return Err(e);
+                    }
+                };
                 
                 info!("DLNA: Play response status: {}", play_status);
                 info!("DLNA: Play response body: {}", play_response_text);
@@ -942,7 +1210,8 @@ impl SwDlnaSender {
         } else {
             // Use original URL for local streams
             info!("DLNA: Using direct URL (no proxy needed): {}", stream_url);
-            if let Some(ref av_url) = *self.imp().av_transport_url.borrow() {
+            let av_url = self.imp().av_transport_url.borrow().clone();
+            if let Some(av_url) = av_url {
                 let metadata = format!(
                     r#"<DIDL-Lite xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/" xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/">
 <item id="0" parentID="-1" restricted="0">
@@ -961,12 +1230,12 @@ impl SwDlnaSender {
                 );
 
                 info!("DLNA: Sending SetAVTransportURI with direct URL: {}", stream_url);
-                soap_action(av_url, "urn:schemas-upnp-org:service:AVTransport:1", "SetAVTransportURI", &body)?;
+                soap_action(&av_url, "urn:schemas-upnp-org:service:AVTransport:1", "SetAVTransportURI", &body).await?;
 
                 // Send Play command to start playback
                 info!("DLNA: Sending Play command to start playback");
                 let play_body = "<InstanceID>0</InstanceID><Speed>1</Speed>";
-                soap_action(av_url, "urn:schemas-upnp-org:service:AVTransport:1", "Play", play_body)?;
+                soap_action(&av_url, "urn:schemas-upnp-org:service:AVTransport:1", "Play", play_body).await?;
                 
                 info!("DLNA: ✅ SetAVTransportURI + Play commands sent successfully");
             } else {
@@ -978,20 +1247,21 @@ impl SwDlnaSender {
         Ok(())
     }
 
-    pub fn start_playback(&self) -> Result<(), Box<dyn Error>> {
+    pub async fn start_playback(&self) -> Result<(), DeviceError> {
         if !self.is_connected() {
             return Ok(());
         }
 
-        if let Some(ref av_url) = *self.imp().av_transport_url.borrow() {
+        let av_url = self.imp().av_transport_url.borrow().clone();
+        if let Some(av_url) = av_url {
             let body = "<InstanceID>0</InstanceID><Speed>1</Speed>";
-            soap_action(av_url, "urn:schemas-upnp-org:service:AVTransport:1", "Play", body)?;
+            soap_action(&av_url, "urn:schemas-upnp-org:service:AVTransport:1", "Play", body).await?;
         }
 
         Ok(())
     }
 
-    pub fn stop_playback(&self) -> Result<(), Box<dyn Error>> {
+    pub async fn stop_playback(&self) -> Result<(), DeviceError> {
         info!("DLNA: stop_playback() called - sending stop command");
         
         // Always try to send stop command - don't check connection status
@@ -1005,7 +1275,8 @@ impl SwDlnaSender {
         // Step 2: Send stop command to DLNA device
         info!("DLNA: Step 2 - Issue stop command to DLNA device");
         
-        if let Some(ref av_url) = *self.imp().av_transport_url.borrow() {
+        let av_url = self.imp().av_transport_url.borrow().clone();
+        if let Some(av_url) = av_url {
             let body = "<InstanceID>0</InstanceID>";
             let soap_envelope = format!(
                 r#"<?xml version="1.0" encoding="utf-8"?>
@@ -1029,27 +1300,21 @@ impl SwDlnaSender {
             info!("DLNA: {}", soap_envelope);
             info!("DLNA: === END STOP REQUEST ===");
             
-            let client = reqwest::blocking::Client::builder()
+            let request = HTTP_CLIENT
+                .post(&av_url)
                 .timeout(Duration::from_secs(10))
-                .connect_timeout(Duration::from_secs(5))
-                .build()?;
-            
-            let response = match client
-                .post(av_url)
                 .header("SOAPAction", "\"urn:schemas-upnp-org:service:AVTransport:1#Stop\"")
                 .header("Content-Type", "text/xml; charset=\"utf-8\"")
                 .header("Content-Length", soap_envelope.len().to_string())
-                .body(soap_envelope)
-                .send() {
-                    Ok(resp) => resp,
-                    Err(e) => {
-                        error!("DLNA: Stop HTTP request failed: {}", e);
-                        return Err(format!("Stop HTTP request failed: {}", e).into());
-                    }
-                };
+                .body(soap_envelope);
             
-            let status = response.status();
-            let response_text = response.text().unwrap_or_default();
+            let (status, response_text) = match send_request(request).await {
+                Ok(response) => response,
+                Err(e) => {
+                    error!("DLNA: Stop HTTP request failed: {}", e);
+                    return Err(e);
+                }
+            };
             
             info!("DLNA: Stop response status: {}", status);
             info!("DLNA: Stop response body: {}", response_text);
@@ -1069,72 +1334,202 @@ impl SwDlnaSender {
         Ok(())
     }
 
-    pub fn set_volume_dlna(&self, volume: f64) -> Result<(), Box<dyn Error>> {
+    /// Sends an AVTransport action, fails if the device has no AVTransport service
+    async fn transport_action(&self, action: &str, body: &str) -> Result<String, DeviceError> {
+        let av_url = self.imp().av_transport_url.borrow().clone();
+        let Some(av_url) = av_url else {
+            return Err("DLNA device discovery incomplete - no AVTransport service found".into());
+        };
+
+        soap_action(&av_url, "urn:schemas-upnp-org:service:AVTransport:1", action, body).await
+    }
+
+    /// Serves the file to the renderer, returns the URI and the escaped DIDL-Lite metadata for it
+    fn track_resource(&self, file: &Path, title: &str) -> Result<(String, String), DeviceError> {
+        self.imp().cover_server.borrow_mut().start()?;
+
+        let local_ip = self.imp().local_ip.borrow().clone();
+        let path = self.imp().cover_server.borrow().add_track(file.to_path_buf());
+        let uri = format!("http://{}:{}{}", network::url_host(&local_ip), COVER_SERVER_PORT, path);
+
+        let metadata = format!(
+            r#"<DIDL-Lite xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/" xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/">
+<item id="0" parentID="-1" restricted="0">
+<dc:title>{}</dc:title>
+<upnp:class>object.item.audioItem.musicTrack</upnp:class>
+<res protocolInfo="http-get:*:{}:*">{}</res>
+</item>
+</DIDL-Lite>"#,
+            escape_xml(title),
+            cover_server::track_mime_type(file),
+            escape_xml(&uri)
+        );
+
+        Ok((uri, escape_xml(&metadata)))
+    }
+
+    /// Plays a saved track on the renderer, returns the URI under which it gets served
+    pub async fn load_file(&self, file: &Path, title: &str) -> Result<String, DeviceError> {
+        info!("DLNA: Play saved track {:?}", file);
+
+        // A station might be streamed at the moment, the track replaces it
+        self.stop_ffmpeg_server();
+        self.imp().cover_server.borrow().clear_tracks();
+
+        let (uri, metadata) = self.track_resource(file, title)?;
+        let body = format!(
+            "<InstanceID>0</InstanceID><CurrentURI>{}</CurrentURI><CurrentURIMetaData>{}</CurrentURIMetaData>",
+            escape_xml(&uri),
+            metadata
+        );
+        self.transport_action("SetAVTransportURI", &body).await?;
+        self.start_playback().await?;
+
+        Ok(uri)
+    }
+
+    /// Queues a saved track, which the renderer plays after the current one or on `next()`.
+    /// Returns the URI under which it gets served.
+    pub async fn queue_file(&self, file: &Path, title: &str) -> Result<String, DeviceError> {
+        debug!("DLNA: Queue saved track {:?}", file);
+
+        let (uri, metadata) = self.track_resource(file, title)?;
+        let body = format!(
+            "<InstanceID>0</InstanceID><NextURI>{}</NextURI><NextURIMetaData>{}</NextURIMetaData>",
+            escape_xml(&uri),
+            metadata
+        );
+        self.transport_action("SetNextAVTransportURI", &body).await?;
+
+        Ok(uri)
+    }
+
+    pub async fn pause_playback(&self) -> Result<(), DeviceError> {
+        self.transport_action("Pause", "<InstanceID>0</InstanceID>").await?;
+        Ok(())
+    }
+
+    /// Seeks to the given position in seconds
+    pub async fn seek(&self, position: f64) -> Result<(), DeviceError> {
+        let body = format!(
+            "<InstanceID>0</InstanceID><Unit>REL_TIME</Unit><Target>{}</Target>",
+            format_time(position)
+        );
+        self.transport_action("Seek", &body).await?;
+        Ok(())
+    }
+
+    /// Advances to the media which got queued with `queue_file()`
+    pub async fn next(&self) -> Result<(), DeviceError> {
+        self.transport_action("Next", "<InstanceID>0</InstanceID>").await?;
+        Ok(())
+    }
+
+    pub async fn position_info(&self) -> Result<TransportPosition, DeviceError> {
+        let response = self
+            .transport_action("GetPositionInfo", "<InstanceID>0</InstanceID>")
+            .await?;
+
+        let time = |tag| extract_soap_value(&response, tag).and_then(|time| parse_time(&time));
+        Ok(TransportPosition {
+            uri: extract_soap_value(&response, "TrackURI").unwrap_or_default(),
+            position: time("RelTime").unwrap_or_default(),
+            duration: time("TrackDuration").unwrap_or_default(),
+        })
+    }
+
+    /// Transport state of the renderer, e.g. "PLAYING", "PAUSED_PLAYBACK" or "STOPPED"
+    pub async fn transport_state(&self) -> Result<String, DeviceError> {
+        let response = self
+            .transport_action("GetTransportInfo", "<InstanceID>0</InstanceID>")
+            .await?;
+
+        extract_soap_value(&response, "CurrentTransportState")
+            .ok_or_else(|| DeviceError::InvalidResponse("Missing transport state".into()))
+    }
+
+    pub async fn set_volume_dlna(&self, volume: f64) -> Result<(), DeviceError> {
         self.imp().volume.set(volume);
         self.notify_volume();
 
-        if let Some(ref rc_url) = *self.imp().rendering_control_url.borrow() {
+        let rc_url = self.imp().rendering_control_url.borrow().clone();
+        if let Some(rc_url) = rc_url {
             let volume_percent = (volume * 100.0) as u32;
             let body = format!(
                 "<InstanceID>0</InstanceID><Channel>Master</Channel><DesiredVolume>{}</DesiredVolume>",
                 volume_percent
             );
-            soap_action(rc_url, "urn:schemas-upnp-org:service:RenderingControl:1", "SetVolume", &body)?;
+            soap_action(&rc_url, "urn:schemas-upnp-org:service:RenderingControl:1", "SetVolume", &body).await?;
         }
 
         Ok(())
     }
 
-    pub fn set_mute_dlna(&self, mute: bool) -> Result<(), Box<dyn Error>> {
-        if let Some(ref rc_url) = *self.imp().rendering_control_url.borrow() {
+    pub async fn set_mute_dlna(&self, mute: bool) -> Result<(), DeviceError> {
+        let rc_url = self.imp().rendering_control_url.borrow().clone();
+        if let Some(rc_url) = rc_url {
             let mute_value = if mute { "1" } else { "0" };
             let body = format!(
                 "<InstanceID>0</InstanceID><Channel>Master</Channel><DesiredMute>{}</DesiredMute>",
                 mute_value
             );
-            soap_action(rc_url, "urn:schemas-upnp-org:service:RenderingControl:1", "SetMute", &body)?;
+            soap_action(&rc_url, "urn:schemas-upnp-org:service:RenderingControl:1", "SetMute", &body).await?;
             info!("DLNA: Set mute to {} on device", mute);
+
+            self.imp().muted.set(mute);
+            self.notify_muted();
         }
 
         Ok(())
     }
 
-    pub fn get_volume_dlna(&self) -> Result<f64, Box<dyn Error>> {
-        if let Some(ref rc_url) = *self.imp().rendering_control_url.borrow() {
+    pub async fn get_volume_dlna(&self) -> Result<f64, DeviceError> {
+        let rc_url = self.imp().rendering_control_url.borrow().clone();
+        if let Some(rc_url) = rc_url {
             let body = "<InstanceID>0</InstanceID><Channel>Master</Channel>";
-            let response = soap_action(rc_url, "urn:schemas-upnp-org:service:RenderingControl:1", "GetVolume", body)?;
-            
-            // Parse volume from response (simplified - would need XML parsing in production)
-            // For now, return the stored volume
-            Ok(self.imp().volume.get())
+            let response = soap_action(&rc_url, "urn:schemas-upnp-org:service:RenderingControl:1", "GetVolume", body).await?;
+
+            let volume = extract_soap_value(&response, "CurrentVolume")
+                .ok_or_else(|| DeviceError::InvalidResponse("Missing CurrentVolume in GetVolume response".into()))?
+                .parse::<f64>()?;
+            let volume = (volume / 100.0).clamp(0.0, 1.0);
+
+            self.update_rendering_state(Some(volume), None);
+            Ok(volume)
         } else {
             Ok(self.imp().volume.get())
         }
     }
 
     // Update track metadata on DLNA device without interrupting playback
-    pub fn update_track_metadata(&self, new_title: &str) -> Result<(), Box<dyn Error>> {
+    pub async fn update_track_metadata(&self, new_title: &str) -> Result<(), DeviceError> {
         info!("DLNA: Updating track metadata to: {}", new_title);
         
         // Use the stored local IP and port for the streaming URL
         let local_ip = self.imp().local_ip.borrow().clone();
         let port = self.imp().ffmpeg_port.get();
-        let streaming_url = format!("http://{}:{}/stream.mp3", local_ip, port);
+        let output_format = self.imp().output_format.borrow().clone();
+        let streaming_url = format!("http://{}:{}/stream.{}", network::url_host(&local_ip), port, output_format.extension());
+        let album_art = self
+            .album_art_url(&local_ip)
+            .map(|url| format!(r#"<upnp:albumArtURI dlna:profileID="PNG_LRG">{}</upnp:albumArtURI>"#, url))
+            .unwrap_or_default();
         
         // Get device URL from stored device information
-        if let Some(device_url) = self.imp().device.borrow().as_ref() {
-            if let Ok((av_url, _)) = fetch_device_services(device_url) {
+        let device_url = self.imp().device.borrow().clone();
+        if let Some(device_url) = device_url {
+            if let Ok((av_url, _)) = fetch_device_services(&device_url).await {
                 // Create metadata with new track title
                 let escaped_title = new_title.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
                 let metadata = format!(
-                    r#"<DIDL-Lite xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/" xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/">
+                    r#"<DIDL-Lite xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/" xmlns:dlna="urn:schemas-dlna-org:metadata-1-0/" xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/">
 <item id="0" parentID="-1" restricted="0">
 <dc:title>{}</dc:title>
-<upnp:class>object.item.audioItem.musicTrack</upnp:class>
-<res protocolInfo="http-get:*:audio/mpeg:*">{}</res>
+<upnp:class>object.item.audioItem.musicTrack</upnp:class>{}
+<res protocolInfo="http-get:*:{}:*">{}</res>
 </item>
 </DIDL-Lite>"#, 
-                    escaped_title, streaming_url
+                    escaped_title, album_art, output_format.mime_type(), streaming_url
                 );
                 
                 let body = format!(
@@ -1147,7 +1542,7 @@ impl SwDlnaSender {
                     info!("DLNA: SOAPAction: \"urn:schemas-upnp-org:service:AVTransport:1#SetNextAVTransportURI\"");
                     info!("DLNA: Request body: {}", body);
                     
-                    soap_action(&av_url, "urn:schemas-upnp-org:service:AVTransport:1", "SetNextAVTransportURI", &body)?;
+                    soap_action(&av_url, "urn:schemas-upnp-org:service:AVTransport:1", "SetNextAVTransportURI", &body).await?;
                     info!("DLNA: ✅ SetNextAVTransportURI sent successfully - metadata updated");
             } else {
                 warn!("DLNA: Cannot update metadata - failed to fetch device services");
@@ -1166,57 +1561,152 @@ impl Default for SwDlnaSender {
     }
 }
 
-// Helper function to extract StreamTitle from ICY metadata
+// Helper function to extract StreamTitle from ICY metadata,
+// e.g. "StreamTitle='Artist - Title';StreamUrl='';"
 fn extract_icy_title(metadata: &str) -> Option<String> {
-    // Look for StreamTitle in ICY metadata
-    for line in metadata.lines() {
-        if line.starts_with("StreamTitle=") {
-            let title = line.strip_prefix("StreamTitle=")
-                .unwrap_or("")
-                .trim_matches('\'')
-                .trim_matches('"');
-            if !title.is_empty() {
-                return Some(title.to_string());
-            }
-        }
+    let start = metadata.find("StreamTitle=")? + "StreamTitle=".len();
+    let value = &metadata[start..];
+
+    // Titles can contain quotes themselves, so only the closing quote ends the value
+    let title = match value.find("';") {
+        Some(end) => &value[..end],
+        None => value.trim_end_matches('\0').trim_end_matches(';'),
+    };
+    let title = title.trim_matches('\'').trim_matches('"').trim();
+
+    if title.is_empty() {
+        None
+    } else {
+        Some(title.to_string())
     }
-    None
 }
 
-// Fetch ICY metadata from a radio stream URL using HTTP HEAD request
-fn fetch_icy_metadata(url: &str) -> Result<String, Box<dyn std::error::Error>> {
-    let client = reqwest::blocking::Client::builder()
+// Fetch the current title from the ICY metadata of a radio stream, or the name
+// of the stream if it doesn't send any titles
+async fn fetch_icy_metadata(url: &str) -> Result<String, DeviceError> {
+    let request = HTTP_CLIENT
+        .get(url)
         .timeout(Duration::from_secs(10))
-        .build()?;
-    
-    // Send HEAD request to get ICY metadata
-    let response = client
-        .head(url)
         .header("Icy-MetaData", "1")
-        .header("User-Agent", "Shortwave/1.0")
-        .send()?;
-    
-    // Check for ICY metadata in headers
-    if let Some(icy_name) = response.headers().get("icy-name") {
-        if let Ok(name) = icy_name.to_str() {
-            return Ok(name.to_string());
+        .header("User-Agent", "Shortwave/1.0");
+
+    Compat::new(async move {
+        let mut response = request.send().await?;
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_string())
+        };
+        let icy_name = header("icy-name");
+        let icy_metaint = header("icy-metaint")
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .filter(|metaint| *metaint > 0);
+
+        // The first metadata block follows after the first interval of audio data,
+        // it starts with its length in units of 16 bytes
+        if let Some(metaint) = icy_metaint {
+            let mut data = Vec::new();
+            while let Some(chunk) = response.chunk().await? {
+                data.extend_from_slice(&chunk);
+
+                let Some(&length) = data.get(metaint) else {
+                    continue;
+                };
+                let end = metaint + 1 + usize::from(length) * 16;
+                if data.len() >= end {
+                    let metadata = String::from_utf8_lossy(&data[metaint + 1..end]);
+                    if let Some(title) = extract_icy_title(&metadata) {
+                        return Ok(title);
+                    }
+                    break;
+                }
+            }
         }
+
+        Ok::<_, DeviceError>(icy_name.unwrap_or_default())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_lite::future::block_on;
+
+    use super::*;
+    use crate::test_server::{self, IcecastStream, TestResponse, TestServer};
+
+    #[test]
+    fn icy_titles() {
+        assert_eq!(
+            extract_icy_title("StreamTitle='Artist - Title';StreamUrl='';\0\0\0").as_deref(),
+            Some("Artist - Title")
+        );
+        assert_eq!(
+            extract_icy_title("StreamTitle='Don't Stop';").as_deref(),
+            Some("Don't Stop")
+        );
+        assert_eq!(extract_icy_title("StreamTitle='';"), None);
+        assert_eq!(extract_icy_title("StreamUrl='http://example.org';"), None);
     }
-    
-    // Try a brief GET request to extract StreamTitle from initial metadata
-    let response = client
-        .get(url)
-        .header("Icy-MetaData", "1")
-        .header("User-Agent", "Shortwave/1.0")
-        .send()?;
-    
-    // Check if we have ICY metadata in response
-    if let Some(icy_metaint) = response.headers().get("icy-metaint") {
-        info!("DLNA: Stream supports ICY metadata with interval: {:?}", icy_metaint);
-        // For now, return empty string - the actual metadata extraction 
-        // would require streaming the full audio data which is complex
-        return Ok(String::new());
+
+    #[test]
+    fn icy_title_of_stream() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            "/titles" => {
+                IcecastStream::response(request, &["Artist - First Song", "Artist - Second Song"])
+                    .header("icy-name", "Test Radio")
+            }
+            _ => TestResponse::stream(std::io::repeat(0)).header("icy-name", "Test Radio"),
+        });
+
+        let title = block_on(fetch_icy_metadata(&server.url("/titles"))).unwrap();
+        assert_eq!(title, "Artist - First Song");
+
+        // Without any titles, the name of the stream gets used
+        let title = block_on(fetch_icy_metadata(&server.url("/name"))).unwrap();
+        assert_eq!(title, "Test Radio");
+    }
+
+    #[test]
+    fn renderer_services() {
+        let renderer = test_server::dlna_renderer(40, true);
+
+        let (av_transport_url, rendering_control_url) =
+            block_on(fetch_device_services(&renderer.url("/description.xml"))).unwrap();
+        assert_eq!(av_transport_url, renderer.url("/AVTransport/control"));
+        assert_eq!(
+            rendering_control_url,
+            renderer.url("/RenderingControl/control")
+        );
+
+        let (volume, muted) = block_on(fetch_rendering_state(&rendering_control_url));
+        assert_eq!(volume, Some(0.4));
+        assert_eq!(muted, Some(true));
+
+        let request = renderer.requests().pop().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(
+            request.header("soapaction"),
+            Some("\"urn:schemas-upnp-org:service:RenderingControl:1#GetMute\"")
+        );
+        assert!(request.body.contains("<InstanceID>0</InstanceID>"));
+    }
+
+    #[test]
+    fn failed_soap_action() {
+        let renderer = test_server::dlna_renderer(40, false);
+        let control_url = renderer.url("/RenderingControl/control");
+
+        let service = "urn:schemas-upnp-org:service:RenderingControl:1";
+        let result = block_on(soap_action(&control_url, service, "SetLoudness", ""));
+        assert!(result.is_err());
+
+        let response = block_on(soap_action(&control_url, service, "GetVolume", "")).unwrap();
+        assert_eq!(
+            extract_soap_value(&response, "CurrentVolume").as_deref(),
+            Some("40")
+        );
     }
-    
-    Ok(String::new())
 }
@@ -17,15 +17,23 @@
 use std::sync::{Arc, mpsc, atomic::{AtomicU64, Ordering}};
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
-use std::process::Child;
+use std::io::Write;
+use std::process::{Child, Stdio};
+use async_compat::Compat;
+use futures_lite::future;
 use uuid::Uuid;
 
+use super::{network, DeviceError};
+use crate::database::HttpSettings;
+
 // Commands sent to FFmpeg thread
 #[derive(Debug, Clone)]
 pub enum FfmpegCommand {
     StartStream {
         stream_url: String,
         stream_id: String,
+        http_settings: HttpSettings,
+        output_format: OutputFormat,
         force_restart: bool,
     },
     StopStream,
@@ -56,6 +64,66 @@ pub enum OutputFormat {
     Passthrough, // No transcoding
 }
 
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::Mp3 { bitrate: 128000 }
+    }
+}
+
+impl OutputFormat {
+    /// Picks the format to transcode into from the mime types a device reported as playable.
+    /// MP3 is preferred as it's the most widely supported one, and is also used as fallback
+    /// when the device didn't report anything.
+    pub fn from_mime_types(mime_types: &[String]) -> Self {
+        let supports = |candidates: &[&str]| {
+            mime_types.iter().any(|mime| {
+                // Strip parameters like "audio/L16;rate=44100"
+                let mime = mime.split(';').next().unwrap_or_default().trim().to_lowercase();
+                mime == "*" || mime == "audio/*" || candidates.contains(&mime.as_str())
+            })
+        };
+
+        if mime_types.is_empty() || supports(&["audio/mpeg", "audio/mp3", "audio/x-mpeg", "audio/mpeg3"]) {
+            OutputFormat::Mp3 { bitrate: 128000 }
+        } else if supports(&["audio/aac", "audio/aacp", "audio/x-aac", "audio/vnd.dlna.adts"]) {
+            OutputFormat::Aac { bitrate: 128000 }
+        } else if supports(&["audio/ogg", "audio/x-ogg", "audio/opus", "application/ogg"]) {
+            OutputFormat::Opus { bitrate: 96000 }
+        } else {
+            warn!("FFMPEG-WRAPPER: No supported format in {:?}, falling back to MP3", mime_types);
+            OutputFormat::default()
+        }
+    }
+
+    /// The mime type announced to the device for the proxied stream
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            OutputFormat::Mp3 { .. } => "audio/mpeg",
+            OutputFormat::Aac { .. } => "audio/aac",
+            OutputFormat::Opus { .. } => "audio/ogg",
+            OutputFormat::Passthrough => "application/octet-stream",
+        }
+    }
+
+    /// The file extension of the proxy URL, some devices determine the content type by it
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Mp3 { .. } => "mp3",
+            OutputFormat::Aac { .. } => "aac",
+            OutputFormat::Opus { .. } => "ogg",
+            OutputFormat::Passthrough => "bin",
+        }
+    }
+
+    /// Whether a stream of the given type can be forwarded without transcoding
+    fn matches_stream(&self, stream_type: &StreamType) -> bool {
+        matches!(
+            (self, stream_type),
+            (OutputFormat::Mp3 { .. }, StreamType::Mp3) | (OutputFormat::Aac { .. }, StreamType::Aac)
+        )
+    }
+}
+
 // Stream type detection
 #[derive(Debug, Clone, PartialEq)]
 pub enum StreamType {
@@ -93,6 +161,7 @@ struct FfmpegSession {
     stream_id: String,
     stream_url: String,
     proxy_url: String,
+    output_format: OutputFormat,
     process: Child,
     start_time: Instant,
     bytes_sent: Arc<AtomicU64>,
@@ -122,9 +191,9 @@ impl FfmpegWrapper {
     }
     
     /// Start the FFmpeg wrapper thread
-    pub fn start(&mut self) -> Result<(), String> {
+    pub fn start(&mut self) -> Result<(), DeviceError> {
         if self.thread_handle.is_some() {
-            return Err("FFmpeg wrapper already running".to_string());
+            return Err(DeviceError::Ffmpeg("FFmpeg wrapper already running".to_string()));
         }
         
         let (cmd_sender, cmd_receiver) = mpsc::channel::<FfmpegCommand>();
@@ -143,12 +212,12 @@ impl FfmpegWrapper {
     }
     
     /// Send a command to the FFmpeg thread
-    pub fn send_command(&self, command: FfmpegCommand) -> Result<(), String> {
+    pub fn send_command(&self, command: FfmpegCommand) -> Result<(), DeviceError> {
         if let Some(ref sender) = self.command_sender {
             sender.send(command)
-                .map_err(|e| format!("Failed to send command: {}", e))
+                .map_err(|e| DeviceError::Ffmpeg(format!("Failed to send command: {}", e)))
         } else {
-            Err("FFmpeg wrapper not started".to_string())
+            Err(DeviceError::Ffmpeg("FFmpeg wrapper not started".to_string()))
         }
     }
     
@@ -180,7 +249,7 @@ impl FfmpegWrapper {
         // Process commands
         while let Ok(command) = command_receiver.recv() {
             match command {
-                FfmpegCommand::StartStream { stream_url, stream_id, force_restart } => {
+                FfmpegCommand::StartStream { stream_url, stream_id, http_settings, output_format, force_restart } => {
                     info!("FFMPEG-WRAPPER: StartStream command for {}", stream_url);
                     
                     // Check if we can reuse existing session
@@ -190,7 +259,7 @@ impl FfmpegWrapper {
                         match session.process.try_wait() {
                             Ok(None) => {
                                 // Process is still running
-                                if session.stream_url == stream_url && !force_restart {
+                                if session.stream_url == stream_url && session.output_format == output_format && !force_restart {
                                     info!("FFMPEG-WRAPPER: Reusing existing session for {}", stream_url);
                                     can_reuse = true;
                                     let _ = status_sender.send(FfmpegStatus::Streaming {
@@ -232,7 +301,7 @@ impl FfmpegWrapper {
                     }
                     
                     // Start new session
-                    match Self::start_ffmpeg_session(&stream_url, &stream_id, &status_sender) {
+                    match Self::start_ffmpeg_session(&stream_url, &stream_id, &http_settings, &output_format, &status_sender) {
                         Ok(session) => {
                             let proxy_url = session.proxy_url.clone();
                             current_session = Some(session);
@@ -248,7 +317,7 @@ impl FfmpegWrapper {
                             error!("FFMPEG-WRAPPER: Failed to start session: {}", e);
                             let _ = status_sender.send(FfmpegStatus::Error {
                                 stream_id,
-                                error: e,
+                                error: e.to_string(),
                             });
                         }
                     }
@@ -309,8 +378,10 @@ impl FfmpegWrapper {
     fn start_ffmpeg_session(
         stream_url: &str,
         stream_id: &str,
+        http_settings: &HttpSettings,
+        target_format: &OutputFormat,
         status_sender: &mpsc::Sender<FfmpegStatus>,
-    ) -> Result<FfmpegSession, String> {
+    ) -> Result<FfmpegSession, DeviceError> {
         // Send starting status
         let _ = status_sender.send(FfmpegStatus::Starting {
             stream_id: stream_id.to_string(),
@@ -321,20 +392,49 @@ impl FfmpegWrapper {
         info!("FFMPEG-WRAPPER: Detected stream type: {:?}", stream_type);
         
         // Determine if transcoding is needed
-        let output_format = if matches!(stream_type, StreamType::Mp3) {
+        let output_format = if target_format.matches_stream(&stream_type) {
             OutputFormat::Passthrough
         } else {
-            OutputFormat::Mp3 { bitrate: 128000 }
+            target_format.clone()
         };
+        info!("FFMPEG-WRAPPER: Target format: {:?}, output: {:?}", target_format, output_format);
         
         // Build FFmpeg command
         let mut args = vec![];
         
+        // Credentials must not show up in the process list, so protected streams
+        // get requested here and are handed to FFmpeg through its stdin instead
+        let upstream = if http_settings.credentials.is_some() {
+            Some(Self::request_stream(stream_url, http_settings)?)
+        } else {
+            None
+        };
+        
+        // Add station specific HTTP options, they have to be set before the input URL
+        if upstream.is_none() && !http_settings.user_agent.is_empty() {
+            args.extend_from_slice(&[
+                "-user_agent".to_string(),
+                http_settings.user_agent.clone(),
+            ]);
+        }
+        if upstream.is_none() && !http_settings.headers.is_empty() {
+            let headers: String = http_settings
+                .headers
+                .iter()
+                .map(|(name, value)| format!("{}: {}\r\n", name, value))
+                .collect();
+            args.extend_from_slice(&[
+                "-headers".to_string(),
+                headers,
+            ]);
+        }
+        
         // Add input URL
         info!("FFMPEG-WRAPPER: Adding input URL");
+        let input = if upstream.is_some() { "pipe:0" } else { stream_url };
         args.extend_from_slice(&[
             "-i".to_string(),
-            stream_url.to_string(),
+            input.to_string(),
         ]);
         info!("FFMPEG-WRAPPER: Input URL added, args length: {}", args.len());
         
@@ -353,48 +453,90 @@ impl FfmpegWrapper {
                     "mp3".to_string(),
                 ]);
             }
+            OutputFormat::Aac { bitrate } => {
+                args.extend_from_slice(&[
+                    "-c:a".to_string(),
+                    "aac".to_string(),
+                    "-b:a".to_string(),
+                    format!("{}k", bitrate / 1000).to_string(),
+                    "-f".to_string(),
+                    "adts".to_string(),
+                ]);
+            }
+            OutputFormat::Opus { bitrate } => {
+                args.extend_from_slice(&[
+                    "-c:a".to_string(),
+                    "libopus".to_string(),
+                    "-b:a".to_string(),
+                    format!("{}k", bitrate / 1000).to_string(),
+                    "-f".to_string(),
+                    "ogg".to_string(),
+                ]);
+            }
             OutputFormat::Passthrough => {
                 args.extend_from_slice(&[
                     "-c".to_string(),
                     "copy".to_string(),
                 ]);
             }
-            _ => {}
         }
         
         // Add HTTP server options (use default port 8080)
-        // Use .mp3 extension so Cast devices recognize the content type
+        // Use a matching extension so devices recognize the content type.
+        // Listening on the IPv6 wildcard address also accepts IPv4 connections.
+        let listen_host = if network::has_ipv6() { "[::]" } else { "0.0.0.0" };
         args.extend_from_slice(&[
             "-listen".to_string(),
             "1".to_string(),
-            "http://0.0.0.0:8080/stream.mp3".to_string(),
+            format!("http://{}:8080/stream.{}", listen_host, target_format.extension()),
         ]);
         
-        info!("FFMPEG-WRAPPER: Starting FFmpeg with args: {:?}", args);
-        debug!("FFMPEG-WRAPPER: Full FFmpeg command: ffmpeg {}", args.join(" "));
+        let logged_args = Self::redact_args(&args);
+        info!("FFMPEG-WRAPPER: Starting FFmpeg with args: {:?}", logged_args);
+        debug!("FFMPEG-WRAPPER: Full FFmpeg command: ffmpeg {}", logged_args.join(" "));
         
         // Start FFmpeg process
-        let result = std::process::Command::new("ffmpeg")
-            .args(&args)
-            .spawn();
+        let mut command = std::process::Command::new("ffmpeg");
+        command.args(&args);
+        if upstream.is_some() {
+            command.stdin(Stdio::piped());
+        }
+        let result = command.spawn();
             
-        let process = match result {
+        let mut process = match result {
             Ok(process) => {
                 info!("FFMPEG-WRAPPER: FFmpeg process started successfully");
                 process
             }
             Err(e) => {
                 error!("FFMPEG-WRAPPER: Failed to start FFmpeg: {}", e);
-                error!("FFMPEG-WRAPPER: Command: ffmpeg {}", args.join(" "));
-                return Err(format!("Failed to start FFmpeg: {}", e));
+                error!("FFMPEG-WRAPPER: Command: ffmpeg {}", logged_args.join(" "));
+                return Err(DeviceError::Ffmpeg(format!("Failed to start FFmpeg: {}", e)));
             }
         };
         
+        // Feed the protected stream to FFmpeg, this ends as soon as FFmpeg exits
+        if let (Some(mut upstream), Some(mut stdin)) = (upstream, process.stdin.take()) {
+            thread::spawn(move || {
+                let copy = async move {
+                    while let Some(chunk) = upstream.chunk().await? {
+                        stdin.write_all(&chunk)?;
+                    }
+                    Ok::<(), Box<dyn std::error::Error>>(())
+                };
+
+                if let Err(e) = future::block_on(Compat::new(copy)) {
+                    debug!("FFMPEG-WRAPPER: Stream input ended: {}", e);
+                }
+            });
+        }
+        
         // Create session
         let session = FfmpegSession {
             stream_id: stream_id.to_string(),
             stream_url: stream_url.to_string(),
-            proxy_url: "http://localhost:8080/stream.mp3".to_string(),
+            proxy_url: format!("http://localhost:8080/stream.{}", target_format.extension()),
+            output_format: target_format.clone(),
             process,
             start_time: Instant::now(),
             bytes_sent: Arc::new(AtomicU64::new(0)),
@@ -405,6 +547,44 @@ impl FfmpegWrapper {
         Ok(session)
     }
     
+    /// Request a stream with the station specific HTTP options, including its credentials
+    fn request_stream(
+        stream_url: &str,
+        http_settings: &HttpSettings,
+    ) -> Result<reqwest::Response, DeviceError> {
+        // The shared client times out after 15 seconds, which would end the stream
+        let client = reqwest::Client::builder().build()?;
+        let mut request = client.get(stream_url);
+        
+        if !http_settings.user_agent.is_empty() {
+            request = request.header(reqwest::header::USER_AGENT, &http_settings.user_agent);
+        }
+        for (name, value) in &http_settings.headers {
+            request = request.header(name, value);
+        }
+        if let Some(credentials) = &http_settings.credentials {
+            request = request.basic_auth(&credentials.username, Some(&credentials.password));
+        }
+        
+        // This runs on the FFmpeg thread, reqwest needs a tokio runtime which gets provided by async-compat
+        let response = future::block_on(Compat::new(request.send()))?;
+        Ok(response.error_for_status()?)
+    }
+    
+    /// Arguments for log output, header values may contain secrets like tokens
+    fn redact_args(args: &[String]) -> Vec<String> {
+        args.iter()
+            .enumerate()
+            .map(|(i, arg)| {
+                if i > 0 && args[i - 1] == "-headers" {
+                    "<redacted>".to_string()
+                } else {
+                    arg.clone()
+                }
+            })
+            .collect()
+    }
+    
     /// Detect stream type from URL
     fn detect_stream_type(url: &str) -> StreamType {
         if url.ends_with(".mp3") {
@@ -14,18 +14,24 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+mod cast_receiver;
 mod cast_sender;
+mod cover_server;
 mod dlna_sender;
 #[allow(clippy::module_inception)]
 mod device;
 mod device_discovery;
+mod device_error;
 mod device_kind;
 mod device_model;
 mod ffmpeg_wrapper;
+pub mod network;
 
+pub use cast_receiver::SwCastReceiver;
 pub use cast_sender::SwCastSender;
 pub use device::SwDevice;
 pub use device_discovery::SwDeviceDiscovery;
+pub use device_error::DeviceError;
 pub use device_kind::SwDeviceKind;
 pub use device_model::SwDeviceModel;
 pub use dlna_sender::{SwDlnaSender, get_local_ip_for_device};
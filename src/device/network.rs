@@ -0,0 +1,210 @@
+// Shortwave - network.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, UdpSocket};
+use std::os::fd::AsRawFd;
+
+use if_addrs::IfAddr;
+
+use crate::settings::{settings_manager, Key};
+
+/// An address of a local network interface which can be used to reach devices.
+/// Interfaces with several addresses are listed once per address.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkInterface {
+    pub name: String,
+    pub index: Option<u32>,
+    pub ip: IpAddr,
+    pub netmask: IpAddr,
+}
+
+impl NetworkInterface {
+    /// Whether the address is part of the subnet of this interface
+    pub fn contains(&self, address: IpAddr) -> bool {
+        match (self.ip, self.netmask, address) {
+            (IpAddr::V4(ip), IpAddr::V4(mask), IpAddr::V4(address)) => {
+                let mask = u32::from(mask);
+                u32::from(ip) & mask == u32::from(address) & mask
+            }
+            (IpAddr::V6(ip), IpAddr::V6(mask), IpAddr::V6(address)) => {
+                let mask = u128::from(mask);
+                u128::from(ip) & mask == u128::from(address) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// All addresses of interfaces which are up, loopback excluded. Link-local IPv6
+/// addresses are skipped, since they can't be used in URLs without a scope.
+pub fn interfaces() -> Vec<NetworkInterface> {
+    let interfaces = if_addrs::get_if_addrs().unwrap_or_else(|e| {
+        warn!("Unable to list network interfaces: {}", e);
+        Vec::new()
+    });
+
+    interfaces
+        .into_iter()
+        .filter(|iface| !iface.is_loopback() && iface.is_oper_up())
+        .filter_map(|iface| {
+            let (ip, netmask) = match iface.addr {
+                IfAddr::V4(addr) => (IpAddr::V4(addr.ip), IpAddr::V4(addr.netmask)),
+                IfAddr::V6(addr) if addr.ip.segments()[0] & 0xffc0 != 0xfe80 => {
+                    (IpAddr::V6(addr.ip), IpAddr::V6(addr.netmask))
+                }
+                IfAddr::V6(_) => return None,
+            };
+
+            Some(NetworkInterface {
+                name: iface.name,
+                index: iface.index,
+                ip,
+                netmask,
+            })
+        })
+        .collect()
+}
+
+/// The name of the interface which got selected in the preferences, `None` if
+/// it should be detected automatically
+pub fn selected_interface_name() -> Option<String> {
+    let name = settings_manager::string(Key::DeviceNetworkInterface);
+    (!name.is_empty()).then_some(name)
+}
+
+/// The addresses of the selected interface, empty if it should be detected
+/// automatically or isn't available anymore
+pub fn selected_interfaces() -> Vec<NetworkInterface> {
+    let Some(name) = selected_interface_name() else {
+        return Vec::new();
+    };
+
+    let interfaces: Vec<_> = interfaces()
+        .into_iter()
+        .filter(|iface| iface.name == name)
+        .collect();
+    if interfaces.is_empty() {
+        warn!(
+            "Selected network interface {} is not available, using automatic detection",
+            name
+        );
+    }
+    interfaces
+}
+
+/// The interfaces on which devices should be searched
+pub fn discovery_interfaces() -> Vec<NetworkInterface> {
+    let selected = selected_interfaces();
+    if selected.is_empty() {
+        interfaces()
+    } else {
+        selected
+    }
+}
+
+/// Whether any interface has an usable IPv6 address
+pub fn has_ipv6() -> bool {
+    interfaces().iter().any(|iface| iface.ip.is_ipv6())
+}
+
+/// The local address under which the given device can reach us. Prefers the
+/// selected interface, then the interface sharing a subnet with the device, and
+/// falls back to the address of the default route to the device.
+pub fn local_ip_for(address: IpAddr) -> io::Result<IpAddr> {
+    let selected = selected_interfaces();
+    let candidates = if selected.is_empty() {
+        interfaces()
+    } else {
+        selected.clone()
+    };
+
+    if let Some(iface) = candidates.iter().find(|iface| iface.contains(address)) {
+        debug!("Using interface {} to reach {}", iface.name, address);
+        return Ok(iface.ip);
+    }
+
+    if let Some(iface) = selected
+        .iter()
+        .find(|iface| iface.ip.is_ipv4() == address.is_ipv4())
+    {
+        return Ok(iface.ip);
+    }
+
+    let socket = if address.is_ipv6() {
+        UdpSocket::bind("[::]:0")?
+    } else {
+        UdpSocket::bind("0.0.0.0:0")?
+    };
+    socket.connect((address, 80))?;
+    Ok(socket.local_addr()?.ip())
+}
+
+/// Formats an address so that it can be used as host in URLs,
+/// which requires IPv6 addresses to be enclosed in brackets
+pub fn url_host(address: &str) -> String {
+    // Scoped addresses can't be used in URLs, so the scope gets dropped
+    let unscoped = address.split('%').next().unwrap_or(address);
+    if unscoped.parse::<Ipv6Addr>().is_ok() {
+        format!("[{unscoped}]")
+    } else {
+        address.to_string()
+    }
+}
+
+/// Sets the interface used for outgoing IPv4 multicast packets of the socket
+pub fn set_multicast_interface(socket: &UdpSocket, ip: Ipv4Addr) -> io::Result<()> {
+    let addr = libc::in_addr {
+        s_addr: u32::from(ip).to_be(),
+    };
+
+    let res = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_IP,
+            libc::IP_MULTICAST_IF,
+            &addr as *const libc::in_addr as *const libc::c_void,
+            std::mem::size_of::<libc::in_addr>() as libc::socklen_t,
+        )
+    };
+
+    if res == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Sets the interface used for outgoing IPv6 multicast packets of the socket
+pub fn set_multicast_interface_v6(socket: &UdpSocket, index: u32) -> io::Result<()> {
+    let index = index as libc::c_uint;
+
+    let res = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_IPV6,
+            libc::IPV6_MULTICAST_IF,
+            &index as *const libc::c_uint as *const libc::c_void,
+            std::mem::size_of::<libc::c_uint>() as libc::socklen_t,
+        )
+    };
+
+    if res == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
@@ -0,0 +1,104 @@
+// Shortwave - iso_codes.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Localized country and language names, based on the iso-codes data of the system
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use gettextrs::dgettext;
+use gtk::glib;
+
+#[derive(Deserialize)]
+struct IsoEntry {
+    alpha_2: Option<String>,
+    alpha_3: String,
+    name: String,
+    common_name: Option<String>,
+}
+
+/// Maps lowercase alpha-2 and alpha-3 codes to English names
+struct IsoTable {
+    domain: &'static str,
+    names: HashMap<String, String>,
+}
+
+impl IsoTable {
+    fn load(standard: &'static str, domain: &'static str) -> Self {
+        let names = Self::entries(standard, domain)
+            .into_iter()
+            .flat_map(|entry| {
+                let name = entry.common_name.unwrap_or(entry.name);
+                let alpha_2 = entry
+                    .alpha_2
+                    .map(|code| (code.to_lowercase(), name.clone()));
+                let alpha_3 = (entry.alpha_3.to_lowercase(), name);
+                alpha_2.into_iter().chain(std::iter::once(alpha_3))
+            })
+            .collect();
+
+        Self { domain, names }
+    }
+
+    fn entries(standard: &str, domain: &str) -> Vec<IsoEntry> {
+        let path = glib::system_data_dirs()
+            .into_iter()
+            .map(|dir| {
+                let mut path = PathBuf::from(dir);
+                path.push("iso-codes");
+                path.push("json");
+                path.push(format!("{domain}.json"));
+                path
+            })
+            .find(|path| path.exists());
+
+        let Some(path) = path else {
+            warn!("Unable to find iso-codes data for {domain}");
+            return Vec::new();
+        };
+
+        let res = std::fs::read(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| {
+                let mut tables: HashMap<String, Vec<IsoEntry>> = serde_json::from_slice(&data)?;
+                Ok(tables.remove(standard).unwrap_or_default())
+            });
+
+        res.unwrap_or_else(|err| {
+            warn!("Unable to load iso-codes data {path:?}: {err}");
+            Vec::new()
+        })
+    }
+
+    fn localized_name(&self, code: &str) -> Option<String> {
+        let name = self.names.get(&code.trim().to_lowercase())?;
+        Some(dgettext(self.domain, name.as_str()))
+    }
+}
+
+static COUNTRIES: LazyLock<IsoTable> = LazyLock::new(|| IsoTable::load("3166-1", "iso_3166-1"));
+static LANGUAGES: LazyLock<IsoTable> = LazyLock::new(|| IsoTable::load("639-3", "iso_639-3"));
+
+/// Returns the localized name of a country by its ISO 3166-1 code
+pub fn country_name(code: &str) -> Option<String> {
+    COUNTRIES.localized_name(code)
+}
+
+/// Returns the localized name of a language by its ISO 639 code
+pub fn language_name(code: &str) -> Option<String> {
+    LANGUAGES.localized_name(code)
+}
@@ -0,0 +1,91 @@
+// Shortwave - logger.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::VecDeque;
+use std::env;
+use std::sync::Mutex;
+
+use gtk::glib;
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// How many log lines are kept for the diagnostics dialog
+const MAX_LINES: usize = 500;
+
+static LINES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Forwards everything to pretty_env_logger, and additionally keeps the most
+/// recent lines in memory, so that they can be included in bug reports
+struct SwLogger {
+    inner: Box<dyn Log>,
+}
+
+impl Log for SwLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= LevelFilter::Info || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.inner.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+
+        if record.level() > LevelFilter::Info {
+            return;
+        }
+
+        let time = glib::DateTime::now_local()
+            .and_then(|time| time.format("%T"))
+            .map(|time| time.to_string())
+            .unwrap_or_default();
+        let line = format!(
+            "{time} {:<5} {}: {}",
+            record.level(),
+            record.target(),
+            record.args()
+        );
+
+        let mut lines = LINES.lock().unwrap();
+        if lines.len() >= MAX_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+pub fn init() {
+    let mut builder = pretty_env_logger::formatted_builder();
+    if let Ok(filters) = env::var("RUST_LOG") {
+        builder.parse_filters(&filters);
+    }
+
+    let inner = builder.build();
+    let max_level = inner.filter().max(LevelFilter::Info);
+
+    log::set_boxed_logger(Box::new(SwLogger {
+        inner: Box::new(inner),
+    }))
+    .expect("Unable to initialize logger");
+    log::set_max_level(max_level);
+}
+
+/// The most recent log lines, oldest first
+pub fn recent_lines() -> Vec<String> {
+    LINES.lock().unwrap().iter().cloned().collect()
+}
@@ -36,7 +36,11 @@ mod app;
 #[rustfmt::skip]
 mod config;
 mod i18n;
+mod iso_codes;
+mod logger;
 mod path;
+#[cfg(test)]
+mod test_server;
 
 use std::env;
 
@@ -47,7 +51,7 @@ use crate::app::SwApplication;
 
 fn main() -> glib::ExitCode {
     // Initialize logger
-    pretty_env_logger::init();
+    logger::init();
 
     // Initialize paths
     path::init().expect("Unable to create paths.");
@@ -20,23 +20,68 @@ pub enum Key {
     // API
     ApiLookupDomain,
 
+    // Appearance
+    AppearanceColorScheme,
+    AppearanceCoverShape,
+    AppearanceGridDensity,
+    AppearanceScrollingTitles,
+
+    // Covers
+    CoverLoaderConcurrency,
+    CoverLoaderQueueSize,
+
+    // Devices
+    DeviceBackgroundDiscovery,
+    DeviceCastAppId,
+    DeviceCastReceiver,
+    DeviceNetworkInterface,
+
+    // Discover
+    DiscoverLanguage,
+
     // Library
     LibrarySorting,
     LibrarySortingType,
 
     // Playback
+    PlaybackAudioFilters,
+    PlaybackFadeDuration,
+    PlaybackInterruptionBehavior,
     PlaybackLastStation,
     PlaybackPastTracksCount,
+    PlaybackShuffle,
+    PlaybackShuffleInterval,
+    PlaybackTimeshiftDuration,
     PlaybackVolume,
     PlaybackVolumeLocal,
     PlaybackVolumeCast,
     PlaybackVolumeDlna,
 
+    // Recorders
+    RecordersMaximumDuration,
+    RecordersMaximumSessions,
+
     // Recording
+    RecordingAcoustidApiKey,
+    RecordingConvertFormat,
+    RecordingFilenameStyle,
+    RecordingFolderStructure,
+    RecordingLibraryExport,
+    RecordingLibraryStructure,
     RecordingMaximumDuration,
     RecordingMinimumDuration,
     RecordingMode,
+    RecordingNormalizeLoudness,
     RecordingTrackDirectory,
+    RecordingWriteDirectly,
+
+    // Startup
+    StartupHidden,
+    StartupRestoreGadget,
+    StartupResumePlayback,
+
+    // Stations
+    StationsHideBroken,
 
     // User Interface
     WindowWidth,
@@ -46,4 +91,6 @@ pub enum Key {
 
     BackgroundPlayback,
     Notifications,
+    NotificationsDoNotDisturb,
+    Shortcuts,
 }
@@ -18,3 +18,4 @@ mod key;
 pub use key::Key;
 
 pub mod settings_manager;
+pub mod shortcuts;
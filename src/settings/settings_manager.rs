@@ -14,8 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use anyhow::{anyhow, Result};
 use gio::prelude::*;
 use gtk::{gio, glib};
+use serde_json::{Map, Value};
 
 use crate::config;
 use crate::settings::Key;
@@ -84,3 +86,85 @@ pub fn set_double(key: Key, value: f64) {
     let settings = settings();
     settings.set_double(&key.to_string(), value).unwrap();
 }
+
+/// Keys which only make sense on the machine where they got set, and which therefore aren't
+/// part of exported settings
+fn is_local_key(key: &str) -> bool {
+    key.starts_with("window-") || key == Key::PlaybackLastStation.to_string()
+}
+
+/// Serializes all settings into a JSON bundle which can be imported on another machine.
+/// Values which have no JSON counterpart, e.g. arrays, are stored in the GVariant text format.
+pub fn export() -> String {
+    let settings = settings();
+    let mut values = Map::new();
+
+    for key in settings.settings_schema().unwrap().list_keys() {
+        if is_local_key(&key) {
+            continue;
+        }
+
+        let variant = settings.value(&key);
+        let value = match variant.type_().as_str() {
+            "b" => variant.get::<bool>().map(Value::from),
+            "i" => variant.get::<i32>().map(Value::from),
+            "d" => variant.get::<f64>().map(Value::from),
+            "s" => variant.get::<String>().map(Value::from),
+            _ => Some(Value::from(variant.print(false).to_string())),
+        };
+
+        if let Some(value) = value {
+            values.insert(key.to_string(), value);
+        }
+    }
+
+    let mut bundle = Map::new();
+    bundle.insert("app-id".into(), config::APP_ID.into());
+    bundle.insert("settings".into(), values.into());
+
+    serde_json::to_string_pretty(&Value::from(bundle)).unwrap()
+}
+
+/// Applies the settings of a JSON bundle created by [`export`]. Unknown keys and values with an
+/// unexpected type are skipped. Returns the number of applied settings.
+pub fn import(data: &[u8]) -> Result<u32> {
+    let bundle: Value = serde_json::from_slice(data)?;
+    let values = bundle
+        .get("settings")
+        .and_then(Value::as_object)
+        .ok_or_else(|| anyhow!("No settings found"))?;
+
+    let settings = settings();
+    let schema = settings.settings_schema().unwrap();
+    settings.delay();
+
+    let mut count = 0;
+    for (key, value) in values {
+        if !schema.has_key(key) || is_local_key(key) {
+            warn!("Skipping unknown settings key {key:?}");
+            continue;
+        }
+
+        let value_type = schema.key(key).value_type();
+        let variant = match value_type.as_str() {
+            "b" => value.as_bool().map(|v| v.to_variant()),
+            "i" => value
+                .as_i64()
+                .and_then(|v| i32::try_from(v).ok())
+                .map(|v| v.to_variant()),
+            "d" => value.as_f64().map(|v| v.to_variant()),
+            "s" => value.as_str().map(|v| v.to_variant()),
+            _ => value
+                .as_str()
+                .and_then(|v| glib::Variant::parse(Some(&value_type), v).ok()),
+        };
+
+        match variant {
+            Some(variant) if settings.set_value(key, &variant).is_ok() => count += 1,
+            _ => warn!("Skipping invalid value for settings key {key:?}: {value}"),
+        }
+    }
+
+    settings.apply();
+    Ok(count)
+}
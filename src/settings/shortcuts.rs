@@ -0,0 +1,98 @@
+// Shortwave - shortcuts.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use gtk::prelude::*;
+
+use crate::settings::{settings_manager, Key};
+
+/// Actions whose accelerators can be changed, with their default accelerators
+pub const DEFAULT_ACCELS: &[(&str, &[&str])] = &[
+    ("win.show-preferences", &["<primary>comma"]),
+    ("window.close", &["<primary>w"]),
+    ("app.quit", &["<primary>q"]),
+    ("win.search", &["<primary>f"]),
+    ("win.add-local-station", &[]),
+    ("win.enable-gadget-player", &[]),
+    ("win.disable-gadget-player", &[]),
+    ("player.toggle-playback", &["<primary>space"]),
+    ("player.stop-playback", &[]),
+    ("player.play-next", &[]),
+    ("player.play-something", &[]),
+    ("player.jump-to-live", &[]),
+    ("player.save-recent", &[]),
+    ("player.show-station-details", &[]),
+    ("win.show-display-window", &[]),
+];
+
+fn custom_accels() -> HashMap<String, Vec<String>> {
+    settings_manager::settings()
+        .value(&Key::Shortcuts.to_string())
+        .get()
+        .unwrap_or_default()
+}
+
+fn set_custom_accels(accels: HashMap<String, Vec<String>>) {
+    let settings = settings_manager::settings();
+    settings
+        .set_value(&Key::Shortcuts.to_string(), &accels.to_variant())
+        .unwrap();
+}
+
+/// Returns the accelerators of an action, the custom ones if they have been changed
+pub fn accels(action: &str) -> Vec<String> {
+    if let Some(accels) = custom_accels().remove(action) {
+        return accels;
+    }
+
+    DEFAULT_ACCELS
+        .iter()
+        .find(|(a, _)| *a == action)
+        .map(|(_, accels)| accels.iter().map(|a| a.to_string()).collect())
+        .unwrap_or_default()
+}
+
+pub fn is_customized(action: &str) -> bool {
+    custom_accels().contains_key(action)
+}
+
+/// Stores custom accelerators for an action, an empty list disables the shortcut
+pub fn set_accels(action: &str, accels: Vec<String>) {
+    let mut custom = custom_accels();
+    custom.insert(action.to_string(), accels);
+    set_custom_accels(custom);
+}
+
+/// Restores the default accelerators of an action
+pub fn reset(action: &str) {
+    let mut custom = custom_accels();
+    custom.remove(action);
+    set_custom_accels(custom);
+}
+
+pub fn reset_all() {
+    set_custom_accels(HashMap::new());
+}
+
+/// Applies the current accelerators of all actions to the application
+pub fn apply(app: &impl IsA<gtk::Application>) {
+    for (action, _) in DEFAULT_ACCELS {
+        let accels = accels(action);
+        let accels: Vec<&str> = accels.iter().map(String::as_str).collect();
+        app.set_accels_for_action(action, &accels);
+    }
+}
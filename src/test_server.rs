@@ -0,0 +1,329 @@
+// Shortwave - test_server.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Local HTTP servers for tests, which stand in for radio-browser, Icecast
+//! streams and DLNA renderers
+
+use std::io::{self, Cursor, Read};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use tiny_http::{Header, Response, Server, StatusCode};
+
+/// Amount of audio data between two ICY metadata blocks
+pub const ICY_METAINT: usize = 8192;
+
+/// Number of metadata intervals for which a title gets announced, about three
+/// seconds of audio
+const BLOCKS_PER_TITLE: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct TestRequest {
+    pub method: String,
+    pub path: String,
+    /// Header names are lowercase
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl TestRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub struct TestResponse {
+    status: u16,
+    headers: Vec<Header>,
+    body: Box<dyn Read + Send>,
+    length: Option<usize>,
+}
+
+impl TestResponse {
+    pub fn new(body: impl Into<Vec<u8>>) -> Self {
+        let body = body.into();
+        Self {
+            status: 200,
+            headers: Vec::new(),
+            length: Some(body.len()),
+            body: Box::new(Cursor::new(body)),
+        }
+    }
+
+    /// Response with a body of unknown length, e.g. an endless stream
+    pub fn stream(body: impl Read + Send + 'static) -> Self {
+        Self {
+            status: 200,
+            headers: Vec::new(),
+            length: None,
+            body: Box::new(body),
+        }
+    }
+
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers
+            .push(Header::from_bytes(name, value).expect("Invalid header"));
+        self
+    }
+}
+
+/// Answers every request with the response of the given function, until it gets dropped
+pub struct TestServer {
+    server: Arc<Server>,
+    requests: Arc<Mutex<Vec<TestRequest>>>,
+    thread_handle: Option<JoinHandle<()>>,
+}
+
+impl TestServer {
+    pub fn start<F>(respond: F) -> Self
+    where
+        F: Fn(&TestRequest) -> TestResponse + Send + Sync + 'static,
+    {
+        let server = Arc::new(Server::http("127.0.0.1:0").expect("Unable to start test server"));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let handle = thread::spawn({
+            let server = server.clone();
+            let requests = requests.clone();
+            let respond = Arc::new(respond);
+
+            move || {
+                for mut request in server.incoming_requests() {
+                    let mut body = String::new();
+                    let _ = request.as_reader().read_to_string(&mut body);
+
+                    let test_request = TestRequest {
+                        method: request.method().to_string(),
+                        path: request.url().to_string(),
+                        headers: request
+                            .headers()
+                            .iter()
+                            .map(|header| {
+                                let name = header.field.as_str().as_str().to_lowercase();
+                                (name, header.value.as_str().to_string())
+                            })
+                            .collect(),
+                        body,
+                    };
+                    requests.lock().unwrap().push(test_request.clone());
+
+                    // Streams don't end on their own, so they must not hold up other requests
+                    let respond = respond.clone();
+                    thread::spawn(move || {
+                        let response = respond(&test_request);
+                        let response = Response::new(
+                            StatusCode(response.status),
+                            response.headers,
+                            response.body,
+                            response.length,
+                            None,
+                        );
+                        let _ = request.respond(response);
+                    });
+                }
+            }
+        });
+
+        Self {
+            server,
+            requests,
+            thread_handle: Some(handle),
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        let address = self.server.server_addr().to_ip().unwrap();
+        format!("http://{address}{path}")
+    }
+
+    /// All requests which have been received so far
+    pub fn requests(&self) -> Vec<TestRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Stub DLNA renderer, which serves its device description at `/description.xml`
+/// and answers the actions of its services with the given volume and mute state
+pub fn dlna_renderer(volume: u32, muted: bool) -> TestServer {
+    TestServer::start(move |request| {
+        if request.path == "/description.xml" {
+            return TestResponse::new(RENDERER_DESCRIPTION).header("Content-Type", "text/xml");
+        }
+
+        // SOAPAction: "urn:schemas-upnp-org:service:RenderingControl:1#GetVolume"
+        let action = request
+            .header("soapaction")
+            .and_then(|action| action.trim_matches('"').split_once('#'))
+            .map(|(_, action)| action);
+
+        let arguments = match (request.path.as_str(), action) {
+            ("/RenderingControl/control", Some("GetVolume")) => {
+                format!("<CurrentVolume>{volume}</CurrentVolume>")
+            }
+            ("/RenderingControl/control", Some("GetMute")) => {
+                format!("<CurrentMute>{}</CurrentMute>", u8::from(muted))
+            }
+            ("/AVTransport/control", Some(_)) => String::new(),
+            _ => return TestResponse::new("Invalid Action").status(500),
+        };
+
+        let response = format!(
+            r#"<?xml version="1.0"?>
+<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/">
+<s:Body><u:{0}Response>{arguments}</u:{0}Response></s:Body>
+</s:Envelope>"#,
+            action.unwrap_or_default()
+        );
+        TestResponse::new(response).header("Content-Type", "text/xml; charset=\"utf-8\"")
+    })
+}
+
+const RENDERER_DESCRIPTION: &str = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <specVersion><major>1</major><minor>0</minor></specVersion>
+  <device>
+    <deviceType>urn:schemas-upnp-org:device:MediaRenderer:1</deviceType>
+    <friendlyName>Living Room</friendlyName>
+    <manufacturer>Test Audio</manufacturer>
+    <modelName>Renderer 1</modelName>
+    <UDN>uuid:5f9ec1b3-ed59-4f4b-8b8e-4f3a1c0ad001</UDN>
+    <iconList>
+      <icon><mimetype>image/bmp</mimetype><url>/icon.bmp</url></icon>
+      <icon><mimetype>image/png</mimetype><url>/icon.png</url></icon>
+    </iconList>
+    <serviceList>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:AVTransport:1</serviceType>
+        <controlURL>
+          /AVTransport/control
+        </controlURL>
+      </service>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:RenderingControl:1</serviceType>
+        <controlURL>/RenderingControl/control</controlURL>
+      </service>
+    </serviceList>
+    <deviceList>
+      <device>
+        <deviceType>urn:schemas-upnp-org:device:Embedded:1</deviceType>
+        <friendlyName>Embedded Device</friendlyName>
+      </device>
+    </deviceList>
+  </device>
+</root>"#;
+
+/// Endless Icecast stream of silent WAV audio, which announces the given titles
+/// one after another in its ICY metadata. The last title stays once all got announced.
+pub struct IcecastStream {
+    titles: Vec<String>,
+    /// Whether the client asked for ICY metadata
+    metadata: bool,
+    block: usize,
+    announced: Option<usize>,
+    pending: Cursor<Vec<u8>>,
+}
+
+impl IcecastStream {
+    pub fn response(request: &TestRequest, titles: &[&str]) -> TestResponse {
+        let metadata = request.header("icy-metadata") == Some("1");
+        let stream = Self {
+            titles: titles.iter().map(|title| title.to_string()).collect(),
+            metadata,
+            block: 0,
+            announced: None,
+            pending: Cursor::default(),
+        };
+
+        let response = TestResponse::stream(stream).header("Content-Type", "audio/x-wav");
+        if metadata {
+            response.header("icy-metaint", &ICY_METAINT.to_string())
+        } else {
+            response
+        }
+    }
+
+    fn next_block(&mut self) -> Vec<u8> {
+        let mut block = vec![0; ICY_METAINT];
+        if self.block == 0 {
+            block[..44].copy_from_slice(&Self::wav_header());
+        }
+
+        if self.metadata {
+            let index = (self.block / BLOCKS_PER_TITLE).min(self.titles.len().saturating_sub(1));
+
+            // Like Icecast, only send the title again once it changed
+            match self.titles.get(index) {
+                Some(title) if self.announced != Some(index) => {
+                    let mut metadata = format!("StreamTitle='{title}';").into_bytes();
+                    metadata.resize(metadata.len().div_ceil(16) * 16, 0);
+
+                    block.push((metadata.len() / 16) as u8);
+                    block.extend(metadata);
+                    self.announced = Some(index);
+                }
+                _ => block.push(0),
+            }
+        }
+
+        self.block += 1;
+        block
+    }
+
+    /// Header of 44.1 kHz, 16 bit stereo PCM data, with the maximum length
+    /// since the stream doesn't end
+    fn wav_header() -> Vec<u8> {
+        let mut header = Vec::with_capacity(44);
+        header.extend(b"RIFF");
+        header.extend(u32::MAX.to_le_bytes());
+        header.extend(b"WAVEfmt ");
+        header.extend(16u32.to_le_bytes());
+        header.extend(1u16.to_le_bytes());
+        header.extend(2u16.to_le_bytes());
+        header.extend(44_100u32.to_le_bytes());
+        header.extend((44_100u32 * 4).to_le_bytes());
+        header.extend(4u16.to_le_bytes());
+        header.extend(16u16.to_le_bytes());
+        header.extend(b"data");
+        header.extend(u32::MAX.to_le_bytes());
+        header
+    }
+}
+
+impl Read for IcecastStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pending.position() as usize >= self.pending.get_ref().len() {
+            self.pending = Cursor::new(self.next_block());
+        }
+        self.pending.read(buf)
+    }
+}
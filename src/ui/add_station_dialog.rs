@@ -14,6 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::cell::{Cell, RefCell};
+use std::time::Duration;
+
 use adw::prelude::*;
 use adw::subclass::prelude::*;
 use glib::{clone, subclass, Properties};
@@ -21,10 +24,14 @@ use gtk::{gdk, gio, glib, CompositeTemplate};
 use url::Url;
 use uuid::Uuid;
 
-use crate::api::{StationMetadata, SwStation};
+use crate::api::{client, StationMetadata, StreamInfo, SwStation};
 use crate::app::SwApplication;
-use crate::i18n::i18n;
-use crate::ui::SwStationCover;
+use crate::database::StationCredentials;
+use crate::i18n::{i18n, i18n_f};
+use crate::ui::{DisplayError, SwStationCover};
+
+/// Time to wait after the last change of the URL before the stream gets checked
+const PROBE_DELAY: Duration = Duration::from_millis(500);
 
 mod imp {
     use super::*;
@@ -40,12 +47,28 @@ mod imp {
         #[template_child]
         remove_cover_button: TemplateChild<gtk::Button>,
         #[template_child]
+        stream_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
         name_row: TemplateChild<adw::EntryRow>,
         #[template_child]
         url_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        url_spinner: TemplateChild<adw::Spinner>,
+        #[template_child]
+        username_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        password_row: TemplateChild<adw::PasswordEntryRow>,
 
         #[property(get)]
         station: SwStation,
+
+        url: RefCell<Option<Url>>,
+        /// Information about the stream, only available once it has been verified
+        stream_info: RefCell<Option<StreamInfo>>,
+        favicon: RefCell<Option<Url>>,
+        /// Name which has been filled in automatically, and not by the user
+        autofilled_name: RefCell<String>,
+        probe_generation: Cell<u32>,
     }
 
     #[glib::object_subclass]
@@ -63,9 +86,18 @@ mod imp {
                 add_button: TemplateChild::default(),
                 station_cover: TemplateChild::default(),
                 remove_cover_button: TemplateChild::default(),
+                stream_group: TemplateChild::default(),
                 name_row: TemplateChild::default(),
                 url_row: TemplateChild::default(),
+                url_spinner: TemplateChild::default(),
+                username_row: TemplateChild::default(),
+                password_row: TemplateChild::default(),
                 station,
+                url: RefCell::default(),
+                stream_info: RefCell::default(),
+                favicon: RefCell::default(),
+                autofilled_name: RefCell::default(),
+                probe_generation: Cell::default(),
             }
         }
 
@@ -132,37 +164,157 @@ mod imp {
         }
 
         #[template_callback]
-        fn add_station(&self) {
-            SwApplication::default()
-                .library()
-                .add_station(self.obj().station());
+        async fn add_station(&self) {
+            let station = self.obj().station();
+            let username = self.username_row.text();
+            let password = self.password_row.text();
 
+            if !username.trim().is_empty() || !password.is_empty() {
+                StationCredentials::update(
+                    &station.uuid(),
+                    &station.title(),
+                    username.trim(),
+                    &password,
+                )
+                .await
+                .handle_error(i18n("Unable to store station credentials"));
+            }
+
+            SwApplication::default().library().add_station(station);
+            self.obj().close();
+        }
+
+        #[template_callback]
+        fn import_from_file(&self) {
+            let window = SwApplication::default().application_window();
             self.obj().close();
+            window
+                .activate_action("win.import-local-stations", None)
+                .unwrap();
         }
 
         #[template_callback]
         fn update_metadata(&self) {
             let name = self.name_row.text().to_string();
-            let has_name = !name.is_empty();
-            let url = Url::parse(&self.url_row.text()).ok();
+            let url = Url::parse(self.url_row.text().trim()).ok();
 
-            match url {
-                Some(_) => {
-                    self.url_row.remove_css_class("error");
-                    self.add_button.set_sensitive(has_name);
-                }
-                None => {
-                    self.url_row.add_css_class("error");
-                    self.add_button.set_sensitive(false);
-                }
+            if *self.url.borrow() != url {
+                *self.url.borrow_mut() = url.clone();
+                self.schedule_probe();
             }
 
+            let info = self.stream_info.borrow().clone().unwrap_or_default();
             let metadata = StationMetadata {
                 name,
                 url,
+                tags: info.genre.clone().unwrap_or_default(),
+                codec: info.codec().unwrap_or_default(),
+                bitrate: info.bitrate.unwrap_or_default(),
+                homepage: info.homepage,
+                favicon: self.favicon.borrow().clone(),
                 ..Default::default()
             };
             self.obj().station().set_metadata(metadata);
+
+            // Only streams which are verified to work can be added
+            let has_name = !self.name_row.text().is_empty();
+            let is_verified = self.stream_info.borrow().is_some();
+            self.add_button.set_sensitive(has_name && is_verified);
+        }
+
+        #[template_callback]
+        fn update_credentials(&self) {
+            // Protected streams can only be verified with the right credentials
+            self.schedule_probe();
+            self.update_metadata();
+        }
+
+        fn schedule_probe(&self) {
+            let generation = self.probe_generation.get().wrapping_add(1);
+            self.probe_generation.set(generation);
+
+            *self.stream_info.borrow_mut() = None;
+            *self.favicon.borrow_mut() = None;
+            self.url_spinner.set_visible(false);
+            self.stream_group.set_description(None);
+
+            let url = self.url.borrow().clone();
+            match url {
+                Some(url) => {
+                    self.url_row.remove_css_class("error");
+
+                    glib::spawn_future_local(clone!(
+                        #[weak(rename_to = imp)]
+                        self,
+                        async move {
+                            imp.probe_stream(url, generation).await;
+                        }
+                    ));
+                }
+                None => self.url_row.add_css_class("error"),
+            }
+        }
+
+        /// Checks whether the stream works, and fills in the information it announces
+        async fn probe_stream(&self, url: Url, generation: u32) {
+            // Wait until the URL / credentials have been entered completely
+            glib::timeout_future(PROBE_DELAY).await;
+            if self.probe_generation.get() != generation {
+                return;
+            }
+
+            self.url_spinner.set_visible(true);
+            self.stream_group
+                .set_description(Some(&i18n("Checking stream…")));
+
+            let username = self.username_row.text().trim().to_string();
+            let password = self.password_row.text().to_string();
+            let credentials = (!username.is_empty() || !password.is_empty())
+                .then_some(StationCredentials { username, password });
+
+            let res = client::probe_stream(&url, credentials.as_ref()).await;
+            let favicon = match &res {
+                Ok(info) => client::favicon_url(info.homepage.as_ref().unwrap_or(&url)).await,
+                Err(_) => None,
+            };
+
+            // The URL or credentials could have been changed in the meantime
+            if self.probe_generation.get() != generation {
+                return;
+            }
+            self.url_spinner.set_visible(false);
+
+            match res {
+                Ok(info) => {
+                    debug!("Verified stream {url}: {info:?}");
+                    self.stream_group
+                        .set_description(Some(&i18n("The stream is working")));
+
+                    let name = info.name.clone();
+                    *self.stream_info.borrow_mut() = Some(info);
+                    *self.favicon.borrow_mut() = favicon;
+
+                    // Don't overwrite a name which has been entered by the user
+                    let current_name = self.name_row.text();
+                    if let Some(name) = name {
+                        if current_name.is_empty() || current_name == *self.autofilled_name.borrow()
+                        {
+                            *self.autofilled_name.borrow_mut() = name.clone();
+                            self.name_row.set_text(&name);
+                        }
+                    }
+                }
+                Err(err) => {
+                    warn!("Unable to verify stream {url}: {err}");
+                    self.url_row.add_css_class("error");
+                    self.stream_group.set_description(Some(&i18n_f(
+                        "Unable to play stream: {}",
+                        &[&err.to_string()],
+                    )));
+                }
+            }
+
+            self.update_metadata();
         }
     }
 }
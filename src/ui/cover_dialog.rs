@@ -0,0 +1,214 @@
+// Shortwave - cover_dialog.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::OnceCell;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::{clone, subclass, Properties};
+use gtk::{gdk, glib, CompositeTemplate};
+
+use crate::api::SwStation;
+use crate::app::SwApplication;
+use crate::i18n::*;
+use crate::ui::SwStationCover;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate, Properties)]
+    #[template(resource = "/de/haeckerfelix/Shortwave/gtk/cover_dialog.ui")]
+    #[properties(wrapper_type = super::SwCoverDialog)]
+    pub struct SwCoverDialog {
+        #[template_child]
+        toast_overlay: TemplateChild<adw::ToastOverlay>,
+        #[template_child]
+        stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        picture: TemplateChild<gtk::Picture>,
+        #[template_child]
+        station_cover: TemplateChild<SwStationCover>,
+        #[template_child]
+        source_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        copy_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        size_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        custom_cover_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        remove_cover_button: TemplateChild<gtk::Button>,
+
+        #[property(get, set, construct_only)]
+        station: OnceCell<SwStation>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwCoverDialog {
+        const NAME: &'static str = "SwCoverDialog";
+        type ParentType = adw::Dialog;
+        type Type = super::SwCoverDialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+            Self::bind_template_callbacks(klass);
+        }
+
+        fn instance_init(obj: &subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for SwCoverDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.obj()
+                .bind_property("station", &*self.station_cover, "station")
+                .sync_create()
+                .build();
+
+            // Custom covers can only be stored for stations in the library
+            let library = SwApplication::default().library();
+            self.custom_cover_box
+                .set_visible(library.contains_station(&self.obj().station()));
+
+            self.update();
+        }
+    }
+
+    impl WidgetImpl for SwCoverDialog {}
+
+    impl AdwDialogImpl for SwCoverDialog {}
+
+    #[gtk::template_callbacks]
+    impl SwCoverDialog {
+        fn update(&self) {
+            let station = self.obj().station();
+            let custom_cover = station.custom_cover();
+            let favicon_url = station.metadata().favicon;
+
+            self.remove_cover_button.set_visible(custom_cover.is_some());
+            self.copy_button
+                .set_visible(custom_cover.is_none() && favicon_url.is_some());
+
+            if let Some(texture) = custom_cover {
+                self.source_row.set_subtitle(&i18n("Custom Cover"));
+                self.show_texture(&texture);
+            } else if let Some(favicon_url) = favicon_url {
+                self.source_row.set_subtitle(favicon_url.as_str());
+                self.size_row.set_visible(false);
+                self.stack.set_visible_child_name("loading");
+
+                glib::spawn_future_local(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    async move {
+                        let cover_loader = SwApplication::default().cover_loader();
+                        match cover_loader.load_original_cover(&favicon_url).await {
+                            Ok(texture) => imp.show_texture(&texture),
+                            Err(err) => {
+                                warn!("Unable to load original cover {favicon_url}: {err}");
+                                imp.stack.set_visible_child_name("cover");
+                            }
+                        }
+                    }
+                ));
+            } else {
+                self.source_row.set_subtitle(&i18n("No Cover Available"));
+                self.size_row.set_visible(false);
+                self.stack.set_visible_child_name("cover");
+            }
+        }
+
+        fn show_texture(&self, texture: &gdk::Texture) {
+            self.picture.set_paintable(Some(texture));
+            self.stack.set_visible_child_name("picture");
+
+            let size = format!("{} × {}", texture.width(), texture.height());
+            self.size_row.set_subtitle(&i18n_f("{} px", &[&size]));
+            self.size_row.set_visible(true);
+        }
+
+        fn set_custom_cover(&self, texture: Option<gdk::Texture>) {
+            let station = self.obj().station();
+            station.set_custom_cover(texture.as_ref());
+
+            // The dialog might show a copy of the station, so update the library one as well
+            let library = SwApplication::default().library();
+            if let Some(library_station) = library.station_by_uuid(&station.uuid()) {
+                library_station.set_custom_cover(texture.as_ref());
+                library.update_station(&library_station);
+            }
+
+            self.update();
+        }
+
+        #[template_callback]
+        fn copy_source(&self) {
+            if let Some(favicon_url) = self.obj().station().metadata().favicon {
+                self.obj().clipboard().set_text(favicon_url.as_str());
+
+                let toast = adw::Toast::new(&i18n("Copied"));
+                self.toast_overlay.add_toast(toast);
+            }
+        }
+
+        #[template_callback]
+        async fn select_cover_file(&self) {
+            let dialog = gtk::FileDialog::new();
+            dialog.set_title(&i18n("Select Station Cover"));
+
+            let parent = self
+                .obj()
+                .root()
+                .unwrap()
+                .downcast::<gtk::Window>()
+                .unwrap();
+
+            let Ok(file) = dialog.open_future(Some(&parent)).await else {
+                return;
+            };
+
+            match gdk::Texture::from_file(&file) {
+                Ok(texture) => self.set_custom_cover(Some(texture)),
+                Err(err) => {
+                    error!("Unable to open cover file: {}", err.to_string());
+                    let toast = adw::Toast::new(&i18n("Unable to open cover file"));
+                    self.toast_overlay.add_toast(toast);
+                }
+            }
+        }
+
+        #[template_callback]
+        fn remove_cover(&self) {
+            self.set_custom_cover(None);
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct SwCoverDialog(ObjectSubclass<imp::SwCoverDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl SwCoverDialog {
+    pub fn new(station: &SwStation) -> Self {
+        glib::Object::builder().property("station", station).build()
+    }
+}
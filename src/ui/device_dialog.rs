@@ -23,7 +23,8 @@ use gtk::{glib, CompositeTemplate};
 
 use crate::app::SwApplication;
 use crate::audio::SwPlayer;
-use crate::device::SwDevice;
+use crate::device::{DeviceError, SwDevice};
+use crate::i18n::i18n;
 use crate::ui::SwDeviceRow;
 
 mod imp {
@@ -158,6 +159,19 @@ impl SwDeviceDialog {
     pub fn new() -> Self {
         glib::Object::new()
     }
+
+    /// Explains why connecting to a device failed, returns whether it should be tried again
+    pub async fn ask_retry(&self, error: &DeviceError) -> bool {
+        let dialog = adw::AlertDialog::new(Some(&error.title()), Some(&error.description()));
+
+        dialog.add_response("close", &i18n("_Close"));
+        dialog.add_response("retry", &i18n("_Retry"));
+        dialog.set_response_appearance("retry", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("retry"));
+        dialog.set_close_response("close");
+
+        dialog.choose_future(Some(self)).await == "retry"
+    }
 }
 
 
@@ -21,11 +21,11 @@ use adw::subclass::prelude::*;
 use glib::clone;
 use glib::subclass;
 use glib::Properties;
-use gtk::{glib, CompositeTemplate};
+use gtk::{gio, glib, CompositeTemplate};
+use url::Url;
 
 use crate::app::SwApplication;
 use crate::device::SwDevice;
-use crate::ui::DisplayError;
 use crate::ui::SwDeviceDialog;
 
 mod imp {
@@ -35,6 +35,8 @@ mod imp {
     #[template(resource = "/de/haeckerfelix/Shortwave/gtk/device_row.ui")]
     #[properties(wrapper_type = super::SwDeviceRow)]
     pub struct SwDeviceRow {
+        #[template_child]
+        pub icon: TemplateChild<gtk::Image>,
         #[template_child]
         pub spinner: TemplateChild<adw::Spinner>,
         #[property(get, set, construct_only)]
@@ -67,10 +69,34 @@ mod imp {
                 .bind_property("name", &*self.obj(), "title")
                 .sync_create()
                 .build();
-            device
-                .bind_property("model", &*self.obj(), "subtitle")
-                .sync_create()
-                .build();
+            device.connect_manufacturer_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update_subtitle()
+            ));
+            device.connect_model_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update_subtitle()
+            ));
+            self.update_subtitle();
+
+            device.connect_icon_url_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| {
+                    glib::spawn_future_local(clone!(
+                        #[weak]
+                        imp,
+                        async move { imp.load_icon().await }
+                    ));
+                }
+            ));
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                async move { imp.load_icon().await }
+            ));
 
             self.obj().connect_activated(clone!(
                 #[weak(rename_to = imp)]
@@ -79,50 +105,7 @@ mod imp {
                     glib::spawn_future_local(clone!(
                         #[weak]
                         imp,
-                        async move {
-                            let device = imp.obj().device();
-                            let dialog: SwDeviceDialog = imp
-                                .obj()
-                                .ancestor(SwDeviceDialog::static_type())
-                                .unwrap()
-                                .downcast()
-                                .unwrap();
-
-                            dialog.set_sensitive(false);
-                            imp.spinner.set_visible(true);
-
-                            let res = SwApplication::default()
-                                .player()
-                                .connect_device(&device)
-                                .await;
-                            
-                            // Check if this is a Cast compatibility error
-                            if let Err(ref e) = res {
-                                let error_str = e.to_string();
-                                let is_compatibility_error = error_str.contains("Invalid Request")
-                                    || error_str.contains("Media Channel Error");
-                                
-                                if is_compatibility_error && device.kind() == crate::device::SwDeviceKind::Cast {
-                                    if let Some(station) = SwApplication::default().player().station() {
-                                        let station_name = station.title();
-                                        let error_msg = format!("\"{}\" Check if this is a Cast compatibility error 6", station_name);
-                                        Err::<(), _>(std::io::Error::new(std::io::ErrorKind::Other, error_msg))
-                                            .handle_error("");
-                                    } else {
-                                        res.handle_error("Unable to connect with device");
-                                    }
-                                } else {
-                                    res.handle_error("Unable to connect with device");
-                                }
-                            }
-
-                            if res.is_ok() {
-                                dialog.close();
-                            } else {
-                                dialog.set_sensitive(true);
-                                imp.spinner.set_visible(false);
-                            }
-                        }
+                        async move { imp.connect_device().await }
                     ));
                 }
             ));
@@ -131,6 +114,75 @@ mod imp {
 
     impl WidgetImpl for SwDeviceRow {}
 
+    impl SwDeviceRow {
+        async fn connect_device(&self) {
+            let device = self.obj().device();
+            let dialog: SwDeviceDialog = self
+                .obj()
+                .ancestor(SwDeviceDialog::static_type())
+                .unwrap()
+                .downcast()
+                .unwrap();
+
+            loop {
+                dialog.set_sensitive(false);
+                self.spinner.set_visible(true);
+
+                let res = SwApplication::default()
+                    .player()
+                    .connect_device(&device)
+                    .await;
+
+                dialog.set_sensitive(true);
+                self.spinner.set_visible(false);
+
+                match res {
+                    Ok(()) => {
+                        dialog.close();
+                        break;
+                    }
+                    Err(err) => {
+                        warn!("Unable to connect with device {:?}: {}", device.name(), err);
+                        if !dialog.ask_retry(&err).await {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        fn update_subtitle(&self) {
+            let device = self.obj().device();
+            let manufacturer = device.manufacturer();
+            let model = device.model();
+
+            // Some devices already include the manufacturer in the model name
+            let subtitle = if manufacturer.is_empty() || model.starts_with(&manufacturer) {
+                model
+            } else {
+                format!("{manufacturer} {model}")
+            };
+            self.obj().set_subtitle(&subtitle);
+        }
+
+        async fn load_icon(&self) {
+            let Ok(icon_url) = Url::parse(&self.obj().device().icon_url()) else {
+                return;
+            };
+
+            let mut cover_loader = SwApplication::default().cover_loader();
+            let size = self.icon.pixel_size() * self.obj().scale_factor();
+            let res = cover_loader
+                .load_cover(&icon_url, size, gio::Cancellable::new(), &*self.obj())
+                .await;
+
+            match res {
+                Ok(texture) => self.icon.set_paintable(Some(&texture)),
+                Err(e) => debug!("Unable to load device icon {}: {}", icon_url, e),
+            }
+        }
+    }
+
     impl ListBoxRowImpl for SwDeviceRow {}
 
     impl PreferencesRowImpl for SwDeviceRow {}
@@ -0,0 +1,192 @@
+// Shortwave - diagnostics_dialog.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::subclass;
+use gtk::{glib, CompositeTemplate};
+
+use crate::api;
+use crate::app::SwApplication;
+use crate::config;
+use crate::i18n::*;
+use crate::logger;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/de/haeckerfelix/Shortwave/gtk/diagnostics_dialog.ui")]
+    pub struct SwDiagnosticsDialog {
+        #[template_child]
+        toast_overlay: TemplateChild<adw::ToastOverlay>,
+        #[template_child]
+        version_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        server_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        requests_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        playback_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        device_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        proxy_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        log_view: TemplateChild<gtk::TextView>,
+        #[template_child]
+        pipeline_view: TemplateChild<gtk::TextView>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwDiagnosticsDialog {
+        const NAME: &'static str = "SwDiagnosticsDialog";
+        type ParentType = adw::Dialog;
+        type Type = super::SwDiagnosticsDialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+            Self::bind_template_callbacks(klass);
+        }
+
+        fn instance_init(obj: &subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for SwDiagnosticsDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.update();
+        }
+    }
+
+    impl WidgetImpl for SwDiagnosticsDialog {}
+
+    impl AdwDialogImpl for SwDiagnosticsDialog {}
+
+    #[gtk::template_callbacks]
+    impl SwDiagnosticsDialog {
+        #[template_callback]
+        fn update(&self) {
+            let status = Self::status();
+            let unknown = i18n("Unknown");
+            let none = i18n("None");
+
+            self.version_row.set_subtitle(&status.version);
+            self.server_row
+                .set_subtitle(status.server.as_deref().unwrap_or(&unknown));
+            self.requests_row
+                .set_subtitle(status.requests.as_deref().unwrap_or(&none));
+            self.playback_row.set_subtitle(&status.playback);
+            self.device_row
+                .set_subtitle(status.device.as_deref().unwrap_or(&none));
+            self.proxy_row
+                .set_subtitle(status.proxy.as_deref().unwrap_or(&none));
+
+            self.log_view
+                .buffer()
+                .set_text(&logger::recent_lines().join("\n"));
+            self.pipeline_view
+                .buffer()
+                .set_text(&SwApplication::default().player().pipeline_graph());
+        }
+
+        #[template_callback]
+        fn copy_report(&self) {
+            let status = Self::status();
+            let report = format!(
+                "Version: {}\nAPI server: {}\nAPI requests: {}\nPlayback state: {}\nDevice: {}\n\
+                 Stream proxy: {}\n\nRecent log:\n{}\n\nPipeline graph:\n{}\n",
+                status.version,
+                status.server.unwrap_or_default(),
+                status.requests.unwrap_or_default(),
+                status.playback,
+                status.device.unwrap_or_default(),
+                status.proxy.unwrap_or_default(),
+                logger::recent_lines().join("\n"),
+                SwApplication::default().player().pipeline_graph(),
+            );
+
+            self.obj().clipboard().set_text(&report);
+
+            let toast = adw::Toast::new(&i18n("Copied"));
+            self.toast_overlay.add_toast(toast);
+        }
+
+        fn status() -> Status {
+            let app = SwApplication::default();
+            let player = app.player();
+
+            Status {
+                version: format!("{} ({})", config::VERSION, config::PROFILE),
+                server: app.rb_server(),
+                requests: Self::request_summary(),
+                playback: player.state().to_string(),
+                device: player
+                    .device()
+                    .map(|d| format!("{} ({}, {})", d.name(), d.kind(), d.address())),
+                proxy: player.device_proxy_url(),
+            }
+        }
+
+        fn request_summary() -> Option<String> {
+            let stats = api::request_stats();
+            let average = stats.average()?;
+
+            let mut summary = format!(
+                "{} sent, {} failed, average {} ms, slowest {} ms",
+                stats.count,
+                stats.failed,
+                average.as_millis(),
+                stats.slowest.as_millis()
+            );
+            if let Some(version) = stats.last_version {
+                summary.push_str(&format!(", {version}"));
+            }
+
+            Some(summary)
+        }
+    }
+
+    /// Untranslated, since it mainly ends up in bug reports
+    struct Status {
+        version: String,
+        server: Option<String>,
+        requests: Option<String>,
+        playback: String,
+        device: Option<String>,
+        proxy: Option<String>,
+    }
+}
+
+glib::wrapper! {
+    pub struct SwDiagnosticsDialog(ObjectSubclass<imp::SwDiagnosticsDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl SwDiagnosticsDialog {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl Default for SwDiagnosticsDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -17,19 +17,31 @@
 // Original source: Key Rack / Sophie Herold
 // https://gitlab.gnome.org/sophie-h/key-rack/-/blob/9ca245815be2b81faa8cd028ed6030efe19d6832/src/utils/error.rs
 
+use std::any::Any;
 use std::fmt::Display;
 
 use adw::prelude::*;
 
 use crate::app::SwApplication;
+use crate::device::DeviceError;
 use crate::ui::SwApplicationWindow;
 
 pub trait DisplayError<E> {
     fn handle_error(&self, title: impl AsRef<str>);
     fn handle_error_in(&self, title: impl AsRef<str>, window: &SwApplicationWindow);
+    fn handle_error_toast(&self, title: impl AsRef<str>);
 }
 
-impl<E: Display, T> DisplayError<E> for Result<T, E> {
+/// Device errors come with an explanation what the user can do about them,
+/// which is more helpful than the raw error message
+fn error_message<E: Display + 'static>(err: &E) -> String {
+    match (err as &dyn Any).downcast_ref::<DeviceError>() {
+        Some(err) => err.description(),
+        None => err.to_string(),
+    }
+}
+
+impl<E: Display + 'static, T> DisplayError<E> for Result<T, E> {
     fn handle_error(&self, title: impl AsRef<str>) {
         if let Some(window) = SwApplication::default().active_window() {
             let window = window.downcast::<SwApplicationWindow>().unwrap();
@@ -42,7 +54,7 @@ impl<E: Display, T> DisplayError<E> for Result<T, E> {
             error!("{}: {err}", title.as_ref());
 
             let heading = title.as_ref().to_string();
-            let body = err.to_string();
+            let body = error_message(err);
             let transient_for = window.clone();
 
             // Show alert dialog directly without toast
@@ -55,4 +67,16 @@ impl<E: Display, T> DisplayError<E> for Result<T, E> {
             msg.present(Some(&transient_for));
         }
     }
+
+    fn handle_error_toast(&self, title: impl AsRef<str>) {
+        if let Err(err) = self {
+            error!("{}: {err}", title.as_ref());
+
+            if let Some(window) = SwApplication::default().active_window() {
+                let window = window.downcast::<SwApplicationWindow>().unwrap();
+                let message = error_message(err);
+                window.show_notification(&format!("{}: {message}", title.as_ref()));
+            }
+        }
+    }
 }
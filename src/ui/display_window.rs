@@ -0,0 +1,178 @@
+// Shortwave - display_window.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::{clone, subclass, Properties};
+use gtk::{gdk, glib, CompositeTemplate};
+
+use crate::app::SwApplication;
+use crate::audio::SwPlayer;
+use crate::ui::{SwMarqueeLabel, SwStationCover};
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate, Properties)]
+    #[template(resource = "/de/haeckerfelix/Shortwave/gtk/display_window.ui")]
+    #[properties(wrapper_type = super::SwDisplayWindow)]
+    pub struct SwDisplayWindow {
+        #[template_child]
+        toolbar_view: TemplateChild<adw::ToolbarView>,
+        #[template_child]
+        clock_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        station_cover: TemplateChild<SwStationCover>,
+
+        #[property(get=Self::player)]
+        player: PhantomData<SwPlayer>,
+
+        clock_source: RefCell<Option<glib::SourceId>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwDisplayWindow {
+        const NAME: &'static str = "SwDisplayWindow";
+        type ParentType = adw::Window;
+        type Type = super::SwDisplayWindow;
+
+        fn class_init(klass: &mut Self::Class) {
+            // Ensure that the label type is registered before the template gets used
+            SwMarqueeLabel::static_type();
+
+            Self::bind_template(klass);
+
+            klass.install_action("display.toggle-fullscreen", None, |win, _, _| {
+                win.set_fullscreened(!win.is_fullscreen());
+            });
+            klass.add_binding_action(
+                gdk::Key::F11,
+                gdk::ModifierType::empty(),
+                "display.toggle-fullscreen",
+            );
+            klass.add_binding_action(gdk::Key::Escape, gdk::ModifierType::empty(), "window.close");
+        }
+
+        fn instance_init(obj: &subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for SwDisplayWindow {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            // The header bar would only be distracting when shown on a dedicated screen
+            self.obj().connect_fullscreened_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |win| imp.toolbar_view.set_reveal_top_bars(!win.is_fullscreen())
+            ));
+
+            self.update_clock();
+            let source = glib::timeout_add_seconds_local(
+                1,
+                clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    #[upgrade_or]
+                    glib::ControlFlow::Break,
+                    move || {
+                        imp.update_clock();
+                        glib::ControlFlow::Continue
+                    }
+                ),
+            );
+            *self.clock_source.borrow_mut() = Some(source);
+        }
+
+        fn dispose(&self) {
+            if let Some(source) = self.clock_source.take() {
+                source.remove();
+            }
+        }
+    }
+
+    impl WidgetImpl for SwDisplayWindow {}
+
+    impl WindowImpl for SwDisplayWindow {}
+
+    impl AdwWindowImpl for SwDisplayWindow {}
+
+    impl SwDisplayWindow {
+        fn player(&self) -> SwPlayer {
+            SwApplication::default().player()
+        }
+
+        fn update_clock(&self) {
+            let time = glib::DateTime::now_local()
+                .and_then(|now| now.format("%R"))
+                .map(|time| time.to_string())
+                .unwrap_or_default();
+
+            if self.clock_label.label() != time {
+                self.clock_label.set_label(&time);
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    /// Window which only shows what's currently playing, meant to be shown on
+    /// another monitor
+    pub struct SwDisplayWindow(ObjectSubclass<imp::SwDisplayWindow>)
+        @extends gtk::Widget, gtk::Window, adw::Window,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl SwDisplayWindow {
+    pub fn new() -> Self {
+        glib::Object::builder()
+            .property("application", SwApplication::default())
+            .build()
+    }
+
+    /// Shows the window fullscreen on a monitor other than the one of `parent`,
+    /// if there is any
+    pub fn present_on_other_monitor(&self, parent: &impl IsA<gtk::Window>) {
+        let display = parent.display();
+        let current = parent
+            .surface()
+            .and_then(|surface| display.monitor_at_surface(&surface));
+
+        let other = display
+            .monitors()
+            .iter::<gdk::Monitor>()
+            .flatten()
+            .find(|monitor| Some(monitor) != current.as_ref());
+
+        if let Some(monitor) = other {
+            self.fullscreen_on_monitor(&monitor);
+        }
+
+        self.present();
+    }
+}
+
+impl Default for SwDisplayWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,157 @@
+// Shortwave - import_stations_dialog.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::{OnceCell, RefCell};
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::{clone, subclass};
+use gtk::{glib, CompositeTemplate};
+use url::Url;
+use uuid::Uuid;
+
+use crate::api::{PlaylistEntry, StationMetadata, SwStation};
+use crate::app::SwApplication;
+use crate::i18n::ni18n_f;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/de/haeckerfelix/Shortwave/gtk/import_stations_dialog.ui")]
+    pub struct SwImportStationsDialog {
+        #[template_child]
+        stations_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        add_button: TemplateChild<gtk::Button>,
+
+        pub entries: OnceCell<Vec<PlaylistEntry>>,
+        rows: RefCell<Vec<(gtk::CheckButton, adw::EntryRow)>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwImportStationsDialog {
+        const NAME: &'static str = "SwImportStationsDialog";
+        type ParentType = adw::Dialog;
+        type Type = super::SwImportStationsDialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+            Self::bind_template_callbacks(klass);
+        }
+
+        fn instance_init(obj: &subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for SwImportStationsDialog {}
+
+    impl WidgetImpl for SwImportStationsDialog {}
+
+    impl AdwDialogImpl for SwImportStationsDialog {}
+
+    #[gtk::template_callbacks]
+    impl SwImportStationsDialog {
+        pub fn add_rows(&self) {
+            for entry in self.entries.get().unwrap() {
+                let name = entry
+                    .name
+                    .clone()
+                    .or_else(|| entry.url.host_str().map(str::to_string))
+                    .unwrap_or_else(|| entry.url.to_string());
+
+                let row = adw::EntryRow::builder()
+                    .title(entry.url.as_str())
+                    .use_markup(false)
+                    .build();
+                row.set_text(&name);
+                let check_button = gtk::CheckButton::builder()
+                    .active(true)
+                    .valign(gtk::Align::Center)
+                    .build();
+                row.add_prefix(&check_button);
+
+                check_button.connect_active_notify(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move |_| imp.update_add_button()
+                ));
+                row.connect_text_notify(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move |_| imp.update_add_button()
+                ));
+
+                self.stations_group.add(&row);
+                self.rows.borrow_mut().push((check_button, row));
+            }
+
+            self.update_add_button();
+        }
+
+        /// Selected stations, the ones without name get skipped
+        fn selected(&self) -> Vec<(String, Url)> {
+            self.rows
+                .borrow()
+                .iter()
+                .zip(self.entries.get().unwrap())
+                .filter(|((check_button, _), _)| check_button.is_active())
+                .map(|((_, row), entry)| (row.text().trim().to_string(), entry.url.clone()))
+                .filter(|(name, _)| !name.is_empty())
+                .collect()
+        }
+
+        fn update_add_button(&self) {
+            let count = self.selected().len() as u32;
+            self.add_button.set_label(&ni18n_f(
+                "Add {} Station",
+                "Add {} Stations",
+                count,
+                &[&count.to_string()],
+            ));
+            self.add_button.set_sensitive(count > 0);
+        }
+
+        #[template_callback]
+        fn add_stations(&self) {
+            let library = SwApplication::default().library();
+
+            for (name, url) in self.selected() {
+                let uuid = Uuid::new_v4().to_string();
+                let metadata = StationMetadata::new(name, url);
+                library.add_station(SwStation::new(&uuid, true, metadata, None));
+            }
+
+            self.obj().close();
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct SwImportStationsDialog(ObjectSubclass<imp::SwImportStationsDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl SwImportStationsDialog {
+    pub fn new(entries: Vec<PlaylistEntry>) -> Self {
+        let dialog: Self = glib::Object::new();
+        dialog.imp().entries.set(entries).unwrap();
+        dialog.imp().add_rows();
+        dialog
+    }
+}
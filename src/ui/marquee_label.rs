@@ -0,0 +1,282 @@
+// Shortwave - marquee_label.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::{Cell, OnceCell, RefCell};
+use std::marker::PhantomData;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::{clone, Properties, SignalHandlerId};
+use gtk::graphene::Point;
+use gtk::{glib, gsk, pango};
+
+use crate::settings::{settings_manager, Key};
+
+mod imp {
+    use super::*;
+
+    /// Scrolling speed in pixels per second
+    const SPEED: f64 = 30.0;
+    /// How long the label rests at both ends, in microseconds
+    const PAUSE: i64 = 2_000_000;
+
+    #[derive(Debug, Default, Properties)]
+    #[properties(wrapper_type = super::SwMarqueeLabel)]
+    pub struct SwMarqueeLabel {
+        #[property(get = Self::label, set = Self::set_label)]
+        label: PhantomData<String>,
+        #[property(get = Self::xalign, set = Self::set_xalign)]
+        xalign: PhantomData<f32>,
+        #[property(get = Self::attributes, set = Self::set_attributes, nullable)]
+        attributes: PhantomData<Option<pango::AttrList>>,
+        /// Whether text which doesn't fit gets scrolled instead of ellipsized
+        #[property(get, set = Self::set_scrolling)]
+        scrolling: Cell<bool>,
+
+        child: OnceCell<gtk::Label>,
+        tick_id: RefCell<Option<gtk::TickCallbackId>>,
+        start_time: Cell<Option<i64>>,
+        /// How far the label has to be moved to show its end
+        distance: Cell<i32>,
+        offset: Cell<f64>,
+        animations_handler: RefCell<Option<SignalHandlerId>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwMarqueeLabel {
+        const NAME: &'static str = "SwMarqueeLabel";
+        type ParentType = gtk::Widget;
+        type Type = super::SwMarqueeLabel;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_css_name("marquee");
+            klass.set_accessible_role(gtk::AccessibleRole::Group);
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for SwMarqueeLabel {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().set_overflow(gtk::Overflow::Hidden);
+
+            let child = gtk::Label::builder()
+                .xalign(0.0)
+                .ellipsize(pango::EllipsizeMode::End)
+                .build();
+            child.set_parent(&*self.obj());
+            self.child.set(child).unwrap();
+
+            settings_manager::bind_property(
+                Key::AppearanceScrollingTitles,
+                &*self.obj(),
+                "scrolling",
+            );
+
+            // Respect the reduced motion preference
+            let handler = self
+                .obj()
+                .settings()
+                .connect_gtk_enable_animations_notify(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move |_| imp.update_ellipsize()
+                ));
+            *self.animations_handler.borrow_mut() = Some(handler);
+        }
+
+        fn dispose(&self) {
+            if let Some(handler) = self.animations_handler.take() {
+                self.obj().settings().disconnect(handler);
+            }
+
+            if let Some(child) = self.child.get() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for SwMarqueeLabel {
+        fn measure(&self, orientation: gtk::Orientation, _for_size: i32) -> (i32, i32, i32, i32) {
+            let (minimum, natural, minimum_baseline, natural_baseline) =
+                self.child().measure(orientation, -1);
+
+            if orientation == gtk::Orientation::Horizontal {
+                // The text either gets ellipsized or scrolled, so it can always shrink
+                (0, natural, -1, -1)
+            } else {
+                (minimum, natural, minimum_baseline, natural_baseline)
+            }
+        }
+
+        fn size_allocate(&self, width: i32, height: i32, baseline: i32) {
+            let child = self.child();
+            let (_, natural, _, _) = child.measure(gtk::Orientation::Horizontal, -1);
+            let distance = natural - width;
+
+            if self.is_scrolling() && distance > 0 {
+                self.distance.set(distance);
+                self.start();
+
+                // Right-to-left text starts at the end of the label, so it
+                // has to be scrolled in the opposite direction
+                let offset = if self.is_rtl() {
+                    self.offset.get() - distance as f64
+                } else {
+                    -self.offset.get()
+                };
+
+                let transform = gsk::Transform::new().translate(&Point::new(offset as f32, 0.0));
+                child.allocate(natural, height, baseline, Some(transform));
+            } else {
+                self.stop();
+                child.allocate(width, height, baseline, None);
+            }
+        }
+    }
+
+    impl SwMarqueeLabel {
+        fn child(&self) -> &gtk::Label {
+            self.child.get().unwrap()
+        }
+
+        fn label(&self) -> String {
+            self.child().label().to_string()
+        }
+
+        fn set_label(&self, label: String) {
+            if self.child().label() == label {
+                return;
+            }
+
+            // Start again from the beginning
+            self.start_time.set(None);
+            self.offset.set(0.0);
+
+            self.child().set_label(&label);
+            self.obj().notify_label();
+        }
+
+        fn xalign(&self) -> f32 {
+            self.child().xalign()
+        }
+
+        fn set_xalign(&self, xalign: f32) {
+            self.child().set_xalign(xalign);
+        }
+
+        fn attributes(&self) -> Option<pango::AttrList> {
+            self.child().attributes()
+        }
+
+        fn set_attributes(&self, attributes: Option<pango::AttrList>) {
+            self.child().set_attributes(attributes.as_ref());
+        }
+
+        fn set_scrolling(&self, scrolling: bool) {
+            self.scrolling.set(scrolling);
+            self.update_ellipsize();
+        }
+
+        fn is_rtl(&self) -> bool {
+            self.child().layout().direction(0) == pango::Direction::Rtl
+        }
+
+        fn is_scrolling(&self) -> bool {
+            self.scrolling.get() && self.obj().settings().is_gtk_enable_animations()
+        }
+
+        fn update_ellipsize(&self) {
+            let Some(child) = self.child.get() else {
+                return;
+            };
+
+            if self.is_scrolling() {
+                child.set_ellipsize(pango::EllipsizeMode::None);
+            } else {
+                child.set_ellipsize(pango::EllipsizeMode::End);
+            }
+
+            self.obj().queue_resize();
+        }
+
+        fn start(&self) {
+            if self.tick_id.borrow().is_some() {
+                return;
+            }
+
+            let tick_id = self.obj().add_tick_callback(|obj, frame_clock| {
+                obj.imp().tick(frame_clock.frame_time());
+                glib::ControlFlow::Continue
+            });
+            *self.tick_id.borrow_mut() = Some(tick_id);
+        }
+
+        fn stop(&self) {
+            if let Some(tick_id) = self.tick_id.take() {
+                tick_id.remove();
+            }
+
+            self.start_time.set(None);
+            self.offset.set(0.0);
+        }
+
+        /// Moves the label to the end, rests there, and moves it back again
+        fn tick(&self, now: i64) {
+            let start_time = self.start_time.get().unwrap_or(now);
+            self.start_time.set(Some(start_time));
+
+            let distance = self.distance.get() as f64;
+            let travel = ((distance / SPEED) * 1_000_000.0).max(1.0) as i64;
+            let elapsed = (now - start_time) % (2 * (PAUSE + travel));
+
+            let offset = if elapsed < PAUSE {
+                0.0
+            } else if elapsed < PAUSE + travel {
+                distance * (elapsed - PAUSE) as f64 / travel as f64
+            } else if elapsed < 2 * PAUSE + travel {
+                distance
+            } else {
+                distance * (1.0 - (elapsed - 2 * PAUSE - travel) as f64 / travel as f64)
+            };
+
+            if (offset - self.offset.get()).abs() >= 0.5 {
+                self.offset.set(offset);
+                self.obj().queue_allocate();
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    /// Label which scrolls text back and forth instead of ellipsizing it if
+    /// there isn't enough space available
+    pub struct SwMarqueeLabel(ObjectSubclass<imp::SwMarqueeLabel>)
+        @extends gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl SwMarqueeLabel {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl Default for SwMarqueeLabel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
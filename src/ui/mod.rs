@@ -20,33 +20,65 @@ pub mod search;
 
 pub mod about_dialog;
 mod add_station_dialog;
+mod cover_dialog;
+mod cover_shape;
 mod device_dialog;
 mod device_indicator;
 mod device_row;
+mod diagnostics_dialog;
 mod display_error;
+mod display_window;
+mod grid_density;
+mod import_stations_dialog;
+mod marquee_label;
 mod preferences_dialog;
+mod recorders_dialog;
 mod recording_indicator;
+mod recovery_dialog;
+mod rules_dialog;
 mod scalable_image;
+mod shortcuts_dialog;
+mod sparkline;
+mod station_comparison_dialog;
 mod station_cover;
 mod station_dialog;
 mod station_row;
 mod track_dialog;
+mod track_preview;
 mod track_row;
+mod trim_dialog;
 mod volume_control;
+mod waveform;
 mod window;
 
 pub use add_station_dialog::SwAddStationDialog;
+pub use cover_dialog::SwCoverDialog;
+pub use cover_shape::SwCoverShape;
 pub use device_dialog::SwDeviceDialog;
 pub use device_indicator::SwDeviceIndicator;
 pub use device_row::SwDeviceRow;
+pub use diagnostics_dialog::SwDiagnosticsDialog;
 pub use display_error::DisplayError;
+pub use display_window::SwDisplayWindow;
+pub use grid_density::SwGridDensity;
+pub use import_stations_dialog::SwImportStationsDialog;
+pub use marquee_label::SwMarqueeLabel;
 pub use preferences_dialog::SwPreferencesDialog;
+pub use recorders_dialog::SwRecordersDialog;
 pub use recording_indicator::SwRecordingIndicator;
+pub use recovery_dialog::SwRecoveryDialog;
+pub use rules_dialog::SwRulesDialog;
 pub use scalable_image::SwScalableImage;
+pub use shortcuts_dialog::SwShortcutsDialog;
+pub use sparkline::SwSparkline;
+pub use station_comparison_dialog::SwStationComparisonDialog;
 pub use station_cover::SwStationCover;
 pub use station_dialog::SwStationDialog;
 pub use station_row::SwStationRow;
 pub use track_dialog::SwTrackDialog;
+pub use track_preview::SwTrackPreview;
 pub use track_row::SwTrackRow;
+pub use trim_dialog::SwTrimDialog;
 pub use volume_control::SwVolumeControl;
+pub use waveform::SwWaveform;
 pub use window::SwApplicationWindow;
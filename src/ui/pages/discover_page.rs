@@ -0,0 +1,346 @@
+// Shortwave - discover_page.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use futures_util::future;
+use glib::{clone, subclass, Properties};
+use gtk::{glib, CompositeTemplate};
+use inflector::Inflector;
+
+use crate::api::{
+    client, Error, StationRequest, SwStation, SwStationModel, SwStationSorter, SwStationSorting,
+};
+use crate::app::SwApplication;
+use crate::database::SavedSearch;
+use crate::settings::{settings_manager, Key};
+use crate::ui::SwStationRow;
+use crate::utils;
+
+/// How many stations each section shows
+const SECTION_SIZE: usize = 20;
+/// How many of the most frequent tags of trending stations get listed
+const TRENDING_TAGS: usize = 5;
+
+mod imp {
+    use super::*;
+
+    #[derive(Default, Debug, Properties, CompositeTemplate)]
+    #[template(resource = "/de/haeckerfelix/Shortwave/gtk/discover_page.ui")]
+    #[properties(wrapper_type = super::SwDiscoverPage)]
+    pub struct SwDiscoverPage {
+        #[template_child]
+        stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        popular_listview: TemplateChild<gtk::ListView>,
+        #[template_child]
+        trending_listview: TemplateChild<gtk::ListView>,
+        #[template_child]
+        trending_tags_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        recent_listview: TemplateChild<gtk::ListView>,
+        #[template_child]
+        saved_searches_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        failure_statuspage: TemplateChild<adw::StatusPage>,
+
+        popular_model: SwStationModel,
+        trending_model: SwStationModel,
+        recent_model: SwStationModel,
+
+        loaded: Cell<bool>,
+
+        #[property(get, set=Self::set_hide_broken)]
+        hide_broken: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwDiscoverPage {
+        const NAME: &'static str = "SwDiscoverPage";
+        type ParentType = adw::NavigationPage;
+        type Type = super::SwDiscoverPage;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+            Self::bind_template_callbacks(klass);
+        }
+
+        fn instance_init(obj: &subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for SwDiscoverPage {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            // Ensure that row type is registered
+            SwStationRow::static_type();
+
+            let sections = [
+                (&*self.popular_listview, &self.popular_model),
+                (&*self.trending_listview, &self.trending_model),
+                (&*self.recent_listview, &self.recent_model),
+            ];
+
+            for (listview, model) in sections {
+                let model = gtk::NoSelection::new(Some(model.clone()));
+                listview.set_model(Some(&model));
+                listview.connect_activate(Self::station_activated);
+            }
+
+            settings_manager::bind_property(Key::StationsHideBroken, &*self.obj(), "hide-broken");
+            self.stack.set_visible_child_name("spinner");
+        }
+    }
+
+    impl WidgetImpl for SwDiscoverPage {
+        fn map(&self) {
+            self.parent_map();
+
+            // Saved searches get executed again every time, since they could
+            // have been changed in the meantime
+            let loaded = self.loaded.get();
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                async move {
+                    if loaded {
+                        imp.load_saved_searches().await;
+                    } else {
+                        imp.refresh().await;
+                    }
+                }
+            ));
+        }
+    }
+
+    impl NavigationPageImpl for SwDiscoverPage {}
+
+    #[gtk::template_callbacks]
+    impl SwDiscoverPage {
+        fn set_hide_broken(&self, hide_broken: bool) {
+            if self.hide_broken.replace(hide_broken) == hide_broken || !self.loaded.get() {
+                return;
+            }
+
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                async move {
+                    imp.refresh().await;
+                }
+            ));
+        }
+
+        #[template_callback]
+        async fn refresh(&self) {
+            self.stack.set_visible_child_name("spinner");
+
+            match self.load_sections().await {
+                Ok(()) => {
+                    self.loaded.set(true);
+                    self.stack.set_visible_child_name("content");
+                    self.load_saved_searches().await;
+                }
+                Err(e) => {
+                    self.stack.set_visible_child_name("failure");
+                    self.failure_statuspage
+                        .set_description(Some(&e.to_string()));
+                }
+            }
+        }
+
+        async fn load_sections(&self) -> Result<(), Error> {
+            debug!("Update discover sections...");
+
+            let popular = StationRequest {
+                limit: Some(100),
+                order: Some("votes".into()),
+                reverse: Some(true),
+                ..utils::recommendation_request()
+            };
+
+            let trending = StationRequest {
+                limit: Some(SECTION_SIZE as u32),
+                order: Some("clicktrend".into()),
+                reverse: Some(true),
+                ..Default::default()
+            };
+
+            // radio-browser doesn't expose when a station got added, but new
+            // stations are the ones which got changed most recently
+            let recent = StationRequest {
+                limit: Some(SECTION_SIZE as u32),
+                order: Some("changetimestamp".into()),
+                reverse: Some(true),
+                ..Default::default()
+            };
+
+            let (popular, trending, recent) = future::try_join3(
+                client::station_request(popular),
+                client::station_request(trending),
+                client::station_request(recent),
+            )
+            .await?;
+
+            // Anything more than 50k votes can be considered as botted spam
+            let popular = popular
+                .into_iter()
+                .filter(|s| s.metadata().votes < 50_000)
+                .take(SECTION_SIZE)
+                .collect();
+            self.popular_model.set_stations(popular);
+
+            self.trending_tags_label
+                .set_label(&Self::frequent_tags(&trending).join(" · "));
+            self.trending_tags_label
+                .set_visible(!self.trending_tags_label.label().is_empty());
+            self.trending_model.set_stations(trending);
+
+            self.recent_model.set_stations(recent);
+
+            Ok(())
+        }
+
+        /// Adds a section for each saved search which should be shown on this page
+        async fn load_saved_searches(&self) {
+            let searches: Vec<SavedSearch> = SavedSearch::all()
+                .into_iter()
+                .filter(|search| search.show_in_discover)
+                .collect();
+
+            let requests = searches.iter().map(|search| {
+                let request = StationRequest {
+                    limit: Some(SECTION_SIZE as u32),
+                    ..search.request.clone()
+                };
+                client::station_request(request)
+            });
+            let results = future::join_all(requests).await;
+
+            let location = if searches
+                .iter()
+                .any(|search| search.sorting == SwStationSorting::Nearest)
+            {
+                utils::current_location()
+                    .await
+                    .inspect_err(|e| warn!("Unable to determine location: {}", e))
+                    .ok()
+            } else {
+                None
+            };
+
+            while let Some(child) = self.saved_searches_box.first_child() {
+                self.saved_searches_box.remove(&child);
+            }
+
+            for (search, res) in searches.into_iter().zip(results) {
+                match res {
+                    Ok(stations) if !stations.is_empty() => {
+                        self.add_saved_search_section(&search, stations, location)
+                    }
+                    Ok(_) => (),
+                    Err(e) => warn!("Unable to load saved search {}: {}", search.name, e),
+                }
+            }
+        }
+
+        fn add_saved_search_section(
+            &self,
+            search: &SavedSearch,
+            stations: Vec<SwStation>,
+            location: Option<(f64, f64)>,
+        ) {
+            let label = gtk::Label::builder()
+                .label(&search.name)
+                .wrap(true)
+                .xalign(0.0)
+                .margin_top(12)
+                .css_classes(["heading"])
+                .build();
+
+            let model = SwStationModel::new();
+            model.set_stations(stations);
+
+            let sorter = SwStationSorter::new();
+            sorter.set_sorting(search.sorting);
+            sorter.set_sorting_type(search.sorting_type);
+            sorter.set_location(location);
+            let model = gtk::SortListModel::new(Some(model), Some(sorter));
+
+            // All carousels show the same rows
+            let listview = gtk::ListView::builder()
+                .orientation(gtk::Orientation::Horizontal)
+                .single_click_activate(true)
+                .model(&gtk::NoSelection::new(Some(model)))
+                .css_classes(["station-carousel"])
+                .build();
+            listview.set_factory(self.popular_listview.factory().as_ref());
+            listview.connect_activate(Self::station_activated);
+
+            let scrolled_window = gtk::ScrolledWindow::builder()
+                .vscrollbar_policy(gtk::PolicyType::Never)
+                .propagate_natural_height(true)
+                .child(&listview)
+                .build();
+
+            self.saved_searches_box.append(&label);
+            self.saved_searches_box.append(&scrolled_window);
+        }
+
+        fn station_activated(listview: &gtk::ListView, pos: u32) {
+            let model = listview.model().unwrap();
+            let station = model.item(pos).unwrap().downcast::<SwStation>().unwrap();
+
+            // Play the station directly when activated
+            glib::spawn_future_local(async move {
+                let player = SwApplication::default().player();
+                player.set_station(station).await;
+            });
+        }
+
+        /// The most frequently used tags of the given stations
+        fn frequent_tags(stations: &[SwStation]) -> Vec<String> {
+            let mut counts: HashMap<String, usize> = HashMap::new();
+            for station in stations {
+                for tag in station.metadata().tags.split(',') {
+                    let tag = tag.trim().to_lowercase();
+                    if !tag.is_empty() {
+                        *counts.entry(tag).or_default() += 1;
+                    }
+                }
+            }
+
+            let mut tags: Vec<_> = counts.into_iter().collect();
+            tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            tags.into_iter()
+                .take(TRENDING_TAGS)
+                .map(|(tag, _)| tag.to_title_case())
+                .collect()
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct SwDiscoverPage(ObjectSubclass<imp::SwDiscoverPage>)
+        @extends gtk::Widget, adw::NavigationPage,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
@@ -19,15 +19,17 @@ use std::cell::Cell;
 use adw::prelude::*;
 use adw::subclass::prelude::*;
 use glib::{clone, subclass, Properties};
-use gtk::{glib, CompositeTemplate};
+use gtk::{gdk, glib, CompositeTemplate};
 
-use crate::api::{SwStation, SwStationSorter, SwStationSorting, SwStationSortingType};
+use crate::api::{
+    SwStation, SwStationSectionSorter, SwStationSorter, SwStationSorting, SwStationSortingType,
+};
 use crate::app::SwApplication;
 use crate::config;
-use crate::database::SwLibraryStatus;
+use crate::database::{StationHistory, SwLibraryStatus};
 use crate::i18n::*;
 use crate::settings::{settings_manager, Key};
-use crate::ui::SwStationRow;
+use crate::ui::{SwGridDensity, SwStationRow};
 
 mod imp {
     use super::*;
@@ -39,14 +41,28 @@ mod imp {
         #[template_child]
         status_page: TemplateChild<adw::StatusPage>,
         #[template_child]
+        search_bar: TemplateChild<gtk::SearchBar>,
+        #[template_child]
+        search_entry: TemplateChild<gtk::SearchEntry>,
+        #[template_child]
         stack: TemplateChild<gtk::Stack>,
         #[template_child]
         pub(super) gridview: TemplateChild<gtk::GridView>,
+        /// Used instead of the grid when the stations are grouped into sections,
+        /// as [`gtk::GridView`] can't display section headers
+        #[template_child]
+        listview: TemplateChild<gtk::ListView>,
+
+        filter: gtk::StringFilter,
+        /// Groups the stations by the value they get sorted by
+        section_sorter: SwStationSectionSorter,
 
         #[property(get, set, builder(SwStationSorting::default()))]
         sorting: Cell<SwStationSorting>,
         #[property(get, set, builder(SwStationSortingType::Ascending))]
         sorting_type: Cell<SwStationSortingType>,
+        #[property(get, set=Self::set_density, builder(SwGridDensity::default()))]
+        density: Cell<SwGridDensity>,
     }
 
     #[glib::object_subclass]
@@ -57,6 +73,7 @@ mod imp {
 
         fn class_init(klass: &mut Self::Class) {
             Self::bind_template(klass);
+            Self::bind_template_callbacks(klass);
             klass.install_property_action("library.set-sorting", "sorting");
             klass.install_property_action("library.set-sorting-type", "sorting-type");
         }
@@ -74,6 +91,7 @@ mod imp {
 
             settings_manager::bind_property(Key::LibrarySorting, &*self.obj(), "sorting");
             settings_manager::bind_property(Key::LibrarySortingType, &*self.obj(), "sorting-type");
+            settings_manager::bind_property(Key::AppearanceGridDensity, &*self.obj(), "density");
 
             let sorter = SwStationSorter::new();
             self.obj()
@@ -86,7 +104,65 @@ mod imp {
                 .bidirectional()
                 .build();
 
-            let model = gtk::SortListModel::new(Some(library.model()), Some(sorter.clone()));
+            self.obj()
+                .bind_property("sorting", &self.section_sorter, "sorting")
+                .sync_create()
+                .build();
+            self.obj()
+                .bind_property("sorting-type", &self.section_sorter, "sorting-type")
+                .sync_create()
+                .build();
+            self.obj().connect_sorting_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update_stack_page()
+            ));
+
+            // The listening history changes while playing, so keep it up to date
+            self.obj().connect_sorting_notify(clone!(
+                #[weak]
+                sorter,
+                move |page| page.imp().update_play_stats(&sorter)
+            ));
+            self.obj().connect_map(clone!(
+                #[weak]
+                sorter,
+                move |page| page.imp().update_play_stats(&sorter)
+            ));
+
+            // Type-to-search filters the stations by their title
+            let expression = gtk::PropertyExpression::new(
+                SwStation::static_type(),
+                gtk::Expression::NONE,
+                "title",
+            );
+            self.filter.set_expression(Some(&expression));
+            self.filter.set_match_mode(gtk::StringFilterMatchMode::Substring);
+            self.filter.set_ignore_case(true);
+
+            let model = gtk::FilterListModel::new(Some(library.model()), Some(self.filter.clone()));
+            let model = gtk::SortListModel::new(Some(model), Some(sorter.clone()));
+            model.set_section_sorter(Some(&self.section_sorter));
+
+            self.search_bar.connect_entry(&*self.search_entry);
+            self.search_bar.set_key_capture_widget(Some(&*self.obj()));
+
+            let controller = gtk::EventControllerKey::new();
+            controller.connect_key_pressed(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                #[upgrade_or]
+                glib::Propagation::Proceed,
+                move |_, key, _, _| {
+                    if key == gdk::Key::Down {
+                        imp.focus_results();
+                        glib::Propagation::Stop
+                    } else {
+                        glib::Propagation::Proceed
+                    }
+                }
+            ));
+            self.search_entry.add_controller(controller);
 
             // Ensure that row type is registered
             SwStationRow::static_type();
@@ -94,16 +170,36 @@ mod imp {
             // Station grid view
             let model = gtk::NoSelection::new(Some(model));
             self.gridview.set_model(Some(&model));
-
             self.gridview.connect_activate(|gridview, pos| {
-                let model = gridview.model().unwrap();
-                let station = model.item(pos).unwrap().downcast::<SwStation>().unwrap();
-                
-                // Play the station directly when activated
-                glib::spawn_future_local(async move {
-                    let player = SwApplication::default().player();
-                    player.set_station(station).await;
-                });
+                Self::activate_station(&gridview.model().unwrap(), pos)
+            });
+
+            // Station list view with section headers
+            let header_factory = gtk::SignalListItemFactory::new();
+            header_factory.connect_setup(|_, header| {
+                let label = gtk::Label::builder()
+                    .xalign(0.0)
+                    .css_classes(["heading"])
+                    .build();
+                let header = header.downcast_ref::<gtk::ListHeader>().unwrap();
+                header.set_child(Some(&label));
+            });
+            header_factory.connect_bind(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_, header| {
+                    let header = header.downcast_ref::<gtk::ListHeader>().unwrap();
+                    let label = header.child().and_downcast::<gtk::Label>().unwrap();
+                    if let Some(station) = header.item().and_downcast::<SwStation>() {
+                        label.set_label(&imp.section_sorter.section_title(&station));
+                    }
+                }
+            ));
+
+            self.listview.set_model(Some(&model));
+            self.listview.set_header_factory(Some(&header_factory));
+            self.listview.connect_activate(|listview, pos| {
+                Self::activate_station(&listview.model().unwrap(), pos)
             });
 
             // Setup empty state page
@@ -132,11 +228,70 @@ mod imp {
 
     impl NavigationPageImpl for SwLibraryPage {}
 
+    #[gtk::template_callbacks]
     impl SwLibraryPage {
+        #[template_callback]
+        fn search_changed(&self) {
+            self.filter.set_search(Some(self.search_entry.text().trim()));
+        }
+
+        /// Moves the keyboard focus from the search entry to the first station
+        #[template_callback]
+        fn focus_results(&self) {
+            if self.gridview.model().is_some_and(|m| m.n_items() > 0) {
+                if self.section_sorter.is_grouped() {
+                    self.listview
+                        .scroll_to(0, gtk::ListScrollFlags::FOCUS, None);
+                } else {
+                    self.gridview
+                        .scroll_to(0, gtk::ListScrollFlags::FOCUS, None);
+                }
+            }
+        }
+
+        /// Plays the station directly when activated
+        fn activate_station(model: &gtk::SelectionModel, pos: u32) {
+            let station = model.item(pos).unwrap().downcast::<SwStation>().unwrap();
+
+            glib::spawn_future_local(async move {
+                let player = SwApplication::default().player();
+                player.set_station(station).await;
+            });
+        }
+
+        fn update_play_stats(&self, sorter: &SwStationSorter) {
+            if matches!(
+                self.obj().sorting(),
+                SwStationSorting::RecentlyPlayed | SwStationSorting::MostPlayed
+            ) {
+                sorter.set_play_stats(StationHistory::play_stats());
+            }
+        }
+
+        pub(super) fn start_search(&self) {
+            self.search_bar.set_search_mode(true);
+            self.search_entry.grab_focus();
+        }
+
+        fn set_density(&self, density: SwGridDensity) {
+            self.density.set(density);
+
+            if density == SwGridDensity::Compact {
+                self.gridview.set_max_columns(4);
+                self.gridview.add_css_class("compact");
+            } else {
+                self.gridview.set_max_columns(3);
+                self.gridview.remove_css_class("compact");
+            }
+        }
+
         fn update_stack_page(&self) {
             let status = SwApplication::default().library().status();
             match status {
                 SwLibraryStatus::Empty => self.stack.set_visible_child_name("empty"),
+                SwLibraryStatus::Content if self.section_sorter.is_grouped() => {
+                    self.stack.set_visible_child_name("sections")
+                }
                 SwLibraryStatus::Content => self.stack.set_visible_child_name("content"),
                 _ => (),
             }
@@ -151,6 +306,10 @@ glib::wrapper! {
 }
 
 impl SwLibraryPage {
+    pub fn start_search(&self) {
+        self.imp().start_search();
+    }
+
     pub fn sorted_model(&self) -> Option<gtk::SortListModel> {
         let selection_model = self.imp().gridview.model()?;
         let no_selection = selection_model.downcast::<gtk::NoSelection>().ok()?;
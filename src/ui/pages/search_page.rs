@@ -14,28 +14,77 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 
 use adw::prelude::*;
 use adw::subclass::prelude::*;
-use glib::{clone, subclass};
-use gtk::{glib, CompositeTemplate};
+use glib::{clone, subclass, Properties};
+use gtk::{gdk, gio, glib, CompositeTemplate};
+use inflector::Inflector;
 use rand::seq::SliceRandom;
 use rand::prelude::IndexedRandom;
+use url::Url;
+use uuid::Uuid;
 
-use crate::api::{client, Error, StationRequest, SwStation, SwStationModel};
+use crate::api::{
+    client, Error, StationMetadata, StationRequest, SwStation, SwStationModel, SwStationSorter,
+    SwStationSorting, SwStationSortingType,
+};
 use crate::app::SwApplication;
-use crate::ui::{DisplayError, SwStationRow};
+use crate::database::SavedSearch;
+use crate::i18n::*;
+use crate::settings::{settings_manager, Key};
+use crate::ui::search::{SwLanguageRow, SwSearchFilterItem};
+use crate::ui::{DisplayError, SwGridDensity, SwStationComparisonDialog, SwStationRow};
+use crate::utils;
+
+/// Minimum bitrates which can be filtered for, in kbit/s
+const BITRATES: [u32; 4] = [64, 128, 192, 320];
+/// Codecs which can be filtered for, as named by radio-browser
+const CODECS: [&str; 5] = ["MP3", "AAC", "AAC+", "OGG", "FLAC"];
+/// How many stations can be compared at once
+const MAX_COMPARED_STATIONS: u64 = 3;
+
+/// Search filters which can be applied in addition to the station name
+#[derive(Debug, Clone, Copy)]
+enum Filter {
+    Country,
+    Language,
+    Tag,
+    Bitrate,
+    Codec,
+}
 
 mod imp {
     use super::*;
 
-    #[derive(Default, Debug, CompositeTemplate)]
+    #[derive(Default, Debug, Properties, CompositeTemplate)]
     #[template(resource = "/de/haeckerfelix/Shortwave/gtk/search_page.ui")]
+    #[properties(wrapper_type = super::SwSearchPage)]
     pub struct SwSearchPage {
         #[template_child]
         search_entry: TemplateChild<gtk::SearchEntry>,
         #[template_child]
+        compare_button: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        search_menu_button: TemplateChild<gtk::MenuButton>,
+        #[template_child]
+        saved_searches_section: TemplateChild<gio::Menu>,
+        #[template_child]
+        filter_button: TemplateChild<gtk::MenuButton>,
+        #[template_child]
+        filter_flowbox: TemplateChild<gtk::FlowBox>,
+        #[template_child]
+        country_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        language_row: TemplateChild<SwLanguageRow>,
+        #[template_child]
+        tag_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        bitrate_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        codec_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
         stack: TemplateChild<gtk::Stack>,
         #[template_child]
         popular_flowbox: TemplateChild<gtk::FlowBox>,
@@ -45,12 +94,35 @@ mod imp {
         search_gridview: TemplateChild<gtk::GridView>,
         #[template_child]
         failure_statuspage: TemplateChild<adw::StatusPage>,
+        #[template_child]
+        url_statuspage: TemplateChild<adw::StatusPage>,
+        #[template_child]
+        add_url_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        compare_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        compare_stations_button: TemplateChild<gtk::Button>,
 
         popular_model: SwStationModel,
         random_model: SwStationModel,
         search_model: SwStationModel,
+        sorter: SwStationSorter,
+        /// Station for the URL which got entered into the search entry
+        url_station: RefCell<Option<SwStation>>,
 
         loaded: Cell<bool>,
+        /// Set while the widgets get updated from a saved search, to avoid
+        /// that every single change triggers a new search
+        applying_saved_search: Cell<bool>,
+
+        #[property(get, set=Self::set_sorting, builder(SwStationSorting::default()))]
+        sorting: Cell<SwStationSorting>,
+        #[property(get, set, builder(SwStationSortingType::Ascending))]
+        sorting_type: Cell<SwStationSortingType>,
+        #[property(get, set=Self::set_density, builder(SwGridDensity::default()))]
+        density: Cell<SwGridDensity>,
+        #[property(get, set=Self::set_hide_broken)]
+        hide_broken: Cell<bool>,
     }
 
     #[glib::object_subclass]
@@ -62,6 +134,31 @@ mod imp {
         fn class_init(klass: &mut Self::Class) {
             Self::bind_template(klass);
             Self::bind_template_callbacks(klass);
+            klass.install_property_action("search.set-sorting", "sorting");
+            klass.install_property_action("search.set-sorting-type", "sorting-type");
+
+            klass.install_action_async("search.save-search", None, |page, _, _| async move {
+                page.imp().save_search().await;
+            });
+            klass.install_action(
+                "search.open-saved-search",
+                Some(glib::VariantTy::STRING),
+                |page, _, target| {
+                    if let Some(name) = target.and_then(|t| t.get::<String>()) {
+                        page.imp().open_saved_search(&name);
+                    }
+                },
+            );
+            klass.install_action(
+                "search.remove-saved-search",
+                Some(glib::VariantTy::STRING),
+                |page, _, target| {
+                    if let Some(name) = target.and_then(|t| t.get::<String>()) {
+                        SavedSearch::delete(&name);
+                        page.imp().update_saved_searches_menu();
+                    }
+                },
+            );
         }
 
         fn instance_init(obj: &subclass::InitializingObject<Self>) {
@@ -69,8 +166,12 @@ mod imp {
         }
     }
 
+    #[glib::derived_properties]
     impl ObjectImpl for SwSearchPage {
         fn constructed(&self) {
+            settings_manager::bind_property(Key::AppearanceGridDensity, &*self.obj(), "density");
+            settings_manager::bind_property(Key::StationsHideBroken, &*self.obj(), "hide-broken");
+
             // Discover view
             let flowbox_widget_func = |s: &glib::Object| {
                 let station: &SwStation = s.downcast_ref().unwrap();
@@ -102,20 +203,85 @@ mod imp {
                 .connect_child_activated(child_activate_func);
 
             // Search grid view
-            let model = gtk::NoSelection::new(Some(self.search_model.clone()));
+            self.obj()
+                .bind_property("sorting", &self.sorter, "sorting")
+                .bidirectional()
+                .build();
+
+            self.obj()
+                .bind_property("sorting-type", &self.sorter, "sorting-type")
+                .bidirectional()
+                .build();
+
+            let model = gtk::SortListModel::new(
+                Some(self.search_model.clone()),
+                Some(self.sorter.clone()),
+            );
+            // Stations only get selected for comparing them, the list items
+            // themselves aren't selectable
+            let model = gtk::MultiSelection::new(Some(model));
+            model.connect_selection_changed(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_, _, _| imp.update_compare_bar()
+            ));
             self.search_gridview.set_model(Some(&model));
 
-            self.search_gridview
-                .connect_activate(|gv: &gtk::GridView, pos| {
+            self.search_gridview.connect_activate(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |gv: &gtk::GridView, pos| {
+                    if imp.compare_button.is_active() {
+                        imp.toggle_compared_station(pos);
+                        return;
+                    }
+
                     let model = gv.model().unwrap();
                     let station = model.item(pos).unwrap().downcast::<SwStation>().unwrap();
-                    
+
                     // Play the station directly when activated
                     glib::spawn_future_local(async move {
                         let player = SwApplication::default().player();
                         player.set_station(station).await;
                     });
-                });
+                }
+            ));
+
+            // Search filters
+            let any = i18n("Any");
+            let bitrates: Vec<String> = std::iter::once(any.clone())
+                .chain(BITRATES.iter().map(|b| i18n_f("{} kbit/s", &[&b.to_string()])))
+                .collect();
+            let bitrates: Vec<&str> = bitrates.iter().map(String::as_str).collect();
+            self.bitrate_row
+                .set_model(Some(&gtk::StringList::new(&bitrates)));
+
+            let codecs: Vec<&str> = std::iter::once(any.as_str()).chain(CODECS).collect();
+            self.codec_row.set_model(Some(&gtk::StringList::new(&codecs)));
+
+            // Typing anywhere on the page goes to the search entry, the down key moves
+            // the focus on to the results
+            self.search_entry.set_key_capture_widget(Some(&*self.obj()));
+
+            let controller = gtk::EventControllerKey::new();
+            controller.connect_key_pressed(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                #[upgrade_or]
+                glib::Propagation::Proceed,
+                move |_, key, _, _| {
+                    if key == gdk::Key::Down && imp.focus_results() {
+                        glib::Propagation::Stop
+                    } else {
+                        glib::Propagation::Proceed
+                    }
+                }
+            ));
+            self.search_entry.add_controller(controller);
+
+            // Saved searches
+            self.obj().action_set_enabled("search.save-search", false);
+            self.update_saved_searches_menu();
 
             self.stack.set_visible_child_name("spinner");
         }
@@ -141,6 +307,58 @@ mod imp {
 
     #[gtk::template_callbacks]
     impl SwSearchPage {
+        fn set_density(&self, density: SwGridDensity) {
+            self.density.set(density);
+
+            let columns = if density == SwGridDensity::Compact {
+                self.search_gridview.add_css_class("compact");
+                4
+            } else {
+                self.search_gridview.remove_css_class("compact");
+                3
+            };
+
+            self.search_gridview.set_max_columns(columns);
+            self.popular_flowbox.set_max_children_per_line(columns);
+            self.random_flowbox.set_max_children_per_line(columns);
+        }
+
+        fn set_sorting(&self, sorting: SwStationSorting) {
+            self.sorting.set(sorting);
+
+            // The location is only looked up when needed, since it requires a permission
+            if sorting == SwStationSorting::Nearest && self.sorter.location().is_none() {
+                glib::spawn_future_local(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    async move {
+                        let res = utils::current_location().await;
+                        res.handle_error_toast(i18n("Unable to determine location"));
+
+                        match res {
+                            Ok(location) => imp.sorter.set_location(Some(location)),
+                            Err(_) => imp.obj().set_sorting(SwStationSorting::Default),
+                        }
+                    }
+                ));
+            }
+        }
+
+        fn set_hide_broken(&self, hide_broken: bool) {
+            if self.hide_broken.replace(hide_broken) == hide_broken || !self.loaded.get() {
+                return;
+            }
+
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                async move {
+                    imp.refresh_discover_page().await;
+                    imp.search().await;
+                }
+            ));
+        }
+
         #[template_callback]
         async fn refresh_discover_page(&self) {
             self.stack.set_visible_child_name("spinner");
@@ -149,6 +367,8 @@ mod imp {
                 Ok(()) => {
                     self.loaded.set(true);
                     self.search_entry.set_sensitive(true);
+                    self.filter_button.set_sensitive(true);
+                    self.search_menu_button.set_sensitive(true);
                     self.stack.set_visible_child_name("discover");
                 }
                 Err(e) => {
@@ -161,15 +381,13 @@ mod imp {
 
         async fn load_discover_stations(&self) -> Result<(), Error> {
             debug!("Update discover stations...");
-            let countrycode = Self::region_code().unwrap_or("GB".into());
 
             // Popular stations
             let request = StationRequest {
                 limit: Some(100),
                 order: Some("votes".into()),
                 reverse: Some(true),
-                countrycode: Some(countrycode.clone()),
-                ..Default::default()
+                ..utils::recommendation_request()
             };
 
             let mut stations = client::station_request(request).await?;
@@ -190,8 +408,7 @@ mod imp {
             let request = StationRequest {
                 limit: Some(18),
                 order: Some("random".into()),
-                countrycode: Some(countrycode),
-                ..Default::default()
+                ..utils::recommendation_request()
             };
 
             let stations = client::station_request(request).await?;
@@ -201,22 +418,387 @@ mod imp {
             Ok(())
         }
 
+        fn selection(&self) -> gtk::MultiSelection {
+            self.search_gridview.model().and_downcast().unwrap()
+        }
+
+        fn toggle_compared_station(&self, position: u32) {
+            let selection = self.selection();
+            if selection.is_selected(position) {
+                selection.unselect_item(position);
+            } else if selection.selection().size() < MAX_COMPARED_STATIONS {
+                selection.select_item(position, false);
+            }
+        }
+
+        fn update_compare_bar(&self) {
+            let count = self.selection().selection().size() as u32;
+            let label = if count == 0 {
+                i18n("Select up to three stations")
+            } else {
+                ni18n_f(
+                    "{} station selected",
+                    "{} stations selected",
+                    count,
+                    &[&count.to_string()],
+                )
+            };
+
+            self.compare_label.set_label(&label);
+            self.compare_stations_button.set_sensitive(count >= 2);
+        }
+
+        #[template_callback]
+        fn compare_mode_changed(&self) {
+            if !self.compare_button.is_active() {
+                self.selection().unselect_all();
+            }
+        }
+
+        #[template_callback]
+        fn compare_stations(&self) {
+            let selection = self.selection();
+            let bitset = selection.selection();
+            let stations: Vec<SwStation> = (0..bitset.size())
+                .filter_map(|i| selection.item(bitset.nth(i as u32)))
+                .filter_map(|item| item.downcast().ok())
+                .collect();
+
+            SwStationComparisonDialog::new(&stations).present(Some(&*self.obj()));
+        }
+
+        /// Moves the keyboard focus to the first visible station, returns `false` if
+        /// there's none
+        fn focus_results(&self) -> bool {
+            match self.stack.visible_child_name().as_deref() {
+                Some("results") => {
+                    self.search_gridview
+                        .scroll_to(0, gtk::ListScrollFlags::FOCUS, None);
+                    true
+                }
+                Some("discover") => self.popular_flowbox.child_focus(gtk::DirectionType::TabForward),
+                _ => false,
+            }
+        }
+
+        pub(super) fn start_search(&self) {
+            self.search_entry.grab_focus();
+            self.search_entry.select_region(0, -1);
+        }
+
         #[template_callback]
         async fn search_changed(&self) {
-            if !self.loaded.get() {
+            self.search().await;
+        }
+
+        #[template_callback]
+        fn filter_changed(&self) {
+            if self.applying_saved_search.get() {
+                return;
+            }
+
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                async move {
+                    imp.search().await;
+                }
+            ));
+        }
+
+        /// The request for the entered search text and the selected filters
+        fn station_request(&self) -> StationRequest {
+            let text = |s: glib::GString| {
+                let s = s.trim().to_string();
+                (!s.is_empty()).then_some(s)
+            };
+
+            let mut request = StationRequest::search_for_name(text(self.search_entry.text()), 1000);
+            request.country = text(self.country_row.text());
+            let language = self.language_row.language();
+            request.language_exact = (!language.is_empty()).then_some(true);
+            request.language = (!language.is_empty()).then_some(language);
+            request.tag = text(self.tag_row.text());
+            request.bitrate_min = (self.bitrate_row.selected() as usize)
+                .checked_sub(1)
+                .and_then(|i| BITRATES.get(i).copied());
+            request.codec = (self.codec_row.selected() as usize)
+                .checked_sub(1)
+                .and_then(|i| CODECS.get(i).map(|c| c.to_string()));
+
+            request
+        }
+
+        /// Shows the filters of the request as chips, which remove the filter when clicked
+        fn update_filter_chips(&self, request: &StationRequest) {
+            self.filter_flowbox.remove_all();
+
+            let filters = [
+                (Filter::Country, request.country.clone()),
+                (
+                    Filter::Language,
+                    request.language.as_ref().map(|l| l.to_title_case()),
+                ),
+                (Filter::Tag, request.tag.clone()),
+                (
+                    Filter::Bitrate,
+                    request
+                        .bitrate_min
+                        .map(|b| i18n_f("At least {} kbit/s", &[&b.to_string()])),
+                ),
+                (Filter::Codec, request.codec.clone()),
+            ];
+
+            for (filter, value) in filters {
+                let Some(value) = value else {
+                    continue;
+                };
+
+                let chip = SwSearchFilterItem::new(&value);
+                chip.set_tooltip_text(Some(&i18n("Remove Filter")));
+                chip.connect_clicked(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move |_| imp.remove_filter(filter)
+                ));
+                self.filter_flowbox.append(&chip);
+            }
+
+            self.filter_flowbox
+                .set_visible(self.filter_flowbox.first_child().is_some());
+        }
+
+        /// Looks up the station behind a stream URL, so that it can be played or added
+        async fn resolve_url(&self, url: Url) {
+            self.stack.set_visible_child_name("spinner");
+            debug!("Resolve URL: {}", url);
+
+            // Prefer the radio-browser entry, since it has the complete metadata
+            let station = match client::station_by_url(&url).await {
+                Ok(Some(station)) => station,
+                res => {
+                    if let Err(e) = res {
+                        warn!("Unable to look up station by URL: {}", e);
+                    }
+
+                    let name = client::stream_icy_name(&url)
+                        .await
+                        .ok()
+                        .flatten()
+                        .or_else(|| url.host_str().map(str::to_string))
+                        .unwrap_or_else(|| url.to_string());
+
+                    let metadata = StationMetadata {
+                        name,
+                        url: Some(url.clone()),
+                        ..Default::default()
+                    };
+                    SwStation::new(&Uuid::new_v4().to_string(), true, metadata, None)
+                }
+            };
+
+            // The search text could have been changed in the meantime
+            if Url::parse(self.search_entry.text().trim()).ok() != Some(url.clone()) {
                 return;
             }
 
-            let text = self.search_entry.text().trim().to_string();
-            let text = if text.is_empty() { None } else { Some(text) };
+            let library = SwApplication::default().library();
+            self.url_statuspage.set_title(&station.title());
+            self.url_statuspage.set_description(Some(url.as_str()));
+            self.add_url_button
+                .set_sensitive(!library.contains_station(&station));
+
+            *self.url_station.borrow_mut() = Some(station);
+            self.stack.set_visible_child_name("url");
+        }
+
+        #[template_callback]
+        async fn play_url_station(&self) {
+            let station = self.url_station.borrow().clone();
+            if let Some(station) = station {
+                SwApplication::default().player().set_station(station).await;
+            }
+        }
+
+        #[template_callback]
+        fn add_url_station(&self) {
+            if let Some(station) = self.url_station.borrow().clone() {
+                SwApplication::default().library().add_station(station);
+                self.add_url_button.set_sensitive(false);
+            }
+        }
+
+        fn remove_filter(&self, filter: Filter) {
+            // Changing the selection of combo rows already triggers a new search
+            match filter {
+                Filter::Country => {
+                    self.country_row.set_text("");
+                    self.filter_changed();
+                }
+                Filter::Language => self.language_row.set_language(""),
+                Filter::Tag => {
+                    self.tag_row.set_text("");
+                    self.filter_changed();
+                }
+                Filter::Bitrate => self.bitrate_row.set_selected(0),
+                Filter::Codec => self.codec_row.set_selected(0),
+            }
+        }
 
-            // Don't search if search entry is empty
-            if text.is_none() {
+        /// Lists the saved searches in the menu, together with a submenu to remove them
+        fn update_saved_searches_menu(&self) {
+            let menu = &*self.saved_searches_section;
+            menu.remove_all();
+
+            let saved_searches = SavedSearch::all();
+            if saved_searches.is_empty() {
+                return;
+            }
+
+            let remove_menu = gio::Menu::new();
+            for search in &saved_searches {
+                let target = search.name.to_variant();
+
+                let item = gio::MenuItem::new(Some(&search.name), None);
+                item.set_action_and_target_value(Some("search.open-saved-search"), Some(&target));
+                menu.append_item(&item);
+
+                let item = gio::MenuItem::new(Some(&search.name), None);
+                item.set_action_and_target_value(
+                    Some("search.remove-saved-search"),
+                    Some(&target),
+                );
+                remove_menu.append_item(&item);
+            }
+
+            menu.append_submenu(Some(&i18n("_Remove Saved Search")), &remove_menu);
+        }
+
+        async fn save_search(&self) {
+            let name_row = adw::EntryRow::builder()
+                .title(i18n("Name"))
+                .text(self.search_entry.text().trim())
+                .build();
+            let discover_row = adw::SwitchRow::builder()
+                .title(i18n("Show in Discover"))
+                .subtitle(i18n("List the results as section on the discover page"))
+                .build();
+
+            let list = gtk::ListBox::new();
+            list.set_selection_mode(gtk::SelectionMode::None);
+            list.add_css_class("boxed-list");
+            list.append(&name_row);
+            list.append(&discover_row);
+
+            let dialog = adw::AlertDialog::new(Some(&i18n("Save Search")), None);
+            dialog.set_extra_child(Some(&list));
+            dialog.add_response("cancel", &i18n("_Cancel"));
+            dialog.add_response("save", &i18n("_Save"));
+            dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+            dialog.set_default_response(Some("save"));
+            dialog.set_close_response("cancel");
+
+            let update_sensitivity = clone!(
+                #[weak]
+                dialog,
+                move |row: &adw::EntryRow| {
+                    dialog.set_response_enabled("save", !row.text().trim().is_empty());
+                }
+            );
+            update_sensitivity(&name_row);
+            name_row.connect_changed(update_sensitivity);
+
+            if dialog.choose_future(Some(&*self.obj())).await != "save" {
+                return;
+            }
+
+            let search = SavedSearch {
+                name: name_row.text().trim().to_string(),
+                request: self.station_request(),
+                sorting: self.sorting.get(),
+                sorting_type: self.sorting_type.get(),
+                show_in_discover: discover_row.is_active(),
+            };
+            search.store();
+
+            self.update_saved_searches_menu();
+        }
+
+        /// Restores the query, filters and sorting of a saved search and executes it
+        fn open_saved_search(&self, name: &str) {
+            let Some(search) = SavedSearch::all().into_iter().find(|s| s.name == name) else {
+                warn!("Saved search {} doesn't exist", name);
+                return;
+            };
+            let request = search.request;
+
+            // The first entry of the combo rows is "Any"
+            let bitrate = BITRATES
+                .iter()
+                .position(|b| Some(*b) == request.bitrate_min)
+                .map_or(0, |i| i as u32 + 1);
+            let codec = CODECS
+                .iter()
+                .position(|c| Some(*c) == request.codec.as_deref())
+                .map_or(0, |i| i as u32 + 1);
+
+            // A changed search text triggers a new search on its own
+            let text = request.name.as_deref().unwrap_or_default();
+            let text_changed = self.search_entry.text() != text;
+
+            self.applying_saved_search.set(true);
+            self.search_entry.set_text(text);
+            self.country_row
+                .set_text(request.country.as_deref().unwrap_or_default());
+            self.language_row
+                .set_language(request.language.as_deref().unwrap_or_default());
+            self.tag_row
+                .set_text(request.tag.as_deref().unwrap_or_default());
+            self.bitrate_row.set_selected(bitrate);
+            self.codec_row.set_selected(codec);
+            self.obj().set_sorting(search.sorting);
+            self.obj().set_sorting_type(search.sorting_type);
+            self.applying_saved_search.set(false);
+
+            if !text_changed {
+                self.filter_changed();
+            }
+        }
+
+        async fn search(&self) {
+            if !self.loaded.get() || self.applying_saved_search.get() {
+                return;
+            }
+
+            let request = self.station_request();
+            self.update_filter_chips(&request);
+
+            // Searching for a stream URL by name wouldn't return anything
+            let url = request
+                .name
+                .as_deref()
+                .and_then(|text| Url::parse(text).ok())
+                .filter(|url| matches!(url.scheme(), "http" | "https"));
+            if let Some(url) = url {
+                self.obj().action_set_enabled("search.save-search", false);
+                self.resolve_url(url).await;
+                return;
+            }
+
+            let has_filter = request.country.is_some()
+                || request.language.is_some()
+                || request.tag.is_some()
+                || request.bitrate_min.is_some()
+                || request.codec.is_some();
+
+            // Don't search if neither a text got entered, nor a filter is set
+            let has_search = request.name.is_some() || has_filter;
+            self.obj().action_set_enabled("search.save-search", has_search);
+            if !has_search {
                 self.stack.set_visible_child_name("discover");
                 return;
             }
 
-            let request = StationRequest::search_for_name(text, 1000);
             self.stack.set_visible_child_name("spinner");
 
             debug!("Search for: {:?}", request);
@@ -230,16 +812,9 @@ mod imp {
                     self.stack.set_visible_child_name("results");
                 }
 
-                self.search_model.clear();
-                self.search_model.add_stations(stations);
+                self.search_model.set_stations(stations);
             }
         }
-
-        fn region_code() -> Option<String> {
-            let locale = sys_locale::get_locale()?;
-            let langtag = language_tags::LanguageTag::parse(&locale).ok()?;
-            langtag.region().map(|s| s.to_string())
-        }
     }
 }
 
@@ -248,3 +823,9 @@ glib::wrapper! {
         @extends gtk::Widget, adw::NavigationPage,
         @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
 }
+
+impl SwSearchPage {
+    pub fn start_search(&self) {
+        self.imp().start_search();
+    }
+}
@@ -23,7 +23,7 @@ use gtk::{glib, CompositeTemplate};
 
 use crate::app::SwApplication;
 use crate::audio::SwPlayer;
-use crate::ui::SwVolumeControl;
+use crate::ui::{SwMarqueeLabel, SwVolumeControl};
 
 mod imp {
     use super::*;
@@ -46,6 +46,9 @@ mod imp {
         type Type = super::SwPlayerGadget;
 
         fn class_init(klass: &mut Self::Class) {
+            // Ensure that the label type is registered before the template gets used
+            SwMarqueeLabel::static_type();
+
             Self::bind_template(klass);
         }
 
@@ -18,12 +18,13 @@ use std::marker::PhantomData;
 
 use adw::prelude::*;
 use adw::subclass::prelude::*;
-use glib::{subclass, Properties};
+use glib::{clone, subclass, Properties};
 use gtk::{glib, CompositeTemplate};
 
 use crate::app::SwApplication;
-use crate::audio::SwPlayer;
-use crate::ui::SwStationCover;
+use crate::audio::{SwPlaybackState, SwPlayer};
+use crate::i18n::{i18n, i18n_f};
+use crate::ui::{SwMarqueeLabel, SwStationCover};
 
 mod imp {
     use super::*;
@@ -34,6 +35,14 @@ mod imp {
     pub struct SwPlayerToolbar {
         #[template_child]
         station_cover: TemplateChild<SwStationCover>,
+        #[template_child]
+        buffering_revealer: TemplateChild<gtk::Revealer>,
+        #[template_child]
+        buffering_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        buffering_bar: TemplateChild<gtk::ProgressBar>,
+        #[template_child]
+        loading_button: TemplateChild<gtk::Button>,
 
         #[property(get=Self::player)]
         pub player: PhantomData<SwPlayer>,
@@ -46,6 +55,9 @@ mod imp {
         type Type = super::SwPlayerToolbar;
 
         fn class_init(klass: &mut Self::Class) {
+            // Ensure that the label type is registered before the template gets used
+            SwMarqueeLabel::static_type();
+
             Self::bind_template(klass);
         }
 
@@ -55,13 +67,49 @@ mod imp {
     }
 
     #[glib::derived_properties]
-    impl ObjectImpl for SwPlayerToolbar {}
+    impl ObjectImpl for SwPlayerToolbar {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let player = self.obj().player();
+
+            player.connect_state_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update_buffering()
+            ));
+            player.connect_buffering_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update_buffering()
+            ));
+
+            self.update_buffering();
+        }
+    }
 
     impl WidgetImpl for SwPlayerToolbar {}
 
     impl BinImpl for SwPlayerToolbar {}
 
     impl SwPlayerToolbar {
+        fn update_buffering(&self) {
+            let player = self.obj().player();
+            let percent = player.buffering();
+            let is_buffering =
+                player.state() == SwPlaybackState::Loading && (1..100).contains(&percent);
+
+            self.buffering_revealer.set_reveal_child(is_buffering);
+
+            let text = if is_buffering {
+                i18n_f("Buffering {}%", &[&percent.to_string()])
+            } else {
+                i18n("Buffering…")
+            };
+            self.buffering_label.set_label(&text);
+            self.buffering_bar.set_fraction(f64::from(percent) / 100.0);
+            self.loading_button.set_tooltip_text(Some(&text));
+        }
+
         fn player(&self) -> SwPlayer {
             SwApplication::default().player()
         }
@@ -20,17 +20,26 @@ use std::marker::PhantomData;
 use adw::prelude::*;
 use adw::subclass::prelude::*;
 use glib::{clone, subclass, Properties};
-use gtk::{glib, CompositeTemplate};
+use gtk::graphene::Rect;
+use gtk::{gdk, glib, CompositeTemplate};
 
 use crate::app::SwApplication;
+use crate::audio::SwPlaybackState;
 use crate::audio::SwPlayer;
+use crate::audio::SwQueueEntry;
 use crate::audio::SwTrack;
+use crate::i18n::{i18n, i18n_f};
 use crate::ui::{
-    SwDeviceIndicator, SwRecordingIndicator, SwStationCover, SwTrackRow, SwVolumeControl,
+    SwCoverDialog, SwDeviceIndicator, SwRecordingIndicator, SwStationCover, SwTrackRow,
+    SwVolumeControl,
 };
+use crate::utils;
 
 mod imp {
     use super::*;
+    static BACKDROP_BLUR_RADIUS: f32 = 48.0;
+    /// Keeps the backdrop subtle, so that the content stays readable
+    static BACKDROP_OPACITY: f64 = 0.35;
 
     #[derive(Debug, Default, Properties, CompositeTemplate)]
     #[template(resource = "/de/haeckerfelix/Shortwave/gtk/player_view.ui")]
@@ -45,9 +54,29 @@ mod imp {
         #[template_child]
         volume_control: TemplateChild<SwVolumeControl>,
         #[template_child]
+        replay_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pause_playback_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        save_recent_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        timeshift_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        queue_revealer: TemplateChild<gtk::Revealer>,
+        #[template_child]
+        queue_remaining_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        queue_listbox: TemplateChild<gtk::ListBox>,
+        #[template_child]
         past_tracks_stack: TemplateChild<gtk::Stack>,
         #[template_child]
         past_tracks_listbox: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        select_button: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        save_selected_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        discard_selected_button: TemplateChild<gtk::Button>,
 
         #[property(get, set)]
         pub show_gadget_button: Cell<bool>,
@@ -83,10 +112,36 @@ mod imp {
                 .bidirectional()
                 .build();
 
-            self.past_tracks_listbox
-                .bind_model(Some(&player.past_tracks()), |track| {
-                    SwTrackRow::new(track.clone().downcast::<SwTrack>().unwrap().clone()).into()
-                });
+            player
+                .bind_property("muted", &*self.volume_control, "toggle-mute")
+                .sync_create()
+                .bidirectional()
+                .build();
+
+            self.past_tracks_listbox.bind_model(
+                Some(&player.past_tracks()),
+                clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    #[upgrade_or_panic]
+                    move |track| {
+                        let track = track.clone().downcast::<SwTrack>().unwrap();
+                        let row = SwTrackRow::new(track);
+
+                        imp.select_button
+                            .bind_property("active", &row, "selection-mode")
+                            .sync_create()
+                            .build();
+                        row.connect_selected_notify(clone!(
+                            #[weak]
+                            imp,
+                            move |_| imp.update_selection_buttons()
+                        ));
+
+                        row.into()
+                    }
+                ),
+            );
 
             player.past_tracks().connect_items_changed(clone!(
                 #[weak(rename_to = imp)]
@@ -96,28 +151,264 @@ mod imp {
                 }
             ));
 
+            self.queue_listbox
+                .bind_model(Some(&player.queue()), |entry| {
+                    let entry = entry.clone().downcast::<SwQueueEntry>().unwrap();
+                    Self::queue_row(&entry).upcast()
+                });
+
+            player.queue().connect_items_changed(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_, _, _, _| imp.update_queue()
+            ));
+            player.connect_queue_remaining_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update_queue()
+            ));
+
+            player.connect_state_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update_timeshift_buttons()
+            ));
+            player.connect_timeshift_duration_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update_timeshift_buttons()
+            ));
+            player.connect_has_device_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update_timeshift_buttons()
+            ));
+
+            player.connect_timeshift_delay_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update_timeshift_label()
+            ));
+            player.connect_recent_audio_duration_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update_save_recent_label()
+            ));
+
+            // Redraw the blurred cover backdrop
+            self.station_cover.connect_texture_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.obj().queue_draw()
+            ));
+            self.obj()
+                .settings()
+                .connect_gtk_enable_animations_notify(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move |_| imp.obj().queue_draw()
+                ));
+
             self.obj().set_show_gadget_button(true);
             self.update_past_tracks_stack();
+            self.update_queue();
+            self.update_timeshift_buttons();
+            self.update_timeshift_label();
+            self.update_save_recent_label();
         }
     }
 
-    impl WidgetImpl for SwPlayerView {}
+    impl WidgetImpl for SwPlayerView {
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            self.snapshot_backdrop(snapshot);
+            self.parent_snapshot(snapshot);
+        }
+    }
 
     impl BinImpl for SwPlayerView {}
 
     #[gtk::template_callbacks]
     impl SwPlayerView {
+        /// Draws a blurred and darkened version of the station cover behind
+        /// the content. Skipped when animations are disabled to reduce effects.
+        fn snapshot_backdrop(&self, snapshot: &gtk::Snapshot) {
+            let Some(cover) = self.station_cover.texture() else {
+                return;
+            };
+
+            if !self.obj().settings().is_gtk_enable_animations() {
+                return;
+            }
+
+            let width = self.obj().width() as f32;
+            let height = self.obj().height() as f32;
+            if width <= 0.0 || height <= 0.0 {
+                return;
+            }
+
+            // Cover the whole view, including the blurred edges
+            let scale = ((width + BACKDROP_BLUR_RADIUS * 2.0) / cover.width() as f32)
+                .max((height + BACKDROP_BLUR_RADIUS * 2.0) / cover.height() as f32);
+            let cover_width = cover.width() as f32 * scale;
+            let cover_height = cover.height() as f32 * scale;
+
+            let bounds = Rect::new(0.0, 0.0, width, height);
+            let cover_rect = Rect::new(
+                (width - cover_width) / 2.0,
+                (height - cover_height) / 2.0,
+                cover_width,
+                cover_height,
+            );
+
+            snapshot.push_clip(&bounds);
+            snapshot.push_opacity(BACKDROP_OPACITY);
+            snapshot.push_blur(BACKDROP_BLUR_RADIUS as f64);
+            snapshot.append_texture(&cover, &cover_rect);
+            snapshot.pop();
+            snapshot.append_color(&gdk::RGBA::new(0.0, 0.0, 0.0, 0.3), &bounds);
+            snapshot.pop();
+            snapshot.pop();
+        }
+
         fn update_past_tracks_stack(&self) {
-            if self.obj().player().past_tracks().n_items() > 0 {
+            let has_tracks = self.obj().player().past_tracks().n_items() > 0;
+            if has_tracks {
                 self.past_tracks_stack
                     .set_visible_child(&*self.past_tracks_listbox);
             }
+
+            self.select_button.set_sensitive(has_tracks);
+            if !has_tracks {
+                self.select_button.set_active(false);
+            }
+            self.update_selection_buttons();
+        }
+
+        fn selected_track_rows(&self) -> Vec<SwTrackRow> {
+            let mut rows = Vec::new();
+
+            let mut child = self.past_tracks_listbox.first_child();
+            while let Some(widget) = child {
+                child = widget.next_sibling();
+
+                if let Ok(row) = widget.downcast::<SwTrackRow>() {
+                    if row.selected() {
+                        rows.push(row);
+                    }
+                }
+            }
+
+            rows
+        }
+
+        fn update_selection_buttons(&self) {
+            let has_selection = !self.selected_track_rows().is_empty();
+            self.save_selected_button.set_sensitive(has_selection);
+            self.discard_selected_button.set_sensitive(has_selection);
+        }
+
+        #[template_callback]
+        fn save_selected(&self) {
+            for row in self.selected_track_rows() {
+                row.track().save_in_background();
+            }
+            self.select_button.set_active(false);
+        }
+
+        #[template_callback]
+        fn discard_selected(&self) {
+            for row in self.selected_track_rows() {
+                row.track().discard();
+            }
+            self.select_button.set_active(false);
+        }
+
+        fn queue_row(entry: &SwQueueEntry) -> adw::ActionRow {
+            let row = adw::ActionRow::builder()
+                .title(entry.station().title())
+                .subtitle(entry.duration_label())
+                .use_markup(false)
+                .build();
+
+            let remove_button = gtk::Button::builder()
+                .icon_name("list-remove-symbolic")
+                .tooltip_text(i18n("Remove From Queue"))
+                .valign(gtk::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            remove_button.connect_clicked(clone!(
+                #[weak]
+                entry,
+                move |_| SwApplication::default()
+                    .player()
+                    .queue()
+                    .remove_entry(&entry)
+            ));
+            row.add_suffix(&remove_button);
+
+            row
+        }
+
+        fn update_queue(&self) {
+            let player = self.obj().player();
+            let remaining = player.queue_remaining();
+
+            self.queue_revealer
+                .set_reveal_child(player.queue().n_items() > 0);
+            self.queue_remaining_label.set_visible(remaining > 0);
+
+            let remaining = format!("{}:{:02}", remaining / 60, remaining % 60);
+            self.queue_remaining_label
+                .set_label(&i18n_f("Switching in {}", &[&remaining]));
+        }
+
+        fn update_timeshift_buttons(&self) {
+            let player = self.obj().player();
+            let visible = player.state() == SwPlaybackState::Playing && player.can_pause();
+            self.pause_playback_button.set_visible(visible);
+
+            let is_active = matches!(
+                player.state(),
+                SwPlaybackState::Playing | SwPlaybackState::Paused
+            );
+            self.replay_button
+                .set_visible(is_active && player.can_pause());
+            self.save_recent_button
+                .set_visible(is_active && player.can_pause());
+        }
+
+        fn update_timeshift_label(&self) {
+            let delay = self.obj().player().timeshift_delay();
+            let delay = format!("{}:{:02}", delay / 60, delay % 60);
+            self.timeshift_label
+                .set_label(&i18n_f("{} behind live", &[&delay]));
+        }
+
+        fn update_save_recent_label(&self) {
+            let seconds = self.obj().player().recent_audio_duration();
+            let label = if seconds > 0 {
+                let duration = utils::format_duration(seconds, false);
+                i18n_f("Save Last {}", &[&duration])
+            } else {
+                i18n("Save Recent Audio")
+            };
+
+            self.save_recent_button.set_tooltip_text(Some(&label));
+            self.save_recent_button
+                .update_property(&[gtk::accessible::Property::Label(&label)]);
         }
 
         fn player(&self) -> SwPlayer {
             SwApplication::default().player()
         }
 
+        #[template_callback]
+        fn show_cover(&self) {
+            if let Some(station) = self.obj().player().station() {
+                SwCoverDialog::new(&station).present(Some(&*self.obj()));
+            }
+        }
+
         #[template_callback]
         fn recording_indicator_clicked(&self) {
             if let Some(track) = self.obj().player().playing_track() {
@@ -14,13 +14,20 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::fs;
+use std::path::Path;
+
 use adw::prelude::*;
 use adw::subclass::prelude::*;
 use glib::{clone, subclass};
 use gtk::{gio, glib, CompositeTemplate};
 
-use crate::i18n::{i18n, ni18n_f};
+use crate::app::SwApplication;
+use crate::device::network;
+use crate::i18n::{i18n, i18n_f, ni18n_f};
+use crate::path;
 use crate::settings::{settings_manager, Key};
+use crate::ui::search::SwLanguageRow;
 
 mod imp {
     use super::*;
@@ -28,19 +35,75 @@ mod imp {
     #[derive(Debug, Default, CompositeTemplate)]
     #[template(resource = "/de/haeckerfelix/Shortwave/gtk/preferences_dialog.ui")]
     pub struct SwPreferencesDialog {
+        // Appearance
+        #[template_child]
+        scrolling_titles_switch: TemplateChild<gtk::Switch>,
+
+        // Discover
+        #[template_child]
+        discover_language_row: TemplateChild<SwLanguageRow>,
+
         // Playback
         #[template_child]
         background_playback_switch: TemplateChild<gtk::Switch>,
         #[template_child]
         notifications_switch: TemplateChild<gtk::Switch>,
+        #[template_child]
+        notifications_dnd_switch: TemplateChild<gtk::Switch>,
+        #[template_child]
+        fade_duration_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        past_tracks_count_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        timeshift_duration_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        shuffle_switch: TemplateChild<gtk::Switch>,
+        #[template_child]
+        shuffle_interval_row: TemplateChild<adw::SpinRow>,
+
+        // Devices
+        #[template_child]
+        device_discovery_switch: TemplateChild<gtk::Switch>,
+        #[template_child]
+        cast_app_id_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        network_interface_row: TemplateChild<adw::ComboRow>,
+
+        // Startup
+        #[template_child]
+        startup_resume_switch: TemplateChild<gtk::Switch>,
+        #[template_child]
+        startup_hidden_switch: TemplateChild<gtk::Switch>,
+        #[template_child]
+        startup_gadget_switch: TemplateChild<gtk::Switch>,
 
         // Recording
         #[template_child]
         recording_track_directory_row: TemplateChild<adw::ActionRow>,
         #[template_child]
+        recording_write_directly_switch: TemplateChild<gtk::Switch>,
+        #[template_child]
+        recording_normalize_loudness_switch: TemplateChild<gtk::Switch>,
+        #[template_child]
+        recording_library_export_switch: TemplateChild<gtk::Switch>,
+        #[template_child]
         recording_maximum_duration_row: TemplateChild<adw::SpinRow>,
         #[template_child]
         recording_minimum_duration_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        recording_acoustid_api_key_row: TemplateChild<adw::EntryRow>,
+
+        // Debug
+        #[template_child]
+        cover_memory_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        past_tracks_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        cover_cache_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        cache_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        recordings_row: TemplateChild<adw::ActionRow>,
     }
 
     #[glib::object_subclass]
@@ -61,6 +124,26 @@ mod imp {
 
     impl ObjectImpl for SwPreferencesDialog {
         fn constructed(&self) {
+            // Appearance
+            let group = gio::SimpleActionGroup::new();
+            group.add_action(&settings_manager::create_action(Key::AppearanceColorScheme));
+            group.add_action(&settings_manager::create_action(Key::AppearanceCoverShape));
+            group.add_action(&settings_manager::create_action(Key::AppearanceGridDensity));
+            self.obj().insert_action_group("appearance", Some(&group));
+
+            settings_manager::bind_property(
+                Key::AppearanceScrollingTitles,
+                &*self.scrolling_titles_switch,
+                "active",
+            );
+
+            // Discover
+            settings_manager::bind_property(
+                Key::DiscoverLanguage,
+                &*self.discover_language_row,
+                "language",
+            );
+
             // Playback
             settings_manager::bind_property(
                 Key::BackgroundPlayback,
@@ -74,10 +157,58 @@ mod imp {
                 "active",
             );
 
+            settings_manager::bind_property(
+                Key::NotificationsDoNotDisturb,
+                &*self.notifications_dnd_switch,
+                "active",
+            );
+
+            settings_manager::bind_property(
+                Key::PlaybackFadeDuration,
+                &*self.fade_duration_row,
+                "value",
+            );
+
+            settings_manager::bind_property(
+                Key::PlaybackPastTracksCount,
+                &*self.past_tracks_count_row,
+                "value",
+            );
+
+            settings_manager::bind_property(
+                Key::PlaybackTimeshiftDuration,
+                &*self.timeshift_duration_row,
+                "value",
+            );
+
+            settings_manager::bind_property(Key::PlaybackShuffle, &*self.shuffle_switch, "active");
+
+            settings_manager::bind_property(
+                Key::PlaybackShuffleInterval,
+                &*self.shuffle_interval_row,
+                "value",
+            );
+
+            let interruption_action =
+                settings_manager::create_action(Key::PlaybackInterruptionBehavior);
+
             // Recording
             let recording_mode_action = settings_manager::create_action(Key::RecordingMode);
+            let filename_style_action =
+                settings_manager::create_action(Key::RecordingFilenameStyle);
+            let folder_structure_action =
+                settings_manager::create_action(Key::RecordingFolderStructure);
+            let convert_format_action =
+                settings_manager::create_action(Key::RecordingConvertFormat);
+            let library_structure_action =
+                settings_manager::create_action(Key::RecordingLibraryStructure);
             let group = gio::SimpleActionGroup::new();
+            group.add_action(&interruption_action);
             group.add_action(&recording_mode_action);
+            group.add_action(&filename_style_action);
+            group.add_action(&folder_structure_action);
+            group.add_action(&convert_format_action);
+            group.add_action(&library_structure_action);
             self.obj().insert_action_group("player", Some(&group));
 
             settings_manager::bind_property(
@@ -94,6 +225,24 @@ mod imp {
                 }
             ));
 
+            settings_manager::bind_property(
+                Key::RecordingWriteDirectly,
+                &*self.recording_write_directly_switch,
+                "active",
+            );
+
+            settings_manager::bind_property(
+                Key::RecordingNormalizeLoudness,
+                &*self.recording_normalize_loudness_switch,
+                "active",
+            );
+
+            settings_manager::bind_property(
+                Key::RecordingLibraryExport,
+                &*self.recording_library_export_switch,
+                "active",
+            );
+
             settings_manager::bind_property(
                 Key::RecordingMaximumDuration,
                 &*self.recording_maximum_duration_row,
@@ -116,6 +265,86 @@ mod imp {
                 &*self.recording_minimum_duration_row,
                 "value",
             );
+
+            settings_manager::bind_property(
+                Key::RecordingAcoustidApiKey,
+                &*self.recording_acoustid_api_key_row,
+                "text",
+            );
+
+            // Devices
+            settings_manager::bind_property(
+                Key::DeviceBackgroundDiscovery,
+                &*self.device_discovery_switch,
+                "active",
+            );
+
+            let group = gio::SimpleActionGroup::new();
+            group.add_action(&settings_manager::create_action(Key::DeviceCastReceiver));
+            self.obj().insert_action_group("devices", Some(&group));
+
+            settings_manager::bind_property(Key::DeviceCastAppId, &*self.cast_app_id_row, "text");
+
+            // The first entry is used for automatic detection
+            let mut names = vec![String::new()];
+            let model = gtk::StringList::new(&[&i18n("Automatic")]);
+            for iface in network::interfaces() {
+                // Interfaces are listed once per address, only show the first one
+                if !names.contains(&iface.name) {
+                    model.append(&format!("{} ({})", iface.name, iface.ip));
+                    names.push(iface.name);
+                }
+            }
+
+            // Keep the selected interface, even if it's currently unavailable
+            let selected = settings_manager::string(Key::DeviceNetworkInterface);
+            if !names.contains(&selected) {
+                model.append(&selected);
+                names.push(selected.clone());
+            }
+
+            self.network_interface_row.set_model(Some(&model));
+            let position = names
+                .iter()
+                .position(|name| name == &selected)
+                .unwrap_or_default();
+            self.network_interface_row.set_selected(position as u32);
+            self.network_interface_row
+                .connect_selected_notify(move |row| {
+                    let name = names
+                        .get(row.selected() as usize)
+                        .cloned()
+                        .unwrap_or_default();
+                    settings_manager::set_string(Key::DeviceNetworkInterface, name);
+                });
+
+            // Startup
+            settings_manager::bind_property(
+                Key::StartupResumePlayback,
+                &*self.startup_resume_switch,
+                "active",
+            );
+
+            settings_manager::bind_property(
+                Key::StartupHidden,
+                &*self.startup_hidden_switch,
+                "active",
+            );
+
+            settings_manager::bind_property(
+                Key::StartupRestoreGadget,
+                &*self.startup_gadget_switch,
+                "active",
+            );
+
+            // Debug
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                async move {
+                    imp.update_debug_information().await;
+                }
+            ));
         }
     }
 
@@ -127,6 +356,130 @@ mod imp {
 
     #[gtk::template_callbacks]
     impl SwPreferencesDialog {
+        async fn update_debug_information(&self) {
+            let app = SwApplication::default();
+
+            let (count, bytes) = app.cover_loader().memory_usage();
+            let text = ni18n_f("{} cover", "{} covers", count as u32, &[&count.to_string()]);
+            self.cover_memory_row
+                .set_subtitle(&format!("{text} · {}", glib::format_size(bytes as u64)));
+
+            let count = app.player().past_tracks().n_items();
+            let text = ni18n_f("{} track", "{} tracks", count, &[&count.to_string()]);
+            self.past_tracks_row.set_subtitle(&text);
+
+            let bytes = app.cover_loader().disk_usage().await;
+            self.cover_cache_row
+                .set_subtitle(&glib::format_size(bytes as u64));
+
+            let bytes = gio::spawn_blocking(|| dir_size(&path::CACHE))
+                .await
+                .unwrap_or_default();
+            self.cache_row.set_subtitle(&glib::format_size(bytes));
+
+            let bytes = gio::spawn_blocking(|| {
+                let mut path = path::DATA.clone();
+                path.push("recording");
+
+                fs::read_dir(path)
+                    .map(|entries| {
+                        entries
+                            .flatten()
+                            .filter_map(|e| e.metadata().ok())
+                            .map(|md| md.len())
+                            .sum()
+                    })
+                    .unwrap_or_default()
+            })
+            .await
+            .unwrap_or_default();
+            self.recordings_row.set_subtitle(&glib::format_size(bytes));
+        }
+
+        #[template_callback]
+        async fn clear_cover_cache(&self) {
+            SwApplication::default().cover_loader().clear_cache().await;
+            self.obj()
+                .add_toast(adw::Toast::new(&i18n("Cover cache cleared")));
+            self.update_debug_information().await;
+        }
+
+        #[template_callback]
+        async fn clear_all_caches(&self) {
+            SwApplication::default().cover_loader().purge_cache().await;
+            self.obj()
+                .add_toast(adw::Toast::new(&i18n("All caches cleared")));
+            self.update_debug_information().await;
+        }
+
+        #[template_callback]
+        async fn export_settings(&self) {
+            let dialog = gtk::FileDialog::new();
+            dialog.set_title(&i18n("Export Settings"));
+            dialog.set_initial_name(Some("shortwave-settings.json"));
+
+            let Ok(file) = dialog.save_future(Some(&self.parent_window())).await else {
+                return;
+            };
+
+            let data = settings_manager::export();
+            let res = file
+                .replace_contents_future(
+                    data,
+                    None,
+                    false,
+                    gio::FileCreateFlags::REPLACE_DESTINATION,
+                )
+                .await;
+
+            let text = match res {
+                Ok(_) => i18n("Settings exported"),
+                Err((_, err)) => {
+                    warn!("Unable to export settings: {err}");
+                    i18n("Unable to export settings")
+                }
+            };
+            self.obj().add_toast(adw::Toast::new(&text));
+        }
+
+        #[template_callback]
+        async fn import_settings(&self) {
+            let dialog = gtk::FileDialog::new();
+            dialog.set_title(&i18n("Import Settings"));
+
+            let Ok(file) = dialog.open_future(Some(&self.parent_window())).await else {
+                return;
+            };
+
+            let res = file
+                .load_contents_future()
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|(data, _)| settings_manager::import(&data));
+
+            let text = match res {
+                Ok(count) => ni18n_f(
+                    "{} setting imported",
+                    "{} settings imported",
+                    count,
+                    &[&count.to_string()],
+                ),
+                Err(err) => {
+                    warn!("Unable to import settings: {err}");
+                    i18n("Unable to import settings")
+                }
+            };
+            self.obj().add_toast(adw::Toast::new(&text));
+        }
+
+        fn parent_window(&self) -> gtk::Window {
+            self.obj()
+                .root()
+                .unwrap()
+                .downcast::<gtk::Window>()
+                .unwrap()
+        }
+
         pub fn select_recording_save_directory(&self) {
             let parent = self
                 .obj()
@@ -166,6 +519,54 @@ mod imp {
             true
         }
 
+        #[template_callback]
+        fn on_fade_duration_output(row: &adw::SpinRow) -> bool {
+            let value = row.value();
+            let text = if value == 0.0 {
+                i18n("Off")
+            } else {
+                i18n_f("{} sec", &[&format!("{value:.1}")])
+            };
+            row.set_text(&text);
+            row.set_width_chars(text.len() as i32);
+            true
+        }
+
+        #[template_callback]
+        fn on_past_tracks_count_output(row: &adw::SpinRow) -> bool {
+            let value = row.value() as u32;
+            let text = ni18n_f("{} track", "{} tracks", value, &[&value.to_string()]);
+            row.set_text(&text);
+            row.set_width_chars(text.len() as i32);
+            true
+        }
+
+        #[template_callback]
+        fn on_timeshift_duration_output(row: &adw::SpinRow) -> bool {
+            let value = row.value() as u32;
+            let text = if value == 0 {
+                i18n("Off")
+            } else {
+                ni18n_f("{} min", "{} min", value, &[&value.to_string()])
+            };
+            row.set_text(&text);
+            row.set_width_chars(text.len() as i32);
+            true
+        }
+
+        #[template_callback]
+        fn on_shuffle_interval_output(row: &adw::SpinRow) -> bool {
+            let value = row.value() as u32;
+            let text = if value == 0 {
+                i18n("Off")
+            } else {
+                ni18n_f("{} min", "{} min", value, &[&value.to_string()])
+            };
+            row.set_text(&text);
+            row.set_width_chars(text.len() as i32);
+            true
+        }
+
         #[template_callback]
         fn on_minimum_duration_output(row: &adw::SpinRow) -> bool {
             let value = row.value() as u32;
@@ -177,6 +578,25 @@ mod imp {
     }
 }
 
+/// Returns the size of all files within a directory in bytes, including subdirectories
+fn dir_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| {
+                    let md = e.metadata().ok()?;
+                    Some(if md.is_dir() {
+                        dir_size(&e.path())
+                    } else {
+                        md.len()
+                    })
+                })
+                .sum()
+        })
+        .unwrap_or_default()
+}
+
 glib::wrapper! {
     pub struct SwPreferencesDialog(ObjectSubclass<imp::SwPreferencesDialog>)
         @extends gtk::Widget, adw::Dialog, adw::PreferencesDialog,
@@ -0,0 +1,240 @@
+// Shortwave - recorders_dialog.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::RefCell;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::{clone, subclass};
+use gtk::{gio, glib, CompositeTemplate};
+
+use crate::app::SwApplication;
+use crate::audio::{SwRecorder, SwRecorderState};
+use crate::i18n::*;
+use crate::settings::{settings_manager, Key};
+use crate::ui::DisplayError;
+use crate::utils;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/de/haeckerfelix/Shortwave/gtk/recorders_dialog.ui")]
+    pub struct SwRecordersDialog {
+        #[template_child]
+        sessions_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        maximum_sessions_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        maximum_duration_row: TemplateChild<adw::SpinRow>,
+        rows: RefCell<Vec<adw::ActionRow>>,
+        handlers: RefCell<Vec<(SwRecorder, glib::SignalHandlerId)>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwRecordersDialog {
+        const NAME: &'static str = "SwRecordersDialog";
+        type ParentType = adw::Dialog;
+        type Type = super::SwRecordersDialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+            Self::bind_template_callbacks(klass);
+        }
+
+        fn instance_init(obj: &subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for SwRecordersDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            settings_manager::bind_property(
+                Key::RecordersMaximumSessions,
+                &*self.maximum_sessions_row,
+                "value",
+            );
+            settings_manager::bind_property(
+                Key::RecordersMaximumDuration,
+                &*self.maximum_duration_row,
+                "value",
+            );
+
+            SwApplication::default()
+                .recorders()
+                .connect_items_changed(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move |_, _, _, _| imp.update_rows()
+                ));
+            self.update_rows();
+        }
+
+        fn dispose(&self) {
+            self.disconnect_handlers();
+        }
+    }
+
+    impl WidgetImpl for SwRecordersDialog {}
+
+    impl AdwDialogImpl for SwRecordersDialog {}
+
+    #[gtk::template_callbacks]
+    impl SwRecordersDialog {
+        fn update_rows(&self) {
+            self.disconnect_handlers();
+            for row in self.rows.borrow_mut().drain(..) {
+                self.sessions_group.remove(&row);
+            }
+
+            let recorders = SwApplication::default().recorders();
+            let description = (recorders.n_items() == 0)
+                .then(|| i18n("No stations are being recorded in the background."));
+            self.sessions_group.set_description(description.as_deref());
+
+            for recorder in recorders.snapshot() {
+                let Ok(recorder) = recorder.downcast::<SwRecorder>() else {
+                    continue;
+                };
+
+                let row = adw::ActionRow::builder()
+                    .title(recorder.station().title())
+                    .use_markup(false)
+                    .build();
+
+                let stop_button = gtk::Button::builder()
+                    .icon_name("media-playback-stop-symbolic")
+                    .tooltip_text(i18n("Stop Recording"))
+                    .valign(gtk::Align::Center)
+                    .css_classes(["flat"])
+                    .build();
+                stop_button.connect_clicked(clone!(
+                    #[weak]
+                    recorder,
+                    move |_| recorder.stop()
+                ));
+
+                let remove_button = gtk::Button::builder()
+                    .icon_name("user-trash-symbolic")
+                    .tooltip_text(i18n("Remove From List"))
+                    .valign(gtk::Align::Center)
+                    .css_classes(["flat"])
+                    .build();
+                remove_button.connect_clicked(clone!(
+                    #[weak]
+                    recorder,
+                    move |_| SwApplication::default()
+                        .recorders()
+                        .remove_recorder(&recorder)
+                ));
+
+                row.add_suffix(&stop_button);
+                row.add_suffix(&remove_button);
+                row.connect_activated(clone!(
+                    #[weak]
+                    recorder,
+                    move |row| {
+                        let launcher = gtk::FileLauncher::new(Some(&recorder.file()));
+                        let window = row.root().and_downcast::<gtk::Window>();
+                        launcher.launch(window.as_ref(), gio::Cancellable::NONE, |res| {
+                            res.handle_error(i18n("Unable to open recording"));
+                        });
+                    }
+                ));
+
+                let update_row = clone!(
+                    #[weak]
+                    row,
+                    #[weak]
+                    stop_button,
+                    #[weak]
+                    remove_button,
+                    move |recorder: &SwRecorder| {
+                        let is_active = recorder.state().is_active();
+                        stop_button.set_visible(is_active);
+                        stop_button.set_sensitive(recorder.state() == SwRecorderState::Recording);
+                        remove_button.set_visible(!is_active);
+                        row.set_activatable(
+                            !is_active && recorder.state() != SwRecorderState::Failed,
+                        );
+                        row.set_subtitle(&Self::summary(recorder));
+                    }
+                );
+                update_row(&recorder);
+
+                let handler = recorder.connect_notify_local(None, move |recorder, _| {
+                    update_row(recorder);
+                });
+                self.handlers.borrow_mut().push((recorder, handler));
+
+                self.sessions_group.add(&row);
+                self.rows.borrow_mut().push(row);
+            }
+        }
+
+        fn disconnect_handlers(&self) {
+            for (recorder, handler) in self.handlers.borrow_mut().drain(..) {
+                recorder.disconnect(handler);
+            }
+        }
+
+        /// Describes the state of a recorder in a single line
+        fn summary(recorder: &SwRecorder) -> String {
+            if recorder.state() == SwRecorderState::Failed {
+                return format!("{} · {}", recorder.state().title(), recorder.error());
+            }
+
+            let mut parts = vec![
+                recorder.state().title(),
+                utils::format_duration(recorder.duration(), false),
+            ];
+            if !recorder.title().is_empty() {
+                parts.push(recorder.title());
+            }
+
+            parts.join(" · ")
+        }
+
+        #[template_callback]
+        fn on_maximum_duration_output(row: &adw::SpinRow) -> bool {
+            let value = row.value() as u32;
+            let text = ni18n_f("{} min", "{} min", value, &[&value.to_string()]);
+            row.set_text(&text);
+            row.set_width_chars(text.len() as i32);
+            true
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct SwRecordersDialog(ObjectSubclass<imp::SwRecordersDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl SwRecordersDialog {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl Default for SwRecordersDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
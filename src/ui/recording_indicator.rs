@@ -14,16 +14,28 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
 
 use adw::prelude::*;
 use adw::subclass::prelude::*;
 use glib::{clone, subclass, Properties};
 use gtk::{glib, CompositeTemplate};
 
-use crate::audio::{SwRecordingState, SwTrack};
+use crate::app::SwApplication;
+use crate::audio::{SwPlayer, SwRecordingState, SwTrack};
+use crate::i18n::i18n;
 use crate::utils;
 
+/// Peak level from which on the recorded audio is considered to be clipped (-0.1 dBFS)
+const CLIPPING_LEVEL: f64 = 0.989;
+/// RMS level below which the recorded audio is considered to be silent (-60 dBFS)
+const SILENCE_LEVEL: f64 = 0.001;
+/// How long the clipping warning stays visible after the last clipped sample
+const CLIPPING_HOLD: Duration = Duration::from_secs(3);
+/// How long the audio needs to be silent before warning about it
+const SILENCE_DELAY: Duration = Duration::from_secs(10);
+
 mod imp {
     use super::*;
 
@@ -31,11 +43,18 @@ mod imp {
     #[template(resource = "/de/haeckerfelix/Shortwave/gtk/recording_indicator.ui")]
     #[properties(wrapper_type = super::SwRecordingIndicator)]
     pub struct SwRecordingIndicator {
+        #[template_child]
+        image: TemplateChild<gtk::Image>,
+        #[template_child]
+        level_bar: TemplateChild<gtk::LevelBar>,
         #[template_child]
         duration_label: TemplateChild<gtk::Label>,
 
         #[property(get, set=Self::set_track)]
         track: RefCell<Option<SwTrack>>,
+
+        last_clipping: Cell<Option<Instant>>,
+        silent_since: Cell<Option<Instant>>,
     }
 
     #[glib::object_subclass]
@@ -54,7 +73,34 @@ mod imp {
     }
 
     #[glib::derived_properties]
-    impl ObjectImpl for SwRecordingIndicator {}
+    impl ObjectImpl for SwRecordingIndicator {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            // The level bar only shows the level, warnings are indicated separately
+            self.level_bar
+                .remove_offset_value(Some(gtk::LEVEL_BAR_OFFSET_LOW));
+            self.level_bar
+                .remove_offset_value(Some(gtk::LEVEL_BAR_OFFSET_HIGH));
+            self.level_bar
+                .remove_offset_value(Some(gtk::LEVEL_BAR_OFFSET_FULL));
+
+            let player = SwApplication::default().player();
+            player.connect_recording_peak_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |player| imp.update_level(player)
+            ));
+            player.connect_recording_paused_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| {
+                    let state = imp.obj().track().map(|t| t.state()).unwrap_or_default();
+                    imp.update_state(state);
+                }
+            ));
+        }
+    }
 
     impl WidgetImpl for SwRecordingIndicator {}
 
@@ -87,13 +133,91 @@ mod imp {
         }
 
         fn update_state(&self, state: SwRecordingState) {
-            if state == SwRecordingState::Recording {
+            let is_paused = SwApplication::default().player().recording_paused();
+            let is_recording = state == SwRecordingState::Recording && !is_paused;
+            if is_recording {
                 self.obj().add_css_class("active");
             } else {
                 self.obj().remove_css_class("active");
             }
 
-            self.obj().set_tooltip_text(Some(&state.title()));
+            self.level_bar.set_visible(is_recording);
+            self.level_bar.set_value(0.0);
+            self.last_clipping.set(None);
+            self.silent_since.set(None);
+            self.update_warning(state);
+        }
+
+        fn update_level(&self, player: &SwPlayer) {
+            let is_recording = self
+                .obj()
+                .track()
+                .is_some_and(|t| t.state() == SwRecordingState::Recording);
+            if !is_recording || player.recording_paused() {
+                return;
+            }
+
+            let now = Instant::now();
+            self.level_bar.set_value(player.recording_peak());
+
+            if player.recording_peak() >= CLIPPING_LEVEL {
+                self.last_clipping.set(Some(now));
+            }
+
+            if player.recording_rms() >= SILENCE_LEVEL {
+                self.silent_since.set(None);
+            } else if self.silent_since.get().is_none() {
+                self.silent_since.set(Some(now));
+            }
+
+            self.update_warning(SwRecordingState::Recording);
+        }
+
+        fn update_warning(&self, state: SwRecordingState) {
+            let obj = self.obj();
+
+            let is_clipping = self
+                .last_clipping
+                .get()
+                .is_some_and(|i| i.elapsed() < CLIPPING_HOLD);
+            let is_silent = self
+                .silent_since
+                .get()
+                .is_some_and(|i| i.elapsed() >= SILENCE_DELAY);
+
+            let warning = if is_clipping {
+                Some(i18n("The recorded audio is clipping"))
+            } else if is_silent {
+                Some(i18n("The recorded audio is silent"))
+            } else {
+                None
+            };
+
+            let is_paused = state == SwRecordingState::Recording
+                && SwApplication::default().player().recording_paused();
+
+            if warning.is_some() {
+                obj.add_css_class("warning");
+                self.image.set_icon_name(Some("dialog-warning-symbolic"));
+            } else if is_paused {
+                obj.remove_css_class("warning");
+                self.image
+                    .set_icon_name(Some("media-playback-pause-symbolic"));
+            } else {
+                obj.remove_css_class("warning");
+                self.image.set_icon_name(Some("media-record-symbolic"));
+            }
+
+            let title = if is_paused {
+                i18n("Recording Paused")
+            } else {
+                state.title()
+            };
+            let tooltip = match warning {
+                Some(warning) => format!("{title}\n{warning}"),
+                None => title,
+            };
+            obj.set_tooltip_text(Some(&tooltip));
         }
     }
 }
@@ -0,0 +1,93 @@
+// Shortwave - recovery_dialog.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::subclass;
+use gtk::{glib, CompositeTemplate};
+
+use crate::app::SwApplication;
+use crate::audio::SwTrack;
+use crate::ui::SwTrackRow;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/de/haeckerfelix/Shortwave/gtk/recovery_dialog.ui")]
+    pub struct SwRecoveryDialog {
+        #[template_child]
+        listbox: TemplateChild<gtk::ListBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwRecoveryDialog {
+        const NAME: &'static str = "SwRecoveryDialog";
+        type ParentType = adw::Dialog;
+        type Type = super::SwRecoveryDialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+            Self::bind_template_callbacks(klass);
+        }
+
+        fn instance_init(obj: &subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for SwRecoveryDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let tracks = SwApplication::default().player().recovered_tracks();
+
+            self.listbox.bind_model(Some(&tracks), |track| {
+                SwTrackRow::new(track.clone().downcast::<SwTrack>().unwrap()).into()
+            });
+        }
+    }
+
+    impl WidgetImpl for SwRecoveryDialog {}
+
+    impl AdwDialogImpl for SwRecoveryDialog {}
+
+    #[gtk::template_callbacks]
+    impl SwRecoveryDialog {
+        #[template_callback]
+        fn discard_all(&self) {
+            SwApplication::default().player().recovered_tracks().clear();
+            self.obj().close();
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct SwRecoveryDialog(ObjectSubclass<imp::SwRecoveryDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl SwRecoveryDialog {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl Default for SwRecoveryDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,409 @@
+// Shortwave - rules_dialog.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::RefCell;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::{clone, subclass};
+use gtk::{glib, CompositeTemplate};
+
+use crate::app::SwApplication;
+use crate::audio::SwRecordingMode;
+use crate::database::AutomationRule;
+use crate::i18n::*;
+
+/// Recording modes a rule can switch to, the first entry keeps the configured mode
+const RECORDING_MODES: [Option<SwRecordingMode>; 4] = [
+    None,
+    Some(SwRecordingMode::Everything),
+    Some(SwRecordingMode::Decide),
+    Some(SwRecordingMode::Nothing),
+];
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/de/haeckerfelix/Shortwave/gtk/rules_dialog.ui")]
+    pub struct SwRulesDialog {
+        #[template_child]
+        rules_group: TemplateChild<adw::PreferencesGroup>,
+        rows: RefCell<Vec<adw::ActionRow>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwRulesDialog {
+        const NAME: &'static str = "SwRulesDialog";
+        type ParentType = adw::Dialog;
+        type Type = super::SwRulesDialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+            Self::bind_template_callbacks(klass);
+        }
+
+        fn instance_init(obj: &subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for SwRulesDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.update_rows();
+        }
+    }
+
+    impl WidgetImpl for SwRulesDialog {}
+
+    impl AdwDialogImpl for SwRulesDialog {}
+
+    #[gtk::template_callbacks]
+    impl SwRulesDialog {
+        fn update_rows(&self) {
+            for row in self.rows.borrow_mut().drain(..) {
+                self.rules_group.remove(&row);
+            }
+
+            let rules = AutomationRule::all();
+            let description = rules
+                .is_empty()
+                .then(|| i18n("No rules have been added yet."));
+            self.rules_group.set_description(description.as_deref());
+
+            for rule in rules {
+                let row = adw::ActionRow::builder()
+                    .title(&rule.name)
+                    .subtitle(Self::summary(&rule))
+                    .use_markup(false)
+                    .activatable(true)
+                    .build();
+
+                let remove_button = gtk::Button::builder()
+                    .icon_name("user-trash-symbolic")
+                    .tooltip_text(i18n("Remove Rule"))
+                    .valign(gtk::Align::Center)
+                    .css_classes(["flat"])
+                    .build();
+                remove_button.connect_clicked(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    #[strong(rename_to = name)]
+                    rule.name,
+                    move |_| {
+                        AutomationRule::delete(&name);
+                        imp.update_rows();
+                    }
+                ));
+
+                let switch = gtk::Switch::builder()
+                    .active(rule.enabled)
+                    .tooltip_text(i18n("Enabled"))
+                    .valign(gtk::Align::Center)
+                    .build();
+                switch.connect_active_notify(clone!(
+                    #[strong(rename_to = name)]
+                    rule.name,
+                    move |switch| {
+                        if let Some(mut rule) = Self::rule(&name) {
+                            rule.enabled = switch.is_active();
+                            rule.store();
+                        }
+                    }
+                ));
+
+                row.add_suffix(&remove_button);
+                row.add_suffix(&switch);
+                row.connect_activated(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    #[strong(rename_to = name)]
+                    rule.name,
+                    move |_| {
+                        let name = name.clone();
+                        glib::spawn_future_local(clone!(
+                            #[weak]
+                            imp,
+                            async move { imp.edit_rule(Some(name)).await }
+                        ));
+                    }
+                ));
+
+                self.rules_group.add(&row);
+                self.rows.borrow_mut().push(row);
+            }
+        }
+
+        #[template_callback]
+        fn add_rule(&self) {
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                async move { imp.edit_rule(None).await }
+            ));
+        }
+
+        async fn edit_rule(&self, name: Option<String>) {
+            let existing = name.and_then(|name| Self::rule(&name));
+            let rule = existing.clone().unwrap_or(AutomationRule {
+                enabled: true,
+                ..Default::default()
+            });
+
+            let name_row = adw::EntryRow::builder()
+                .title(i18n("Name"))
+                .text(&rule.name)
+                .build();
+
+            // The first entry matches all stations. Keep the station of the rule listed, even if
+            // it isn't part of the library anymore.
+            let mut stations = vec![(String::new(), i18n("Any Station"))];
+            for station in SwApplication::default().library().stations() {
+                stations.push((station.uuid(), station.title()));
+            }
+            if !stations.iter().any(|(uuid, _)| *uuid == rule.station_uuid) {
+                stations.push((rule.station_uuid.clone(), rule.station_title.clone()));
+            }
+
+            let titles: Vec<&str> = stations.iter().map(|(_, title)| title.as_str()).collect();
+            let station_row = adw::ComboRow::builder()
+                .title(i18n("Station"))
+                .model(&gtk::StringList::new(&titles))
+                .enable_search(true)
+                .build();
+            let position = stations
+                .iter()
+                .position(|(uuid, _)| *uuid == rule.station_uuid)
+                .unwrap_or_default();
+            station_row.set_selected(position as u32);
+
+            let (start, end) = rule.hours.unwrap_or((18, 20));
+            let start_row = adw::SpinRow::with_range(0.0, 23.0, 1.0);
+            start_row.set_title(&i18n("From Hour"));
+            start_row.set_value(start.into());
+            let end_row = adw::SpinRow::with_range(0.0, 23.0, 1.0);
+            end_row.set_title(&i18n("Until Hour"));
+            end_row.set_value(end.into());
+
+            let hours_row = adw::ExpanderRow::builder()
+                .title(i18n("Only at Certain Hours"))
+                .show_enable_switch(true)
+                .enable_expansion(rule.hours.is_some())
+                .build();
+            hours_row.add_row(&start_row);
+            hours_row.add_row(&end_row);
+
+            let title_row = adw::EntryRow::builder()
+                .title(i18n("Track Title Pattern"))
+                .text(&rule.title_pattern)
+                .build();
+
+            let modes: Vec<String> = RECORDING_MODES
+                .iter()
+                .map(|m| Self::mode_label(*m))
+                .collect();
+            let modes: Vec<&str> = modes.iter().map(String::as_str).collect();
+            let mode_row = adw::ComboRow::builder()
+                .title(i18n("Recording Mode"))
+                .model(&gtk::StringList::new(&modes))
+                .build();
+            let position = RECORDING_MODES
+                .iter()
+                .position(|m| *m == rule.recording_mode)
+                .unwrap_or_default();
+            mode_row.set_selected(position as u32);
+
+            let notify_row = adw::SwitchRow::builder()
+                .title(i18n("Show Notification"))
+                .active(rule.notify)
+                .build();
+            let save_row = adw::SwitchRow::builder()
+                .title(i18n("Save Track"))
+                .subtitle(i18n("Recorded tracks get saved in the track directory"))
+                .active(rule.save)
+                .build();
+
+            let conditions = gtk::ListBox::new();
+            conditions.set_selection_mode(gtk::SelectionMode::None);
+            conditions.add_css_class("boxed-list");
+            conditions.append(&name_row);
+            conditions.append(&station_row);
+            conditions.append(&hours_row);
+            conditions.append(&title_row);
+
+            let actions = gtk::ListBox::new();
+            actions.set_selection_mode(gtk::SelectionMode::None);
+            actions.add_css_class("boxed-list");
+            actions.append(&mode_row);
+            actions.append(&notify_row);
+            actions.append(&save_row);
+
+            let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+            content.append(&conditions);
+            content.append(&actions);
+
+            let heading = if existing.is_some() {
+                i18n("Edit Rule")
+            } else {
+                i18n("Add Rule")
+            };
+            let dialog = adw::AlertDialog::new(Some(&heading), None);
+            dialog.set_extra_child(Some(&content));
+            dialog.add_response("cancel", &i18n("_Cancel"));
+            dialog.add_response("save", &i18n("_Save"));
+            dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+            dialog.set_default_response(Some("save"));
+            dialog.set_close_response("cancel");
+
+            let update_sensitivity = clone!(
+                #[weak]
+                dialog,
+                #[weak]
+                name_row,
+                #[weak]
+                title_row,
+                move || {
+                    let valid_pattern = AutomationRule::is_valid_pattern(title_row.text().trim());
+                    if valid_pattern {
+                        title_row.remove_css_class("error");
+                    } else {
+                        title_row.add_css_class("error");
+                    }
+
+                    let valid_name = !name_row.text().trim().is_empty();
+                    dialog.set_response_enabled("save", valid_name && valid_pattern);
+                }
+            );
+            update_sensitivity();
+            name_row.connect_changed(clone!(
+                #[strong]
+                update_sensitivity,
+                move |_| update_sensitivity()
+            ));
+            title_row.connect_changed(move |_| update_sensitivity());
+
+            if dialog.choose_future(Some(&*self.obj())).await != "save" {
+                return;
+            }
+
+            let (station_uuid, station_title) = stations
+                .get(station_row.selected() as usize)
+                .cloned()
+                .unwrap_or_default();
+            let station_title = if station_uuid.is_empty() {
+                String::new()
+            } else {
+                station_title
+            };
+
+            let new_rule = AutomationRule {
+                name: name_row.text().trim().to_string(),
+                enabled: rule.enabled,
+                station_uuid,
+                station_title,
+                hours: hours_row
+                    .enables_expansion()
+                    .then(|| (start_row.value() as u32, end_row.value() as u32)),
+                title_pattern: title_row.text().trim().to_string(),
+                recording_mode: RECORDING_MODES
+                    .get(mode_row.selected() as usize)
+                    .copied()
+                    .flatten(),
+                notify: notify_row.is_active(),
+                save: save_row.is_active(),
+            };
+
+            // Renaming replaces the previous rule
+            if let Some(existing) = existing {
+                if existing.name != new_rule.name {
+                    AutomationRule::delete(&existing.name);
+                }
+            }
+            new_rule.store();
+
+            self.update_rows();
+        }
+
+        fn rule(name: &str) -> Option<AutomationRule> {
+            AutomationRule::all().into_iter().find(|r| r.name == name)
+        }
+
+        /// Describes the conditions and actions of a rule in a single line
+        fn summary(rule: &AutomationRule) -> String {
+            let mut conditions = Vec::new();
+            if rule.station_uuid.is_empty() {
+                conditions.push(i18n("Any station"));
+            } else {
+                conditions.push(rule.station_title.clone());
+            }
+
+            if let Some((start, end)) = rule.hours {
+                conditions.push(i18n_f(
+                    "{}:00 to {}:00",
+                    &[&start.to_string(), &end.to_string()],
+                ));
+            }
+
+            if !rule.title_pattern.is_empty() {
+                conditions.push(i18n_f("title matches “{}”", &[&rule.title_pattern]));
+            }
+
+            let mut actions = Vec::new();
+            if rule.recording_mode.is_some() {
+                actions.push(Self::mode_label(rule.recording_mode));
+            }
+            if rule.notify {
+                actions.push(i18n("Show Notification"));
+            }
+            if rule.save {
+                actions.push(i18n("Save Track"));
+            }
+            if actions.is_empty() {
+                actions.push(i18n("No actions"));
+            }
+
+            format!("{} → {}", conditions.join(", "), actions.join(", "))
+        }
+
+        fn mode_label(mode: Option<SwRecordingMode>) -> String {
+            match mode {
+                None => i18n("Keep Recording Mode"),
+                Some(SwRecordingMode::Everything) => i18n("Save All Tracks"),
+                Some(SwRecordingMode::Decide) => i18n("Decide for Each Track"),
+                Some(SwRecordingMode::Nothing) => i18n("Record Nothing"),
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct SwRulesDialog(ObjectSubclass<imp::SwRulesDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl SwRulesDialog {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl Default for SwRulesDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
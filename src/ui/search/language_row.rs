@@ -0,0 +1,171 @@
+// Shortwave - language_row.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::{Cell, RefCell};
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::{clone, Properties};
+use gtk::glib;
+use inflector::Inflector;
+
+use crate::api::{client, Language};
+use crate::i18n::*;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, Properties)]
+    #[properties(wrapper_type = super::SwLanguageRow)]
+    pub struct SwLanguageRow {
+        /// Name of the selected language, empty for any language
+        #[property(get, set=Self::set_language)]
+        language: RefCell<String>,
+
+        languages: RefCell<Vec<Language>>,
+        /// Language names in the order of the model, the first entry is "Any"
+        names: RefCell<Vec<String>>,
+        updating_model: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwLanguageRow {
+        const NAME: &'static str = "SwLanguageRow";
+        type ParentType = adw::ComboRow;
+        type Type = super::SwLanguageRow;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for SwLanguageRow {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().set_enable_search(true);
+            self.update_model();
+
+            self.obj().connect_selected_notify(|row| {
+                let imp = row.imp();
+                if imp.updating_model.get() {
+                    return;
+                }
+
+                let name = imp
+                    .names
+                    .borrow()
+                    .get(row.selected() as usize)
+                    .cloned()
+                    .unwrap_or_default();
+                row.set_language(name);
+            });
+
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                async move {
+                    match client::languages().await {
+                        Ok(languages) => {
+                            *imp.languages.borrow_mut() = languages;
+                            imp.update_model();
+                        }
+                        Err(e) => warn!("Unable to retrieve languages: {}", e),
+                    }
+                }
+            ));
+        }
+    }
+
+    impl WidgetImpl for SwLanguageRow {}
+
+    impl ListBoxRowImpl for SwLanguageRow {}
+
+    impl PreferencesRowImpl for SwLanguageRow {}
+
+    impl ActionRowImpl for SwLanguageRow {}
+
+    impl ComboRowImpl for SwLanguageRow {}
+
+    impl SwLanguageRow {
+        fn set_language(&self, language: String) {
+            if *self.language.borrow() == language {
+                return;
+            }
+
+            *self.language.borrow_mut() = language;
+            self.update_selection();
+            self.obj().notify_language();
+        }
+
+        fn update_selection(&self) {
+            let language = self.language.borrow().clone();
+            let position = self.names.borrow().iter().position(|n| *n == language);
+
+            match position {
+                Some(position) => {
+                    self.updating_model.set(true);
+                    self.obj().set_selected(position as u32);
+                    self.updating_model.set(false);
+                }
+                None => self.update_model(),
+            }
+        }
+
+        fn update_model(&self) {
+            let model = gtk::StringList::new(&[&i18n("Any")]);
+            let mut names = vec![String::new()];
+
+            for language in self.languages.borrow().iter() {
+                let count = language.stationcount.to_string();
+                model.append(&i18n_f("{} ({})", &[&language.localized_name(), &count]));
+                names.push(language.name.clone());
+            }
+
+            // Keep the selected language, even if it isn't listed (yet)
+            let selected = self.language.borrow().clone();
+            if !names.contains(&selected) {
+                model.append(&selected.to_title_case());
+                names.push(selected.clone());
+            }
+
+            let position = names
+                .iter()
+                .position(|n| *n == selected)
+                .unwrap_or_default();
+            *self.names.borrow_mut() = names;
+
+            self.updating_model.set(true);
+            self.obj().set_model(Some(&model));
+            self.obj().set_selected(position as u32);
+            self.updating_model.set(false);
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct SwLanguageRow(ObjectSubclass<imp::SwLanguageRow>)
+        @extends gtk::Widget, gtk::ListBoxRow, adw::PreferencesRow, adw::ActionRow, adw::ComboRow,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Actionable;
+}
+
+impl SwLanguageRow {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl Default for SwLanguageRow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
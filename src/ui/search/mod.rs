@@ -14,8 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+mod language_row;
 mod search_filter;
 mod search_filter_item;
 
+pub use language_row::SwLanguageRow;
 pub use search_filter::SwSearchFilter;
 pub use search_filter_item::SwSearchFilterItem;
@@ -54,7 +54,7 @@ mod imp {
             box_.set_margin_start(12);
             box_.set_margin_end(12);
 
-            let image = gtk::Image::from_icon_name("remove-filter-symbolic");
+            let image = gtk::Image::from_icon_name("window-close-symbolic");
 
             let label = gtk::Label::new(None);
             self.obj().bind_property("value", &label, "label").build();
@@ -0,0 +1,265 @@
+// Shortwave - shortcuts_dialog.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::RefCell;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::{clone, subclass};
+use gtk::{gdk, glib, CompositeTemplate};
+
+use crate::app::SwApplication;
+use crate::i18n::i18n;
+use crate::settings::shortcuts;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/de/haeckerfelix/Shortwave/gtk/shortcuts_dialog.ui")]
+    pub struct SwShortcutsDialog {
+        #[template_child]
+        page: TemplateChild<adw::PreferencesPage>,
+
+        /// Rows of all editable actions, with the label showing the current accelerator
+        rows: RefCell<
+            Vec<(
+                &'static str,
+                adw::ActionRow,
+                gtk::ShortcutLabel,
+                gtk::Button,
+            )>,
+        >,
+        /// Action whose shortcut is currently being recorded
+        editing: RefCell<Option<&'static str>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwShortcutsDialog {
+        const NAME: &'static str = "SwShortcutsDialog";
+        type ParentType = adw::Dialog;
+        type Type = super::SwShortcutsDialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+            Self::bind_template_callbacks(klass);
+        }
+
+        fn instance_init(obj: &subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for SwShortcutsDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            for (title, actions) in Self::shortcut_groups() {
+                let group = adw::PreferencesGroup::builder().title(title).build();
+
+                for (action, title) in actions {
+                    let row = adw::ActionRow::builder()
+                        .title(title)
+                        .activatable(true)
+                        .build();
+
+                    let reset_button = gtk::Button::builder()
+                        .icon_name("edit-undo-symbolic")
+                        .tooltip_text(i18n("Reset to Default"))
+                        .valign(gtk::Align::Center)
+                        .build();
+                    reset_button.add_css_class("flat");
+                    reset_button.connect_clicked(clone!(
+                        #[weak(rename_to = imp)]
+                        self,
+                        move |_| {
+                            shortcuts::reset(action);
+                            imp.shortcuts_changed();
+                        }
+                    ));
+
+                    let label = gtk::ShortcutLabel::new("");
+                    label.set_disabled_text(&i18n("Disabled"));
+                    label.set_valign(gtk::Align::Center);
+
+                    row.add_suffix(&reset_button);
+                    row.add_suffix(&label);
+                    row.connect_activated(clone!(
+                        #[weak(rename_to = imp)]
+                        self,
+                        move |_| imp.start_editing(action)
+                    ));
+
+                    group.add(&row);
+                    self.rows
+                        .borrow_mut()
+                        .push((action, row, label, reset_button));
+                }
+
+                self.page.add(&group);
+            }
+
+            // Record the next pressed key combination as new shortcut
+            let controller = gtk::EventControllerKey::new();
+            controller.set_propagation_phase(gtk::PropagationPhase::Capture);
+            controller.connect_key_pressed(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                #[upgrade_or]
+                glib::Propagation::Proceed,
+                move |_, keyval, _, state| imp.key_pressed(keyval, state)
+            ));
+            self.obj().add_controller(controller);
+
+            self.update_rows();
+        }
+    }
+
+    impl WidgetImpl for SwShortcutsDialog {}
+
+    impl AdwDialogImpl for SwShortcutsDialog {}
+
+    #[gtk::template_callbacks]
+    impl SwShortcutsDialog {
+        fn shortcut_groups() -> Vec<(String, Vec<(&'static str, String)>)> {
+            vec![
+                (
+                    i18n("General"),
+                    vec![
+                        ("win.show-preferences", i18n("Open preferences")),
+                        ("window.close", i18n("Close the window")),
+                        ("app.quit", i18n("Quit the application")),
+                    ],
+                ),
+                (
+                    i18n("Library"),
+                    vec![
+                        ("win.search", i18n("Search stations")),
+                        ("win.add-local-station", i18n("Add local station")),
+                        ("win.enable-gadget-player", i18n("Switch to gadget player")),
+                        ("win.disable-gadget-player", i18n("Leave gadget player")),
+                    ],
+                ),
+                (
+                    i18n("Playback"),
+                    vec![
+                        ("player.toggle-playback", i18n("Toggle playback")),
+                        ("player.stop-playback", i18n("Stop playback")),
+                        ("player.play-next", i18n("Play next station in queue")),
+                        ("player.play-something", i18n("Play something")),
+                        ("player.jump-to-live", i18n("Jump to live")),
+                        ("player.save-recent", i18n("Save recent audio")),
+                        ("player.show-station-details", i18n("Show station details")),
+                        ("win.show-display-window", i18n("Open display window")),
+                    ],
+                ),
+            ]
+        }
+
+        fn start_editing(&self, action: &'static str) {
+            *self.editing.borrow_mut() = Some(action);
+            self.update_rows();
+        }
+
+        fn key_pressed(&self, keyval: gdk::Key, state: gdk::ModifierType) -> glib::Propagation {
+            let Some(action) = *self.editing.borrow() else {
+                return glib::Propagation::Proceed;
+            };
+
+            let state = state & gtk::accelerator_get_default_mod_mask();
+            match keyval {
+                gdk::Key::Escape if state.is_empty() => (),
+                gdk::Key::BackSpace if state.is_empty() => {
+                    shortcuts::set_accels(action, Vec::new());
+                }
+                _ => {
+                    // Wait until a complete combination got pressed, not only modifiers
+                    if !gtk::accelerator_valid(keyval, state) {
+                        return glib::Propagation::Stop;
+                    }
+
+                    let accel = gtk::accelerator_name(keyval, state).to_string();
+                    self.remove_conflicting_accel(action, &accel);
+                    shortcuts::set_accels(action, vec![accel]);
+                }
+            }
+
+            self.editing.borrow_mut().take();
+            self.shortcuts_changed();
+            glib::Propagation::Stop
+        }
+
+        /// Ensures that an accelerator is only used by a single action
+        fn remove_conflicting_accel(&self, action: &str, accel: &str) {
+            for (other, _) in shortcuts::DEFAULT_ACCELS {
+                if *other == action {
+                    continue;
+                }
+
+                let accels = shortcuts::accels(other);
+                if accels.iter().any(|a| a == accel) {
+                    let accels = accels.into_iter().filter(|a| a != accel).collect();
+                    shortcuts::set_accels(other, accels);
+                }
+            }
+        }
+
+        fn shortcuts_changed(&self) {
+            shortcuts::apply(&SwApplication::default());
+            self.update_rows();
+        }
+
+        fn update_rows(&self) {
+            let editing = *self.editing.borrow();
+
+            for (action, row, label, reset_button) in self.rows.borrow().iter() {
+                if editing == Some(*action) {
+                    row.set_subtitle(&i18n("Press a key combination…"));
+                } else {
+                    row.set_subtitle("");
+                }
+
+                label.set_accelerator(&shortcuts::accels(action).join(" "));
+                reset_button.set_visible(shortcuts::is_customized(action));
+            }
+        }
+
+        #[template_callback]
+        fn reset_all(&self) {
+            self.editing.borrow_mut().take();
+            shortcuts::reset_all();
+            self.shortcuts_changed();
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct SwShortcutsDialog(ObjectSubclass<imp::SwShortcutsDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl SwShortcutsDialog {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl Default for SwShortcutsDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,114 @@
+// Shortwave - sparkline.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::RefCell;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::clone;
+use gtk::{glib, gsk};
+
+const LINE_WIDTH: f32 = 2.0;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct SwSparkline {
+        pub values: RefCell<Vec<f64>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwSparkline {
+        const NAME: &'static str = "SwSparkline";
+        type ParentType = gtk::Widget;
+        type Type = super::SwSparkline;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_css_name("sparkline");
+        }
+    }
+
+    impl ObjectImpl for SwSparkline {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            adw::StyleManager::default().connect_accent_color_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.obj().queue_draw()
+            ));
+        }
+    }
+
+    impl WidgetImpl for SwSparkline {
+        fn measure(&self, orientation: gtk::Orientation, _for_size: i32) -> (i32, i32, i32, i32) {
+            if orientation == gtk::Orientation::Vertical {
+                (24, 24, -1, -1)
+            } else {
+                (96, 96, -1, -1)
+            }
+        }
+
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            let values = self.values.borrow();
+            if values.len() < 2 {
+                return;
+            }
+
+            // Leave room for the line width, so that it doesn't get cut off
+            let margin = LINE_WIDTH / 2.0;
+            let width = self.obj().width() as f32 - LINE_WIDTH;
+            let height = self.obj().height() as f32 - LINE_WIDTH;
+
+            let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            let range = (max - min).max(1.0);
+
+            let builder = gsk::PathBuilder::new();
+            for (i, value) in values.iter().enumerate() {
+                let x = margin + i as f32 / (values.len() - 1) as f32 * width;
+                let y = margin + (1.0 - ((value - min) / range) as f32) * height;
+
+                if i == 0 {
+                    builder.move_to(x, y);
+                } else {
+                    builder.line_to(x, y);
+                }
+            }
+
+            let accent = adw::StyleManager::default().accent_color_rgba();
+            let stroke = gsk::Stroke::new(LINE_WIDTH);
+            stroke.set_line_join(gsk::LineJoin::Round);
+            stroke.set_line_cap(gsk::LineCap::Round);
+            snapshot.append_stroke(&builder.to_path(), &stroke, &accent);
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct SwSparkline(ObjectSubclass<imp::SwSparkline>)
+        @extends gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl SwSparkline {
+    /// Values which get drawn from left to right, scaled to the height of the widget
+    pub fn set_values(&self, values: Vec<f64>) {
+        *self.imp().values.borrow_mut() = values;
+        self.queue_draw();
+    }
+}
@@ -0,0 +1,239 @@
+// Shortwave - station_comparison_dialog.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::{clone, subclass};
+use gtk::{glib, CompositeTemplate};
+
+use crate::api::{StationMetadata, SwStation};
+use crate::app::SwApplication;
+use crate::i18n::*;
+use crate::ui::SwStationCover;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/de/haeckerfelix/Shortwave/gtk/station_comparison_dialog.ui")]
+    pub struct SwStationComparisonDialog {
+        #[template_child]
+        pub(super) grid: TemplateChild<gtk::Grid>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwStationComparisonDialog {
+        const NAME: &'static str = "SwStationComparisonDialog";
+        type ParentType = adw::Dialog;
+        type Type = super::SwStationComparisonDialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        fn instance_init(obj: &subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for SwStationComparisonDialog {}
+
+    impl WidgetImpl for SwStationComparisonDialog {}
+
+    impl AdwDialogImpl for SwStationComparisonDialog {}
+
+    impl SwStationComparisonDialog {
+        pub(super) fn set_stations(&self, stations: &[SwStation]) {
+            let metadata: Vec<StationMetadata> = stations.iter().map(|s| s.metadata()).collect();
+
+            // Header with cover and name of each station
+            for (column, station) in stations.iter().enumerate() {
+                let cover = glib::Object::builder::<SwStationCover>()
+                    .property("size", 64)
+                    .property("station", station)
+                    .property("halign", gtk::Align::Center)
+                    .build();
+
+                let label = gtk::Label::builder()
+                    .label(station.title())
+                    .wrap(true)
+                    .justify(gtk::Justification::Center)
+                    .css_classes(["heading"])
+                    .build();
+
+                let header = gtk::Box::new(gtk::Orientation::Vertical, 6);
+                header.append(&cover);
+                header.append(&label);
+                self.grid.attach(&header, column as i32 + 1, 0, 1, 1);
+            }
+
+            let bitrates: Vec<i32> = metadata.iter().map(|m| m.bitrate).collect();
+            let votes: Vec<i32> = metadata.iter().map(|m| m.votes).collect();
+
+            let rows: [(String, Vec<(String, Option<&str>)>); 6] = [
+                (
+                    i18n("Country"),
+                    metadata
+                        .iter()
+                        .map(|m| (Self::or_unknown(m.localized_country()), None))
+                        .collect(),
+                ),
+                (
+                    i18n("Codec"),
+                    metadata
+                        .iter()
+                        .map(|m| (Self::or_unknown(m.codec.clone()), None))
+                        .collect(),
+                ),
+                (
+                    i18n("Bitrate"),
+                    metadata
+                        .iter()
+                        .map(|m| {
+                            let bitrate = if m.bitrate == 0 {
+                                i18n("Unknown")
+                            } else {
+                                i18n_f("{} kbit/s", &[&m.bitrate.to_string()])
+                            };
+                            (bitrate, Self::best_class(&bitrates, m.bitrate))
+                        })
+                        .collect(),
+                ),
+                (
+                    i18n("Votes"),
+                    metadata
+                        .iter()
+                        .map(|m| (m.votes.to_string(), Self::best_class(&votes, m.votes)))
+                        .collect(),
+                ),
+                (
+                    i18n("Status"),
+                    stations
+                        .iter()
+                        .zip(&metadata)
+                        .map(|(station, m)| {
+                            if station.is_local() {
+                                (i18n("Unknown"), None)
+                            } else if m.is_online() {
+                                (i18n("Online"), Some("success"))
+                            } else {
+                                (i18n("Offline"), Some("error"))
+                            }
+                        })
+                        .collect(),
+                ),
+                (
+                    i18n("Last Online"),
+                    metadata
+                        .iter()
+                        .map(|m| {
+                            let date = Self::format_date(m.lastcheckoktime_iso8601.as_deref());
+                            (date, None)
+                        })
+                        .collect(),
+                ),
+            ];
+
+            for (row, (title, values)) in rows.into_iter().enumerate() {
+                let row = row as i32 + 1;
+
+                let label = gtk::Label::builder()
+                    .label(title)
+                    .xalign(0.0)
+                    .css_classes(["dim-label"])
+                    .build();
+                self.grid.attach(&label, 0, row, 1, 1);
+
+                for (column, (value, css_class)) in values.into_iter().enumerate() {
+                    let label = gtk::Label::builder()
+                        .label(value)
+                        .wrap(true)
+                        .justify(gtk::Justification::Center)
+                        .selectable(true)
+                        .build();
+
+                    if let Some(css_class) = css_class {
+                        label.add_css_class(css_class);
+                    }
+
+                    self.grid.attach(&label, column as i32 + 1, row, 1, 1);
+                }
+            }
+
+            // Add the favored one directly to the library
+            let library = SwApplication::default().library();
+            for (column, station) in stations.iter().enumerate() {
+                let button = gtk::Button::builder()
+                    .label(i18n("_Add to Library"))
+                    .use_underline(true)
+                    .halign(gtk::Align::Center)
+                    .margin_top(6)
+                    .sensitive(!library.contains_station(station))
+                    .css_classes(["pill"])
+                    .build();
+
+                button.connect_clicked(clone!(
+                    #[weak]
+                    station,
+                    move |button| {
+                        SwApplication::default().library().add_station(station);
+                        button.set_sensitive(false);
+                    }
+                ));
+
+                self.grid.attach(&button, column as i32 + 1, 7, 1, 1);
+            }
+        }
+
+        fn or_unknown(value: String) -> String {
+            if value.trim().is_empty() {
+                i18n("Unknown")
+            } else {
+                value
+            }
+        }
+
+        /// Highlights the highest value, as long as not all values are the same
+        fn best_class(values: &[i32], value: i32) -> Option<&'static str> {
+            let max = values.iter().max().copied().unwrap_or_default();
+            let all_equal = values.iter().all(|v| *v == max);
+            (!all_equal && value == max).then_some("success")
+        }
+
+        fn format_date(iso8601: Option<&str>) -> String {
+            iso8601
+                .and_then(|date| glib::DateTime::from_iso8601(date, None).ok())
+                .and_then(|date| date.to_local().ok())
+                .and_then(|date| date.format("%x").ok())
+                .map(|date| date.to_string())
+                .unwrap_or_else(|| i18n("Unknown"))
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct SwStationComparisonDialog(ObjectSubclass<imp::SwStationComparisonDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl SwStationComparisonDialog {
+    pub fn new(stations: &[SwStation]) -> Self {
+        let dialog: Self = glib::Object::new();
+        dialog.imp().set_stations(stations);
+        dialog
+    }
+}
@@ -17,19 +17,24 @@
 use std::cell::Cell;
 use std::cell::RefCell;
 use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::LazyLock;
 
 use adw::prelude::*;
 use adw::subclass::prelude::*;
-use glib::{clone, subclass, Properties};
-use gtk::{gio, glib, pango, CompositeTemplate};
+use glib::subclass::Signal;
+use glib::{clone, subclass, Properties, SignalHandlerId};
+use gtk::{gdk, gio, glib, pango, CompositeTemplate};
 
 use crate::api::SwStation;
 use crate::app::SwApplication;
 use crate::config;
+use crate::i18n::*;
 
 mod imp {
     use super::*;
     static MAX_COVER_SIZE: i32 = 256;
+    /// Smaller covers are too small to show a retry button on top of them
+    static MIN_RETRY_SIZE: i32 = 64;
 
     #[derive(Debug, Default, Properties, CompositeTemplate)]
     #[template(resource = "/de/haeckerfelix/Shortwave/gtk/station_cover.ui")]
@@ -43,6 +48,8 @@ mod imp {
         stack: TemplateChild<gtk::Stack>,
         #[template_child]
         fallback_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        retry_button: TemplateChild<gtk::Button>,
 
         #[property(get, set=Self::set_size)]
         size: Cell<i32>,
@@ -50,8 +57,17 @@ mod imp {
         station: RefCell<Option<SwStation>>,
         #[property(get)]
         is_loaded: Cell<bool>,
+        /// The loaded cover image, if any
+        #[property(get)]
+        texture: RefCell<Option<gdk::Texture>>,
+        /// Whether the cover can be clicked or activated by keyboard
+        #[property(get, set=Self::set_activatable)]
+        activatable: Cell<bool>,
 
         loader_cancellable: RefCell<Option<gio::Cancellable>>,
+        /// Whether the last attempt to load the cover failed
+        failed: Cell<bool>,
+        network_handler: RefCell<Option<SignalHandlerId>>,
     }
 
     #[glib::object_subclass]
@@ -62,7 +78,9 @@ mod imp {
 
         fn class_init(klass: &mut Self::Class) {
             klass.set_css_name("cover");
+            klass.set_accessible_role(gtk::AccessibleRole::Img);
             Self::bind_template(klass);
+            Self::bind_template_callbacks(klass);
         }
 
         fn instance_init(obj: &subclass::InitializingObject<Self>) {
@@ -80,6 +98,60 @@ mod imp {
 
             self.update_initials();
             self.update_font_size();
+            self.update_accessible_label();
+
+            let gesture = gtk::GestureClick::new();
+            gesture.connect_released(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_, _, _, _| imp.activate()
+            ));
+            self.obj().add_controller(gesture);
+
+            let key_controller = gtk::EventControllerKey::new();
+            key_controller.connect_key_pressed(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                #[upgrade_or]
+                glib::Propagation::Proceed,
+                move |_, key, _, _| match key {
+                    gdk::Key::Return
+                    | gdk::Key::KP_Enter
+                    | gdk::Key::ISO_Enter
+                    | gdk::Key::space
+                        if imp.activatable.get() =>
+                    {
+                        imp.activate();
+                        glib::Propagation::Stop
+                    }
+                    _ => glib::Propagation::Proceed,
+                }
+            ));
+            self.obj().add_controller(key_controller);
+
+            // Try again as soon as the network is back
+            let handler = gio::NetworkMonitor::default().connect_network_changed(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_, available| {
+                    if available && imp.failed.get() {
+                        imp.update_cover();
+                    }
+                }
+            ));
+            *self.network_handler.borrow_mut() = Some(handler);
+        }
+
+        fn dispose(&self) {
+            if let Some(handler) = self.network_handler.take() {
+                gio::NetworkMonitor::default().disconnect(handler);
+            }
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: LazyLock<Vec<Signal>> =
+                LazyLock::new(|| vec![Signal::builder("activated").build()]);
+            SIGNALS.as_ref()
         }
     }
 
@@ -97,6 +169,7 @@ mod imp {
 
     impl BinImpl for SwStationCover {}
 
+    #[gtk::template_callbacks]
     impl SwStationCover {
         fn set_size(&self, size: i32) {
             self.size.set(size);
@@ -106,21 +179,24 @@ mod imp {
             self.placeholder_image.set_pixel_size(size.div_euclid(2));
 
             self.update_font_size();
+            self.set_failed(self.failed.get());
         }
 
         fn set_station(&self, station: Option<&SwStation>) {
             *self.station.borrow_mut() = station.cloned();
 
             // Reset previous cover
-            self.image.set_paintable(gtk::gdk::Paintable::NONE);
+            self.set_texture(None);
             self.stack.set_visible_child_name("fallback");
 
             self.is_loaded.set(false);
             self.obj().notify_is_loaded();
+            self.set_failed(false);
 
             // Set fallback initials
             self.update_initials();
             self.update_font_size();
+            self.update_accessible_label();
 
             // Load new cover, but only if it's mapped
             if self.obj().is_mapped() {
@@ -135,6 +211,7 @@ mod imp {
                         imp.is_loaded.set(false);
                         imp.update_cover();
                         imp.update_initials();
+                        imp.update_accessible_label();
                     }
                 ));
 
@@ -175,6 +252,38 @@ mod imp {
             self.update_color_class();
         }
 
+        fn update_accessible_label(&self) {
+            let label = match self.obj().station() {
+                Some(station) => i18n_f("Cover of {}", &[&station.title()]),
+                None => i18n("No Station"),
+            };
+
+            self.obj()
+                .update_property(&[gtk::accessible::Property::Label(&label)]);
+        }
+
+        fn set_activatable(&self, activatable: bool) {
+            self.activatable.set(activatable);
+
+            let obj = self.obj();
+            obj.set_focusable(activatable);
+            obj.set_cursor_from_name(activatable.then_some("pointer"));
+
+            if activatable {
+                obj.update_property(&[gtk::accessible::Property::Description(&i18n(
+                    "Activate to show a larger preview",
+                ))]);
+            } else {
+                obj.reset_property(gtk::AccessibleProperty::Description);
+            }
+        }
+
+        fn activate(&self) {
+            if self.activatable.get() {
+                self.obj().emit_by_name::<()>("activated", &[]);
+            }
+        }
+
         fn first_char(word: &str) -> Option<char> {
             word.chars()
                 .filter(|c| c.is_alphabetic())
@@ -237,10 +346,7 @@ mod imp {
                 // First check whether we have some custom cover for that station
                 // Usually only for local added stations
                 if let Some(texture) = station.custom_cover() {
-                    // Scale the texture to match the widget size
-                    let size = self.obj().size();
-                    self.image.set_pixel_size(size);
-                    self.image.set_paintable(Some(&texture));
+                    self.set_texture(Some(texture));
                     self.stack.set_visible_child_name("image");
 
                     self.is_loaded.set(true);
@@ -251,17 +357,17 @@ mod imp {
                     let cancellable = gio::Cancellable::new();
                     *self.loader_cancellable.borrow_mut() = Some(cancellable.clone());
 
+                    self.set_failed(false);
+                    self.stack.set_visible_child_name("loading");
+
                     let size = MAX_COVER_SIZE * self.obj().scale_factor();
                     let res = cover_loader
-                        .load_cover(&favicon_url, size, cancellable.clone())
+                        .load_cover(&favicon_url, size, cancellable.clone(), &*self.obj())
                         .await;
 
                     match res {
                         Ok(texture) => {
-                            // Scale the texture to match the widget size
-                            let size = self.obj().size();
-                            self.image.set_pixel_size(size);
-                            self.image.set_paintable(Some(&texture));
+                            self.set_texture(Some(texture));
                             self.stack.set_visible_child_name("image");
 
                             self.is_loaded.set(true);
@@ -274,8 +380,11 @@ mod imp {
                                     station.title(),
                                     station.metadata().favicon.map(|f| f.to_string()),
                                     e.root_cause().to_string()
-                                )
+                                );
+                                self.set_failed(true);
                             }
+
+                            self.stack.set_visible_child_name("fallback");
                         }
                     }
                 } else if station.title().is_empty() {
@@ -288,6 +397,41 @@ mod imp {
             }
         }
 
+        fn set_texture(&self, texture: Option<gdk::Texture>) {
+            // Scale the texture to match the widget size
+            self.image.set_pixel_size(self.obj().size());
+            self.image.set_paintable(texture.as_ref());
+
+            *self.texture.borrow_mut() = texture;
+            self.obj().notify_texture();
+        }
+
+        fn set_failed(&self, failed: bool) {
+            self.failed.set(failed);
+            self.retry_button
+                .set_visible(failed && self.obj().size() >= MIN_RETRY_SIZE);
+        }
+
+        #[template_callback]
+        fn retry(&self) {
+            let Some(favicon_url) = self.obj().station().and_then(|s| s.metadata().favicon) else {
+                return;
+            };
+
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                async move {
+                    // Otherwise the cover loader doesn't try again for a while
+                    SwApplication::default()
+                        .cover_loader()
+                        .forget_failure(&favicon_url)
+                        .await;
+                    imp.load_cover().await;
+                }
+            ));
+        }
+
         fn cancel(&self) {
             if let Some(cancellable) = self.loader_cancellable.borrow_mut().take() {
                 cancellable.cancel();
@@ -14,19 +14,21 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::cell::OnceCell;
+use std::cell::{OnceCell, RefCell};
 
 use adw::prelude::*;
 use adw::subclass::prelude::*;
-use glib::{subclass, Properties};
+use glib::{clone, subclass, Properties};
 use gtk::{gdk, glib, CompositeTemplate};
-use inflector::Inflector;
 use shumate::prelude::*;
 
-use crate::api::SwStation;
+use crate::api::{client, StationMetadata, SwStation};
 use crate::app::SwApplication;
+use crate::database::{
+    HttpSettings, StationCredentials, StationPopularity, StationSettings, TitleParsingRules,
+};
 use crate::i18n::{i18n, i18n_f};
-use crate::ui::SwStationCover;
+use crate::ui::{DisplayError, SwCoverDialog, SwSparkline, SwStationCover};
 
 mod imp {
     use super::*;
@@ -60,10 +62,24 @@ mod imp {
         #[template_child]
         codec_row: TemplateChild<adw::ActionRow>,
         #[template_child]
+        status_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        status_dot: TemplateChild<gtk::Box>,
+        #[template_child]
         bitrate_row: TemplateChild<adw::ActionRow>,
         #[template_child]
         stream_row: TemplateChild<adw::ActionRow>,
         #[template_child]
+        popularity_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        clicks_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        sparkline: TemplateChild<SwSparkline>,
+        #[template_child]
+        trend_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        votes_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
         location_group: TemplateChild<adw::PreferencesGroup>,
         #[template_child]
         country_row: TemplateChild<adw::ActionRow>,
@@ -75,7 +91,29 @@ mod imp {
         map: TemplateChild<shumate::Map>,
         #[template_child]
         map_license: TemplateChild<shumate::License>,
+        #[template_child]
+        notifications_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        strip_prefixes_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        separator_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        swap_artist_title_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        backup_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        user_agent_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        referer_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        headers_view: TemplateChild<gtk::TextView>,
+        #[template_child]
+        username_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        password_row: TemplateChild<adw::PasswordEntryRow>,
         marker: shumate::Marker,
+        /// Uuids of the stations listed in the backup station row
+        backup_uuids: RefCell<Vec<Option<String>>>,
 
         #[property(get, set, construct_only)]
         station: OnceCell<SwStation>,
@@ -88,8 +126,25 @@ mod imp {
         type Type = super::SwStationDialog;
 
         fn class_init(klass: &mut Self::Class) {
+            SwSparkline::static_type();
             Self::bind_template(klass);
             klass.bind_template_callbacks();
+
+            klass.install_action_async(
+                "station.enqueue",
+                Some(glib::VariantTy::UINT32),
+                |dialog, _, target| async move {
+                    let duration = target.and_then(|t| t.get::<u32>()).unwrap_or_default();
+                    let player = SwApplication::default().player();
+                    player.enqueue_station(&dialog.station(), duration);
+                    dialog.close();
+
+                    // Start with the queue right away if nothing is playing yet
+                    if !player.has_station() {
+                        player.play_next().await;
+                    }
+                },
+            );
         }
 
         fn instance_init(obj: &subclass::InitializingObject<Self>) {
@@ -159,11 +214,11 @@ mod imp {
             }
 
             // Language
-            if !metadata.language.is_empty() {
+            let language = metadata.localized_language();
+            if !language.is_empty() {
                 self.information_group.set_visible(true);
                 self.language_row.set_visible(true);
-                self.language_row
-                    .set_subtitle(&metadata.language.to_title_case());
+                self.language_row.set_subtitle(&language);
             }
 
             // Tags
@@ -173,11 +228,26 @@ mod imp {
                 self.tags_row.set_subtitle(&metadata.formatted_tags());
             }
 
+            // Popularity, local stations aren't part of radio-browser
+            if !self.station.get().unwrap().is_local() {
+                self.popularity_group.set_visible(true);
+                self.update_popularity(&metadata);
+
+                glib::spawn_future_local(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    async move {
+                        imp.refresh_popularity().await;
+                    }
+                ));
+            }
+
             // Location
-            if !metadata.country.is_empty() {
+            let country = metadata.localized_country();
+            if !country.is_empty() {
                 self.location_group.set_visible(true);
                 self.country_row.set_visible(true);
-                self.country_row.set_subtitle(&metadata.country);
+                self.country_row.set_subtitle(&country);
             }
             if !metadata.state.is_empty() {
                 self.location_group.set_visible(true);
@@ -195,6 +265,30 @@ mod imp {
                 self.map.center_on(lat, long);
             }
 
+            // Online status, local stations don't get checked by radio-browser
+            if !self.station.get().unwrap().is_local() {
+                self.status_row.set_visible(true);
+
+                if metadata.is_online() {
+                    self.status_row.set_subtitle(&i18n("Online"));
+                    self.status_dot.add_css_class("online");
+                } else {
+                    let last_online = metadata
+                        .lastcheckoktime_iso8601
+                        .as_deref()
+                        .and_then(|date| glib::DateTime::from_iso8601(date, None).ok())
+                        .and_then(|date| date.to_local().ok())
+                        .and_then(|date| date.format("%x").ok());
+
+                    let subtitle = match last_online {
+                        Some(date) => i18n_f("Offline, last online on {}", &[&date]),
+                        None => i18n("Offline"),
+                    };
+                    self.status_row.set_subtitle(&subtitle);
+                    self.status_dot.add_css_class("offline");
+                }
+            }
+
             // Codec
             if !metadata.codec.is_empty() {
                 self.codec_row.set_visible(true);
@@ -219,6 +313,98 @@ mod imp {
 
             self.stream_row.set_subtitle(&subtitle);
             self.stream_row.set_tooltip_text(Some(&url));
+
+            let settings = StationSettings::load(&station.uuid());
+
+            // Notifications
+            self.notifications_row
+                .set_active(!settings.mute_notifications);
+
+            // Title parsing rules
+            let rules = settings.title_parsing;
+            self.strip_prefixes_row
+                .set_text(&rules.strip_prefixes.join(", "));
+            self.separator_row.set_text(&rules.separator);
+            self.swap_artist_title_row
+                .set_active(rules.swap_artist_title);
+
+            // Backup station
+            let mut stations = SwApplication::default().library().stations();
+            stations.retain(|s| s.uuid() != station.uuid());
+            stations.sort_by_key(|s| s.title().to_lowercase());
+
+            let names = gtk::StringList::new(&[&i18n("None")]);
+            let mut uuids = vec![None];
+            for backup in stations {
+                names.append(&backup.title());
+                uuids.push(Some(backup.uuid()));
+            }
+
+            let selected = uuids
+                .iter()
+                .position(|uuid| *uuid == settings.backup_station)
+                .unwrap_or_default();
+            *self.backup_uuids.borrow_mut() = uuids;
+
+            self.backup_row.set_model(Some(&names));
+            self.backup_row.set_selected(selected as u32);
+            self.backup_row.connect_selected_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.store_backup_station()
+            ));
+
+            // HTTP options
+            let mut headers = settings.http.headers;
+            let referer = headers.remove("Referer").unwrap_or_default();
+            self.user_agent_row.set_text(&settings.http.user_agent);
+            self.referer_row.set_text(&referer);
+            self.headers_view
+                .buffer()
+                .set_text(&HttpSettings::format_headers(&headers));
+
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                async move {
+                    imp.load_credentials().await;
+                }
+            ));
+        }
+
+        fn update_popularity(&self, metadata: &StationMetadata) {
+            let clicks = i18n_f(
+                "{} in the last 24 hours",
+                &[&metadata.clickcount.to_string()],
+            );
+            self.clicks_row.set_subtitle(&clicks);
+            self.trend_row
+                .set_subtitle(&format!("{:+}", metadata.clicktrend));
+            self.votes_row.set_subtitle(&metadata.votes.to_string());
+
+            // Only worth drawing when there's something to compare with
+            let uuid = self.obj().station().uuid();
+            let values: Vec<f64> = StationPopularity::click_counts(&uuid)
+                .into_iter()
+                .map(f64::from)
+                .collect();
+            self.sparkline.set_visible(values.len() >= 2);
+            self.sparkline.set_values(values);
+        }
+
+        /// Fetches the current numbers from radio-browser, since the stored
+        /// metadata can be outdated
+        async fn refresh_popularity(&self) {
+            let uuid = self.obj().station().uuid();
+
+            match client::station_metadata_by_uuid(&uuid).await {
+                Ok(Some(metadata)) => {
+                    StationPopularity::record(&uuid, &metadata);
+                    self.update_popularity(&metadata);
+                }
+                Ok(None) => (),
+                Err(err) => warn!("Unable to refresh station popularity: {err}"),
+            }
         }
 
         fn setup_map_widget(&self) {
@@ -246,6 +432,11 @@ mod imp {
             self.map_license.append_map_source(&source);
         }
 
+        #[template_callback]
+        fn show_cover(&self) {
+            SwCoverDialog::new(&self.obj().station()).present(Some(&*self.obj()));
+        }
+
         #[template_callback]
         fn add_station(&self) {
             let obj = self.obj();
@@ -268,6 +459,23 @@ mod imp {
             obj.close();
         }
 
+        #[template_callback]
+        async fn record_in_background(&self) {
+            let station = self.obj().station();
+            let res = SwApplication::default()
+                .recorders()
+                .start_recorder(&station)
+                .await;
+            res.handle_error(i18n("Unable to start recording"));
+
+            if res.is_ok() {
+                let toast = adw::Toast::new(&i18n("Recording in background"));
+                toast.set_button_label(Some(&i18n("_Show")));
+                toast.set_action_name(Some("win.show-recorders"));
+                self.toast_overlay.add_toast(toast);
+            }
+        }
+
         #[template_callback]
         async fn start_playback(&self) {
             let obj = self.obj();
@@ -292,6 +500,98 @@ mod imp {
             obj.close();
         }
 
+        #[template_callback]
+        fn store_title_parsing_rules(&self) {
+            let uuid = self.obj().station().uuid();
+            let mut settings = StationSettings::load(&uuid);
+
+            settings.title_parsing = TitleParsingRules {
+                strip_prefixes: self
+                    .strip_prefixes_row
+                    .text()
+                    .split(',')
+                    .map(|prefix| prefix.trim().to_string())
+                    .filter(|prefix| !prefix.is_empty())
+                    .collect(),
+                separator: self.separator_row.text().to_string(),
+                swap_artist_title: self.swap_artist_title_row.is_active(),
+            };
+            settings.store(&uuid);
+        }
+
+        #[template_callback]
+        fn store_notification_settings(&self) {
+            let uuid = self.obj().station().uuid();
+            let mut settings = StationSettings::load(&uuid);
+
+            settings.mute_notifications = !self.notifications_row.is_active();
+            settings.store(&uuid);
+        }
+
+        fn store_backup_station(&self) {
+            let uuid = self.obj().station().uuid();
+            let mut settings = StationSettings::load(&uuid);
+
+            let selected = self.backup_row.selected() as usize;
+            settings.backup_station = self
+                .backup_uuids
+                .borrow()
+                .get(selected)
+                .cloned()
+                .flatten();
+            settings.store(&uuid);
+        }
+
+        #[template_callback]
+        fn store_http_settings(&self) {
+            let uuid = self.obj().station().uuid();
+            let mut settings = StationSettings::load(&uuid);
+
+            let buffer = self.headers_view.buffer();
+            let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+            let mut headers = HttpSettings::parse_headers(&text);
+            let referer = self.referer_row.text().trim().to_string();
+            if !referer.is_empty() {
+                headers.insert("Referer".into(), referer);
+            }
+
+            settings.http.user_agent = self.user_agent_row.text().trim().to_string();
+            settings.http.headers = headers;
+            settings.store(&uuid);
+        }
+
+        #[template_callback]
+        async fn store_credentials(&self) {
+            let station = self.obj().station();
+            let username = self.username_row.text();
+            let password = self.password_row.text();
+
+            StationCredentials::update(
+                &station.uuid(),
+                &station.title(),
+                username.trim(),
+                &password,
+            )
+            .await
+            .handle_error(i18n("Unable to store station credentials"));
+        }
+
+        async fn load_credentials(&self) {
+            let uuid = self.obj().station().uuid();
+            if !StationSettings::load(&uuid).http.authentication {
+                return;
+            }
+
+            match StationCredentials::load(&uuid).await {
+                Ok(Some(credentials)) => {
+                    self.username_row.set_text(&credentials.username);
+                    self.password_row.set_text(&credentials.password);
+                }
+                Ok(None) => (),
+                Err(err) => warn!("Unable to load station credentials: {err}"),
+            }
+        }
+
         #[template_callback]
         fn copy_stream_clipboard(&self) {
             let metadata = self.obj().station().metadata();
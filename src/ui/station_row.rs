@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 
 use adw::subclass::prelude::*;
 use glib::clone;
@@ -22,13 +22,13 @@ use glib::subclass;
 use glib::Properties;
 use gtk::prelude::*;
 use gtk::{glib, CompositeTemplate};
-use inflector::Inflector;
 
 use crate::api::StationMetadata;
 use crate::api::SwStation;
-use crate::ui::SwStationCover;
+use crate::settings::{settings_manager, Key};
+use crate::ui::{SwGridDensity, SwStationCover};
 use crate::SwApplication;
-use crate::i18n::i18n;
+use crate::i18n::{i18n, i18n_f};
 
 mod imp {
     use super::*;
@@ -40,8 +40,12 @@ mod imp {
         #[template_child]
         station_label: TemplateChild<gtk::Label>,
         #[template_child]
+        status_dot: TemplateChild<gtk::Box>,
+        #[template_child]
         subtitle_label: TemplateChild<gtk::Label>,
         #[template_child]
+        badges_box: TemplateChild<gtk::Box>,
+        #[template_child]
         station_cover: TemplateChild<SwStationCover>,
         #[template_child]
         local_image: TemplateChild<gtk::Image>,
@@ -52,6 +56,8 @@ mod imp {
 
         #[property(get, set=Self::set_station)]
         station: RefCell<Option<SwStation>>,
+        #[property(get, set=Self::set_density, builder(SwGridDensity::default()))]
+        density: Cell<SwGridDensity>,
     }
 
     #[glib::object_subclass]
@@ -74,6 +80,8 @@ mod imp {
         fn constructed(&self) {
             self.parent_constructed();
 
+            settings_manager::bind_property(Key::AppearanceGridDensity, &*self.obj(), "density");
+
             // Connect to player state changes to update play button icon
             let player = SwApplication::default().player();
             player.connect_state_notify(clone!(
@@ -123,6 +131,17 @@ mod imp {
     impl BinImpl for SwStationRow {}
 
     impl SwStationRow {
+        fn set_density(&self, density: SwGridDensity) {
+            self.density.set(density);
+
+            let (cover_size, width) = match density {
+                SwGridDensity::Comfortable => (64, 300),
+                SwGridDensity::Compact => (48, 240),
+            };
+            self.station_cover.set_size(cover_size);
+            self.obj().set_width_request(width);
+        }
+
         fn set_station(&self, station: Option<&SwStation>) {
             if let Some(station) = station {
                 station.connect_metadata_notify(clone!(
@@ -143,7 +162,22 @@ mod imp {
 
         fn set_metadata(&self, metadata: StationMetadata) {
             self.station_label.set_text(&metadata.name);
-            let mut subtitle = metadata.country.to_title_case();
+            self.update_badges(&metadata);
+
+            // Local stations don't get checked by radio-browser
+            let is_local = self.station.borrow().as_ref().is_some_and(|s| s.is_local());
+            self.status_dot.set_visible(!is_local);
+            if metadata.is_online() {
+                self.status_dot.remove_css_class("offline");
+                self.status_dot.add_css_class("online");
+                self.status_dot.set_tooltip_text(Some(&i18n("Online")));
+            } else {
+                self.status_dot.remove_css_class("online");
+                self.status_dot.add_css_class("offline");
+                self.status_dot.set_tooltip_text(Some(&i18n("Offline")));
+            }
+
+            let mut subtitle = metadata.localized_country();
 
             if subtitle.is_empty() {
                 subtitle = metadata.tags;
@@ -155,6 +189,42 @@ mod imp {
             self.subtitle_label.set_visible(!subtitle.is_empty());
         }
         
+        /// Shows the stream quality, so that stations can be compared at a glance
+        fn update_badges(&self, metadata: &StationMetadata) {
+            while let Some(child) = self.badges_box.first_child() {
+                self.badges_box.remove(&child);
+            }
+
+            let mut badges = Vec::new();
+            if metadata.bitrate > 0 {
+                let bitrate = metadata.bitrate.to_string();
+                badges.push((
+                    i18n_f("{}k", &[&bitrate]),
+                    i18n_f("{} kbit/s", &[&bitrate]),
+                ));
+            }
+
+            let codec = metadata.codec.trim().to_uppercase();
+            if !codec.is_empty() && codec != "UNKNOWN" {
+                badges.push((codec, i18n("Codec")));
+            }
+
+            if metadata.hls == 1 {
+                badges.push(("HLS".into(), i18n("HTTP Live Streaming")));
+            }
+
+            for (label, tooltip) in &badges {
+                let badge = gtk::Label::builder()
+                    .label(label)
+                    .tooltip_text(tooltip)
+                    .css_classes(["badge"])
+                    .build();
+                self.badges_box.append(&badge);
+            }
+
+            self.badges_box.set_visible(!badges.is_empty());
+        }
+
         fn update_play_button_icon(&self) {
             let player = SwApplication::default().player();
             let current_station = player.station();
@@ -18,12 +18,13 @@ use std::cell::RefCell;
 
 use adw::prelude::*;
 use adw::subclass::prelude::*;
-use glib::{subclass, Properties};
+use glib::{clone, subclass, Properties};
 use gtk::{gio, glib, CompositeTemplate};
 
-use super::SwStationDialog;
+use super::{SwStationDialog, SwTrackPreview};
 use crate::app::SwApplication;
 use crate::audio::{SwRecordingMode, SwRecordingState, SwTrack};
+use crate::i18n::i18n;
 use crate::utils;
 
 mod imp {
@@ -48,13 +49,19 @@ mod imp {
         #[template_child]
         save_track_switch: TemplateChild<gtk::Switch>,
         #[template_child]
-        cancel_button: TemplateChild<gtk::Button>,
+        recording_buttons_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pause_button: TemplateChild<gtk::Button>,
         #[template_child]
         save_button: TemplateChild<gtk::Button>,
         #[template_child]
         play_button: TemplateChild<gtk::Button>,
         #[template_child]
         recording_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        playback_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        track_preview: TemplateChild<SwTrackPreview>,
 
         #[property(get, set, construct_only, type=SwTrack)]
         track: RefCell<Option<SwTrack>>,
@@ -83,7 +90,9 @@ mod imp {
             let player = SwApplication::default().player();
 
             let track = self.obj().track();
+            track.hold();
             track.insert_actions(&*self.obj());
+            self.track_preview.set_track(Some(&track));
 
             track
                 .bind_property("state", &*self.subtitle_label, "label")
@@ -107,7 +116,7 @@ mod imp {
                 .transform_to(|b, d: u64| {
                     let duration = utils::format_duration(d, false);
                     let track = b.source().unwrap().downcast::<SwTrack>().unwrap();
-                    let file = track.file();
+                    let file = track.saved_to().unwrap_or(track.file());
 
                     Some(
                         if let Ok(res) = file.measure_disk_usage(
@@ -151,19 +160,31 @@ mod imp {
                 .build();
 
             player
-                .bind_property("recording-mode", &*self.save_track_row, "visible")
+                .bind_property("active-recording-mode", &*self.save_track_row, "visible")
                 .transform_to(|_, state: SwRecordingMode| Some(state == SwRecordingMode::Decide))
                 .sync_create()
                 .build();
 
             track
-                .bind_property("state", &*self.cancel_button, "visible")
+                .bind_property("state", &*self.recording_buttons_box, "visible")
                 .transform_to(|_, state: SwRecordingState| {
                     Some(state == SwRecordingState::Recording)
                 })
                 .sync_create()
                 .build();
 
+            player
+                .bind_property("recording-paused", &*self.pause_button, "label")
+                .transform_to(|_, paused: bool| {
+                    if paused {
+                        Some(i18n("Resume Recording"))
+                    } else {
+                        Some(i18n("Pause Recording"))
+                    }
+                })
+                .sync_create()
+                .build();
+
             track
                 .bind_property("state", &*self.save_button, "visible")
                 .transform_to(|_, state: SwRecordingState| {
@@ -181,10 +202,17 @@ mod imp {
                 .sync_create()
                 .build();
 
-            track
-                .bind_property("is-saved", &*self.play_button, "visible")
-                .sync_create()
-                .build();
+            track.connect_is_saved_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update_playback()
+            ));
+            player.connect_file_track_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update_playback()
+            ));
+            self.update_playback();
 
             self.recording_label.connect_activate_link(|label, _| {
                 label
@@ -195,6 +223,10 @@ mod imp {
                 glib::Propagation::Stop
             });
         }
+
+        fn dispose(&self) {
+            self.obj().track().release();
+        }
     }
 
     impl WidgetImpl for SwTrackDialog {}
@@ -203,6 +235,16 @@ mod imp {
 
     #[gtk::template_callbacks]
     impl SwTrackDialog {
+        fn update_playback(&self) {
+            let track = self.obj().track();
+            let player = SwApplication::default().player();
+            let is_playing_track = player.file_track().as_ref() == Some(&track);
+
+            self.play_button
+                .set_visible(track.is_saved() && !is_playing_track);
+            self.playback_box.set_visible(is_playing_track);
+        }
+
         #[template_callback]
         fn show_station_details(&self) {
             let dialog = SwStationDialog::new(&self.obj().track().station());
@@ -0,0 +1,205 @@
+// Shortwave - track_preview.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::{Cell, RefCell};
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::{clone, subclass, Properties};
+use gtk::{glib, CompositeTemplate};
+
+use crate::app::SwApplication;
+use crate::audio::{SwPlaybackState, SwTrack};
+use crate::i18n::i18n;
+use crate::utils;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, Properties, CompositeTemplate)]
+    #[template(resource = "/de/haeckerfelix/Shortwave/gtk/track_preview.ui")]
+    #[properties(wrapper_type = super::SwTrackPreview)]
+    pub struct SwTrackPreview {
+        #[template_child]
+        toggle_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        next_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        position_revealer: TemplateChild<gtk::Revealer>,
+        #[template_child]
+        position_scale: TemplateChild<gtk::Scale>,
+        #[template_child]
+        position_label: TemplateChild<gtk::Label>,
+
+        #[property(get, set=Self::set_track)]
+        track: RefCell<Option<SwTrack>>,
+        /// Whether the played position is shown as text next to the slider
+        #[property(get, set)]
+        show_position: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwTrackPreview {
+        const NAME: &'static str = "SwTrackPreview";
+        type ParentType = adw::Bin;
+        type Type = super::SwTrackPreview;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+            Self::bind_template_callbacks(klass);
+        }
+
+        fn instance_init(obj: &subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for SwTrackPreview {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let player = SwApplication::default().player();
+
+            player.connect_file_track_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update()
+            ));
+            player.connect_file_state_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update()
+            ));
+            player.connect_file_position_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update()
+            ));
+            player.connect_file_remote_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update()
+            ));
+
+            self.update();
+        }
+    }
+
+    impl WidgetImpl for SwTrackPreview {}
+
+    impl BinImpl for SwTrackPreview {}
+
+    #[gtk::template_callbacks]
+    impl SwTrackPreview {
+        fn set_track(&self, track: Option<SwTrack>) {
+            if let Some(track) = &track {
+                track.connect_is_saved_notify(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move |_| imp.update()
+                ));
+            }
+
+            *self.track.borrow_mut() = track;
+            self.update();
+        }
+
+        fn is_playing_track(&self) -> bool {
+            let track = self.obj().track();
+            track.is_some() && SwApplication::default().player().file_track() == track
+        }
+
+        fn update(&self) {
+            let player = SwApplication::default().player();
+            let is_saved = self.obj().track().is_some_and(|track| track.is_saved());
+            self.obj().set_visible(is_saved);
+
+            let is_playing_track = self.is_playing_track();
+            self.position_revealer.set_reveal_child(is_playing_track);
+
+            // Skipping is only offered for tracks which are played on a DLNA renderer
+            self.next_button
+                .set_visible(is_playing_track && player.file_remote());
+            self.next_button.set_sensitive(player.has_next_track());
+
+            if is_playing_track && player.file_state() == SwPlaybackState::Playing {
+                self.toggle_button
+                    .set_icon_name("media-playback-pause-symbolic");
+                self.toggle_button.set_tooltip_text(Some(&i18n("Pause")));
+            } else {
+                self.toggle_button
+                    .set_icon_name("media-playback-start-symbolic");
+                self.toggle_button.set_tooltip_text(Some(&i18n("Play")));
+            }
+
+            if !is_playing_track {
+                return;
+            }
+
+            let adjustment = self.position_scale.adjustment();
+            adjustment.set_upper(player.file_duration());
+            adjustment.set_value(player.file_position());
+
+            let position = utils::format_duration(player.file_position() as u64, false);
+            self.position_label.set_label(&position);
+        }
+
+        #[template_callback]
+        fn toggle_playback(&self) {
+            let player = SwApplication::default().player();
+
+            if self.is_playing_track() {
+                player.toggle_track_playback();
+            } else if let Some(track) = self.obj().track() {
+                track.play();
+            }
+        }
+
+        #[template_callback]
+        async fn play_next(&self) {
+            SwApplication::default().player().play_next_track().await;
+        }
+
+        #[template_callback]
+        fn seek(
+            &self,
+            _scroll: gtk::ScrollType,
+            value: f64,
+            _scale: &gtk::Scale,
+        ) -> glib::Propagation {
+            SwApplication::default().player().seek_track(value);
+            glib::Propagation::Proceed
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct SwTrackPreview(ObjectSubclass<imp::SwTrackPreview>)
+        @extends gtk::Widget, adw::Bin,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl SwTrackPreview {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl Default for SwTrackPreview {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -14,15 +14,16 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::cell::OnceCell;
+use std::cell::{Cell, OnceCell};
 
 use adw::prelude::*;
 use adw::subclass::prelude::*;
-use glib::{subclass, Properties};
+use glib::{clone, subclass, Properties};
 use gtk::{glib, CompositeTemplate};
 
 use crate::audio::SwRecordingState;
 use crate::audio::SwTrack;
+use crate::ui::SwTrackPreview;
 use crate::utils;
 
 mod imp {
@@ -38,9 +39,17 @@ mod imp {
         pub save_button: TemplateChild<gtk::Button>,
         #[template_child]
         pub saved_checkmark_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub track_preview: TemplateChild<SwTrackPreview>,
+        #[template_child]
+        select_check: TemplateChild<gtk::CheckButton>,
 
         #[property(get, set, construct_only)]
         pub track: OnceCell<SwTrack>,
+        #[property(get, set=Self::set_selection_mode)]
+        selection_mode: Cell<bool>,
+        #[property(get, set)]
+        selected: Cell<bool>,
     }
 
     #[glib::object_subclass]
@@ -115,6 +124,17 @@ mod imp {
                 .bind_property("is-saved", &*self.saved_checkmark_button, "visible")
                 .sync_create()
                 .build();
+
+            track.connect_state_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update_select_check()
+            ));
+            track.connect_is_saved_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.update_select_check()
+            ));
         }
     }
 
@@ -126,7 +146,31 @@ mod imp {
 
     impl ActionRowImpl for SwTrackRow {
         fn activate(&self) {
-            SwApplication::default().show_track_dialog(&self.obj().track());
+            if !self.obj().selection_mode() {
+                SwApplication::default().show_track_dialog(&self.obj().track());
+            } else if self.select_check.is_sensitive() {
+                self.obj().set_selected(!self.obj().selected());
+            }
+        }
+    }
+
+    impl SwTrackRow {
+        fn set_selection_mode(&self, selection_mode: bool) {
+            self.selection_mode.set(selection_mode);
+            self.update_select_check();
+        }
+
+        /// Only tracks which can still be saved or discarded can be selected
+        fn update_select_check(&self) {
+            let track = self.obj().track();
+            let selectable = track.state().is_recorded() && !track.is_saved();
+
+            self.select_check.set_visible(self.obj().selection_mode());
+            self.select_check.set_sensitive(selectable);
+
+            if !selectable || !self.obj().selection_mode() {
+                self.obj().set_selected(false);
+            }
         }
     }
 }
@@ -0,0 +1,211 @@
+// Shortwave - trim_dialog.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::{Cell, RefCell};
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::{clone, subclass, Properties};
+use gtk::{glib, CompositeTemplate};
+
+use super::SwWaveform;
+use crate::audio::{transcoder, SwTrack};
+use crate::i18n::i18n;
+
+/// Number of bars the waveform consists of
+const WAVEFORM_SECTIONS: u64 = 200;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, Properties, CompositeTemplate)]
+    #[template(resource = "/de/haeckerfelix/Shortwave/gtk/trim_dialog.ui")]
+    #[properties(wrapper_type = super::SwTrimDialog)]
+    pub struct SwTrimDialog {
+        #[template_child]
+        toast_overlay: TemplateChild<adw::ToastOverlay>,
+        #[template_child]
+        stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        waveform: TemplateChild<SwWaveform>,
+        #[template_child]
+        times_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        start_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        end_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        trim_button: TemplateChild<gtk::Button>,
+
+        #[property(get, set, construct_only, type=SwTrack)]
+        track: RefCell<Option<SwTrack>>,
+        is_trimming: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwTrimDialog {
+        const NAME: &'static str = "SwTrimDialog";
+        type ParentType = adw::Dialog;
+        type Type = super::SwTrimDialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            SwWaveform::static_type();
+
+            Self::bind_template(klass);
+            Self::bind_template_callbacks(klass);
+        }
+
+        fn instance_init(obj: &subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for SwTrimDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            for row in [&*self.start_row, &*self.end_row] {
+                row.connect_value_notify(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move |_| imp.update_trim_button()
+                ));
+            }
+
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                async move {
+                    imp.load_waveform().await;
+                }
+            ));
+        }
+    }
+
+    impl WidgetImpl for SwTrimDialog {}
+
+    impl AdwDialogImpl for SwTrimDialog {}
+
+    #[gtk::template_callbacks]
+    impl SwTrimDialog {
+        async fn load_waveform(&self) {
+            let Some(file) = self.obj().track().saved_to() else {
+                return;
+            };
+
+            let res = match transcoder::duration(&file).await {
+                Ok(duration) => transcoder::waveform(&file, duration, WAVEFORM_SECTIONS)
+                    .await
+                    .map(|peaks| (duration, peaks)),
+                Err(err) => Err(err),
+            };
+
+            let (duration, peaks) = match res {
+                Ok(res) => res,
+                Err(err) => {
+                    warn!("Unable to load waveform: {err}");
+                    let toast = adw::Toast::new(&i18n("Unable to load waveform"));
+                    self.toast_overlay.add_toast(toast);
+                    self.stack.set_visible(false);
+                    return;
+                }
+            };
+
+            let duration = duration.mseconds() as f64 / 1000.0;
+            self.waveform.set_duration(duration);
+            self.waveform.set_end(duration);
+            self.waveform.set_peaks(peaks);
+
+            for row in [&*self.start_row, &*self.end_row] {
+                row.adjustment().set_upper(duration);
+            }
+
+            self.waveform
+                .bind_property("start", &*self.start_row, "value")
+                .sync_create()
+                .bidirectional()
+                .build();
+            self.waveform
+                .bind_property("end", &*self.end_row, "value")
+                .sync_create()
+                .bidirectional()
+                .build();
+
+            self.stack.set_visible_child_name("waveform");
+            self.times_group.set_sensitive(true);
+            self.update_trim_button();
+        }
+
+        fn update_trim_button(&self) {
+            // Nothing to trim if the whole track is selected
+            let is_modified =
+                self.waveform.start() > 0.0 || self.waveform.end() < self.waveform.duration();
+
+            self.trim_button
+                .set_sensitive(is_modified && !self.is_trimming.get());
+        }
+
+        #[template_callback]
+        fn on_time_output(row: &adw::SpinRow) -> bool {
+            let seconds = row.value().round() as u64;
+            row.set_text(&format!("{}:{:02}", seconds / 60, seconds % 60));
+            true
+        }
+
+        #[template_callback]
+        async fn trim(&self) {
+            let start =
+                gstreamer::ClockTime::from_mseconds((self.waveform.start() * 1000.0) as u64);
+            let end = gstreamer::ClockTime::from_mseconds((self.waveform.end() * 1000.0) as u64);
+
+            self.is_trimming.set(true);
+            self.update_trim_button();
+            self.stack.set_visible_child_name("loading");
+            self.times_group.set_sensitive(false);
+
+            let res = self.obj().track().trim(start, end).await;
+
+            self.is_trimming.set(false);
+            match res {
+                Ok(()) => {
+                    self.obj().close();
+                }
+                Err(err) => {
+                    error!("Unable to trim track: {err}");
+                    let toast = adw::Toast::new(&i18n("Unable to trim track"));
+                    self.toast_overlay.add_toast(toast);
+
+                    self.stack.set_visible_child_name("waveform");
+                    self.times_group.set_sensitive(true);
+                    self.update_trim_button();
+                }
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct SwTrimDialog(ObjectSubclass<imp::SwTrimDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl SwTrimDialog {
+    pub fn new(track: &SwTrack) -> Self {
+        glib::Object::builder().property("track", track).build()
+    }
+}
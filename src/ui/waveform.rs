@@ -0,0 +1,212 @@
+// Shortwave - waveform.rs
+// Copyright (C) 2025  Felix Häcker <haeckerfelix@gnome.org>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::{Cell, RefCell};
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use glib::{clone, Properties};
+use gtk::glib;
+use gtk::graphene::Rect;
+
+/// Shortest part of the track which can be kept, in seconds
+const MINIMUM_LENGTH: f64 = 1.0;
+const HANDLE_WIDTH: f32 = 3.0;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, Properties)]
+    #[properties(wrapper_type = super::SwWaveform)]
+    pub struct SwWaveform {
+        /// Duration of the track in seconds
+        #[property(get, set = Self::set_duration)]
+        duration: Cell<f64>,
+        /// Start of the kept part in seconds
+        #[property(get, set = Self::set_start)]
+        start: Cell<f64>,
+        /// End of the kept part in seconds
+        #[property(get, set = Self::set_end)]
+        end: Cell<f64>,
+
+        pub peaks: RefCell<Vec<f64>>,
+        dragged_handle: Cell<Option<Handle>>,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Handle {
+        Start,
+        End,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SwWaveform {
+        const NAME: &'static str = "SwWaveform";
+        type ParentType = gtk::Widget;
+        type Type = super::SwWaveform;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_css_name("waveform");
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for SwWaveform {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let gesture = gtk::GestureDrag::new();
+            gesture.connect_drag_begin(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_, x, _| {
+                    // Move the handle which is closest to the pointer
+                    let position = imp.position_at(x);
+                    let handle =
+                        if (position - imp.start.get()).abs() <= (position - imp.end.get()).abs() {
+                            Handle::Start
+                        } else {
+                            Handle::End
+                        };
+
+                    imp.dragged_handle.set(Some(handle));
+                    imp.move_handle(handle, position);
+                }
+            ));
+            gesture.connect_drag_update(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |gesture, offset_x, _| {
+                    let (Some(handle), Some((start_x, _))) =
+                        (imp.dragged_handle.get(), gesture.start_point())
+                    else {
+                        return;
+                    };
+
+                    imp.move_handle(handle, imp.position_at(start_x + offset_x));
+                }
+            ));
+            gesture.connect_drag_end(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_, _, _| imp.dragged_handle.set(None)
+            ));
+            self.obj().add_controller(gesture);
+
+            adw::StyleManager::default().connect_accent_color_notify(clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| imp.obj().queue_draw()
+            ));
+        }
+    }
+
+    impl WidgetImpl for SwWaveform {
+        fn measure(&self, orientation: gtk::Orientation, _for_size: i32) -> (i32, i32, i32, i32) {
+            if orientation == gtk::Orientation::Vertical {
+                (96, 96, -1, -1)
+            } else {
+                (64, 256, -1, -1)
+            }
+        }
+
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            let width = self.obj().width() as f32;
+            let height = self.obj().height() as f32;
+            let duration = self.duration.get();
+            if duration <= 0.0 {
+                return;
+            }
+
+            let accent = adw::StyleManager::default().accent_color_rgba();
+            let dimmed = self.obj().color().with_alpha(0.3);
+
+            // One bar per peak, parts which get cut off are dimmed
+            let peaks = self.peaks.borrow();
+            let bar_width = width / peaks.len().max(1) as f32;
+            for (i, peak) in peaks.iter().enumerate() {
+                let x = i as f32 * bar_width;
+                let position = (x + bar_width / 2.0) as f64 / width as f64 * duration;
+                let kept = position >= self.start.get() && position <= self.end.get();
+
+                let bar_height = (*peak as f32 * height).max(1.0);
+                let rect = Rect::new(
+                    x,
+                    (height - bar_height) / 2.0,
+                    (bar_width - 1.0).max(1.0),
+                    bar_height,
+                );
+                snapshot.append_color(if kept { &accent } else { &dimmed }, &rect);
+            }
+
+            for position in [self.start.get(), self.end.get()] {
+                let x = (position / duration) as f32 * width;
+                let x = x.clamp(0.0, width - HANDLE_WIDTH);
+                snapshot.append_color(&accent, &Rect::new(x, 0.0, HANDLE_WIDTH, height));
+            }
+        }
+    }
+
+    impl SwWaveform {
+        fn set_duration(&self, duration: f64) {
+            self.duration.set(duration.max(0.0));
+            self.obj().queue_draw();
+        }
+
+        fn set_start(&self, start: f64) {
+            let start = start.min(self.end.get() - MINIMUM_LENGTH).max(0.0);
+            if self.start.replace(start) != start {
+                self.obj().notify_start();
+                self.obj().queue_draw();
+            }
+        }
+
+        fn set_end(&self, end: f64) {
+            let duration = self.duration.get();
+            let end = end.max(self.start.get() + MINIMUM_LENGTH).min(duration);
+            if self.end.replace(end) != end {
+                self.obj().notify_end();
+                self.obj().queue_draw();
+            }
+        }
+
+        fn position_at(&self, x: f64) -> f64 {
+            let width = self.obj().width().max(1) as f64;
+            (x / width).clamp(0.0, 1.0) * self.duration.get()
+        }
+
+        fn move_handle(&self, handle: Handle, position: f64) {
+            match handle {
+                Handle::Start => self.obj().set_start(position),
+                Handle::End => self.obj().set_end(position),
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct SwWaveform(ObjectSubclass<imp::SwWaveform>)
+        @extends gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl SwWaveform {
+    /// Peak levels between 0.0 and 1.0, evenly spread over the duration of the track
+    pub fn set_peaks(&self, peaks: Vec<f64>) {
+        *self.imp().peaks.borrow_mut() = peaks;
+        self.queue_draw();
+    }
+}
@@ -14,33 +14,45 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::cell::Cell;
+
 use adw::prelude::*;
 use adw::subclass::prelude::*;
-use glib::clone;
 use glib::subclass::InitializingObject;
+use glib::{clone, Properties};
 use gtk::{gio, glib, CompositeTemplate};
 
+use crate::api::PlaylistEntry;
 use crate::app::SwApplication;
 use crate::audio::SwPlaybackState;
 use crate::config;
-use crate::i18n::i18n;
+use crate::i18n::{i18n, i18n_f};
 use crate::settings::{settings_manager, Key};
-use crate::ui::pages::{SwLibraryPage, SwSearchPage};
+use crate::ui::pages::{SwDiscoverPage, SwLibraryPage, SwSearchPage};
 use crate::ui::player::{SwPlayerGadget, SwPlayerToolbar, SwPlayerView};
 use crate::ui::{
-    about_dialog, SwAddStationDialog, SwDeviceDialog, SwPreferencesDialog, SwStationDialog,
+    about_dialog, DisplayError, SwAddStationDialog, SwDeviceDialog, SwDiagnosticsDialog,
+    SwDisplayWindow, SwImportStationsDialog, SwPreferencesDialog, SwCoverShape, SwRecordersDialog, SwRecoveryDialog, SwRulesDialog, SwShortcutsDialog, SwStationDialog,
 };
 use crate::utils;
 
+/// Libraries with fewer stations start with the discover page
+const DISCOVER_LIBRARY_SIZE: u32 = 5;
+
 mod imp {
     use super::*;
 
-    #[derive(Debug, Default, CompositeTemplate)]
+    #[derive(Debug, Default, CompositeTemplate, Properties)]
     #[template(resource = "/de/haeckerfelix/Shortwave/gtk/window.ui")]
+    #[properties(wrapper_type = super::SwApplicationWindow)]
     pub struct SwApplicationWindow {
+        #[template_child]
+        pub(super) navigation_view: TemplateChild<adw::NavigationView>,
         #[template_child]
         pub(super) library_page: TemplateChild<SwLibraryPage>,
         #[template_child]
+        pub(super) discover_page: TemplateChild<SwDiscoverPage>,
+        #[template_child]
         pub(super) search_page: TemplateChild<SwSearchPage>,
 
         #[template_child]
@@ -51,6 +63,11 @@ mod imp {
         pub(super) player_view: TemplateChild<SwPlayerView>,
         #[template_child]
         pub toast_overlay: TemplateChild<adw::ToastOverlay>,
+
+        #[property(get, set=Self::set_cover_shape, builder(SwCoverShape::default()))]
+        cover_shape: Cell<SwCoverShape>,
+
+        display_window: glib::WeakRef<SwDisplayWindow>,
     }
 
     #[glib::object_subclass]
@@ -72,6 +89,45 @@ mod imp {
             klass.install_action_async("player.toggle-playback", None, |_, _, _| async move {
                 SwApplication::default().player().toggle_playback().await;
             });
+            klass.install_action_async("player.play-next", None, |_, _, _| async move {
+                SwApplication::default().player().play_next().await;
+            });
+            klass.install_action_async("player.play-something", None, |_, _, _| async move {
+                SwApplication::default().player().play_suggestion().await;
+            });
+            klass.install_action("player.clear-queue", None, |_, _, _| {
+                SwApplication::default().player().queue().clear();
+            });
+            klass.install_action("player.pause-playback", None, |_, _, _| {
+                SwApplication::default().player().pause_playback();
+            });
+            klass.install_action("player.replay", None, |_, _, _| {
+                SwApplication::default().player().replay();
+            });
+            klass.install_action("player.jump-to-live", None, |_, _, _| {
+                SwApplication::default().player().jump_to_live();
+            });
+            klass.install_action_async("player.save-recent", None, |win, _, _| async move {
+                let res = SwApplication::default().player().save_recent_audio().await;
+                res.handle_error_toast(i18n("Unable to save recent audio"));
+
+                if let Ok((file, seconds)) = res {
+                    let duration = utils::format_duration(seconds, false);
+                    let toast = adw::Toast::new(&i18n_f("Saved the last {}", &[&duration]));
+                    toast.set_button_label(Some(&i18n("_Open")));
+                    toast.connect_button_clicked(clone!(
+                        #[weak]
+                        win,
+                        move |_| {
+                            let launcher = gtk::FileLauncher::new(Some(&file));
+                            launcher.launch(Some(&win), gio::Cancellable::NONE, |res| {
+                                res.handle_error(i18n("Unable to open recording"));
+                            });
+                        }
+                    ));
+                    win.add_toast(toast);
+                }
+            });
             klass.install_action("player.show-device-connect", None, move |win, _, _| {
                 let is_visible = win
                     .visible_dialog()
@@ -109,6 +165,12 @@ mod imp {
                     SwAddStationDialog::new().present(Some(win));
                 }
             });
+            klass.install_action_async("win.import-local-stations", None, |win, _, _| async move {
+                win.import_local_stations().await;
+            });
+            klass.install_action("win.search", None, move |win, _, _| {
+                win.start_search();
+            });
             klass.install_action("win.add-public-station", None, move |win, _, _| {
                 win.show_uri("https://www.radio-browser.info/add");
             });
@@ -128,6 +190,67 @@ mod imp {
                     SwPreferencesDialog::new().present(Some(win));
                 }
             });
+            klass.install_action("win.show-shortcuts-editor", None, move |win, _, _| {
+                let is_visible = win
+                    .visible_dialog()
+                    .map(|d| d.downcast::<SwShortcutsDialog>().is_ok())
+                    .unwrap_or(false);
+
+                if !is_visible {
+                    SwShortcutsDialog::new().present(Some(win));
+                }
+            });
+            klass.install_action("win.show-automation-rules", None, move |win, _, _| {
+                let is_visible = win
+                    .visible_dialog()
+                    .map(|d| d.downcast::<SwRulesDialog>().is_ok())
+                    .unwrap_or(false);
+
+                if !is_visible {
+                    SwRulesDialog::new().present(Some(win));
+                }
+            });
+            klass.install_action("win.show-recorders", None, move |win, _, _| {
+                let is_visible = win
+                    .visible_dialog()
+                    .map(|d| d.downcast::<SwRecordersDialog>().is_ok())
+                    .unwrap_or(false);
+
+                if !is_visible {
+                    SwRecordersDialog::new().present(Some(win));
+                }
+            });
+            klass.install_action("win.show-diagnostics", None, move |win, _, _| {
+                let is_visible = win
+                    .visible_dialog()
+                    .map(|d| d.downcast::<SwDiagnosticsDialog>().is_ok())
+                    .unwrap_or(false);
+
+                if !is_visible {
+                    SwDiagnosticsDialog::new().present(Some(win));
+                }
+            });
+            klass.install_action("win.show-recovered-tracks", None, move |win, _, _| {
+                let is_visible = win
+                    .visible_dialog()
+                    .map(|d| d.downcast::<SwRecoveryDialog>().is_ok())
+                    .unwrap_or(false);
+
+                if !is_visible {
+                    SwRecoveryDialog::new().present(Some(win));
+                }
+            });
+            klass.install_action("win.show-display-window", None, move |win, _, _| {
+                // Only one display window is needed
+                if let Some(display_window) = win.imp().display_window.upgrade() {
+                    display_window.present();
+                    return;
+                }
+
+                let display_window = SwDisplayWindow::new();
+                display_window.present_on_other_monitor(win);
+                win.imp().display_window.set(Some(&display_window));
+            });
             klass.install_action("win.about", None, move |win, _, _| {
                 let is_visible = win
                     .visible_dialog()
@@ -145,6 +268,7 @@ mod imp {
         }
     }
 
+    #[glib::derived_properties]
     impl ObjectImpl for SwApplicationWindow {
         fn constructed(&self) {
             self.parent_constructed();
@@ -156,10 +280,40 @@ mod imp {
             }
 
             // Restore window geometry
-            let width = settings_manager::integer(Key::WindowWidth);
-            let height = settings_manager::integer(Key::WindowHeight);
+            let mut width = settings_manager::integer(Key::WindowWidth);
+            let mut height = settings_manager::integer(Key::WindowHeight);
+
+            // The window got closed in gadget mode, use the size from before instead
+            if height < 150 && !settings_manager::boolean(Key::StartupRestoreGadget) {
+                width = settings_manager::integer(Key::WindowPreviousWidth);
+                height = settings_manager::integer(Key::WindowPreviousHeight);
+            }
             obj.set_default_size(width, height);
 
+            settings_manager::bind_property(Key::AppearanceCoverShape, &*obj, "cover-shape");
+
+            // A small library doesn't offer much to choose from, so suggest some stations
+            let library = SwApplication::default().library();
+            if library.model().n_items() < DISCOVER_LIBRARY_SIZE {
+                self.navigation_view.push_by_tag("discover");
+            }
+
+            // Screen readers don't notice when the track labels change, so announce it
+            SwApplication::default()
+                .player()
+                .connect_playing_track_notify(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move |player| {
+                        if let Some(track) = player.playing_track() {
+                            imp.obj().announce(
+                                &i18n_f("Now playing: {}", &[&track.title()]),
+                                gtk::AccessibleAnnouncementPriority::Medium,
+                            );
+                        }
+                    }
+                ));
+
             // Monitor window size changes for auto gadget mode
             let window_weak = obj.downgrade();
             obj.connect_default_height_notify(move |_window| {
@@ -213,6 +367,19 @@ mod imp {
                 debug!("Hide window");
                 self.obj().set_visible(false);
 
+                glib::Propagation::Stop
+            } else if player.is_recording() && self.obj().is_visible() {
+                // Give the user the chance to keep the recorded data before it gets discarded
+                glib::spawn_future_local(clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    async move {
+                        if imp.obj().confirm_quit().await {
+                            imp.obj().close();
+                        }
+                    }
+                ));
+
                 glib::Propagation::Stop
             } else {
                 debug!("Close window");
@@ -226,6 +393,16 @@ mod imp {
     impl AdwApplicationWindowImpl for SwApplicationWindow {}
 
     impl SwApplicationWindow {
+        fn set_cover_shape(&self, shape: SwCoverShape) {
+            self.cover_shape.set(shape);
+
+            if shape == SwCoverShape::Round {
+                self.obj().add_css_class("round-covers");
+            } else {
+                self.obj().remove_css_class("round-covers");
+            }
+        }
+
         async fn verify_background_portal_permissions(&self) {
             // Verify whether app has permissions for background playback
             let has_permissions = utils::background_portal_permissions().await;
@@ -275,10 +452,68 @@ impl SwApplicationWindow {
         glib::Object::builder().build()
     }
 
+    /// Asks whether the currently recorded track should be saved before quitting.
+    /// Returns `false` if quitting got cancelled.
+    pub async fn confirm_quit(&self) -> bool {
+        let player = SwApplication::default().player();
+        if !player.is_recording() {
+            return true;
+        }
+
+        self.present();
+
+        let dialog = adw::AlertDialog::new(
+            Some(&i18n("Save Current Recording?")),
+            Some(&i18n(
+                "The track is still being recorded. If you quit now, it will be incomplete.",
+            )),
+        );
+
+        dialog.add_response("cancel", &i18n("_Cancel"));
+        dialog.add_response("discard", &i18n("_Discard"));
+        dialog.add_response("save", &i18n("_Save"));
+        dialog.set_response_appearance("discard", adw::ResponseAppearance::Destructive);
+        dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("save"));
+        dialog.set_close_response("cancel");
+
+        match dialog.choose_future(Some(self)).await.as_str() {
+            "save" => {
+                let res = player.save_recording().await;
+                res.handle_error_toast(i18n("Unable to save track"));
+                res.is_ok()
+            }
+            "discard" => {
+                player.cancel_recording();
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub fn show_notification(&self, text: &str) {
         self.imp().toast_overlay.add_toast(adw::Toast::new(text));
     }
 
+    pub fn add_toast(&self, toast: adw::Toast) {
+        self.imp().toast_overlay.add_toast(toast);
+    }
+
+    /// Searches the library when it's visible, otherwise all stations
+    pub fn start_search(&self) {
+        let imp = self.imp();
+        let tag = imp.navigation_view.visible_page().and_then(|p| p.tag());
+
+        if tag.as_deref() == Some("library") {
+            imp.library_page.start_search();
+        } else {
+            if tag.as_deref() != Some("search") {
+                imp.navigation_view.push_by_tag("search");
+            }
+            imp.search_page.start_search();
+        }
+    }
+
     pub fn enable_gadget_player(&self, enable: bool) {
         if enable {
             // Save current window size before entering gadget mode
@@ -306,6 +541,45 @@ impl SwApplicationWindow {
         });
     }
 
+    /// Lets the user pick a playlist file, and shows its streams for adding them as local stations
+    pub async fn import_local_stations(&self) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some(&i18n("Playlists")));
+        for mime_type in [
+            "audio/x-mpegurl",
+            "audio/mpegurl",
+            "audio/x-scpls",
+            "text/plain",
+        ] {
+            filter.add_mime_type(mime_type);
+        }
+
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+
+        let dialog = gtk::FileDialog::new();
+        dialog.set_title(&i18n("Import Local Stations"));
+        dialog.set_filters(Some(&filters));
+
+        let Ok(file) = dialog.open_future(Some(self)).await else {
+            return;
+        };
+
+        let res = file.load_contents_future().await;
+        res.handle_error(i18n("Unable to open playlist"));
+        let Ok((data, _)) = res else {
+            return;
+        };
+
+        let entries = PlaylistEntry::parse_all(&String::from_utf8_lossy(&data));
+        if entries.is_empty() {
+            self.show_notification(&i18n("No stream URLs found in the file"));
+            return;
+        }
+
+        SwImportStationsDialog::new(entries).present(Some(self));
+    }
+
     pub fn library_page(&self) -> SwLibraryPage {
         self.imp().library_page.get()
     }
@@ -15,9 +15,15 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use ashpd::desktop::background::Background;
-use gtk::glib;
+use ashpd::desktop::location::{Accuracy, LocationProxy};
+use ashpd::desktop::settings::Settings;
+use futures_util::StreamExt;
+use gtk::prelude::*;
+use gtk::{gio, glib};
 
+use crate::api::StationRequest;
 use crate::i18n::{gettext_f, ni18n_f};
+use crate::settings::{settings_manager, Key};
 
 /// Extension trait for Option that adds the is_none_or method
 pub trait OptionExt<T> {
@@ -117,6 +123,31 @@ pub fn ellipsize_end<S: std::fmt::Display>(x: S, max_len: usize) -> String {
     }
 }
 
+/// The region of the system locale, e.g. "DE" for "de_DE.UTF-8"
+pub fn region_code() -> Option<String> {
+    let locale = sys_locale::get_locale()?;
+    let langtag = language_tags::LanguageTag::parse(&locale).ok()?;
+    langtag.region().map(|s| s.to_string())
+}
+
+/// Base request for recommended stations, which are limited to the preferred
+/// language, or to the region if no language is set
+pub fn recommendation_request() -> StationRequest {
+    let language = settings_manager::string(Key::DiscoverLanguage);
+    if language.is_empty() {
+        StationRequest {
+            countrycode: Some(region_code().unwrap_or("GB".into())),
+            ..Default::default()
+        }
+    } else {
+        StationRequest {
+            language: Some(language),
+            language_exact: Some(true),
+            ..Default::default()
+        }
+    }
+}
+
 /// Detect if we're running under KDE Plasma
 pub fn is_kde_plasma() -> bool {
     if let Ok(desktop) = std::env::var("XDG_CURRENT_DESKTOP") {
@@ -148,3 +179,63 @@ pub async fn background_portal_permissions() -> bool {
         true
     }
 }
+
+/// The current location as latitude and longitude, as provided by the location portal.
+/// The accuracy of a city is sufficient to find nearby stations.
+pub async fn current_location() -> ashpd::Result<(f64, f64)> {
+    let proxy = LocationProxy::new().await?;
+    let session = proxy
+        .create_session(None, None, Some(Accuracy::City))
+        .await?;
+    let mut updates = proxy.receive_location_updated().await?;
+
+    proxy.start(&session, None).await?;
+    let location = updates.next().await;
+    session.close().await?;
+
+    let location = location.ok_or(ashpd::Error::NoResponse)?;
+    debug!(
+        "Current location: {}, {} (accuracy: {}m)",
+        location.latitude(),
+        location.longitude(),
+        location.accuracy()
+    );
+
+    Ok((location.latitude(), location.longitude()))
+}
+
+/// Whether the desktop is in "Do Not Disturb" mode, which means that no notification banners
+/// should be shown
+pub async fn do_not_disturb() -> bool {
+    // GNOME hides notification banners while "Do Not Disturb" is enabled
+    if let Ok(settings) = Settings::new().await {
+        if let Ok(show_banners) = settings
+            .read::<bool>("org.gnome.desktop.notifications", "show-banners")
+            .await
+        {
+            return !show_banners;
+        }
+    }
+
+    // Other notification servers, e.g. KDE Plasma, expose it as "Inhibited" property
+    let proxy = gio::DBusProxy::for_bus_future(
+        gio::BusType::Session,
+        gio::DBusProxyFlags::DO_NOT_AUTO_START,
+        None,
+        "org.freedesktop.Notifications",
+        "/org/freedesktop/Notifications",
+        "org.freedesktop.Notifications",
+    )
+    .await;
+
+    match proxy {
+        Ok(proxy) => proxy
+            .cached_property("Inhibited")
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or_default(),
+        Err(err) => {
+            debug!("Unable to check notification inhibition: {err}");
+            false
+        }
+    }
+}